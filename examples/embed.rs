@@ -0,0 +1,70 @@
+// the board alone inside an app of its own, with a log of what happened beside it. run it with
+// `cargo run --example embed`
+
+use iced::widget::{button, column, container, row, scrollable, text};
+use iced::{Element, Length, Subscription, Task};
+use minsweeper_native_client::board_widget::{self, Assist, BoardConfig, BoardEvent, BoardWidget};
+use minsweeper_rs::board::ConventionalSize;
+use minsweeper_rs::solver::mia::MiaSolver;
+use std::sync::Arc;
+use tokio::sync::mpsc::UnboundedReceiver;
+
+fn main() -> iced::Result {
+    iced::application(Host::new, Host::update, Host::view)
+            .subscription(Host::subscription)
+            .title("Embedded board")
+            .run()
+}
+
+struct Host {
+    board: BoardWidget,
+    events: UnboundedReceiver<BoardEvent>,
+    log: Vec<String>,
+}
+
+#[derive(Clone, Debug)]
+enum Message {
+    Board(board_widget::Message),
+    Restart,
+    Step,
+}
+
+impl Host {
+    fn new() -> (Self, Task<Message>) {
+        let config = BoardConfig { assist: Assist::Steps, ..BoardConfig::default() };
+        let (mut board, start) = BoardWidget::new(ConventionalSize::Intermediate.size(), Some(Arc::new(MiaSolver::default())), config);
+        let events = board.events();
+        (Self { board, events, log: vec![] }, start.map(Message::Board))
+    }
+
+    fn update(&mut self, message: Message) -> Task<Message> {
+        let task = match message {
+            Message::Board(message) => self.board.update(message),
+            Message::Restart => self.board.update(board_widget::Message::Restart),
+            Message::Step => self.board.update(board_widget::Message::Step),
+        };
+        while let Ok(event) = self.events.try_recv() {
+            self.log.push(format!("{:?}", event));
+        }
+        task.map(Message::Board)
+    }
+
+    fn view(&self) -> Element<'_, Message> {
+        row![
+            container(self.board.view().map(Message::Board))
+                    .padding(10),
+            column![
+                row![
+                    button("Restart").on_press(Message::Restart),
+                    button("Step").on_press(Message::Step),
+                ].spacing(10),
+                scrollable(column(self.log.iter().rev().map(|line| text(line).size(12).into())))
+                        .height(Length::Fill),
+            ].spacing(10).padding(10).width(Length::Fill),
+        ].into()
+    }
+
+    fn subscription(&self) -> Subscription<Message> {
+        self.board.subscription().map(Message::Board)
+    }
+}
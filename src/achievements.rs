@@ -0,0 +1,187 @@
+use crate::i18n::t;
+use crate::minsweeper::GameOver;
+use minsweeper_rs::board::{BoardSize, ConventionalSize};
+use minsweeper_rs::GameStatus;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[cfg(test)]
+mod tests;
+
+/// wins in a row for [`Achievement::Streak`]
+const STREAK: u32 = 5;
+
+/// finished games for [`Achievement::Played`]
+const PLAYED: u32 = 100;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Difficulty {
+    Beginner,
+    Intermediate,
+    Expert,
+}
+
+impl Difficulty {
+    const ALL: [Difficulty; 3] = [Difficulty::Beginner, Difficulty::Intermediate, Difficulty::Expert];
+
+    /// custom sizes don't have one
+    fn of(size: BoardSize) -> Option<Difficulty> {
+        Difficulty::ALL.into_iter().find(|difficulty| difficulty.size() == size)
+    }
+
+    fn size(self) -> BoardSize {
+        match self {
+            Difficulty::Beginner => ConventionalSize::Beginner.size(),
+            Difficulty::Intermediate => ConventionalSize::Intermediate.size(),
+            Difficulty::Expert => ConventionalSize::Expert.size(),
+        }
+    }
+
+    /// what a win has to beat for [`Achievement::Fast`]
+    fn fast(self) -> Duration {
+        Duration::from_secs(match self {
+            Difficulty::Beginner => 10,
+            Difficulty::Intermediate => 60,
+            Difficulty::Expert => 180,
+        })
+    }
+}
+
+impl Display for Difficulty {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Difficulty::Beginner => t!("size-beginner"),
+            Difficulty::Intermediate => t!("size-intermediate"),
+            Difficulty::Expert => t!("size-expert"),
+        })
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Achievement {
+    FirstWin(Difficulty),
+    Fast(Difficulty),
+    Streak,
+    Played,
+    /// a no flags win without an undo, a guarded reveal or a retry
+    FlawlessNoFlags,
+    /// a win without a single flag ever going down, in no flags mode or not
+    NoFlagsPlaced,
+}
+
+impl Achievement {
+    /// in the order the dialog lists them
+    pub fn all() -> impl Iterator<Item = Achievement> {
+        Difficulty::ALL.into_iter().map(Achievement::FirstWin)
+                .chain(Difficulty::ALL.into_iter().map(Achievement::Fast))
+                .chain([Achievement::Streak, Achievement::Played, Achievement::FlawlessNoFlags, Achievement::NoFlagsPlaced])
+    }
+
+    pub fn description(self) -> String {
+        match self {
+            Achievement::FirstWin(difficulty) => t!("achievement-first-win-explained", difficulty = difficulty),
+            Achievement::Fast(difficulty) => t!("achievement-fast-explained", difficulty = difficulty,
+                    seconds = difficulty.fast().as_secs()),
+            Achievement::Streak => t!("achievement-streak-explained", count = STREAK),
+            Achievement::Played => t!("achievement-played-explained", count = PLAYED),
+            Achievement::FlawlessNoFlags => t!("achievement-flawless-no-flags-explained").to_string(),
+            Achievement::NoFlagsPlaced => t!("achievement-no-flags-placed-explained").to_string(),
+        }
+    }
+}
+
+impl Display for Achievement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Achievement::FirstWin(difficulty) => f.write_str(&t!("achievement-first-win", difficulty = difficulty)),
+            Achievement::Fast(difficulty) => f.write_str(&t!("achievement-fast", difficulty = difficulty)),
+            Achievement::Streak => f.write_str(t!("achievement-streak")),
+            Achievement::Played => f.write_str(t!("achievement-played")),
+            Achievement::FlawlessNoFlags => f.write_str(t!("achievement-flawless-no-flags")),
+            Achievement::NoFlagsPlaced => f.write_str(t!("achievement-no-flags-placed")),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct Unlocked {
+    pub achievement: Achievement,
+    /// seconds since the epoch
+    pub at: u64,
+}
+
+/// what's been earned so far, kept in the stats file
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Achievements {
+    unlocked: Vec<Unlocked>,
+    /// games won or lost by the player, abandoned ones don't count
+    played: u32,
+    /// the player's wins in a row across every size, a loss or an abandoned game ends it
+    streak: u32,
+}
+
+impl Achievements {
+    pub fn unlocked(&self, achievement: Achievement) -> Option<&Unlocked> {
+        self.unlocked.iter().find(|unlocked| unlocked.achievement == achievement)
+    }
+
+    /// counts `game_over` towards them, returning the ones it just unlocked. a game the solver
+    /// made any move in earns none for the player
+    pub fn record(&mut self, game_over: &GameOver) -> Vec<Achievement> {
+        if game_over.solver_played() {
+            return vec![]
+        }
+        let won = game_over.status == GameStatus::Won;
+        if matches!(game_over.status, GameStatus::Won | GameStatus::Lost) {
+            self.played += 1;
+        }
+        self.streak = if won { self.streak + 1 } else { 0 };
+
+        let mut met = vec![];
+        if self.played >= PLAYED {
+            met.push(Achievement::Played);
+        }
+        if won {
+            if let Some(difficulty) = Difficulty::of(game_over.size) {
+                met.push(Achievement::FirstWin(difficulty));
+                if game_over.elapsed < difficulty.fast() && !game_over.retry && !game_over.assisted() {
+                    met.push(Achievement::Fast(difficulty));
+                }
+            }
+            if self.streak >= STREAK {
+                met.push(Achievement::Streak);
+            }
+            if game_over.no_flags && !game_over.retry && !game_over.assisted() {
+                met.push(Achievement::FlawlessNoFlags);
+            }
+            if !game_over.flagged {
+                met.push(Achievement::NoFlagsPlaced);
+            }
+        }
+
+        let at = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+        met.retain(|achievement| self.unlocked(*achievement).is_none());
+        self.unlocked.extend(met.iter().map(|achievement| Unlocked { achievement: *achievement, at }));
+        met
+    }
+}
+
+/// `at` as a day, like 2024-03-09, in UTC
+pub fn date(at: u64) -> String {
+    // days to a civil date, from Howard Hinnant's `civil_from_days`
+    let days = (at / 86400) as i64 + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{}-{:02}-{:02}", year, month, day)
+}
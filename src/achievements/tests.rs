@@ -0,0 +1,131 @@
+use super::{Achievement, Achievements, Difficulty, PLAYED, STREAK};
+use crate::minsweeper::GameOver;
+use minsweeper_rs::board::{BoardSize, ConventionalSize};
+use minsweeper_rs::GameStatus;
+use std::time::Duration;
+
+fn game_over(status: GameStatus) -> GameOver {
+    GameOver {
+        size: BoardSize::new(20, 20, 50).unwrap(),
+        status,
+        elapsed: Duration::from_secs(600),
+        clicks: 0,
+        player_clicks: 0,
+        bbbv: None,
+        seed: 0,
+        revealed_by_player: 0,
+        revealed_by_solver: 0,
+        auto: false,
+        retry: false,
+        undos: 0,
+        guarded: 0,
+        guesses: 0,
+        no_flags: false,
+        flagged: true,
+        eligible: true,
+    }
+}
+
+fn won() -> GameOver {
+    game_over(GameStatus::Won)
+}
+
+fn beginner(elapsed: Duration) -> GameOver {
+    GameOver { size: ConventionalSize::Beginner.size(), elapsed, ..won() }
+}
+
+#[test]
+fn the_solver_earns_nothing() {
+    let mut achievements = Achievements::default();
+    let met = achievements.record(&GameOver { auto: true, ..beginner(Duration::from_secs(1)) });
+    assert_eq!(met, []);
+    assert_eq!((achievements.played, achievements.streak), (0, 0));
+}
+
+#[test]
+fn a_step_assisted_win_earns_nothing() {
+    let mut achievements = Achievements::default();
+    let stepped = GameOver { revealed_by_player: 60, revealed_by_solver: 3, ..beginner(Duration::from_secs(1)) };
+    assert_eq!(achievements.record(&stepped), []);
+    assert_eq!(achievements.record(&GameOver { auto: true, ..stepped }), []);
+    assert_eq!((achievements.played, achievements.streak), (0, 0));
+    assert!(achievements.unlocked.is_empty());
+}
+
+#[test]
+fn a_solver_win_doesnt_end_the_streak() {
+    let mut achievements = Achievements::default();
+    for _ in 0..STREAK - 1 {
+        achievements.record(&won());
+    }
+    achievements.record(&GameOver { auto: true, ..won() });
+    assert_eq!(achievements.record(&won()), [Achievement::Streak]);
+}
+
+#[test]
+fn a_loss_resets_the_streak() {
+    let mut achievements = Achievements::default();
+    for _ in 0..STREAK - 1 {
+        achievements.record(&won());
+    }
+    achievements.record(&game_over(GameStatus::Lost));
+    for _ in 0..STREAK - 1 {
+        assert_eq!(achievements.record(&won()), []);
+    }
+    assert_eq!(achievements.record(&won()), [Achievement::Streak]);
+}
+
+#[test]
+fn an_abandoned_game_resets_the_streak_without_being_played() {
+    let mut achievements = Achievements::default();
+    achievements.record(&won());
+    achievements.record(&game_over(GameStatus::Playing));
+    assert_eq!((achievements.played, achievements.streak), (1, 0));
+}
+
+#[test]
+fn fast_has_to_beat_the_threshold() {
+    let fast = Difficulty::Beginner.fast();
+    let mut achievements = Achievements::default();
+    assert_eq!(achievements.record(&beginner(fast)), [Achievement::FirstWin(Difficulty::Beginner)]);
+    assert_eq!(achievements.record(&beginner(fast - Duration::from_millis(1))), [Achievement::Fast(Difficulty::Beginner)]);
+}
+
+#[test]
+fn each_difficulty_has_its_own_threshold() {
+    let mut achievements = Achievements::default();
+    let intermediate = GameOver { size: ConventionalSize::Intermediate.size(), elapsed: Duration::from_secs(59), ..won() };
+    assert!(achievements.record(&intermediate).contains(&Achievement::Fast(Difficulty::Intermediate)));
+    let expert = GameOver { size: ConventionalSize::Expert.size(), elapsed: Duration::from_secs(180), ..won() };
+    assert!(!achievements.record(&expert).contains(&Achievement::Fast(Difficulty::Expert)));
+}
+
+#[test]
+fn a_retry_or_help_isnt_fast() {
+    let quick = beginner(Duration::from_secs(1));
+    let mut achievements = Achievements::default();
+    assert!(!achievements.record(&GameOver { retry: true, ..quick }).contains(&Achievement::Fast(Difficulty::Beginner)));
+    assert!(!achievements.record(&GameOver { undos: 1, ..quick }).contains(&Achievement::Fast(Difficulty::Beginner)));
+    assert!(!achievements.record(&GameOver { guarded: 1, ..quick }).contains(&Achievement::Fast(Difficulty::Beginner)));
+    assert!(achievements.record(&quick).contains(&Achievement::Fast(Difficulty::Beginner)));
+}
+
+#[test]
+fn played_counts_wins_and_losses() {
+    let mut achievements = Achievements::default();
+    for game in 1..PLAYED {
+        let status = if game % 2 == 0 { GameStatus::Won } else { GameStatus::Lost };
+        assert!(!achievements.record(&game_over(status)).contains(&Achievement::Played));
+    }
+    achievements.record(&game_over(GameStatus::Playing));
+    assert_eq!(achievements.record(&game_over(GameStatus::Lost)), [Achievement::Played]);
+    assert_eq!(achievements.played, PLAYED);
+}
+
+#[test]
+fn an_achievement_is_only_unlocked_once() {
+    let mut achievements = Achievements::default();
+    assert!(achievements.record(&beginner(Duration::from_secs(30))).contains(&Achievement::FirstWin(Difficulty::Beginner)));
+    assert!(!achievements.record(&beginner(Duration::from_secs(30))).contains(&Achievement::FirstWin(Difficulty::Beginner)));
+    assert!(achievements.unlocked(Achievement::FirstWin(Difficulty::Beginner)).is_some());
+}
@@ -0,0 +1,102 @@
+use crate::i18n::t;
+use crate::minsweeper;
+use crate::minsweeper::MinsweeperGame;
+use crate::texture::Texture;
+use iced::widget::{button, container, row, text};
+use iced::{Element, Length, Task};
+use iced_core::alignment::{Horizontal, Vertical};
+use minsweeper_rs::GameStatus;
+
+/// the line above the two boards saying how the race is going
+pub const BANNER_HEIGHT: f32 = 40.0;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Side {
+    Player,
+    Solver,
+}
+
+/// the solver's copy of the player's board, it starts on the same mines with the same first
+/// click as soon as the player has made theirs
+#[derive(Debug)]
+pub struct Arena {
+    rival: MinsweeperGame,
+    /// the rival has been given the player's board
+    started: bool,
+    winner: Option<Side>,
+}
+
+impl Arena {
+
+    /// `rival` should be a fresh game that plays itself
+    pub fn new(mut rival: MinsweeperGame) -> Self {
+        rival.watch();
+        Self {
+            rival,
+            started: false,
+            winner: None,
+        }
+    }
+
+    pub fn rival(&self) -> &MinsweeperGame {
+        &self.rival
+    }
+
+    pub fn rival_mut(&mut self) -> &mut MinsweeperGame {
+        &mut self.rival
+    }
+
+    pub fn update(&mut self, message: minsweeper::Message, player: &MinsweeperGame) -> Task<minsweeper::Message> {
+        let task = self.rival.update(message);
+        self.judge(player);
+        task
+    }
+
+    /// catches the rival up with whatever the player just did
+    pub fn sync(&mut self, player: &MinsweeperGame) -> Task<minsweeper::Message> {
+        self.rival.set_paused(player.paused());
+        self.judge(player);
+        if self.started {
+            return Task::none()
+        }
+        let (Some(layout), Some(opening)) = (player.layout(), player.opening()) else { return Task::none() };
+        self.started = true;
+        self.rival.play_layout(layout)
+                .chain(Task::done(minsweeper::Message::Reveal(opening)))
+    }
+
+    /// whoever wins first or makes the other win by losing, once it's decided it stays decided
+    fn judge(&mut self, player: &MinsweeperGame) {
+        if self.winner.is_some() || !self.started {
+            return
+        }
+        self.winner = match (player.game_status(), self.rival.game_status()) {
+            (GameStatus::Won, _) | (_, GameStatus::Lost) => Some(Side::Player),
+            (_, GameStatus::Won) | (GameStatus::Lost, _) => Some(Side::Solver),
+            _ => None,
+        };
+    }
+
+    pub fn change_textures(&mut self, texture: Texture) {
+        self.rival.change_textures(texture);
+    }
+
+    /// the race so far, a rematch restarts the player's board and the rival with it
+    pub fn banner(&self) -> Element<'_, minsweeper::Message> {
+        let status = match (self.started, self.winner) {
+            (false, _) => t!("arena-waiting"),
+            (true, None) => t!("arena-racing"),
+            (true, Some(Side::Player)) => t!("arena-player-won"),
+            (true, Some(Side::Solver)) => t!("arena-solver-won"),
+        };
+        container(row![text(status)]
+                .push(self.winner.map(|_| button(t!("arena-rematch")).on_press(minsweeper::Message::Restart)))
+                .spacing(10)
+                .align_y(Vertical::Center))
+                .height(BANNER_HEIGHT)
+                .width(Length::Fill)
+                .align_x(Horizontal::Center)
+                .align_y(Vertical::Center)
+                .into()
+    }
+}
@@ -0,0 +1,193 @@
+use crate::launch;
+use crate::minsweeper::{Game, Provenance, SolverType};
+use crate::solvers::{self, KnownSolver};
+use minsweeper_rs::board::{BoardSize, ConventionalSize, Point};
+use minsweeper_rs::solver::start::SafeStart;
+use minsweeper_rs::solver::{Move, Operation, Solver};
+use minsweeper_rs::{CellState, GameState, GameStatus};
+use rand::rngs::StdRng;
+use rand::seq::IndexedRandom;
+use rand::SeedableRng;
+use serde_json::json;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// `--size` isn't here, it means the same thing when launching the gui
+const FLAGS: [&str; 3] = ["--bench", "--games", "--json"];
+const DEFAULT_GAMES: usize = 100;
+
+struct Options {
+    solver: KnownSolver,
+    size: BoardSize,
+    games: usize,
+    json: bool,
+}
+
+#[derive(Default)]
+struct Totals {
+    wins: usize,
+    moves: usize,
+    guesses: usize,
+    time: Duration,
+}
+
+/// runs the benchmark if any of its flags were passed, returning the exit code, or `None` to go on
+/// to the gui
+pub fn from_args() -> Option<i32> {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    if !args.iter().any(|arg| FLAGS.contains(&arg.as_str())) {
+        return None
+    }
+
+    match parse(&args) {
+        Ok(options) => {
+            run(options);
+            Some(0)
+        }
+        Err(problem) => {
+            eprintln!("{}", problem);
+            eprintln!();
+            eprintln!("{}", usage());
+            Some(2)
+        }
+    }
+}
+
+fn parse(args: &[String]) -> Result<Options, String> {
+    let mut solver = None;
+    let mut size = ConventionalSize::Expert.size();
+    let mut games = DEFAULT_GAMES;
+    let mut json = false;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+        match arg.as_str() {
+            "--bench" => {
+                let id = value()?;
+                solver = Some(solvers::find(id)
+                        .map(KnownSolver::from)
+                        .ok_or_else(|| format!("there's no solver called {:?}", id))?);
+            }
+            "--size" => {
+                let name = value()?;
+                size = launch::parse_size(name).ok_or_else(|| format!("{:?} isn't a board size", name))?;
+            }
+            "--games" => {
+                let count = value()?;
+                games = count.parse()
+                        .ok()
+                        .filter(|games| *games > 0)
+                        .ok_or_else(|| format!("{:?} isn't a number of games", count))?;
+            }
+            "--json" => json = true,
+            _ => return Err(format!("{:?} isn't something the benchmark understands", arg)),
+        }
+    }
+
+    let solver = solver.ok_or("--bench needs the solver to run")?;
+    Ok(Options { solver, size, games, json })
+}
+
+fn usage() -> String {
+    format!("usage: minsweeper-native-client --bench <solver> [--size <size>] [--games <n>] [--json]\n\
+            \n\
+            {}\n\
+            \n\
+            the size defaults to expert and games to {}", launch::options(), DEFAULT_GAMES)
+}
+
+fn run(options: Options) {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .expect("should always be able to start a runtime");
+    let solver = SolverType::from(options.solver);
+    let mut totals = Totals::default();
+    let mut rng = StdRng::from_os_rng();
+
+    for _ in 0..options.games {
+        let seed = rand::random();
+        runtime.block_on(play(options.size, &solver, seed, &mut rng, &mut totals));
+    }
+
+    let games = options.games as f64;
+    let win_rate = totals.wins as f64 / games;
+    let average_moves = totals.moves as f64 / games;
+    let average_guesses = totals.guesses as f64 / games;
+    let average_time = totals.time.as_secs_f64() * 1000.0 / games;
+
+    if options.json {
+        println!("{}", json!({
+            "solver": options.solver.id(),
+            "width": options.size.width().get(),
+            "height": options.size.height().get(),
+            "mines": options.size.mines().get(),
+            "games": options.games,
+            "wins": totals.wins,
+            "win_rate": win_rate,
+            "average_moves": average_moves,
+            "average_time_ms": average_time,
+            "guesses": totals.guesses,
+            "average_guesses": average_guesses,
+        }));
+    } else {
+        println!("{} on {}×{} with {} mines, {} games",
+                options.solver, options.size.width(), options.size.height(), options.size.mines(), options.games);
+        println!("win rate:      {:.1}% ({} won)", win_rate * 100.0, totals.wins);
+        println!("moves:         {:.1} on average", average_moves);
+        println!("solve time:    {:.2}ms on average", average_time);
+        println!("guesses:       {} ({:.2} on average)", totals.guesses, average_guesses);
+    }
+}
+
+/// plays one game from an opening click in the middle, guessing a random cell whenever the solver
+/// has nothing, boards are only made safe to start so the solver being measured doesn't pick them
+async fn play(size: BoardSize, solver: &SolverType, seed: u64, rng: &mut StdRng, totals: &mut Totals) {
+    let game = Arc::new(Game::new(size));
+    game.start_with_solver(Arc::new(SafeStart), seed).await;
+    let opening = (size.width().get() / 2, size.height().get() / 2);
+    let _ = game.left_click(opening, Provenance::Player).await;
+
+    let started = Instant::now();
+    let mut step = 0;
+    let mut gamestate = game.gamestate().await;
+    while gamestate.status == GameStatus::Playing {
+        step += 1;
+        let by = Provenance::Solver(step);
+        let before = gamestate;
+        if let Some(Move { actions, .. }) = solver.solve(&before) {
+            for action in actions {
+                let _ = match action.operation {
+                    Operation::Reveal | Operation::Chord => game.left_click(action.point, by).await,
+                    Operation::Flag => game.right_click(action.point, by).await,
+                };
+            }
+        }
+        gamestate = game.gamestate().await;
+
+        // no move, or one that did nothing, either way it's down to luck
+        if unchanged(&before, &gamestate) {
+            let Some(point) = unknown_cells(&gamestate).choose(rng).copied() else { break };
+            let _ = game.left_click(point, by).await;
+            gamestate = game.gamestate().await;
+            totals.guesses += 1;
+        }
+        totals.moves += 1;
+    }
+
+    totals.time += started.elapsed();
+    if gamestate.status == GameStatus::Won {
+        totals.wins += 1;
+    }
+}
+
+fn unchanged(before: &GameState, after: &GameState) -> bool {
+    before.board.size().points()
+            .all(|point| before.board[point].cell_state == after.board[point].cell_state)
+}
+
+fn unknown_cells(gamestate: &GameState) -> Vec<Point> {
+    gamestate.board.size().points()
+            .filter(|point| gamestate.board[*point].cell_state == CellState::Unknown)
+            .collect()
+}
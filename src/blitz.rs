@@ -0,0 +1,125 @@
+use crate::dialog::DialogId;
+use crate::i18n::t;
+use crate::minsweeper;
+use crate::texture::Texture;
+use iced::widget::{button, container, row, text};
+use iced::{widget, Element, Length};
+use iced_core::alignment::{Horizontal, Vertical};
+use std::time::{Duration, Instant};
+
+#[cfg(test)]
+mod tests;
+
+/// how long a blitz lasts before losses take time off
+pub const DURATION: Duration = Duration::from_secs(180);
+/// the countdown and tallies above the board
+pub const BANNER_HEIGHT: f32 = 56.0;
+
+#[derive(Clone, Debug)]
+pub enum Message {
+    /// starts a blitz, over whatever was being played
+    Start,
+    Tick,
+    /// back to the usual game once the results have been seen
+    Leave,
+}
+
+/// as many beginner boards as can be cleared before the countdown runs out
+#[derive(Debug)]
+pub struct Blitz {
+    /// when time runs out, brought forward by each loss and pushed back by each pause
+    ends_at: Instant,
+    paused_at: Option<Instant>,
+    penalty: Duration,
+    cleared: u32,
+    losses: u32,
+    /// the best before this run, once time is up
+    result: Option<u32>,
+}
+
+impl Blitz {
+
+    pub fn new(now: Instant, penalty: Duration) -> Self {
+        Self {
+            ends_at: now + DURATION,
+            paused_at: None,
+            penalty,
+            cleared: 0,
+            losses: 0,
+            result: None,
+        }
+    }
+
+    pub fn remaining(&self, now: Instant) -> Duration {
+        self.ends_at.saturating_duration_since(self.paused_at.unwrap_or(now))
+    }
+
+    pub fn set_paused(&mut self, paused: bool, now: Instant) {
+        match self.paused_at {
+            None if paused => self.paused_at = Some(now),
+            Some(paused_at) if !paused => {
+                self.ends_at += now.saturating_duration_since(paused_at);
+                self.paused_at = None;
+            }
+            _ => {}
+        }
+    }
+
+    pub fn won(&mut self) {
+        self.cleared += 1;
+    }
+
+    /// a board lost, or given up on by restarting it
+    pub fn lost(&mut self, now: Instant) {
+        self.losses += 1;
+        // a penalty reaching back before the clock's start ends the run there and then
+        self.ends_at = self.ends_at.checked_sub(self.penalty).unwrap_or(now);
+    }
+
+    pub fn cleared(&self) -> u32 {
+        self.cleared
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.result.is_some()
+    }
+
+    /// stops the run, `best` is the record it's up against
+    pub fn finish(&mut self, best: u32) {
+        self.result = Some(best);
+    }
+
+    /// the countdown in the counter's digits, with the tally beside it
+    pub fn banner<'a>(&self, texture: Texture, now: Instant) -> Element<'a, Message> {
+        // rounded up so it only reads 000 once time is actually up
+        let remaining = self.remaining(now).as_millis().div_ceil(1000);
+        container(row![
+            minsweeper::digit_display(texture, remaining as isize, 3, 1.0),
+            text(t!("blitz-cleared", count = self.cleared)),
+            text(t!("blitz-losses", count = self.losses)),
+        ].spacing(20).align_y(Vertical::Center))
+                .height(BANNER_HEIGHT)
+                .width(Length::Fill)
+                .align_x(Horizontal::Center)
+                .align_y(Vertical::Center)
+                .into()
+    }
+
+    pub fn dialog<'a>(&self, id: DialogId) -> Option<Element<'a, Message>> {
+        let best = self.result.filter(|_| id == DialogId::BlitzResults)?;
+        Some(widget::column![
+            text(t!("blitz-over")),
+            text(t!("blitz-cleared", count = self.cleared)),
+            text(t!("blitz-losses", count = self.losses)),
+            text(if self.cleared > best {
+                t!("blitz-new-best").to_string()
+            } else {
+                t!("blitz-best", count = best)
+            }),
+            row![
+                button(t!("close")).on_press(Message::Leave),
+                button(t!("blitz-again")).on_press(Message::Start),
+            ].spacing(10),
+        ].spacing(10).into())
+    }
+}
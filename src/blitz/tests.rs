@@ -0,0 +1,30 @@
+use super::*;
+
+const PENALTY: Duration = Duration::from_secs(10);
+
+#[test]
+fn a_loss_takes_the_penalty_off_the_clock() {
+    let now = Instant::now();
+    let mut blitz = Blitz::new(now, PENALTY);
+    blitz.lost(now);
+    assert_eq!(blitz.remaining(now), DURATION - PENALTY);
+    assert_eq!(blitz.losses, 1);
+}
+
+#[test]
+fn penalties_never_take_the_clock_below_zero() {
+    let now = Instant::now();
+    let mut blitz = Blitz::new(now, DURATION * 2);
+    blitz.lost(now);
+    assert!(blitz.remaining(now).is_zero());
+}
+
+#[test]
+fn a_pause_holds_the_countdown() {
+    let now = Instant::now();
+    let mut blitz = Blitz::new(now, PENALTY);
+    blitz.set_paused(true, now + Duration::from_secs(10));
+    assert_eq!(blitz.remaining(now + Duration::from_secs(100)), DURATION - Duration::from_secs(10));
+    blitz.set_paused(false, now + Duration::from_secs(100));
+    assert_eq!(blitz.remaining(now + Duration::from_secs(110)), DURATION - Duration::from_secs(20));
+}
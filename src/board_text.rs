@@ -0,0 +1,403 @@
+use crate::dialog::Flagged;
+use crate::minsweeper;
+use crate::privacy;
+use iced::widget::{button, pick_list, row, text};
+use iced::{widget, Task};
+use minsweeper_rs::board::{BoardSize, Point};
+use minsweeper_rs::{CellType, GameState};
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+#[cfg(test)]
+mod tests;
+
+/// two dialects scoring closer than this are both offered instead of picking one
+const AMBIGUITY: f32 = 0.1;
+
+/// the longest side a board read from a file may have, anything longer is a broken or hostile file
+/// and not worth allocating for
+pub const MAX_SIDE: usize = 10_000;
+
+/// where the mines are on a board, what every dialect reads and writes
+#[derive(Clone, Debug)]
+pub struct Layout {
+    pub size: BoardSize,
+    pub mines: Vec<Point>,
+}
+
+impl Layout {
+    pub fn from_gamestate(gamestate: &GameState) -> Self {
+        let size = gamestate.board.size();
+        Self {
+            size,
+            mines: size.points()
+                    .filter(|point| gamestate.board[*point].cell_type == CellType::Mine)
+                    .collect(),
+        }
+    }
+
+    pub fn new(width: usize, height: usize, mines: Vec<Point>) -> Result<Self, String> {
+        if width > MAX_SIDE || height > MAX_SIDE {
+            return Err(format!("a {}×{} board is bigger than {}×{}", width, height, MAX_SIDE, MAX_SIDE))
+        }
+        BoardSize::new(width, height, mines.len())
+                .map(|size| Self { size, mines })
+                .map_err(|e| e.to_string())
+    }
+
+    /// the board as a file has it, if it's one that could have been played: not too big, and with
+    /// every mine on the board and in a cell of its own. the size is checked before anything is
+    /// made that big
+    pub fn checked(width: usize, height: usize, mines: Vec<Point>) -> Option<Self> {
+        if width > MAX_SIDE || height > MAX_SIDE || mines.len() > width * height {
+            return None
+        }
+        let in_bounds = mines.iter().all(|(x, y)| *x < width && *y < height);
+        let unique = mines.iter().collect::<HashSet<_>>().len() == mines.len();
+        if !in_bounds || !unique {
+            return None
+        }
+        Self::new(width, height, mines).ok()
+    }
+}
+
+/// one way of writing a board down as text
+pub trait Dialect: Sync {
+    fn name(&self) -> &'static str;
+
+    /// how much the input looks like this dialect from 0 to 1, `None` if it can't be it at all
+    fn score(&self, input: &str) -> Option<f32>;
+
+    fn parse(&self, input: &str) -> Result<Layout, String>;
+
+    fn write(&self, layout: &Layout) -> String;
+}
+
+pub static DIALECTS: &[&dyn Dialect] = &[
+    &Grid { name: ". and *", safe: &['.'], mines: &['*'], numbered: false },
+    &Grid { name: "0-8 and M", safe: &['0', '1', '2', '3', '4', '5', '6', '7', '8'], mines: &['M'], numbered: true },
+    &Grid { name: "x and o", safe: &['o'], mines: &['x'], numbered: false },
+    &Coordinates,
+];
+
+pub fn dialect(name: &str) -> Option<&'static dyn Dialect> {
+    DIALECTS.iter()
+            .copied()
+            .find(|dialect| dialect.name() == name)
+}
+
+/// every dialect that could have written the input, most likely first
+pub fn detect(input: &str) -> Vec<(&'static dyn Dialect, f32)> {
+    let mut scores = DIALECTS.iter()
+            .filter_map(|dialect| dialect.score(input).map(|score| (*dialect, score)))
+            .collect::<Vec<_>>();
+    scores.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+    scores
+}
+
+/// the dialects worth offering for scores as [`detect`] sorts them, the best one and any that came
+/// too close to it to pick between
+fn candidates<'a>(scores: &[(&'a dyn Dialect, f32)]) -> Vec<&'a dyn Dialect> {
+    let Some((_, best)) = scores.first() else { return vec![] };
+    scores.iter()
+            .take_while(|(_, score)| best - score < AMBIGUITY)
+            .map(|(dialect, _)| *dialect)
+            .collect()
+}
+
+/// mines in a sensible share of the board, anything else is more likely a different dialect
+fn plausibility(mines: usize, cells: usize) -> f32 {
+    let density = mines as f32 / cells.max(1) as f32;
+    if mines == 0 {
+        0.2
+    } else if density <= 0.5 {
+        1.0
+    } else if density < 1.0 {
+        0.5
+    } else {
+        0.1
+    }
+}
+
+/// one character per cell, one line per row
+struct Grid {
+    name: &'static str,
+    safe: &'static [char],
+    mines: &'static [char],
+    /// safe cells are written as their number
+    numbered: bool,
+}
+
+impl Grid {
+    fn rows(input: &str) -> Vec<Vec<char>> {
+        input.lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(|line| line.chars().collect())
+                .collect()
+    }
+
+    /// every number written down as `(x, y, number, mines around it)`
+    fn numbers<'a>(&'a self, rows: &'a [Vec<char>]) -> impl Iterator<Item = (usize, usize, usize, usize)> + 'a {
+        let at = |x: usize, y: usize| rows.get(y).and_then(|row| row.get(x)).copied();
+        rows.iter()
+                .enumerate()
+                .flat_map(|(y, row)| row.iter().enumerate().map(move |(x, c)| (x, y, *c)))
+                .filter_map(|(x, y, c)| c.to_digit(10).map(|number| (x, y, number as usize)))
+                .map(move |(x, y, number)| {
+                    let around = (y.saturating_sub(1)..=y + 1)
+                            .flat_map(|ny| (x.saturating_sub(1)..=x + 1).map(move |nx| (nx, ny)))
+                            .filter(|&(nx, ny)| (nx, ny) != (x, y))
+                            .filter(|&(nx, ny)| at(nx, ny).is_some_and(|c| self.mines.contains(&c)))
+                            .count();
+                    (x, y, number, around)
+                })
+    }
+
+    fn mines(rows: &[Vec<char>], mines: &[char]) -> Vec<Point> {
+        rows.iter()
+                .enumerate()
+                .flat_map(|(y, row)| row.iter()
+                        .enumerate()
+                        .filter(|(_, c)| mines.contains(c))
+                        .map(move |(x, _)| (x, y)))
+                .collect()
+    }
+}
+
+impl Dialect for Grid {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn score(&self, input: &str) -> Option<f32> {
+        let rows = Self::rows(input);
+        let width = rows.first()?.len();
+        if !rows.iter().flatten().all(|c| self.safe.contains(c) || self.mines.contains(c)) {
+            return None
+        }
+
+        let consistency = rows.iter().filter(|row| row.len() == width).count() as f32 / rows.len() as f32;
+        let cells = rows.iter().map(Vec::len).sum();
+        let mines = Self::mines(&rows, self.mines);
+        let mut score = consistency * plausibility(mines.len(), cells);
+
+        // numbers that agree with the mines around them are a strong sign
+        if self.numbered {
+            let (agreeing, numbers) = self.numbers(&rows)
+                    .fold((0, 0), |(agreeing, numbers), (_, _, number, around)| {
+                        (agreeing + (around == number) as usize, numbers + 1)
+                    });
+            if numbers > 0 {
+                score *= 0.5 + 0.5 * agreeing as f32 / numbers as f32;
+            }
+        }
+
+        Some(score)
+    }
+
+    fn parse(&self, input: &str) -> Result<Layout, String> {
+        let rows = Self::rows(input);
+        let width = rows.first().ok_or("the file is empty")?.len();
+        for (y, row) in rows.iter().enumerate() {
+            if row.len() != width {
+                return Err(format!("line {} is {} cells long, the first one is {}", y + 1, row.len(), width))
+            }
+            if let Some(c) = row.iter().find(|c| !self.safe.contains(c) && !self.mines.contains(c)) {
+                return Err(format!("line {} has a '{}'", y + 1, c))
+            }
+        }
+        if self.numbered && let Some((x, y, number, around)) = self.numbers(&rows).find(|(_, _, number, around)| number != around) {
+            return Err(format!("line {} has a {} in column {} with {} mines around it", y + 1, number, x + 1, around))
+        }
+        Layout::new(width, rows.len(), Self::mines(&rows, self.mines))
+    }
+
+    fn write(&self, layout: &Layout) -> String {
+        let gamestate = minsweeper::layout_from_mines(layout.size, &layout.mines);
+        let mut out = String::new();
+        for y in 0..layout.size.height().get() {
+            for x in 0..layout.size.width().get() {
+                out.push(match gamestate.board[(x, y)].cell_type {
+                    CellType::Mine => self.mines[0],
+                    CellType::Safe(number) if self.numbered => char::from_digit(number as u32, 10).unwrap_or('0'),
+                    _ => self.safe[0],
+                });
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// `width,height` on the first line and then `x,y` for every mine
+struct Coordinates;
+
+impl Coordinates {
+    fn pairs(input: &str) -> Option<Vec<(usize, usize)>> {
+        input.lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(|line| {
+                    let (a, b) = line.split_once(',')?;
+                    Some((a.trim().parse().ok()?, b.trim().parse().ok()?))
+                })
+                .collect()
+    }
+}
+
+impl Dialect for Coordinates {
+    fn name(&self) -> &'static str {
+        "coordinates (CSV)"
+    }
+
+    fn score(&self, input: &str) -> Option<f32> {
+        let pairs = Self::pairs(input)?;
+        let (&(width, height), mines) = pairs.split_first()?;
+        if width > MAX_SIDE || height > MAX_SIDE {
+            return None
+        }
+        let cells = width.checked_mul(height)?;
+        let inside = mines.iter().filter(|(x, y)| *x < width && *y < height).count();
+        Some(inside as f32 / mines.len().max(1) as f32 * plausibility(mines.len(), cells))
+    }
+
+    fn parse(&self, input: &str) -> Result<Layout, String> {
+        let pairs = Self::pairs(input).ok_or("every line should be two numbers separated by a comma")?;
+        let (&(width, height), mines) = pairs.split_first().ok_or("the file is empty")?;
+        if let Some((x, y)) = mines.iter().find(|(x, y)| *x >= width || *y >= height) {
+            return Err(format!("the mine at {},{} is outside the {}×{} board", x, y, width, height))
+        }
+        let mut unique = mines.to_vec();
+        unique.sort_unstable();
+        unique.dedup();
+        Layout::new(width, height, unique)
+    }
+
+    fn write(&self, layout: &Layout) -> String {
+        let mut out = format!("{},{}\n", layout.size.width(), layout.size.height());
+        for (x, y) in &layout.mines {
+            out.push_str(&format!("{},{}\n", x, y));
+        }
+        out
+    }
+}
+
+/// input that more than one dialect fits, waiting for the user to say which
+#[derive(Debug)]
+struct Ambiguous {
+    input: String,
+    choices: Vec<&'static str>,
+    chosen: &'static str,
+}
+
+#[derive(Debug, Default)]
+pub struct BoardImporter {
+    ambiguous: Option<Ambiguous>,
+    error: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub enum Message {
+    Pick,
+    Open(Option<PathBuf>),
+    Read(Result<String, String>),
+    Choose(&'static str),
+    Confirm,
+    Cancel,
+    /// the board to play and the dialect it was read as
+    Loaded(Layout, &'static str),
+    DismissError,
+}
+
+impl BoardImporter {
+
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        match message {
+            Message::Pick => {
+                return Task::future(rfd::AsyncFileDialog::new()
+                        .add_filter("Board", &["txt", "csv"])
+                        .pick_file())
+                        .map(|handle| Message::Open(handle.map(|handle| handle.path().to_path_buf())))
+            }
+            Message::Open(None) => {}
+            Message::Open(Some(path)) => {
+                return Task::future(async move {
+                    tokio::task::spawn_blocking(move || std::fs::read_to_string(path)
+                            .map_err(|e| e.to_string()))
+                            .await
+                            .unwrap_or_else(|e| Err(e.to_string()))
+                }).map(Message::Read)
+            }
+            Message::Read(Err(e)) => self.error = Some(e),
+            Message::Read(Ok(input)) => {
+                match candidates(&detect(&input)).as_slice() {
+                    [] => self.error = Some("This doesn't look like any board format".to_string()),
+                    [only] => return self.load(*only, &input),
+                    choices => self.ambiguous = Some(Ambiguous {
+                        choices: choices.iter().map(|dialect| dialect.name()).collect(),
+                        chosen: choices[0].name(),
+                        input,
+                    }),
+                }
+            }
+            Message::Choose(name) => {
+                if let Some(ambiguous) = &mut self.ambiguous {
+                    ambiguous.chosen = name;
+                }
+            }
+            Message::Confirm => {
+                let Some(ambiguous) = self.ambiguous.take() else { return Task::none() };
+                if let Some(dialect) = dialect(ambiguous.chosen) {
+                    return self.load(dialect, &ambiguous.input)
+                }
+            }
+            Message::Cancel => self.ambiguous = None,
+            Message::Loaded(..) => {}
+            Message::DismissError => self.error = None,
+        }
+        Task::none()
+    }
+
+    fn load(&mut self, dialect: &'static dyn Dialect, input: &str) -> Task<Message> {
+        match dialect.parse(input) {
+            Ok(layout) => Task::done(Message::Loaded(layout, dialect.name())),
+            Err(e) => {
+                self.error = Some(e);
+                Task::none()
+            }
+        }
+    }
+
+    pub fn dialogs<'a>(&self) -> impl Iterator<Item = Flagged<'a, Message>> {
+        let mut vec = vec![];
+
+        if let Some(ambiguous) = &self.ambiguous {
+            vec.push(Flagged::new(
+                widget::column![
+                    text!("This board could be written in more than one way"),
+                    row![
+                        text!("Read it as "),
+                        pick_list(ambiguous.choices.clone(), Some(ambiguous.chosen), Message::Choose),
+                    ].align_y(iced::alignment::Vertical::Center),
+                    row![
+                        button("Cancel").on_press(Message::Cancel),
+                        button("Open").on_press(Message::Confirm),
+                    ].spacing(10),
+                ].spacing(10).into(),
+                Message::Cancel));
+        }
+
+        if let Some(error) = &self.error {
+            vec.push(Flagged::new(
+                widget::column![
+                    text!("Can't open board"),
+                    text(privacy::redact(error)),
+                    button("OK").on_press(Message::DismissError),
+                ].spacing(10).into(),
+                Message::DismissError));
+        }
+
+        vec.into_iter()
+    }
+}
@@ -0,0 +1,127 @@
+use super::*;
+
+fn coordinates(input: &str) -> Option<f32> {
+    dialect("coordinates (CSV)").unwrap().score(input)
+}
+
+#[test]
+fn a_huge_size_is_not_a_board() {
+    assert_eq!(coordinates(&format!("{},{}\n0,0", usize::MAX, usize::MAX)), None);
+    assert_eq!(coordinates(&format!("{},2\n0,0", MAX_SIDE + 1)), None);
+    assert!(dialect("coordinates (CSV)").unwrap().parse(&format!("{},{}\n0,0", usize::MAX, 2)).is_err());
+}
+
+#[test]
+fn sizes_up_to_the_limit_are_read() {
+    assert!(coordinates(&format!("{},{}\n0,0", MAX_SIDE, MAX_SIDE)).is_some_and(|score| score > 0.0));
+    let layout = dialect("coordinates (CSV)").unwrap().parse("9,9\n0,0\n8,8\n0,0").unwrap();
+    assert_eq!((layout.size.width().get(), layout.size.height().get()), (9, 9));
+    assert_eq!(layout.mines, [(0, 0), (8, 8)]);
+}
+
+#[test]
+fn layouts_are_bounded() {
+    assert!(Layout::new(MAX_SIDE + 1, 1, vec![]).is_err());
+    assert!(Layout::new(1, MAX_SIDE + 1, vec![]).is_err());
+}
+
+#[test]
+fn a_written_board_reads_back_in_every_dialect() {
+    let layout = Layout::new(4, 3, vec![(0, 0), (3, 2)]).unwrap();
+    for dialect in DIALECTS {
+        let read = dialect.parse(&dialect.write(&layout)).unwrap_or_else(|e| panic!("{}: {}", dialect.name(), e));
+        assert_eq!(read.size, layout.size, "{}", dialect.name());
+        assert_eq!(read.mines, layout.mines, "{}", dialect.name());
+    }
+}
+
+/// files every dialect should refuse, by what's wrong with them
+const MALFORMED: [(&str, &str, &str); 13] = [
+    (". and *", "ragged rows", include_str!("fixtures/dots_ragged.txt")),
+    (". and *", "an unknown glyph", include_str!("fixtures/dots_unknown.txt")),
+    (". and *", "nothing but mines", include_str!("fixtures/dots_all_mines.txt")),
+    ("0-8 and M", "ragged rows", include_str!("fixtures/numbers_ragged.txt")),
+    ("0-8 and M", "an unknown glyph", include_str!("fixtures/numbers_unknown.txt")),
+    ("0-8 and M", "a number the mines don't add up to", include_str!("fixtures/numbers_miscounted.txt")),
+    ("x and o", "ragged rows", include_str!("fixtures/crosses_ragged.txt")),
+    ("x and o", "an unknown glyph", include_str!("fixtures/crosses_unknown.txt")),
+    ("x and o", "nothing but mines", include_str!("fixtures/crosses_all_mines.txt")),
+    ("coordinates (CSV)", "a line that isn't a pair", include_str!("fixtures/coordinates_ragged.txt")),
+    ("coordinates (CSV)", "something that isn't a number", include_str!("fixtures/coordinates_unknown.txt")),
+    ("coordinates (CSV)", "nothing but mines", include_str!("fixtures/coordinates_all_mines.txt")),
+    ("coordinates (CSV)", "a mine outside the board", include_str!("fixtures/coordinates_outside.txt")),
+];
+
+#[test]
+fn malformed_files_are_refused() {
+    for (name, what, input) in MALFORMED {
+        assert!(dialect(name).unwrap().parse(input).is_err(), "{} with {}", name, what);
+    }
+}
+
+#[test]
+fn a_file_with_glyphs_a_dialect_doesnt_use_isnt_detected_as_it() {
+    for (name, _, input) in MALFORMED.iter().filter(|(_, what, _)| ["an unknown glyph", "something that isn't a number"].contains(what)) {
+        assert_eq!(dialect(name).unwrap().score(input), None, "{}", name);
+    }
+}
+
+#[test]
+fn a_malformed_file_isnt_taken_for_another_dialect() {
+    for (name, what, input) in MALFORMED {
+        let others = detect(input).into_iter()
+                .map(|(dialect, _)| dialect.name())
+                .filter(|other| *other != name)
+                .collect::<Vec<_>>();
+        assert_eq!(others, Vec::<&str>::new(), "{} with {}", name, what);
+    }
+}
+
+#[test]
+fn a_miscounted_number_says_where_it_is() {
+    let e = dialect("0-8 and M").unwrap().parse(include_str!("fixtures/numbers_miscounted.txt")).unwrap_err();
+    assert_eq!(e, "line 2 has a 2 in column 3 with 1 mines around it");
+}
+
+#[test]
+fn every_dialect_is_the_only_candidate_for_what_it_writes() {
+    let layout = Layout::new(4, 3, vec![(0, 0), (3, 2)]).unwrap();
+    for dialect in DIALECTS {
+        let candidates = candidates(&detect(&dialect.write(&layout))).into_iter()
+                .map(|candidate| candidate.name())
+                .collect::<Vec<_>>();
+        assert_eq!(candidates, [dialect.name()]);
+    }
+}
+
+/// two dialects that only differ in how they write mines, so a board without any reads as both
+static DOTS: Grid = Grid { name: "dots", safe: &['.'], mines: &['*'], numbered: false };
+static HASHES: Grid = Grid { name: "hashes", safe: &['.'], mines: &['#'], numbered: false };
+
+#[test]
+fn input_two_dialects_both_read_offers_both() {
+    let scores = [&DOTS as &dyn Dialect, &HASHES].into_iter()
+            .filter_map(|dialect| dialect.score("....\n....").map(|score| (dialect, score)))
+            .collect::<Vec<_>>();
+    let names = candidates(&scores).into_iter().map(|dialect| dialect.name()).collect::<Vec<_>>();
+    assert_eq!(names, ["dots", "hashes"]);
+}
+
+#[test]
+fn only_dialects_close_to_the_best_are_offered() {
+    let scores = [(&DOTS as &dyn Dialect, 0.9), (&HASHES, 0.85), (DIALECTS[0], 0.5)];
+    let names = candidates(&scores).into_iter().map(|dialect| dialect.name()).collect::<Vec<_>>();
+    assert_eq!(names, ["dots", "hashes"]);
+
+    let scores = [(&DOTS as &dyn Dialect, 0.9), (&HASHES, 0.7)];
+    assert_eq!(candidates(&scores).len(), 1);
+    assert!(candidates(&[]).is_empty());
+}
+
+#[test]
+fn a_file_no_dialect_reads_is_an_error() {
+    let mut importer = BoardImporter::default();
+    let _ = importer.update(Message::Read(Ok("nothing like a board".to_string())));
+    assert!(importer.error.is_some());
+    assert!(importer.ambiguous.is_none());
+}
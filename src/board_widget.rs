@@ -0,0 +1,312 @@
+use crate::minsweeper::tasks::Tasks;
+use crate::minsweeper::{Click, Game, MouseAction, MouseBindings, Provenance, SolverType};
+use crate::texture::Texture;
+use iced::widget::{column, mouse_area, row, svg};
+use iced::{event, mouse, Element, Event, Subscription, Task};
+use minsweeper_rs::board::{Board, BoardSize, Point};
+use minsweeper_rs::solver::start::SafeStart;
+use minsweeper_rs::solver::{Action, Operation};
+use minsweeper_rs::{Cell, CellState, CellType, GameState, GameStatus};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+// the board alone for embedding in another iced app. it's built on the game engine and the
+// textures only, the app's menus, counters, dialogs, stats, journal, settings and windows never
+// come into it
+
+/// what the solver does for the player
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum Assist {
+    /// every move is the player's
+    #[default]
+    Off,
+    /// [`Message::Step`] plays the solver's next move
+    Steps,
+    /// the solver plays every move it's sure of after each of the player's
+    Auto,
+}
+
+/// how the board looks and plays
+#[derive(Copy, Clone, Debug)]
+pub struct BoardConfig {
+    pub texture: Texture,
+    /// which buttons reveal, flag and chord
+    pub bindings: MouseBindings,
+    pub assist: Assist,
+    /// in pixels
+    pub cell_size: f32,
+}
+
+impl Default for BoardConfig {
+    fn default() -> Self {
+        Self {
+            texture: Texture::default(),
+            bindings: MouseBindings::default(),
+            assist: Assist::Off,
+            cell_size: 24.0,
+        }
+    }
+}
+
+/// what happened on the board, in the order it did
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum BoardEvent {
+    /// a new game is ready, the first one as well as every restart
+    Started,
+    /// a left click opened this many cells, a chord's included
+    Revealed { point: Point, by: Provenance, cells: usize },
+    Flagged { point: Point, by: Provenance },
+    Unflagged { point: Point, by: Provenance },
+    Won,
+    Lost,
+}
+
+#[derive(Clone, Debug)]
+pub enum Message {
+    Press(Point, mouse::Button),
+    Release(Point, mouse::Button),
+    /// a button was let go anywhere, off the board too
+    Released,
+    Restart,
+    /// asks the solver for a move and plays it, with [`Assist::Steps`] or [`Assist::Auto`]
+    Step,
+    /// the game with this generation is ready
+    Started(u64, GameState),
+    /// clicks landed on the game with this generation
+    Applied(u64, Vec<Applied>),
+    /// the solver's move for the game with this generation
+    Solved(u64, Option<Vec<Action>>),
+}
+
+/// a click that went in, `gamestate` is `None` if it changed nothing
+#[derive(Clone, Debug)]
+pub struct Applied {
+    click: Click,
+    by: Provenance,
+    /// cells revealed before it
+    before: usize,
+    gamestate: Option<GameState>,
+}
+
+pub struct BoardWidget {
+    game: Arc<Game>,
+    /// played again on every restart, `None` generates a new board each time
+    layout: Option<GameState>,
+    solver: Option<SolverType>,
+    config: BoardConfig,
+    gamestate: GameState,
+    /// bumped by every restart, anything from the games before it is dropped
+    generation: u64,
+    tasks: Tasks,
+    /// solver moves played so far
+    steps: u32,
+    pressed: Option<Point>,
+    events: mpsc::UnboundedSender<BoardEvent>,
+}
+
+impl BoardWidget {
+    /// a board of `size` with a new layout every game, the first click is always safe. the task
+    /// starts the first game
+    pub fn new(size: BoardSize, solver: Option<SolverType>, config: BoardConfig) -> (Self, Task<Message>) {
+        Self::start(size, None, solver, config)
+    }
+
+    /// a board that's always `layout`, mines and all
+    pub fn with_layout(layout: GameState, solver: Option<SolverType>, config: BoardConfig) -> (Self, Task<Message>) {
+        Self::start(layout.board.size(), Some(layout), solver, config)
+    }
+
+    fn start(size: BoardSize, layout: Option<GameState>, solver: Option<SolverType>, config: BoardConfig) -> (Self, Task<Message>) {
+        let mut widget = Self {
+            game: Arc::new(Game::new(size)),
+            layout,
+            solver,
+            config,
+            gamestate: GameState::new(GameStatus::Never, Board::new(size, Cell::new(CellType::Unknown, CellState::Unknown)), 0),
+            generation: 0,
+            tasks: Tasks::default(),
+            steps: 0,
+            pressed: None,
+            events: mpsc::unbounded_channel().0,
+        };
+        let task = widget.restart();
+        (widget, task)
+    }
+
+    /// everything that happens on the board from now on, a receiver handed out before stops
+    /// getting anything
+    pub fn events(&mut self) -> mpsc::UnboundedReceiver<BoardEvent> {
+        let (events, receiver) = mpsc::unbounded_channel();
+        self.events = events;
+        receiver
+    }
+
+    pub fn gamestate(&self) -> &GameState {
+        &self.gamestate
+    }
+
+    pub fn status(&self) -> GameStatus {
+        self.gamestate.status
+    }
+
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        match message {
+            Message::Press(point, button) => {
+                self.pressed = Some(point);
+                if self.config.bindings.action(button) == Some(MouseAction::Flag) {
+                    return self.apply(vec![(Click::Right(point), Provenance::Player)])
+                }
+            }
+            Message::Release(point, button) => {
+                self.pressed = None;
+                let revealed = self.gamestate.board[point].cell_state == CellState::Revealed;
+                match self.config.bindings.action(button) {
+                    Some(MouseAction::Reveal) => return self.apply(vec![(Click::Left(point), Provenance::Player)]),
+                    // only chords, a hidden cell is left alone
+                    Some(MouseAction::Chord) if revealed => return self.apply(vec![(Click::Left(point), Provenance::Player)]),
+                    _ => {}
+                }
+            }
+            Message::Released => self.pressed = None,
+            Message::Restart => return self.restart(),
+            Message::Step if self.config.assist != Assist::Off => return self.ask(),
+            Message::Step => {}
+            Message::Started(generation, gamestate) => {
+                if generation == self.generation {
+                    self.gamestate = gamestate;
+                    self.send(BoardEvent::Started);
+                }
+            }
+            Message::Applied(generation, applied) => {
+                if generation == self.generation {
+                    return self.applied(applied)
+                }
+            }
+            Message::Solved(generation, Some(actions)) => {
+                if generation == self.generation {
+                    self.steps += 1;
+                    let by = Provenance::Solver(self.steps);
+                    return self.apply(actions.into_iter()
+                            .map(|action| (match action.operation {
+                                Operation::Reveal | Operation::Chord => Click::Left(action.point),
+                                Operation::Flag => Click::Right(action.point),
+                            }, by))
+                            .collect())
+                }
+            }
+            Message::Solved(_, None) => {}
+        }
+        Task::none()
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let size = self.gamestate.board.size();
+        let playing = self.gamestate.status == GameStatus::Playing;
+        column((0..size.height().get()).map(|y| row((0..size.width().get()).map(|x| {
+            let point = (x, y);
+            let down = playing && self.pressed == Some(point);
+            mouse_area(svg(self.config.texture.get_cell_asset(self.gamestate.board[point], down))
+                    .width(self.config.cell_size)
+                    .height(self.config.cell_size))
+                    .on_press(Message::Press(point, mouse::Button::Left))
+                    .on_middle_press(Message::Press(point, mouse::Button::Middle))
+                    .on_right_press(Message::Press(point, mouse::Button::Right))
+                    .on_release(Message::Release(point, mouse::Button::Left))
+                    .on_middle_release(Message::Release(point, mouse::Button::Middle))
+                    .on_right_release(Message::Release(point, mouse::Button::Right))
+                    .into()
+        })).into()))
+                .into()
+    }
+
+    /// lets go of a cell that was pressed and then dragged off the board
+    pub fn subscription(&self) -> Subscription<Message> {
+        event::listen_with(|event, _, _| match event {
+            Event::Mouse(mouse::Event::ButtonReleased(_)) => Some(Message::Released),
+            _ => None,
+        })
+    }
+
+    fn restart(&mut self) -> Task<Message> {
+        self.tasks.cancel_all();
+        self.generation += 1;
+        self.steps = 0;
+        self.pressed = None;
+        let generation = self.generation;
+        let game = self.game.clone();
+        let layout = self.layout.clone();
+        Task::future(async move {
+            match layout {
+                Some(layout) => game.start_with_layout(layout, 0).await,
+                None => game.start_with_solver(Arc::new(SafeStart), rand::random()).await,
+            }
+        }).map(move |gamestate| Message::Started(generation, gamestate))
+    }
+
+    /// plays the clicks one after the other
+    fn apply(&self, clicks: Vec<(Click, Provenance)>) -> Task<Message> {
+        let game = self.game.clone();
+        let generation = self.generation;
+        Task::future(self.tasks.run(async move {
+            let mut applied = vec![];
+            for (click, by) in clicks {
+                let before = revealed(&game.gamestate().await);
+                let result = match (click, by) {
+                    // the solver only ever means to put a flag down
+                    (Click::Right(point), Provenance::Solver(_)) => game.flag(point, by).await,
+                    _ => game.click(click, by).await,
+                };
+                applied.push(Applied { click, by, before, gamestate: result.ok() });
+            }
+            applied
+        })).and_then(move |applied| Task::done(Message::Applied(generation, applied)))
+    }
+
+    fn applied(&mut self, applied: Vec<Applied>) -> Task<Message> {
+        let mut changed = false;
+        for Applied { click, by, before, gamestate } in applied {
+            let Some(gamestate) = gamestate else { continue };
+            self.send(match click {
+                Click::Left(point) => BoardEvent::Revealed { point, by, cells: revealed(&gamestate).saturating_sub(before) },
+                Click::Right(point) if gamestate.board[point].cell_state == CellState::Flagged => BoardEvent::Flagged { point, by },
+                Click::Right(point) => BoardEvent::Unflagged { point, by },
+            });
+            match (self.gamestate.status, gamestate.status) {
+                (GameStatus::Playing, GameStatus::Won) => self.send(BoardEvent::Won),
+                (GameStatus::Playing, GameStatus::Lost) => self.send(BoardEvent::Lost),
+                _ => {}
+            }
+            self.gamestate = gamestate;
+            changed = true;
+        }
+        // auto goes on for as long as the solver's moves change something
+        if changed && self.config.assist == Assist::Auto {
+            return self.ask()
+        }
+        Task::none()
+    }
+
+    fn ask(&self) -> Task<Message> {
+        let Some(solver) = self.solver.clone() else { return Task::none() };
+        if self.gamestate.status != GameStatus::Playing {
+            return Task::none()
+        }
+        let gamestate = self.gamestate.clone();
+        let generation = self.generation;
+        // external solvers take their time
+        Task::future(self.tasks.run(tokio::task::spawn_blocking(move || solver.solve(&gamestate)
+                .map(|found| found.actions.into_iter().collect::<Vec<_>>()))))
+                .and_then(move |found| Task::done(Message::Solved(generation, found.ok().flatten())))
+    }
+
+    fn send(&self, event: BoardEvent) {
+        // nobody listening is fine
+        let _ = self.events.send(event);
+    }
+}
+
+fn revealed(gamestate: &GameState) -> usize {
+    gamestate.board.size().points()
+            .filter(|point| gamestate.board[*point].cell_state == CellState::Revealed)
+            .count()
+}
@@ -0,0 +1,108 @@
+use futures_util::future::BoxFuture;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+#[cfg(test)]
+mod tests;
+
+/// the longest a running game goes without reading the clock, its timer ticks every second. a
+/// longer gap between two readings is the machine having been asleep
+const SUSPEND_GAP: Duration = Duration::from_secs(5);
+
+/// where every timing feature gets the time from, so they all agree on it and something else can
+/// stand in for the real one
+pub trait Clock: Debug + Send + Sync {
+    fn now(&self) -> Instant;
+
+    /// finishes once `duration` has passed on this clock
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()>;
+
+    fn elapsed(&self, since: Instant) -> Duration {
+        self.now().saturating_duration_since(since)
+    }
+}
+
+pub type SharedClock = Arc<dyn Clock>;
+
+/// the real time less however long the machine was suspended, [`Instant`] leaves a suspend out
+/// on some platforms and not on others
+#[derive(Debug)]
+pub struct SystemClock {
+    suspensions: Mutex<Suspensions>,
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self { suspensions: Mutex::new(Suspensions::new(Instant::now())) }
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        let real = Instant::now();
+        self.suspensions.lock()
+                .map_or(real, |mut suspensions| suspensions.observe(real))
+    }
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
+
+/// how much of the real time has gone by while suspended, as far as gaps between readings tell
+#[derive(Debug)]
+struct Suspensions {
+    last: Instant,
+    skipped: Duration,
+}
+
+impl Suspensions {
+    fn new(now: Instant) -> Self {
+        Self { last: now, skipped: Duration::ZERO }
+    }
+
+    /// `real` with the suspended time taken out. a gap only counts up to [`SUSPEND_GAP`], so a
+    /// reading after a long while with nothing running still moves on by that much
+    fn observe(&mut self, real: Instant) -> Instant {
+        let gap = real.saturating_duration_since(self.last);
+        if gap > SUSPEND_GAP {
+            self.skipped += gap - SUSPEND_GAP;
+        }
+        self.last = self.last.max(real);
+        real.checked_sub(self.skipped).unwrap_or(real)
+    }
+}
+
+pub fn system() -> SharedClock {
+    Arc::new(SystemClock::default())
+}
+
+/// time that only moves when a test moves it, every sleep is over as soon as it starts
+#[cfg(test)]
+#[derive(Debug)]
+pub(crate) struct TestClock {
+    now: Mutex<Instant>,
+}
+
+#[cfg(test)]
+impl TestClock {
+    pub fn new() -> Self {
+        Self { now: Mutex::new(Instant::now()) }
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        *self.now.lock().unwrap() += duration;
+    }
+}
+
+#[cfg(test)]
+impl Clock for TestClock {
+    fn now(&self) -> Instant {
+        *self.now.lock().unwrap()
+    }
+
+    fn sleep(&self, _duration: Duration) -> BoxFuture<'static, ()> {
+        Box::pin(std::future::ready(()))
+    }
+}
@@ -0,0 +1,31 @@
+use super::*;
+
+#[test]
+fn readings_close_together_follow_the_real_time() {
+    let start = Instant::now();
+    let mut suspensions = Suspensions::new(start);
+    for second in 1..=10 {
+        let real = start + Duration::from_secs(second);
+        assert_eq!(suspensions.observe(real), real);
+    }
+}
+
+#[test]
+fn a_suspend_is_left_out_from_then_on() {
+    let start = Instant::now();
+    let mut suspensions = Suspensions::new(start);
+    suspensions.observe(start + Duration::from_secs(1));
+    let woke = start + Duration::from_secs(3600);
+    let seen = suspensions.observe(woke);
+    assert_eq!(seen.duration_since(start), Duration::from_secs(1) + SUSPEND_GAP);
+    assert_eq!(suspensions.observe(woke + Duration::from_secs(1)), seen + Duration::from_secs(1));
+}
+
+#[test]
+fn the_test_clock_only_moves_when_told() {
+    let clock = TestClock::new();
+    let start = clock.now();
+    assert_eq!(clock.now(), start);
+    clock.advance(Duration::from_secs(3));
+    assert_eq!(clock.elapsed(start), Duration::from_secs(3));
+}
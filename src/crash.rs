@@ -0,0 +1,113 @@
+use crate::journal::Recovered;
+use crate::minsweeper::{Click, History, Provenance};
+use crate::{paths, settings_menu};
+use minsweeper_rs::board::{BoardSize, Point};
+use minsweeper_rs::CellType;
+use serde::{Deserialize, Serialize};
+use std::backtrace::Backtrace;
+use std::io;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// reports that were already shown get this extension instead of json
+const SEEN: &str = "seen";
+
+/// the game being played as of the last repaint, kept ready so the panic hook never has to reach
+/// into the game itself and its locks
+static GAME: Mutex<Option<GameSnapshot>> = Mutex::new(None);
+
+/// what the journal would replay, without the journal's framing
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct GameSnapshot {
+    width: usize,
+    height: usize,
+    mines: Vec<Point>,
+    seed: u64,
+    clicks: Vec<(Click, Provenance)>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CrashFile {
+    report: String,
+    game: Option<GameSnapshot>,
+}
+
+/// a crash from a previous run, to be shown once
+#[derive(Debug)]
+pub struct CrashReport {
+    pub report: String,
+    pub game: Option<Recovered>,
+}
+
+/// keeps `history` for a report, `None` once there's no game in progress anymore
+pub fn remember(history: Option<&History>) {
+    let snapshot = history.map(|history| {
+        let size = history.layout.board.size();
+        GameSnapshot {
+            width: size.width().get(),
+            height: size.height().get(),
+            mines: size.points()
+                    .filter(|point| history.layout.board[*point].cell_type == CellType::Mine)
+                    .collect(),
+            seed: history.seed,
+            clicks: history.clicks.clone(),
+        }
+    });
+    if let Ok(mut game) = GAME.lock() {
+        *game = snapshot;
+    }
+}
+
+/// writes a report for any panic before the default hook prints it
+pub fn install() {
+    let default = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        // the panic could have happened anywhere, holding anything, so nothing here waits on a lock
+        let game = GAME.try_lock().ok().and_then(|game| game.clone());
+        settings_menu::save_unsaved();
+        let report = format!("{}\n\n{}", info, Backtrace::force_capture());
+        if let Err(e) = write(CrashFile { report, game }) {
+            eprintln!("failed to write a crash report: {}", e);
+        }
+        default(info);
+    }));
+}
+
+fn write(crash: CrashFile) -> io::Result<()> {
+    let folder = paths::crashes_dir()?;
+    let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+    let json = serde_json::to_string(&crash)?;
+    std::fs::write(folder.join(format!("crash-{}.json", timestamp)), json)
+}
+
+/// the newest report that hasn't been shown yet, every unseen report is marked seen
+pub fn take_report() -> Option<CrashReport> {
+    let mut unseen = std::fs::read_dir(paths::crashes_dir().ok()?).ok()?
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|extension| extension == "json"))
+            .collect::<Vec<_>>();
+    // the timestamps are all as long for a good while yet
+    unseen.sort();
+    for path in &unseen {
+        if let Err(e) = std::fs::rename(path, path.with_extension(SEEN)) {
+            tracing::warn!("failed to mark {} as seen: {}", path.display(), e);
+        }
+    }
+
+    let newest = unseen.last()?.with_extension(SEEN);
+    let crash = std::fs::read(&newest)
+            .and_then(|bytes| serde_json::from_slice::<CrashFile>(&bytes).map_err(io::Error::other))
+            .inspect_err(|e| tracing::warn!("failed to read {}: {}", newest.display(), e))
+            .ok()?;
+    let game = crash.game.and_then(|game| Some(Recovered {
+        size: BoardSize::new(game.width, game.height, game.mines.len()).ok()?,
+        mines: game.mines,
+        seed: game.seed,
+        clicks: game.clicks,
+    }));
+    Some(CrashReport { report: crash.report, game })
+}
@@ -0,0 +1,157 @@
+use iced::Element;
+
+#[cfg(test)]
+mod tests;
+
+/// dialogs that are opened and closed by name rather than by whatever flags happen to be set
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum DialogId {
+    Resume,
+    CustomSize,
+    Seed,
+    GameOver,
+    MouseBindings,
+    SolverChain,
+    ExternalSolver,
+    /// an external solver failed mid game
+    ExternalSolverFailed,
+    /// naming a board from the editor to keep
+    SaveBoard,
+    /// picking a kept board to edit
+    LoadBoard,
+    /// how a blitz went, once its time is up
+    BlitzResults,
+    /// a forgiving game was just lost, it can be undone or accepted
+    Undo,
+    /// a reveal on a certain mine is waiting to be confirmed
+    MistakeGuard,
+    /// the end of the log file
+    Logs,
+    /// the app crashed last time, with the report and the game it was in
+    Crash,
+    /// every keyboard shortcut, from the same table the keys go by
+    Shortcuts,
+}
+
+impl DialogId {
+    pub fn priority(self) -> Priority {
+        match self {
+            DialogId::Resume | DialogId::Crash | DialogId::CustomSize | DialogId::Seed | DialogId::MouseBindings
+                    | DialogId::SolverChain | DialogId::ExternalSolver | DialogId::ExternalSolverFailed
+                    | DialogId::SaveBoard | DialogId::LoadBoard | DialogId::Logs => Priority::Blocking,
+            DialogId::BlitzResults | DialogId::Undo | DialogId::MistakeGuard => Priority::Blocking,
+            // only a summary, a new game can be started from the keyboard right through it
+            // and the shortcuts can be tried out while they're listed
+            DialogId::GameOver | DialogId::Shortcuts => Priority::Passive,
+        }
+    }
+
+    pub fn close_policy(self) -> ClosePolicy {
+        match self {
+            // the journal is only thrown away when the user says so
+            // the board underneath doesn't take input anymore, the dialog is the way out
+            // a loss has to be either undone or accepted
+            // and a guarded reveal is counted by which way it went
+            DialogId::Resume | DialogId::Crash | DialogId::BlitzResults | DialogId::Undo | DialogId::MistakeGuard => ClosePolicy::Explicit,
+            DialogId::CustomSize | DialogId::Seed | DialogId::GameOver | DialogId::MouseBindings
+                    | DialogId::SolverChain | DialogId::ExternalSolver | DialogId::ExternalSolverFailed
+                    | DialogId::SaveBoard | DialogId::LoadBoard | DialogId::Logs | DialogId::Shortcuts => ClosePolicy::Dismissable,
+        }
+    }
+}
+
+/// blocking dialogs always sit above passive ones, whichever opened last
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Priority {
+    /// keyboard shortcuts still reach the game underneath
+    Passive,
+    Blocking,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ClosePolicy {
+    /// escape and clicking outside close it
+    Dismissable,
+    /// only its own buttons close it
+    Explicit,
+}
+
+#[derive(Clone, Debug)]
+pub enum Message {
+    Open(DialogId),
+    Close(DialogId),
+    /// escape or a click outside, meant for whatever is on top
+    Dismiss,
+}
+
+#[derive(Debug)]
+struct Entry {
+    id: DialogId,
+    opened: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct DialogStack {
+    entries: Vec<Entry>,
+    opened: u64,
+}
+
+impl DialogStack {
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::Open(id) => self.open(id),
+            Message::Close(id) => self.close(id),
+            Message::Dismiss => {
+                if let Some(id) = self.topmost() && id.close_policy() == ClosePolicy::Dismissable {
+                    self.close(id);
+                }
+            }
+        }
+    }
+
+    /// opening one that's already open brings it to the front of its priority
+    pub fn open(&mut self, id: DialogId) {
+        self.entries.retain(|entry| entry.id != id);
+        self.opened += 1;
+        self.entries.push(Entry { id, opened: self.opened });
+        self.entries.sort_by_key(|entry| (entry.id.priority(), entry.opened));
+    }
+
+    pub fn close(&mut self, id: DialogId) {
+        self.entries.retain(|entry| entry.id != id);
+    }
+
+    /// bottom to top
+    pub fn ordered(&self) -> impl Iterator<Item = DialogId> + '_ {
+        self.entries.iter().map(|entry| entry.id)
+    }
+
+    pub fn topmost(&self) -> Option<DialogId> {
+        self.entries.last().map(|entry| entry.id)
+    }
+
+    /// whether input meant for the game should be held back
+    pub fn blocking(&self) -> bool {
+        self.topmost().is_some_and(|id| id.priority() == Priority::Blocking)
+    }
+}
+
+/// a dialog that's up for as long as some flag of its owner is set, rather than being on the
+/// stack, with what closes it for when escape is pressed over it
+pub struct Flagged<'a, M> {
+    pub content: Element<'a, M>,
+    pub close: M,
+}
+
+impl<'a, M: 'a> Flagged<'a, M> {
+    pub fn new(content: Element<'a, M>, close: M) -> Self {
+        Self { content, close }
+    }
+
+    pub fn map<N: 'a>(self, f: impl Fn(M) -> N + 'a) -> Flagged<'a, N> {
+        Flagged {
+            close: f(self.close),
+            content: self.content.map(f),
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use super::*;
+
+fn stack(ids: &[DialogId]) -> DialogStack {
+    let mut stack = DialogStack::default();
+    for id in ids {
+        stack.open(*id);
+    }
+    stack
+}
+
+fn ordered(stack: &DialogStack) -> Vec<DialogId> {
+    stack.ordered().collect()
+}
+
+#[test]
+fn the_last_opened_goes_on_top() {
+    let stack = stack(&[DialogId::Seed, DialogId::CustomSize]);
+    assert_eq!(ordered(&stack), [DialogId::Seed, DialogId::CustomSize]);
+    assert_eq!(stack.topmost(), Some(DialogId::CustomSize));
+}
+
+#[test]
+fn blocking_dialogs_stay_above_passive_ones() {
+    let stack = stack(&[DialogId::Undo, DialogId::GameOver]);
+    assert_eq!(ordered(&stack), [DialogId::GameOver, DialogId::Undo]);
+    assert!(stack.blocking());
+}
+
+#[test]
+fn only_a_passive_dialog_lets_the_game_have_its_keys() {
+    let mut stack = stack(&[DialogId::GameOver, DialogId::Shortcuts]);
+    assert!(!stack.blocking());
+    stack.open(DialogId::Seed);
+    assert!(stack.blocking());
+    stack.close(DialogId::Seed);
+    assert!(!stack.blocking());
+    assert!(!DialogStack::default().blocking());
+}
+
+#[test]
+fn opening_again_brings_it_to_the_front_once() {
+    let mut stack = stack(&[DialogId::Seed, DialogId::CustomSize]);
+    stack.open(DialogId::Seed);
+    assert_eq!(ordered(&stack), [DialogId::CustomSize, DialogId::Seed]);
+}
+
+#[test]
+fn dismissing_closes_only_the_top_and_only_if_it_allows() {
+    let mut dismissable = stack(&[DialogId::Seed, DialogId::CustomSize]);
+    dismissable.update(Message::Dismiss);
+    assert_eq!(ordered(&dismissable), [DialogId::Seed]);
+
+    let mut explicit = stack(&[DialogId::Seed, DialogId::Undo]);
+    explicit.update(Message::Dismiss);
+    assert_eq!(ordered(&explicit), [DialogId::Seed, DialogId::Undo], "a loss has to be settled first");
+    explicit.update(Message::Close(DialogId::Undo));
+    explicit.update(Message::Dismiss);
+    assert_eq!(explicit.topmost(), None);
+}
+
+#[test]
+fn closing_one_that_is_not_open_does_nothing() {
+    let mut stack = stack(&[DialogId::Seed]);
+    stack.update(Message::Close(DialogId::Logs));
+    assert_eq!(ordered(&stack), [DialogId::Seed]);
+}
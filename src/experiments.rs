@@ -0,0 +1,103 @@
+use serde::de::IgnoredAny;
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Debug, Formatter};
+
+#[cfg(test)]
+mod tests;
+
+/// something unfinished that stays out of the menus until it's turned on in the experiments panel
+#[derive(Copy, Clone)]
+pub struct Experiment {
+    /// what it's stored under in settings, never reuse one
+    pub id: &'static str,
+    pub label: &'static str,
+    pub description: &'static str,
+    pub stability: &'static str,
+    /// asked before turning it off, for experiments that lose something when torn down
+    pub confirm_disable: Option<&'static str>,
+}
+
+impl Debug for Experiment {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.id)
+    }
+}
+
+impl PartialEq for Experiment {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Experiment {}
+
+pub const LATENCY_OVERLAY: Experiment = Experiment {
+    id: "latency_overlay",
+    label: "Latency overlay",
+    description: "Shows how long clicks take to reach the screen, toggled with F12 or from the View menu",
+    stability: "for debugging, numbers are approximate",
+    confirm_disable: Some("The latency measured so far will be discarded."),
+};
+
+pub const CANVAS_BOARD: Experiment = Experiment {
+    id: "canvas_board",
+    label: "Canvas board everywhere",
+    description: "Draws every board with the renderer otherwise only used for very large ones",
+    stability: "may look slightly different from the regular board",
+    confirm_disable: None,
+};
+
+pub const ALL: &[Experiment] = &[LATENCY_OVERLAY, CANVAS_BOARD];
+
+/// the experiments that are turned on, saved as `{ id: { "enabled": true } }` so each one has its
+/// own namespace, ids that aren't in [`ALL`] anymore are dropped when loading
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Experiments(BTreeSet<&'static str>);
+
+impl Experiments {
+    pub fn enabled(&self, experiment: Experiment) -> bool {
+        self.0.contains(experiment.id)
+    }
+
+    pub fn set(&mut self, experiment: Experiment, enabled: bool) {
+        if enabled {
+            self.0.insert(experiment.id);
+        } else {
+            self.0.remove(experiment.id);
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct State {
+    enabled: bool,
+}
+
+impl Serialize for Experiments {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for id in &self.0 {
+            map.serialize_entry(id, &State { enabled: true })?;
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Experiments {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Entry {
+            State(State),
+            Unknown(IgnoredAny),
+        }
+
+        let entries = BTreeMap::<String, Entry>::deserialize(deserializer)?;
+        Ok(Experiments(ALL.iter()
+                .filter(|experiment| matches!(entries.get(experiment.id), Some(Entry::State(State { enabled: true }))))
+                .map(|experiment| experiment.id)
+                .collect()))
+    }
+}
@@ -0,0 +1,51 @@
+use super::*;
+use serde_json::json;
+
+fn parse(value: serde_json::Value) -> Experiments {
+    serde_json::from_value(value).unwrap()
+}
+
+#[test]
+fn ids_are_unique() {
+    let ids = ALL.iter().map(|experiment| experiment.id).collect::<BTreeSet<_>>();
+    assert_eq!(ids.len(), ALL.len());
+}
+
+#[test]
+fn enabled_experiments_round_trip() {
+    let mut experiments = Experiments::default();
+    experiments.set(CANVAS_BOARD, true);
+
+    let value = serde_json::to_value(&experiments).unwrap();
+    assert_eq!(value, json!({ "canvas_board": { "enabled": true } }));
+    assert_eq!(parse(value), experiments);
+}
+
+#[test]
+fn turning_one_off_forgets_it() {
+    let mut experiments = Experiments::default();
+    experiments.set(LATENCY_OVERLAY, true);
+    experiments.set(CANVAS_BOARD, true);
+    experiments.set(LATENCY_OVERLAY, false);
+
+    assert!(!experiments.enabled(LATENCY_OVERLAY));
+    assert!(experiments.enabled(CANVAS_BOARD));
+    assert_eq!(serde_json::to_value(&experiments).unwrap(), json!({ "canvas_board": { "enabled": true } }));
+}
+
+#[test]
+fn unknown_disabled_and_malformed_entries_are_dropped() {
+    let experiments = parse(json!({
+        "gone_experiment": { "enabled": true },
+        "latency_overlay": { "enabled": false },
+        "canvas_board": "yes",
+    }));
+    assert_eq!(experiments, Experiments::default());
+
+    let experiments = parse(json!({
+        "gone_experiment": { "enabled": true, "level": 3 },
+        "latency_overlay": { "enabled": true, "added_later": [] },
+    }));
+    assert!(experiments.enabled(LATENCY_OVERLAY));
+    assert!(!experiments.enabled(CANVAS_BOARD));
+}
@@ -0,0 +1,243 @@
+use crate::dialog::Flagged;
+use iced::widget::{button, container, row, text};
+use iced::{widget, Element, Task};
+use iced_core::alignment::Vertical;
+use std::fmt::{Debug, Formatter};
+use std::fs::File;
+use std::io;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+#[cfg(test)]
+mod tests;
+
+const TOAST_DURATION: Duration = Duration::from_secs(6);
+
+// the windows and macos save panels always ask before returning an existing file, whether the
+// linux portals do depends on the desktop so there we ask ourselves
+const DIALOG_CONFIRMS_OVERWRITE: bool = cfg!(any(target_os = "windows", target_os = "macos"));
+
+type Writer = Box<dyn FnOnce(&mut dyn Write) -> io::Result<()> + Send>;
+
+/// something to write to a file the user picks, every exporter goes through this so none of them
+/// can clobber an existing file without asking or leave half a file behind
+pub struct Request {
+    name: String,
+    filter: (&'static str, &'static [&'static str]),
+    write: Writer,
+}
+
+impl Request {
+    pub fn new(name: impl Into<String>, filter: (&'static str, &'static [&'static str]),
+               write: impl FnOnce(&mut dyn Write) -> io::Result<()> + Send + 'static) -> Self {
+        Self { name: name.into(), filter, write: Box::new(write) }
+    }
+}
+
+impl Debug for Request {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Request {{ name: {:?}, filter: {:?} }}", self.name, self.filter)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Toast {
+    id: u64,
+    text: String,
+    folder: Option<PathBuf>,
+}
+
+#[derive(Debug, Default)]
+pub struct Exporter {
+    pending: Option<Request>,
+    conflict: Option<PathBuf>,
+    toast: Option<Toast>,
+    toasts: u64,
+}
+
+#[derive(Clone, Debug)]
+pub enum Message {
+    /// `confirmed` is whether the save dialog already agreed to replace an existing file
+    Chosen { path: Option<PathBuf>, confirmed: bool },
+    Overwrite,
+    Rename,
+    Cancel,
+    Written(Result<PathBuf, String>),
+    OpenFolder(PathBuf),
+    DismissToast(u64),
+}
+
+impl Exporter {
+
+    pub fn export(&mut self, request: Request) -> Task<Message> {
+        let dialog = rfd::AsyncFileDialog::new()
+                .set_file_name(&request.name)
+                .add_filter(request.filter.0, request.filter.1);
+        self.pending = Some(request);
+        self.conflict = None;
+
+        Task::future(dialog.save_file())
+                .map(|handle| Message::Chosen {
+                    path: handle.map(|handle| handle.path().to_path_buf()),
+                    confirmed: DIALOG_CONFIRMS_OVERWRITE,
+                })
+    }
+
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        match message {
+            Message::Chosen { path: None, .. } | Message::Cancel => {
+                self.pending = None;
+                self.conflict = None;
+            }
+            Message::Chosen { path: Some(path), confirmed } => {
+                if !confirmed && path.exists() {
+                    self.conflict = Some(path);
+                } else {
+                    return self.write(path)
+                }
+            }
+            Message::Overwrite => {
+                if let Some(path) = self.conflict.take() {
+                    return self.write(path)
+                }
+            }
+            Message::Rename => {
+                if let Some(path) = self.conflict.take() {
+                    return self.write(available_name(&path))
+                }
+            }
+            Message::Written(result) => {
+                return match result {
+                    Ok(path) => self.show(format!("Saved {}", file_name(&path)), path.parent().map(Path::to_path_buf)),
+                    Err(e) => {
+                        tracing::error!("failed to export: {}", e);
+                        self.show(format!("Export failed: {}", e), None)
+                    }
+                }
+            }
+            Message::OpenFolder(folder) => {
+                if let Err(e) = open_folder(&folder) {
+                    tracing::error!("failed to open {}: {}", folder.display(), e);
+                }
+            }
+            Message::DismissToast(id) => {
+                if self.toast.as_ref().is_some_and(|toast| toast.id == id) {
+                    self.toast = None;
+                }
+            }
+        }
+        Task::none()
+    }
+
+    /// a toast that isn't about a file that was written
+    pub fn notify(&mut self, text: impl Into<String>) -> Task<Message> {
+        self.show(text.into(), None)
+    }
+
+    fn show(&mut self, text: String, folder: Option<PathBuf>) -> Task<Message> {
+        self.toasts += 1;
+        let id = self.toasts;
+        self.toast = Some(Toast { id, text, folder });
+        Task::future(tokio::time::sleep(TOAST_DURATION))
+                .map(move |_| Message::DismissToast(id))
+    }
+
+    fn write(&mut self, path: PathBuf) -> Task<Message> {
+        let Some(request) = self.pending.take() else { return Task::none() };
+        Task::future(async move {
+            tokio::task::spawn_blocking(move || write_atomically(&path, request.write)
+                    .map(|_| path)
+                    .map_err(|e| e.to_string()))
+                    .await
+                    .unwrap_or_else(|e| Err(e.to_string()))
+        }).map(Message::Written)
+    }
+
+    pub fn dialogs<'a>(&self) -> impl Iterator<Item = Flagged<'a, Message>> {
+        let mut vec = vec![];
+
+        if let Some(path) = &self.conflict {
+            vec.push(Flagged::new(
+                widget::column![
+                    text!("{} already exists", file_name(path)),
+                    text!("Replace it, or save as {}?", file_name(&available_name(path))),
+                    row![
+                        button("Cancel").on_press(Message::Cancel),
+                        button("Keep both").on_press(Message::Rename),
+                        button("Replace").on_press(Message::Overwrite),
+                    ].spacing(10),
+                ].spacing(10).into(),
+                Message::Cancel));
+        }
+
+        vec.into_iter()
+    }
+
+    pub fn toast<'a>(&self) -> Option<Element<'a, Message>> {
+        let toast = self.toast.as_ref()?;
+        Some(container(row![
+            text(toast.text.clone()),
+        ].push(toast.folder.clone().map(|folder| button("Open folder").on_press(Message::OpenFolder(folder))))
+        .push(button("×").on_press(Message::DismissToast(toast.id)))
+        .spacing(10)
+        .align_y(Vertical::Center))
+                .padding(10)
+                .style(container::rounded_box)
+                .into())
+    }
+}
+
+/// writes next to the target and renames over it so a failing writer never leaves a partial file
+fn write_atomically(path: &Path, write: Writer) -> io::Result<()> {
+    let temp = path.with_file_name(format!(".{}.tmp", file_name(path)));
+
+    let result = File::create(&temp).and_then(|mut file| {
+        write(&mut file)?;
+        file.flush()?;
+        file.sync_all()
+    });
+
+    match result {
+        Ok(()) => std::fs::rename(&temp, path),
+        Err(e) => {
+            let _ = std::fs::remove_file(&temp);
+            Err(e)
+        }
+    }
+}
+
+/// `name.ext` becomes the first free one of `name-2.ext`, `name-3.ext`, …
+pub fn available_name(path: &Path) -> PathBuf {
+    let stem = path.file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+    let extension = path.extension()
+            .map(|extension| format!(".{}", extension.to_string_lossy()))
+            .unwrap_or_default();
+
+    (2..)
+            .map(|n| path.with_file_name(format!("{}-{}{}", stem, n, extension)))
+            .find(|candidate| !candidate.exists())
+            .expect("ran out of file names")
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+}
+
+pub fn open_folder(folder: &Path) -> io::Result<()> {
+    let program = if cfg!(target_os = "windows") {
+        "explorer"
+    } else if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    };
+    std::process::Command::new(program)
+            .arg(folder)
+            .spawn()
+            .map(drop)
+}
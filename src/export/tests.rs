@@ -0,0 +1,122 @@
+use super::*;
+
+struct Folder(PathBuf);
+
+impl Folder {
+    fn new(test: &str) -> Self {
+        let folder = std::env::temp_dir().join(format!("minsweeper-export-{}-{}", std::process::id(), test));
+        let _ = std::fs::remove_dir_all(&folder);
+        std::fs::create_dir_all(&folder).unwrap();
+        Self(folder)
+    }
+
+    fn file(&self, name: &str, contents: &str) -> PathBuf {
+        let path = self.0.join(name);
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    fn entries(&self) -> Vec<String> {
+        let mut entries = std::fs::read_dir(&self.0).unwrap()
+                .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+                .collect::<Vec<_>>();
+        entries.sort();
+        entries
+    }
+}
+
+impl Drop for Folder {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+fn request(contents: &'static str) -> Request {
+    Request::new("board.txt", ("Text", &["txt"]), move |out| out.write_all(contents.as_bytes()))
+}
+
+#[test]
+fn available_name_skips_taken_names() {
+    let folder = Folder::new("available");
+    let path = folder.file("board.txt", "");
+    assert_eq!(available_name(&path), folder.0.join("board-2.txt"));
+
+    folder.file("board-2.txt", "");
+    assert_eq!(available_name(&path), folder.0.join("board-3.txt"));
+
+    let bare = folder.file("board", "");
+    assert_eq!(available_name(&bare), folder.0.join("board-2"));
+}
+
+#[test]
+fn writes_replace_the_target_whole() {
+    let folder = Folder::new("write");
+    let path = folder.file("board.txt", "old");
+
+    write_atomically(&path, request("new").write).unwrap();
+
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "new");
+    assert_eq!(folder.entries(), ["board.txt"]);
+}
+
+#[test]
+fn failing_writes_leave_nothing_behind() {
+    let folder = Folder::new("failing");
+    let path = folder.file("board.txt", "old");
+    let fresh = folder.0.join("fresh.txt");
+
+    for target in [&path, &fresh] {
+        let failing = Request::new("board.txt", ("Text", &["txt"]), |out| {
+            out.write_all(b"half")?;
+            Err(io::Error::other("gave up"))
+        });
+        assert!(write_atomically(target, failing.write).is_err());
+    }
+
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "old");
+    assert_eq!(folder.entries(), ["board.txt"]);
+}
+
+#[test]
+fn existing_files_are_asked_about() {
+    let folder = Folder::new("conflict");
+    let path = folder.file("board.txt", "old");
+    let mut exporter = Exporter { pending: Some(request("new")), ..Default::default() };
+
+    let _ = exporter.update(Message::Chosen { path: Some(path.clone()), confirmed: false });
+    assert_eq!(exporter.conflict.as_ref(), Some(&path));
+    assert!(exporter.pending.is_some());
+    assert_eq!(exporter.dialogs().count(), 1);
+
+    let _ = exporter.update(Message::Cancel);
+    assert!(exporter.conflict.is_none());
+    assert!(exporter.pending.is_none());
+    assert_eq!(exporter.dialogs().count(), 0);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "old");
+}
+
+#[test]
+fn confirmed_or_new_files_are_written_straight_away() {
+    let folder = Folder::new("confirmed");
+    let existing = folder.file("board.txt", "old");
+    let fresh = folder.0.join("fresh.txt");
+
+    for (path, confirmed) in [(existing, true), (fresh, false)] {
+        let mut exporter = Exporter { pending: Some(request("new")), ..Default::default() };
+        let _ = exporter.update(Message::Chosen { path: Some(path), confirmed });
+        assert!(exporter.conflict.is_none());
+        assert!(exporter.pending.is_none(), "the request should have gone to be written");
+    }
+}
+
+#[test]
+fn keeping_both_moves_on_from_the_conflict() {
+    let folder = Folder::new("rename");
+    let path = folder.file("board.txt", "old");
+    let mut exporter = Exporter { pending: Some(request("new")), ..Default::default() };
+
+    let _ = exporter.update(Message::Chosen { path: Some(path), confirmed: false });
+    let _ = exporter.update(Message::Rename);
+    assert!(exporter.conflict.is_none());
+    assert!(exporter.pending.is_none());
+}
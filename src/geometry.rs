@@ -0,0 +1,96 @@
+use crate::paths;
+use iced::{window, Point, Size};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+#[cfg(test)]
+mod tests;
+
+const MIN_SIZE: Size = Size::new(200.0, 200.0);
+const MAX_SIZE: Size = Size::new(16384.0, 16384.0);
+/// no desktop reaches this far, a position past it was saved wrong
+const MAX_COORDINATE: f32 = 65536.0;
+
+/// where the main window was and how big it was, kept across launches
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Geometry {
+    pub width: f32,
+    pub height: f32,
+    /// `None` until the window reports where it is, some platforms never do
+    pub position: Option<(f32, f32)>,
+}
+
+impl Geometry {
+    pub fn new(size: Size, position: Option<Point>) -> Self {
+        Self {
+            width: size.width,
+            height: size.height,
+            position: position.map(|position| (position.x, position.y)),
+        }
+    }
+
+    /// what was saved when the app last closed, clamped to something that can be opened
+    pub fn load() -> Option<Self> {
+        Self::load_from(&paths::window_file().ok()?)
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        self.save_to(&paths::window_file()?)
+    }
+
+    pub fn load_from(path: &Path) -> Option<Self> {
+        let file = File::open(path).ok()?;
+        let geometry = serde_json::from_reader::<_, Self>(io::BufReader::new(file)).ok()?;
+        geometry.sanitized()
+    }
+
+    pub fn save_to(&self, path: &Path) -> io::Result<()> {
+        let temp = path.with_extension("json.tmp");
+        let mut file = File::create(&temp)?;
+        serde_json::to_writer(&mut file, self)?;
+        file.sync_all()?;
+        drop(file);
+
+        std::fs::rename(temp, path)
+    }
+
+    fn sanitized(self) -> Option<Self> {
+        if !self.width.is_finite() || !self.height.is_finite() {
+            return None
+        }
+        let position = self.position
+                .filter(|(x, y)| x.is_finite() && y.is_finite()
+                        && x.abs() < MAX_COORDINATE && y.abs() < MAX_COORDINATE);
+        Some(Self {
+            width: self.width.clamp(MIN_SIZE.width, MAX_SIZE.width),
+            height: self.height.clamp(MIN_SIZE.height, MAX_SIZE.height),
+            position,
+        })
+    }
+
+    pub fn size(&self) -> Size {
+        Size::new(self.width, self.height)
+    }
+
+    pub fn position(&self) -> window::Position {
+        match self.position {
+            Some((x, y)) => window::Position::Specific(Point::new(x, y)),
+            None => window::Position::default(),
+        }
+    }
+}
+
+/// `size` made small enough to fit on a monitor of `monitor`
+pub fn fit_to_monitor(size: Size, monitor: Size) -> Size {
+    Size::new(size.width.min(monitor.width), size.height.min(monitor.height))
+}
+
+/// `position` moved so a window of `size` at it is wholly on a monitor of `monitor`, which only
+/// reports its size so it's taken to start at the origin
+pub fn fit_position(position: Point, size: Size, monitor: Size) -> Point {
+    let size = fit_to_monitor(size, monitor);
+    Point::new(position.x.clamp(0.0, monitor.width - size.width),
+        position.y.clamp(0.0, monitor.height - size.height))
+}
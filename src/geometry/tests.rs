@@ -0,0 +1,47 @@
+use super::*;
+
+const MONITOR: Size = Size::new(1920.0, 1080.0);
+
+#[test]
+fn a_window_too_big_for_the_monitor_is_shrunk_to_it() {
+    assert_eq!(fit_to_monitor(Size::new(3000.0, 500.0), MONITOR), Size::new(1920.0, 500.0));
+    assert_eq!(fit_to_monitor(Size::new(800.0, 600.0), MONITOR), Size::new(800.0, 600.0));
+}
+
+#[test]
+fn a_window_on_screen_stays_where_it_is() {
+    let position = Point::new(100.0, 200.0);
+    assert_eq!(fit_position(position, Size::new(800.0, 600.0), MONITOR), position);
+}
+
+#[test]
+fn a_window_off_screen_is_brought_back_on() {
+    let size = Size::new(800.0, 600.0);
+    assert_eq!(fit_position(Point::new(5000.0, 3000.0), size, MONITOR), Point::new(1120.0, 480.0));
+    assert_eq!(fit_position(Point::new(-400.0, -50.0), size, MONITOR), Point::ORIGIN);
+    assert_eq!(fit_position(Point::new(1500.0, 100.0), size, MONITOR), Point::new(1120.0, 100.0));
+}
+
+#[test]
+fn a_window_as_big_as_the_monitor_goes_in_its_corner() {
+    assert_eq!(fit_position(Point::new(300.0, 300.0), Size::new(4000.0, 4000.0), MONITOR), Point::ORIGIN);
+}
+
+#[test]
+fn saved_geometry_is_clamped_to_what_can_be_opened() {
+    let geometry = Geometry { width: 10.0, height: f32::MAX, position: Some((f32::NAN, 0.0)) }.sanitized().unwrap();
+    assert_eq!(geometry.size(), Size::new(MIN_SIZE.width, MAX_SIZE.height));
+    assert_eq!(geometry.position, None);
+    assert!(Geometry { width: f32::INFINITY, height: 100.0, position: None }.sanitized().is_none());
+}
+
+#[test]
+fn a_placement_comes_back_from_its_own_file() {
+    let path = std::env::temp_dir().join(format!("minsweeper-geometry-{}.json", std::process::id()));
+    let geometry = Geometry { width: 500.0, height: 400.0, position: Some((30.0, 40.0)) };
+    geometry.save_to(&path).unwrap();
+    let loaded = Geometry::load_from(&path);
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(loaded, Some(geometry));
+    assert_eq!(Geometry::load_from(&path), None);
+}
@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::LazyLock;
+use toml_edit::DocumentMut;
+
+/// the current language's text for a key, with each `{name}` in it filled in from the arguments
+///
+/// ```ignore
+/// t!("menu-file")
+/// t!("mines-left", count = remaining)
+/// ```
+#[macro_export]
+macro_rules! t {
+    ($key:literal) => {
+        $crate::i18n::text($key)
+    };
+    ($key:literal, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::fill($crate::i18n::text($key), &[$((stringify!($name), &$value as &dyn std::fmt::Display)),+])
+    };
+}
+pub use t;
+
+macro_rules! languages {
+    ($($(#[$attr:meta])* $variant:ident => $code:literal, $name:literal;)*) => {
+        #[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+        pub enum Language {
+            $($(#[$attr])* $variant,)*
+        }
+
+        impl Language {
+            pub const ALL: &[Language] = &[$(Language::$variant),*];
+
+            /// what the language calls itself, so it can be found in the menu whatever's showing
+            pub fn name(self) -> &'static str {
+                match self {
+                    $(Language::$variant => $name,)*
+                }
+            }
+
+            fn strings(self) -> &'static HashMap<String, String> {
+                match self {
+                    $(Language::$variant => {
+                        static STRINGS: LazyLock<HashMap<String, String>> = LazyLock::new(||
+                                parse($code, include_str!(concat!($code, ".toml"))));
+                        &STRINGS
+                    })*
+                }
+            }
+        }
+    };
+}
+
+// every language is one line here plus its `<code>.toml`, keys it leaves out show in English
+languages! {
+    #[default]
+    English => "en", "English";
+}
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+
+pub fn set_language(language: Language) {
+    let index = Language::ALL.iter()
+            .position(|known| *known == language)
+            .unwrap_or_default();
+    CURRENT.store(index, Ordering::Relaxed);
+}
+
+pub fn language() -> Language {
+    Language::ALL[CURRENT.load(Ordering::Relaxed)]
+}
+
+/// the text for `key` in the current language, or in English if it hasn't got one, or the key
+/// itself if neither has so a missing string shows up instead of an empty label
+pub fn text(key: &'static str) -> &'static str {
+    language().strings().get(key)
+            .or_else(|| Language::English.strings().get(key))
+            .map_or(key, String::as_str)
+}
+
+/// puts each argument in where its `{name}` is
+pub fn fill(text: &str, arguments: &[(&str, &dyn Display)]) -> String {
+    arguments.iter().fold(text.to_string(), |text, (name, value)|
+            text.replace(&format!("{{{}}}", name), &value.to_string()))
+}
+
+/// a flat table of keys to strings, anything else in the file is skipped with a complaint
+fn parse(code: &str, source: &str) -> HashMap<String, String> {
+    let document = match source.parse::<DocumentMut>() {
+        Ok(document) => document,
+        Err(e) => {
+            tracing::warn!("{}.toml isn't valid toml: {}", code, e);
+            return HashMap::new()
+        }
+    };
+    document.iter()
+            .filter_map(|(key, item)| match item.as_str() {
+                Some(text) => Some((key.to_string(), text.to_string())),
+                None => {
+                    tracing::warn!("{}.toml: {} isn't a string", code, key);
+                    None
+                }
+            })
+            .collect()
+}
@@ -0,0 +1,241 @@
+use crate::board_text::Layout;
+use crate::minsweeper::{Click, History, Provenance};
+use crate::paths;
+use minsweeper_rs::board::{BoardSize, Point};
+use minsweeper_rs::CellType;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+#[cfg(test)]
+mod tests;
+
+const BATCH_SIZE: usize = 16;
+
+/// clicks that don't make up a batch still reach the disk after this long
+pub const FLUSH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// a journal bigger than this wasn't written by a game, it's not read at all
+const MAX_BYTES: u64 = 64 * 1024 * 1024;
+
+#[derive(Debug, Serialize, Deserialize)]
+enum Record {
+    Header {
+        width: usize,
+        height: usize,
+        mines: Vec<Point>,
+        seed: u64,
+    },
+    Click(Click),
+    Auto {
+        click: Click,
+        step: u32,
+    },
+}
+
+#[derive(Debug, Default)]
+pub struct Journal {
+    epoch: Option<u64>,
+    written: usize,
+    /// when the clicks were last written, unwritten ones wait at most [`FLUSH_INTERVAL`] past it
+    flushed: Option<Instant>,
+    file: Option<File>,
+    /// where it's written, the user's journal file if not set
+    path: Option<PathBuf>,
+    /// never writes anything, for games that aren't the user's
+    off: bool,
+}
+
+impl Journal {
+
+    #[cfg(test)]
+    pub fn off() -> Self {
+        Self { off: true, ..Self::default() }
+    }
+
+    #[cfg(test)]
+    pub fn at(path: PathBuf) -> Self {
+        Self { path: Some(path), ..Self::default() }
+    }
+
+    fn path(&self) -> io::Result<PathBuf> {
+        self.path.clone().map_or_else(paths::journal_file, Ok)
+    }
+
+    pub fn should_flush(&self, history: &History, now: Instant) -> bool {
+        let unwritten = history.clicks.len().saturating_sub(self.written);
+        self.epoch != Some(history.epoch)
+                || unwritten >= BATCH_SIZE
+                || unwritten > 0 && self.flushed.is_none_or(|flushed| now.saturating_duration_since(flushed) >= FLUSH_INTERVAL)
+    }
+
+    pub fn write(&mut self, history: &History, now: Instant) -> io::Result<()> {
+        if self.off {
+            return Ok(())
+        }
+        if self.epoch != Some(history.epoch) {
+            let size = history.layout.board.size();
+            let mines = size.points()
+                    .filter(|point| history.layout.board[*point].cell_type == CellType::Mine)
+                    .collect();
+
+            let mut file = File::create(self.path()?)?;
+            write_record(&mut file, &Record::Header {
+                width: size.width().get(),
+                height: size.height().get(),
+                mines,
+                seed: history.seed,
+            })?;
+            self.file = Some(file);
+            self.epoch = Some(history.epoch);
+            self.written = 0;
+        }
+
+        let Some(file) = &mut self.file else { return Ok(()) };
+
+        for (click, by) in &history.clicks[self.written..] {
+            write_record(file, &match *by {
+                Provenance::Player => Record::Click(*click),
+                Provenance::Solver(step) => Record::Auto { click: *click, step },
+            })?;
+        }
+        self.written = history.clicks.len();
+        self.flushed = Some(now);
+
+        Ok(())
+    }
+
+    /// another handle on the file, to get what's been written onto the disk without holding up
+    /// whoever wrote it
+    pub fn sync_handle(&self) -> io::Result<Option<File>> {
+        self.file.as_ref().map(File::try_clone).transpose()
+    }
+
+    pub fn clear(&mut self) {
+        self.epoch = None;
+        self.written = 0;
+        self.flushed = None;
+        if self.file.take().is_some() && let Ok(path) = self.path() {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+fn write_record(file: &mut File, record: &Record) -> io::Result<()> {
+    let payload = serde_json::to_vec(record)?;
+    let length = u32::try_from(payload.len())
+            .map_err(io::Error::other)?;
+
+    let mut buffer = Vec::with_capacity(payload.len() + 8);
+    buffer.extend_from_slice(&length.to_le_bytes());
+    buffer.extend_from_slice(&crc32fast::hash(&payload).to_le_bytes());
+    buffer.extend_from_slice(&payload);
+    file.write_all(&buffer)
+}
+
+fn read_records(bytes: &[u8]) -> Vec<Record> {
+    let mut records = vec![];
+    let mut rest = bytes;
+
+    while let Some((header, body)) = rest.split_first_chunk::<8>() {
+        let length = u32::from_le_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        let checksum = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+
+        let Some((payload, remaining)) = body.split_at_checked(length) else { break };
+        if crc32fast::hash(payload) != checksum {
+            break
+        }
+        let Ok(record) = serde_json::from_slice(payload) else { break };
+
+        records.push(record);
+        rest = remaining;
+    }
+
+    records
+}
+
+#[derive(Clone, Debug)]
+pub struct Recovered {
+    pub size: BoardSize,
+    pub mines: Vec<Point>,
+    pub seed: u64,
+    pub clicks: Vec<(Click, Provenance)>,
+}
+
+pub fn recover() -> Option<Recovered> {
+    recover_from(&paths::journal_file().ok()?, &paths::clean_exit_file().ok()?)
+}
+
+fn recover_from(path: &Path, clean_exit: &Path) -> Option<Recovered> {
+    let journal = std::fs::metadata(path).ok()?;
+    let clean = std::fs::metadata(clean_exit)
+            .and_then(|marker| Ok(marker.modified()? >= journal.modified()?))
+            .unwrap_or(false);
+    let _ = std::fs::remove_file(clean_exit);
+
+    if clean {
+        let _ = std::fs::remove_file(path);
+        return None
+    }
+    if journal.len() > MAX_BYTES {
+        tracing::warn!("journal is {} bytes, more than a game writes", journal.len());
+        return None
+    }
+
+    let mut bytes = vec![];
+    File::open(path)
+            .and_then(|mut file| file.read_to_end(&mut bytes))
+            .inspect_err(|e| tracing::warn!("failed to read journal: {}", e))
+            .ok()?;
+
+    let mut records = read_records(&bytes).into_iter();
+    let Some(Record::Header { width, height, mines, seed }) = records.next() else {
+        tracing::warn!("journal has no valid header");
+        return None
+    };
+    let Some(Layout { size, mines }) = Layout::checked(width, height, mines) else {
+        tracing::warn!("journal has a board that couldn't have been played");
+        return None
+    };
+    let on_board = |click: &Click| {
+        let (Click::Left((x, y)) | Click::Right((x, y))) = *click;
+        x < width && y < height
+    };
+    // a click off the board is as broken as a bad checksum, the replay stops before it
+    let clicks = records
+            .filter_map(|record| match record {
+                Record::Click(click) => Some((click, Provenance::Player)),
+                Record::Auto { click, step } => Some((click, Provenance::Solver(step))),
+                Record::Header { .. } => None,
+            })
+            .take_while(|(click, _)| on_board(click))
+            .collect();
+
+    Some(Recovered { size, mines, seed, clicks })
+}
+
+/// keeps the journal of a game that wasn't resumed in the journals folder
+pub fn archive() -> io::Result<()> {
+    archive_into(&paths::journal_file()?, &paths::journals_dir()?)
+}
+
+fn archive_into(journal: &Path, folder: &Path) -> io::Result<()> {
+    let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+    std::fs::rename(journal, folder.join(format!("journal-{}.bin", timestamp)))
+}
+
+/// `None` if there's nowhere to keep one
+pub fn active_path() -> Option<PathBuf> {
+    paths::journal_file().ok()
+}
+
+pub fn mark_clean_exit() -> io::Result<()> {
+    File::create(paths::clean_exit_file()?)?;
+    Ok(())
+}
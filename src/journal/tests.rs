@@ -0,0 +1,206 @@
+use super::*;
+use crate::minsweeper::{layout_from_mines, Game};
+use minsweeper_rs::CellState;
+use std::time::Instant;
+
+/// ```text
+/// . . M 2 .
+/// . . M 2 .
+/// . . 1 1 .
+/// ```
+const MINES: [Point; 2] = [(2, 0), (2, 1)];
+
+/// the right columns opened, a mine flagged by the solver and the left side opened, the game
+/// still going with (2, 2) left
+const CLICKS: [(Click, Provenance); 3] = [
+    (Click::Left((4, 1)), Provenance::Player),
+    (Click::Right((2, 0)), Provenance::Solver(1)),
+    (Click::Left((0, 0)), Provenance::Player),
+];
+
+struct Folder(PathBuf);
+
+impl Folder {
+    fn new(test: &str) -> Self {
+        let folder = std::env::temp_dir().join(format!("minsweeper-journal-{}-{}", std::process::id(), test));
+        let _ = std::fs::remove_dir_all(&folder);
+        std::fs::create_dir_all(&folder).unwrap();
+        Self(folder)
+    }
+
+    fn journal(&self) -> PathBuf {
+        self.0.join("journal.bin")
+    }
+
+    fn recover(&self) -> Option<Recovered> {
+        recover_from(&self.journal(), &self.0.join("clean-exit"))
+    }
+}
+
+impl Drop for Folder {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+fn size() -> BoardSize {
+    BoardSize::new(5, 3, MINES.len()).unwrap()
+}
+
+/// a game started on the board with `clicks` played on it
+fn played(clicks: &[(Click, Provenance)]) -> Game {
+    let game = Game::new(size());
+    game.blocking_start_with_layout(layout_from_mines(size(), &MINES), 7);
+    let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    for (click, by) in clicks {
+        let _ = runtime.block_on(game.click(*click, *by));
+    }
+    game
+}
+
+/// the journal of a game with [`CLICKS`] played on it
+fn written(folder: &Folder) -> Vec<u8> {
+    let game = played(&CLICKS);
+    let mut journal = Journal::at(folder.journal());
+    journal.write(&game.blocking_history().unwrap(), Instant::now()).unwrap();
+    drop(journal);
+    std::fs::read(folder.journal()).unwrap()
+}
+
+/// where each record starts, read off the lengths in the framing
+fn record_starts(bytes: &[u8]) -> Vec<usize> {
+    let mut starts = vec![];
+    let mut at = 0;
+    while at < bytes.len() {
+        starts.push(at);
+        at += 8 + u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap()) as usize;
+    }
+    starts
+}
+
+#[test]
+fn a_journal_reads_back() {
+    let folder = Folder::new("reads-back");
+    written(&folder);
+
+    let recovered = folder.recover().unwrap();
+    assert_eq!(recovered.size, size());
+    assert_eq!(recovered.mines, MINES);
+    assert_eq!(recovered.seed, 7);
+    assert_eq!(recovered.clicks, CLICKS);
+}
+
+#[test]
+fn a_truncated_tail_loses_only_the_click_it_cut() {
+    let folder = Folder::new("truncated");
+    let bytes = written(&folder);
+    std::fs::write(folder.journal(), &bytes[..bytes.len() - 3]).unwrap();
+
+    assert_eq!(folder.recover().unwrap().clicks, CLICKS[..2]);
+}
+
+#[test]
+fn a_checksum_that_doesnt_match_stops_the_replay_there() {
+    let folder = Folder::new("checksum");
+    let mut bytes = written(&folder);
+    // the last byte of the first click's payload
+    let starts = record_starts(&bytes);
+    bytes[starts[2] - 1] ^= 0xff;
+    std::fs::write(folder.journal(), &bytes).unwrap();
+
+    assert_eq!(folder.recover().unwrap().clicks, []);
+}
+
+#[test]
+fn replaying_a_journal_gets_the_game_back_as_it_was() {
+    let folder = Folder::new("replay");
+    written(&folder);
+
+    let recovered = folder.recover().unwrap();
+    let replayed = Game::new(recovered.size);
+    replayed.blocking_start_with_layout(layout_from_mines(recovered.size, &recovered.mines), recovered.seed);
+    let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    for (click, by) in recovered.clicks {
+        let _ = runtime.block_on(replayed.click(click, by));
+    }
+
+    let (original, replayed) = (played(&CLICKS).blocking_gamestate(), replayed.blocking_gamestate());
+    assert_eq!(replayed.status, original.status);
+    for point in size().points() {
+        assert_eq!(replayed.board[point].cell_state, original.board[point].cell_state, "{:?}", point);
+    }
+    assert_eq!(replayed.board[(2, 2)].cell_state, CellState::Unknown);
+}
+
+#[test]
+fn a_board_that_couldnt_have_been_played_isnt_recovered() {
+    let folder = Folder::new("bounds");
+    let header = |mines: Vec<Point>| {
+        let mut file = File::create(folder.journal()).unwrap();
+        write_record(&mut file, &Record::Header { width: 5, height: 3, mines, seed: 0 }).unwrap();
+    };
+
+    header(vec![(5, 0)]);
+    assert!(folder.recover().is_none());
+    header(vec![(1, 1), (1, 1)]);
+    assert!(folder.recover().is_none());
+
+    let mut file = File::create(folder.journal()).unwrap();
+    write_record(&mut file, &Record::Header { width: usize::MAX, height: 2, mines: vec![(0, 0)], seed: 0 }).unwrap();
+    assert!(folder.recover().is_none());
+}
+
+#[test]
+fn a_click_off_the_board_ends_the_replay() {
+    let folder = Folder::new("off-board");
+    written(&folder);
+    let mut file = File::options().append(true).open(folder.journal()).unwrap();
+    write_record(&mut file, &Record::Click(Click::Left((9, 9)))).unwrap();
+    write_record(&mut file, &Record::Click(Click::Left((2, 2)))).unwrap();
+
+    assert_eq!(folder.recover().unwrap().clicks, CLICKS);
+}
+
+#[test]
+fn a_journal_too_big_to_be_a_game_isnt_read() {
+    let folder = Folder::new("oversized");
+    written(&folder);
+    File::options().append(true).open(folder.journal()).unwrap()
+            .set_len(MAX_BYTES + 1).unwrap();
+
+    assert!(folder.recover().is_none());
+}
+
+#[test]
+fn declining_keeps_the_journal_in_the_archive() {
+    let folder = Folder::new("archive");
+    let bytes = written(&folder);
+    let archive = folder.0.join("journals");
+    std::fs::create_dir_all(&archive).unwrap();
+
+    archive_into(&folder.journal(), &archive).unwrap();
+
+    assert!(!folder.journal().exists());
+    let kept = std::fs::read_dir(&archive).unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect::<Vec<_>>();
+    assert_eq!(kept.len(), 1);
+    assert_eq!(std::fs::read(&kept[0]).unwrap(), bytes);
+}
+
+#[test]
+fn clicks_short_of_a_batch_are_flushed_once_the_interval_is_up() {
+    let game = played(&CLICKS[..1]);
+    let history = game.blocking_history().unwrap();
+    let folder = Folder::new("interval");
+    let mut journal = Journal::at(folder.journal());
+    let now = Instant::now();
+    assert!(journal.should_flush(&history, now), "a new game is written straight away");
+    journal.write(&history, now).unwrap();
+    assert!(!journal.should_flush(&history, now + FLUSH_INTERVAL), "there's nothing new");
+
+    let history = played(&CLICKS).blocking_history().unwrap();
+    let history = History { epoch: journal.epoch.unwrap(), ..history };
+    assert!(!journal.should_flush(&history, now + FLUSH_INTERVAL / 2));
+    assert!(journal.should_flush(&history, now + FLUSH_INTERVAL));
+}
@@ -0,0 +1,108 @@
+use crate::solvers::{self, KnownSolver};
+use crate::texture::Texture;
+use minsweeper_rs::board::{BoardSize, ConventionalSize};
+
+/// settings from the command line, they only last for this session unless `save` is set
+#[derive(Clone, Debug, Default)]
+pub struct Launch {
+    pub size: Option<BoardSize>,
+    pub texture: Option<Texture>,
+    pub solver: Option<KnownSolver>,
+    pub auto: bool,
+    pub save: bool,
+    /// everything is kept next to the executable instead of in the user's folders
+    pub portable: bool,
+}
+
+/// prints the usage and exits if something on the command line doesn't make sense
+pub fn from_args() -> Launch {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    if args.iter().any(|arg| arg == "--help") {
+        println!("{}", usage());
+        std::process::exit(0)
+    }
+    parse(&args).unwrap_or_else(|problem| {
+        eprintln!("{}", problem);
+        eprintln!();
+        eprintln!("{}", usage());
+        std::process::exit(2)
+    })
+}
+
+fn parse(args: &[String]) -> Result<Launch, String> {
+    let mut launch = Launch::default();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+        match arg.as_str() {
+            "--size" => {
+                let name = value()?;
+                launch.size = Some(parse_size(name).ok_or_else(|| format!("{:?} isn't a board size", name))?);
+            }
+            "--theme" => {
+                let name = value()?;
+                launch.texture = Some(Texture::BUILT_IN.iter()
+                        .copied()
+                        .find(|texture| theme_name(*texture) == name.to_lowercase())
+                        .ok_or_else(|| format!("{:?} isn't a theme", name))?);
+            }
+            "--solver" => {
+                let id = value()?;
+                launch.solver = Some(solvers::find(id)
+                        .map(KnownSolver::from)
+                        .ok_or_else(|| format!("there's no solver called {:?}", id))?);
+            }
+            "--auto" => launch.auto = true,
+            "--save" => launch.save = true,
+            "--portable" => launch.portable = true,
+            _ => return Err(format!("{:?} isn't something minsweeper understands", arg)),
+        }
+    }
+    Ok(launch)
+}
+
+/// a conventional size by name, or `WIDTHxHEIGHT:MINES`
+pub fn parse_size(name: &str) -> Option<BoardSize> {
+    match name.to_lowercase().as_str() {
+        "beginner" => Some(ConventionalSize::Beginner.size()),
+        "intermediate" => Some(ConventionalSize::Intermediate.size()),
+        "expert" => Some(ConventionalSize::Expert.size()),
+        custom => {
+            let (width, rest) = custom.split_once('x')?;
+            let (height, mines) = rest.split_once(':')?;
+            BoardSize::new(width.parse().ok()?, height.parse().ok()?, mines.parse().ok()?).ok()
+        }
+    }
+}
+
+fn theme_name(texture: Texture) -> String {
+    format!("{:?}", texture).to_lowercase()
+}
+
+/// the solvers and sizes both the launch flags and the benchmark take
+pub fn options() -> String {
+    let solvers = solvers::registry().iter()
+            .map(|entry| format!("    {:<24}{}", entry.id, entry.label))
+            .collect::<Vec<_>>()
+            .join("\n");
+    format!("solvers:\n{}\n\
+            \n\
+            sizes:\n    beginner, intermediate, expert, or WIDTHxHEIGHT:MINES like 30x16:99", solvers)
+}
+
+fn usage() -> String {
+    let themes = Texture::BUILT_IN.iter()
+            .map(|texture| theme_name(*texture))
+            .collect::<Vec<_>>()
+            .join(", ");
+    format!("usage: minsweeper-native-client [--size <size>] [--theme <theme>] [--solver <solver>] [--auto] [--save] [--portable]\n\
+            \x20      minsweeper-native-client --bench <solver> [--size <size>] [--games <n>] [--json]\n\
+            \n\
+            without --save the other flags only last until minsweeper is closed\n\
+            --portable keeps settings and everything else in a minsweeper-data folder next to the executable\n\
+            \n\
+            {}\n\
+            \n\
+            themes:\n    {}", options(), themes)
+}
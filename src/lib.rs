@@ -0,0 +1,31 @@
+pub mod achievements;
+pub mod arena;
+pub mod bench;
+pub mod blitz;
+pub mod board_text;
+pub mod board_widget;
+pub mod clock;
+pub mod crash;
+pub mod dialog;
+pub mod experiments;
+pub mod export;
+pub mod geometry;
+pub mod i18n;
+pub mod journal;
+pub mod launch;
+pub mod logging;
+pub mod metrics;
+pub mod minsweeper;
+pub mod paths;
+pub mod power;
+pub mod privacy;
+pub mod saved_boards;
+pub mod settings_menu;
+pub mod share;
+pub mod shortcuts;
+pub mod solvers;
+pub mod sound;
+pub mod stats;
+pub mod storage;
+pub mod texture;
+pub mod windows;
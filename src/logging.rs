@@ -0,0 +1,113 @@
+use crate::paths;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::sync::OnceLock;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::{fmt, reload, EnvFilter, Registry};
+
+const LOG_PREFIX: &str = "minsweeper";
+/// a file a day, the oldest go once there are more than this
+const KEPT_LOGS: usize = 7;
+
+/// the file writer flushes on its own thread until this is dropped, so it lives as long as the app
+static GUARD: OnceLock<WorkerGuard> = OnceLock::new();
+static LEVEL: OnceLock<reload::Handle<LevelFilter, Registry>> = OnceLock::new();
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    pub const ALL: [LogLevel; 5] = [LogLevel::Error, LogLevel::Warn, LogLevel::Info, LogLevel::Debug, LogLevel::Trace];
+}
+
+impl Display for LogLevel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            LogLevel::Error => "Error",
+            LogLevel::Warn => "Warn",
+            LogLevel::Info => "Info",
+            LogLevel::Debug => "Debug",
+            LogLevel::Trace => "Trace",
+        })
+    }
+}
+
+impl From<LogLevel> for LevelFilter {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Error => LevelFilter::ERROR,
+            LogLevel::Warn => LevelFilter::WARN,
+            LogLevel::Info => LevelFilter::INFO,
+            LogLevel::Debug => LevelFilter::DEBUG,
+            LogLevel::Trace => LevelFilter::TRACE,
+        }
+    }
+}
+
+/// logs to a daily file under the data folder, and to stderr too when `RUST_LOG` is set. a log
+/// folder that can't be written to only loses the file, nothing here panics
+pub fn init() {
+    let (level, handle) = reload::Layer::new(LevelFilter::from(LogLevel::default()));
+    let file = paths::logs_dir()
+            .map_err(|e| e.to_string())
+            .and_then(|folder| RollingFileAppender::builder()
+                    .rotation(Rotation::DAILY)
+                    .filename_prefix(LOG_PREFIX)
+                    .filename_suffix("log")
+                    .max_log_files(KEPT_LOGS)
+                    .build(folder)
+                    .map_err(|e| e.to_string()))
+            .inspect_err(|e| eprintln!("not logging to a file: {}", e))
+            .ok()
+            .map(|appender| {
+                let (writer, guard) = tracing_appender::non_blocking(appender);
+                let _ = GUARD.set(guard);
+                fmt::layer()
+                        .with_writer(writer)
+                        .with_ansi(false)
+            });
+    let stderr = std::env::var_os("RUST_LOG").map(|_| fmt::layer()
+            .with_writer(std::io::stderr)
+            .with_filter(EnvFilter::from_default_env()));
+
+    if tracing_subscriber::registry()
+            .with(file.with_filter(level))
+            .with(stderr)
+            .try_init()
+            .is_ok() {
+        let _ = LEVEL.set(handle);
+    }
+}
+
+/// what gets written to the log file from now on
+pub fn set_level(level: LogLevel) {
+    if let Some(handle) = LEVEL.get() {
+        let _ = handle.reload(LevelFilter::from(level));
+    }
+}
+
+/// up to the last `lines` lines of the newest log file
+pub fn tail(lines: usize) -> std::io::Result<String> {
+    let newest = std::fs::read_dir(paths::logs_dir()?)?
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with(LOG_PREFIX)))
+            // the date in the name sorts the same as the files were made
+            .max();
+    let Some(newest) = newest else { return Ok(String::new()) };
+    let contents = String::from_utf8_lossy(&std::fs::read(newest)?).into_owned();
+    let skip = contents.lines().count().saturating_sub(lines);
+    Ok(contents.lines().skip(skip).collect::<Vec<_>>().join("\n"))
+}
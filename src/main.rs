@@ -1,40 +1,151 @@
+use minsweeper_native_client::i18n::t;
+use minsweeper_native_client::{arena, bench, blitz, board_text, clock, crash, dialog, experiments, export, geometry, i18n, journal, launch, logging, metrics, minsweeper, paths, power, privacy, saved_boards, settings_menu, share, shortcuts, solvers, sound, stats, storage, texture, windows};
 use derive_more::From;
-use directories::ProjectDirs;
-use iced::{widget, window, Element, Subscription, Task};
-use iced_core::{mouse, Event};
-use std::sync::LazyLock;
-
-mod minsweeper;
-mod settings_menu;
-mod texture;
-
-static DIRS: LazyLock<ProjectDirs> = LazyLock::new(||
-        ProjectDirs::from("", "canaryprism", "minsweeper-native-client")
-                .expect("couldn't obtain project directories"));
+use iced::{theme, widget, window, Element, Length, Subscription, Task, Theme};
+use iced::keyboard;
+use iced_core::alignment::{Horizontal, Vertical};
+use iced_core::{mouse, touch, Event};
+use minsweeper_rs::board::{BoardSize, ConventionalSize};
+use minsweeper_rs::GameStatus;
+use std::collections::HashMap;
+use std::time::Duration;
+use windows::Detached;
 
 fn main() -> iced::Result {
+    if let Some(code) = bench::from_args() {
+        std::process::exit(code)
+    }
+    let launch = launch::from_args();
+    if launch.portable && let Err(e) = paths::make_portable() {
+        eprintln!("can't run portable: {}", e);
+        std::process::exit(1)
+    }
+    logging::init();
+    crash::install();
     println!("mewo");
-    iced::application(State::init, State::update, State::view)
-            .window(window::Settings {
-                icon: Some(window::icon::from_file_data(include_bytes!("texture/icon.png"), None).unwrap()),
-                ..Default::default()
-            })
+    texture::Texture::check_manifests();
+    iced::daemon(move || State::init(&launch), State::update, State::view)
+            .title(State::title)
             .subscription(State::subscriptions)
+            .theme(State::theme)
+            .style(State::style)
+            .scale_factor(State::scale_factor)
             .run()
 }
 
+/// the menu bar above the game, for sizing the window around the board
+const MENU_BAR_HEIGHT: f32 = 36.0;
+
+fn window_settings() -> window::Settings {
+    window::Settings {
+        icon: Some(window::icon::from_file_data(include_bytes!("texture/icon.png"), None).unwrap()),
+        exit_on_close_request: false,
+        ..Default::default()
+    }
+}
+
+/// things done to the main window itself rather than what's in it
+#[derive(Copy, Clone, Debug)]
+pub enum WindowMessage {
+    ToggleFullscreen,
+    AlwaysOnTop(bool),
+    /// what the window is in after a resize, fullscreen can be left through the system too
+    Mode(window::Mode),
+}
+
+/// what the main window is being used for
+#[derive(Debug)]
+enum Mode {
+    Normal,
+    Blitz(blitz::Blitz),
+}
+
 #[derive(Debug)]
 pub struct State {
+    windows: windows::Windows,
+    /// where each kind of detached window was last, whether it's open or not
+    placements: HashMap<Detached, geometry::Geometry>,
     settings_menu: settings_menu::SettingsMenu,
     minsweeper: minsweeper::MinsweeperGame,
+    mode: Mode,
+    /// the solver's board next to the player's, while racing it
+    arena: Option<arena::Arena>,
+    statistics: stats::Statistics,
+    storage: storage::Storage,
+    exporter: export::Exporter,
+    installer: texture::install::Installer,
+    board_importer: board_text::BoardImporter,
+    saved_boards: saved_boards::SavedBoards,
+    dialogs: dialog::DialogStack,
+    recovered: Option<journal::Recovered>,
+    /// what went wrong when the app last crashed, shown until dismissed
+    crash: Option<String>,
+    power: power::PowerSource,
+    debug_overlay: bool,
+    clock: clock::SharedClock,
+    /// light or dark, `None` until the system said which
+    system_theme: theme::Mode,
+    /// the last thing an external solver did wrong, shown until dismissed
+    solver_failure: Option<solvers::external::ExternalError>,
+    /// the main window's size and position as of its last move or resize, saved on close
+    geometry: Option<geometry::Geometry>,
+    /// while set `geometry` is left alone, so leaving fullscreen goes back to it
+    fullscreen: bool,
+    /// the main window's size as it is now, fullscreen or not
+    size: Option<iced::Size>,
 }
 
-impl Default for State {
-    fn default() -> Self {
-        let settings_menu = settings_menu::SettingsMenu::default();
+impl State {
+    fn new(settings_menu: settings_menu::SettingsMenu) -> Self {
+        sound::set_volume(settings_menu.settings().volume());
+        i18n::set_language(settings_menu.settings().language());
+        privacy::set_streamer_mode(settings_menu.settings().streamer_mode());
+        let power = if settings_menu.settings().throttle_on_battery() {
+            power::detect()
+        } else {
+            power::PowerSource::Unknown
+        };
+        let clock = clock::system();
+        let journal = journal::recover();
+        let mut crash = crash::take_report();
+        // the crash's game is as of the last repaint, the journal's only as of its last flush
+        let recovered = crash.as_mut()
+                .and_then(|crash| crash.game.take())
+                .or(journal);
+        let mut dialogs = dialog::DialogStack::default();
+        if crash.is_some() {
+            // which offers to resume as well
+            dialogs.open(dialog::DialogId::Crash);
+        } else if recovered.is_some() {
+            dialogs.open(dialog::DialogId::Resume);
+        }
         Self {
-            minsweeper: make_game(settings_menu.settings()),
+            windows: windows::Windows::default(),
+            placements: Detached::ALL.into_iter()
+                    .filter_map(|view| Some((view, view.load_placement()?)))
+                    .collect(),
+            // the window says which way it's turned once it's open
+            minsweeper: make_game(settings_menu.settings(), settings_menu.settings().texture(), false, false, power, clock.clone()),
             settings_menu,
+            mode: Mode::Normal,
+            arena: None,
+            statistics: stats::Statistics::default(),
+            storage: storage::Storage::default(),
+            exporter: export::Exporter::default(),
+            installer: texture::install::Installer::default(),
+            board_importer: board_text::BoardImporter::default(),
+            saved_boards: saved_boards::SavedBoards::default(),
+            dialogs,
+            recovered,
+            crash: crash.map(|crash| crash.report),
+            power,
+            debug_overlay: false,
+            clock,
+            system_theme: theme::Mode::None,
+            solver_failure: None,
+            geometry: None,
+            fullscreen: false,
+            size: None,
         }
     }
 }
@@ -43,85 +154,1265 @@ impl Default for State {
 pub enum Message {
     Settings(settings_menu::Message),
     Minsweeper(minsweeper::Message),
+    /// the solver's board in the arena
+    #[from(skip)]
+    Rival(minsweeper::Message),
+    Statistics(stats::Message),
+    Storage(storage::Message),
+    Export(export::Message),
+    Install(texture::install::Message),
+    Board(board_text::Message),
+    Blitz(blitz::Message),
+    SavedBoards(saved_boards::Message),
+    Dialog(dialog::Message),
+    Resume(bool),
+    CopyCrashReport,
+    DismissCrash,
+    CloseRequested(window::Id),
+    PollPower,
+    ToggleDebugOverlay,
+    Submit,
+    /// whatever dialog is on top, and a chord that's being held
+    CloseDialog,
+    /// settings are written and every window closed, like closing the main one
+    Quit,
+    Power(power::PowerSource),
+    SystemTheme(theme::Mode),
+    SolverFailed(solvers::external::ExternalError),
+    /// the pack in use changed on disk
+    PackReloaded(texture::pack::Reloaded),
+    Window(WindowMessage),
+    WindowChanged(window::Id, window::Event),
+    /// the monitor the main window opened on, `None` if it isn't on one
+    PlaceWindow(window::Id, Option<iced::Size>),
 }
 
 impl State {
 
-    pub fn init() -> (Self, Task<Message>) {
-        (Self::default(), Task::done(minsweeper::Message::Restart).map(Into::into))
+    pub fn init(launch: &launch::Launch) -> (Self, Task<Message>) {
+        let geometry = geometry::Geometry::load();
+        let (id, open) = window::open(match geometry {
+            Some(geometry) => window::Settings {
+                size: geometry.size(),
+                position: geometry.position(),
+                ..window_settings()
+            },
+            None => window_settings(),
+        });
+        let state = Self {
+            windows: windows::Windows::with_main(id),
+            size: geometry.map(|geometry| geometry.size()),
+            geometry,
+            ..Self::new(settings_menu::SettingsMenu::with_launch(launch))
+        };
+        logging::set_level(state.settings_menu.settings().log_level());
+        let always_on_top = state.settings_menu.settings().always_on_top();
+        (state, Task::batch([
+            open.then(move |id| Task::batch([
+                if always_on_top {
+                    window::set_level(id, window::Level::AlwaysOnTop)
+                } else {
+                    Task::none()
+                },
+                // wherever it was saved might not be on screen anymore
+                match geometry {
+                    Some(_) => window::monitor_size(id)
+                            .map(move |monitor| Message::PlaceWindow(id, monitor)),
+                    None => Task::none(),
+                },
+            ])),
+            Task::done(minsweeper::Message::Restart).map(Into::into),
+            iced::system::theme().map(Message::SystemTheme),
+        ]))
     }
 
     pub fn update(&mut self, message: Message) -> Task<Message> {
         match message {
+            // picking the size that's already being played keeps the game going
+            Message::Settings(settings_menu::Message::ChangeSize(size)) if size == self.settings_menu.settings().size() => {
+                self.update(dialog::Message::Close(dialog::DialogId::CustomSize).into())
+            }
+            Message::Settings(settings_menu::Message::Dialog(e)) | Message::Minsweeper(minsweeper::Message::Dialog(e))
+                    | Message::SavedBoards(saved_boards::Message::Dialog(e)) => {
+                self.update(e.into())
+            }
+            Message::Dialog(e) => {
+                self.dialogs.update(e);
+                Task::none()
+            }
+            Message::Minsweeper(minsweeper::Message::ToggleFlagMode) => {
+                self.update(settings_menu::Message::FlagMode(!self.settings_menu.settings().flag_mode()).into())
+            }
+            Message::Settings(settings_menu::Message::Pause) => {
+                self.update(minsweeper::Message::Pause.into())
+            }
+            Message::Settings(settings_menu::Message::Step) => {
+                self.update(minsweeper::Message::Step.into())
+            }
+            Message::Settings(settings_menu::Message::Fullscreen) => {
+                self.update(WindowMessage::ToggleFullscreen.into())
+            }
+            Message::Settings(settings_menu::Message::CopyBoard) | Message::Minsweeper(minsweeper::Message::CopyBoard) => {
+                let text = share::text_grid(&t!("title", game = self.minsweeper.title()), &self.minsweeper.displayed_gamestate());
+                Task::batch([
+                    iced::clipboard::write(text),
+                    self.exporter.notify(t!("board-copied"))
+                            .map(Into::into),
+                ])
+            }
+            Message::Settings(settings_menu::Message::ExportBoardImage) | Message::Minsweeper(minsweeper::Message::ExportBoardImage) => {
+                let gamestate = self.minsweeper.displayed_gamestate();
+                let texture = self.texture();
+                let cell_size = self.settings_menu.settings().image_cell_size();
+                self.exporter.export(export::Request::new("board.png", ("PNG", &["png"]), move |writer| {
+                    let png = share::render_png(&gamestate, texture, cell_size)
+                            .map_err(std::io::Error::other)?;
+                    writer.write_all(&png)
+                })).map(Into::into)
+            }
+            Message::Minsweeper(minsweeper::Message::PlayEdited) => match self.minsweeper.edited() {
+                Some(layout) => self.play_board(layout),
+                None => Task::none(),
+            },
+            Message::Minsweeper(minsweeper::Message::SaveEdited) => match self.minsweeper.edited() {
+                Some(layout) => self.update(saved_boards::Message::Save(layout).into()),
+                None => Task::none(),
+            },
+            Message::Minsweeper(minsweeper::Message::BrowseBoards) => {
+                self.update(saved_boards::Message::Browse.into())
+            }
+            Message::Settings(settings_menu::Message::FitWindow) => {
+                let Some(id) = self.windows.main() else { return Task::none() };
+                let board = self.fitting_size();
+                // the window is sized in pixels before the ui scale, everything in it after
+                let size = iced::Size::new(board.width, board.height + MENU_BAR_HEIGHT)
+                        * self.settings_menu.settings().ui_scale();
+                window::monitor_size(id)
+                        .then(move |monitor| window::resize(id, monitor
+                                .map_or(size, |monitor| geometry::fit_to_monitor(size, monitor))))
+            }
             Message::Settings(e) => {
-                let task = self.settings_menu.update(e.clone());
-                use settings_menu::Message::*;
-                match e {
-                    ChangeSize(_) | ChangeSolver(_) => {
-                        self.minsweeper = make_game(self.settings_menu.settings());
-                        return Task::done(minsweeper::Message::Restart)
-                                .map(Into::into)
+                // changing a setting picks the game back up
+                if !e.is_passive() {
+                    self.minsweeper.set_paused(false);
+                    self.minsweeper.stop_auto_restart();
+                }
+                let task = self.settings_menu.update(e.clone())
+                        .map(Into::into);
+                Task::batch([task, self.apply_setting(e), self.sync_arena(), self.sync_blitz()])
+            }
+            // the board is only there to look at once a blitz is over, and it's played without help
+            Message::Minsweeper(e) if match &self.mode {
+                Mode::Blitz(blitz) => (blitz.is_over() && e.is_input()) || matches!(e, minsweeper::Message::Step),
+                Mode::Normal => false,
+            } => Task::none(),
+            Message::Minsweeper(e) => {
+                let mut task = Task::none();
+                let now = self.clock.now();
+                if let minsweeper::Message::GameOver(game_over) = &e
+                        && let Mode::Blitz(blitz) = &mut self.mode
+                        && !blitz.is_over() {
+                    // the next board comes straight away, win or lose
+                    match game_over.status {
+                        GameStatus::Won => blitz.won(),
+                        GameStatus::Lost => blitz.lost(now),
+                        _ => {}
                     }
-                    ChangeTexture(texture) => {
-                        self.minsweeper.change_textures(texture)
+                    if matches!(game_over.status, GameStatus::Won | GameStatus::Lost) {
+                        task = Task::done(minsweeper::Message::Restart.into());
                     }
-                    Auto(_) | ChangeAutoSolver(_) | ChangeAutoDelay(_) => {
-                        self.minsweeper.set_auto(self.settings_menu.settings().auto().cloned())
+                }
+                // walking away from a board that's going badly costs the same as losing it
+                if matches!(e, minsweeper::Message::Restart | minsweeper::Message::RestartSameBoard)
+                        && self.minsweeper.game_status() == GameStatus::Playing
+                        && let Mode::Blitz(blitz) = &mut self.mode
+                        && !blitz.is_over() {
+                    blitz.lost(now);
+                }
+                if let minsweeper::Message::GameOver(game_over) = &e {
+                    // blitz boards are a run of their own, kept apart from the Beginner record
+                    if matches!(self.mode, Mode::Normal) {
+                        task = Task::batch([task, self.statistics.update(stats::Message::Record(*game_over))
+                                .map(Into::into)]);
                     }
-                    FlagChord(value) => {
-                        self.minsweeper.set_flag_chord(value)
+                    if matches!(game_over.status, GameStatus::Won | GameStatus::Lost) {
+                        task = task.chain(self.storage.update(storage::Message::Enforce(self.settings_menu.settings().retention()))
+                                .map(Into::into));
                     }
-                    HoverChord(value) => {
-                        self.minsweeper.set_hover_chord(value)
+                }
+                // the rival starts over with the player, as soon as the new board is opened
+                if matches!(e, minsweeper::Message::Restart | minsweeper::Message::RestartSameBoard) {
+                    self.reset_arena();
+                }
+                let task = Task::batch([
+                    task,
+                    self.minsweeper.update(e)
+                            .map(Into::into),
+                ]);
+                Task::batch([task, self.sync_arena(), self.sync_blitz()])
+            }
+            Message::Blitz(blitz::Message::Start) => {
+                self.dialogs.close(dialog::DialogId::BlitzResults);
+                self.abandon_game();
+                let settings = self.settings_menu.settings();
+                self.minsweeper = make_game_with_size(settings, ConventionalSize::Beginner.size(), self.texture(), self.portrait(), self.spectating(), self.power, self.clock.clone());
+                self.minsweeper.set_auto(None);
+                self.minsweeper.set_game_over_dialog(false);
+                self.minsweeper.set_forgiving(false);
+                self.minsweeper.set_mistake_guard(false);
+                self.minsweeper.set_rules(minsweeper::rules::RuleStack::default());
+                self.mode = Mode::Blitz(blitz::Blitz::new(self.clock.now(), settings.blitz_penalty()));
+                Task::done(minsweeper::Message::Restart)
+                        .map(Into::into)
+            }
+            Message::Blitz(blitz::Message::Tick) => self.sync_blitz(),
+            Message::Blitz(blitz::Message::Leave) => {
+                self.dialogs.close(dialog::DialogId::BlitzResults);
+                self.abandon_game();
+                self.minsweeper = make_game(self.settings_menu.settings(), self.texture(), self.portrait(), self.spectating(), self.power, self.clock.clone());
+                Task::done(minsweeper::Message::Restart)
+                        .map(Into::into)
+            }
+            Message::Rival(e) => match &mut self.arena {
+                Some(arena) => arena.update(e, &self.minsweeper)
+                        .map(Message::Rival),
+                None => Task::none(),
+            },
+            Message::Storage(e) => {
+                let mut task = Task::none();
+                if let storage::Message::ChangeRetention(retention) = e {
+                    task = self.settings_menu.update(settings_menu::Message::ChangeRetention(retention))
+                            .map(Into::into);
+                }
+                Task::batch([
+                    task,
+                    self.storage.update(e)
+                            .map(Into::into),
+                ])
+            }
+            Message::Statistics(stats::Message::Detach) => {
+                let _ = self.statistics.update(stats::Message::Dialog(false));
+                self.detach(Detached::Statistics)
+            }
+            Message::Statistics(stats::Message::DetachAchievements) => {
+                let _ = self.statistics.update(stats::Message::Achievements(false));
+                self.detach(Detached::Achievements)
+            }
+            Message::Statistics(stats::Message::Export) => {
+                let csv = self.statistics.csv();
+                self.exporter.export(export::Request::new("statistics.csv", ("CSV", &["csv"]),
+                        move |writer| writer.write_all(csv.as_bytes())))
+                        .map(Into::into)
+            }
+            Message::Statistics(stats::Message::Unlocked(unlocked)) => {
+                let names = unlocked.iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                self.exporter.notify(t!("achievement-unlocked", names = names))
+                        .map(Into::into)
+            }
+            Message::Statistics(e) => {
+                self.statistics.update(e)
+                        .map(Into::into)
+            }
+            Message::Export(e) => {
+                self.exporter.update(e)
+                        .map(Into::into)
+            }
+            Message::Install(e) => {
+                // a replaced pack that's in use has to be redrawn with its new assets
+                if let texture::install::Message::Installed(Ok(id)) = &e
+                        && self.texture() == texture::Texture::Pack(*id) {
+                    self.change_textures(texture::Texture::Pack(*id));
+                }
+                self.installer.update(e)
+                        .map(Into::into)
+            }
+            Message::Board(board_text::Message::Loaded(layout, dialect)) => {
+                Task::batch([
+                    self.play_board(layout),
+                    self.exporter.notify(t!("opened-board", dialect = dialect))
+                            .map(Into::into),
+                ])
+            }
+            Message::Board(e) => {
+                self.board_importer.update(e)
+                        .map(Into::into)
+            }
+            Message::SavedBoards(e) => {
+                let mut task = Task::none();
+                match &e {
+                    saved_boards::Message::Saved(Ok(name)) => {
+                        task = self.exporter.notify(t!("board-saved", name = name))
+                                .map(Into::into);
+                    }
+                    saved_boards::Message::Loaded(Ok(layout)) => {
+                        self.abandon_game();
+                        self.minsweeper = make_game_with_size(self.settings_menu.settings(), layout.size, self.texture(), self.portrait(), self.spectating(), self.power, self.clock.clone());
+                        self.minsweeper.edit(layout.mines.iter().copied());
+                        self.reset_arena();
                     }
                     _ => {}
                 }
-                task.map(Into::into)
+                Task::batch([
+                    task,
+                    self.saved_boards.update(e)
+                            .map(Into::into),
+                ])
             }
-            Message::Minsweeper(e) => {
-                self.minsweeper.update(e)
+            Message::CopyCrashReport => {
+                let Some(report) = &self.crash else { return Task::none() };
+                iced::clipboard::write(privacy::redact(report))
+            }
+            Message::DismissCrash => {
+                self.crash = None;
+                self.dialogs.close(dialog::DialogId::Crash);
+                Task::none()
+            }
+            Message::Resume(resume) => {
+                self.dialogs.close(dialog::DialogId::Resume);
+                self.dialogs.close(dialog::DialogId::Crash);
+                self.crash = None;
+                let Some(recovered) = self.recovered.take() else { return Task::none() };
+                if !resume {
+                    if let Err(e) = journal::archive() {
+                        tracing::error!("failed to archive journal: {}", e);
+                    }
+                    return Task::none()
+                }
+
+                self.minsweeper = make_game_with_size(self.settings_menu.settings(), recovered.size, self.texture(), self.portrait(), self.spectating(), self.power, self.clock.clone());
+                self.reset_arena();
+                let layout = minsweeper::layout_from_mines(recovered.size, &recovered.mines);
+                self.minsweeper.resume(layout, recovered.seed, recovered.clicks)
+                        .map(Into::into)
+            }
+            Message::CloseRequested(id) => {
+                if !self.windows.is_main(id) {
+                    let view = self.windows.close(id);
+                    if let Some(view) = view && let Some(placement) = self.placements.get(&view) {
+                        view.save_placement(placement);
+                    }
+                    // the game goes on without it, only the snapshot it needed stops being kept
+                    if view == Some(Detached::Spectator) {
+                        self.minsweeper.set_spectated(false);
+                    }
+                    return window::close(id)
+                }
+
+                self.settings_menu.flush();
+                if let Some(geometry) = self.geometry
+                        && let Err(e) = geometry.save() {
+                    tracing::error!("failed to save the window geometry: {}", e);
+                }
+                solvers::external::kill_all();
+                if let Err(e) = journal::mark_clean_exit() {
+                    tracing::error!("failed to mark clean exit: {}", e);
+                }
+                for view in self.windows.views() {
+                    if let Some(placement) = self.placements.get(&view) {
+                        view.save_placement(placement);
+                    }
+                }
+                // detached windows belong to the main one, the daemon itself keeps running until told
+                // to exit
+                Task::batch(self.windows.close_all()
+                        .into_iter()
+                        .map(window::close))
+                        .chain(iced::exit())
+            }
+            Message::Window(e) => {
+                let Some(id) = self.windows.main() else { return Task::none() };
+                match e {
+                    WindowMessage::ToggleFullscreen => {
+                        self.fullscreen = !self.fullscreen;
+                        if self.fullscreen {
+                            window::set_mode(id, window::Mode::Fullscreen)
+                        } else {
+                            let restore = self.geometry
+                                    .map_or_else(Task::none, |geometry| window::resize(id, geometry.size()));
+                            window::set_mode(id, window::Mode::Windowed)
+                                    .chain(restore)
+                        }
+                    }
+                    WindowMessage::AlwaysOnTop(value) => {
+                        window::set_level(id, if value { window::Level::AlwaysOnTop } else { window::Level::Normal })
+                    }
+                    WindowMessage::Mode(mode) => {
+                        self.fullscreen = mode == window::Mode::Fullscreen;
+                        Task::none()
+                    }
+                }
+            }
+            Message::WindowChanged(id, e) => {
+                if let Some(view) = self.windows.view(id) {
+                    let placement = self.placements.get(&view).copied();
+                    let placement = match (e, placement) {
+                        (window::Event::Opened { position, size }, _) => Some(geometry::Geometry::new(size, position)),
+                        (window::Event::Moved(position), Some(placement)) => Some(geometry::Geometry {
+                            position: Some((position.x, position.y)),
+                            ..placement
+                        }),
+                        (window::Event::Resized(size), Some(placement)) => Some(geometry::Geometry {
+                            width: size.width,
+                            height: size.height,
+                            ..placement
+                        }),
+                        _ => placement,
+                    };
+                    if let Some(placement) = placement {
+                        self.placements.insert(view, placement);
+                    }
+                    return Task::none()
+                }
+                // some platforms minimise a window by shrinking it to nothing, others only say so
+                // when asked. fullscreen can be left through the system too, which only shows as a
+                // resize
+                let asked = match e {
+                    window::Event::Resized(size) if size.width == 0.0 || size.height == 0.0 =>
+                        return self.update(minsweeper::Message::ClearPressed.into()),
+                    window::Event::Resized(_) if self.windows.is_main(id) => Task::batch([
+                        window::is_minimized(id)
+                                .then(|minimized| match minimized {
+                                    Some(true) => Task::done(minsweeper::Message::ClearPressed.into()),
+                                    _ => Task::none(),
+                                }),
+                        window::mode(id)
+                                .map(|mode| Message::Window(WindowMessage::Mode(mode))),
+                    ]),
+                    _ => Task::none(),
+                };
+                if self.windows.is_main(id) && let window::Event::Opened { size, .. } | window::Event::Resized(size) = e {
+                    self.size = Some(size);
+                    self.sync_portrait();
+                }
+                if self.windows.is_main(id) && !self.fullscreen {
+                    match e {
+                        window::Event::Opened { position, size } => {
+                            self.geometry = Some(geometry::Geometry::new(size, position));
+                        }
+                        window::Event::Moved(position) => {
+                            if let Some(geometry) = &mut self.geometry {
+                                geometry.position = Some((position.x, position.y));
+                            }
+                        }
+                        window::Event::Resized(size) => {
+                            if let Some(geometry) = &mut self.geometry {
+                                geometry.width = size.width;
+                                geometry.height = size.height;
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                asked
+            }
+            Message::PlaceWindow(id, monitor) => match (monitor, self.placement(id)) {
+                (None, _) => window::move_to(id, iced::Point::ORIGIN),
+                (Some(monitor), Some(geometry)) => {
+                    let size = geometry::fit_to_monitor(geometry.size(), monitor);
+                    let resized = if size != geometry.size() {
+                        window::resize(id, size)
+                    } else {
+                        Task::none()
+                    };
+                    let moved = match geometry.position {
+                        Some((x, y)) => {
+                            let position = iced::Point::new(x, y);
+                            let fitted = geometry::fit_position(position, size, monitor);
+                            if fitted != position { window::move_to(id, fitted) } else { Task::none() }
+                        }
+                        None => Task::none(),
+                    };
+                    Task::batch([resized, moved])
+                }
+                (Some(_), None) => Task::none(),
+            },
+            Message::PollPower => {
+                Task::future(tokio::task::spawn_blocking(power::detect))
+                        .map(|source| Message::Power(source.unwrap_or(power::PowerSource::Unknown)))
+            }
+            Message::Submit => {
+                // the seed field submits by itself
+                let action = match self.dialogs.topmost() {
+                    Some(dialog::DialogId::CustomSize) => self.settings_menu.custom_size_action(),
+                    _ => None,
+                };
+                match action {
+                    Some(action) => self.update(action.into()),
+                    None => Task::none(),
+                }
+            }
+            Message::CloseDialog => {
+                // the stacked dialogs are drawn over the flagged ones
+                let closed = match self.dialogs.topmost() {
+                    Some(_) => {
+                        self.dialogs.update(dialog::Message::Dismiss);
+                        Task::none()
+                    }
+                    None => match self.dialogs().last().map(|flagged| flagged.close) {
+                        Some(close) => self.update(close),
+                        None => Task::none(),
+                    },
+                };
+                closed.chain(self.update(minsweeper::Message::CancelChord.into()))
+            }
+            Message::Quit => match self.windows.main() {
+                Some(id) => self.update(Message::CloseRequested(id)),
+                None => iced::exit(),
+            },
+            Message::PackReloaded(reloaded) => {
+                if self.texture() != texture::Texture::Pack(reloaded.id) {
+                    return Task::none()
+                }
+                self.change_textures(texture::Texture::Pack(reloaded.id));
+                if reloaded.kept.is_empty() {
+                    Task::none()
+                } else {
+                    self.exporter.notify(t!("texture-reload-kept", files = reloaded.kept.join(", ")))
+                            .map(Into::into)
+                }
+            }
+            Message::SolverFailed(failure) => {
+                self.solver_failure = Some(failure);
+                self.update(dialog::Message::Open(dialog::DialogId::ExternalSolverFailed).into())
+            }
+            Message::ToggleDebugOverlay => {
+                if !self.settings_menu.settings().experiments().enabled(experiments::LATENCY_OVERLAY) {
+                    return Task::none()
+                }
+                self.debug_overlay = !self.debug_overlay;
+                metrics::set_enabled(self.debug_overlay);
+                Task::none()
+            }
+            Message::SystemTheme(mode) => {
+                if mode != self.system_theme {
+                    self.system_theme = mode;
+                    self.change_textures(self.texture());
+                }
+                Task::none()
+            }
+            Message::Power(source) => {
+                if source != self.power {
+                    self.power = source;
+                    self.apply_auto();
+                }
+                Task::none()
+            }
+        }
+    }
+
+    /// pushes a settings change that was just applied out to the parts of the app it affects
+    fn apply_setting(&mut self, message: settings_menu::Message) -> Task<Message> {
+        use settings_menu::Message::*;
+        match message {
+            ChangeSize(_) | ChangeSolver(_) | UseSolverChain if self.settings_menu.refusal().is_some() => {}
+            // the limit only matters to a time trial
+            ApplyTimeLimit if !self.settings_menu.settings().rules().timed() => {}
+            ChangeSize(_) | ChangeFirstClick(_) | ChangeSolver(_) | UseSolverChain | ApplySolverChain | UseExternalSolver
+                    | ApplyExternalSolver | ToggleRule(..) | ApplyTimeLimit | Arena(_) | NoFlags(_) => {
+                self.abandon_game();
+                self.minsweeper = make_game(self.settings_menu.settings(), self.texture(), self.portrait(), self.spectating(), self.power, self.clock.clone());
+                return Task::done(minsweeper::Message::Restart)
+                        .map(Into::into)
+            }
+            ChangeLanguage(language) => {
+                i18n::set_language(language)
+            }
+            ChangeTexture(_) | FollowSystem(_) => {
+                self.change_textures(self.texture())
+            }
+            ThrottleOnBattery(true) => {
+                self.apply_auto();
+                return Task::done(Message::PollPower)
+            }
+            Auto(_) | ChangeAutoSolver(_) | ChangeAutoDelay(_) | ChangeAutoGuess(_) | AutoRestart(_) | ChangeAutoRestartDelay(_) | ThrottleOnBattery(_) | ChangeBatteryMinDelay(_) => {
+                self.apply_auto()
+            }
+            FlagChord(value) => {
+                self.minsweeper.set_flag_chord(value)
+            }
+            DragFlag(value) => {
+                self.minsweeper.set_drag_flag(value)
+            }
+            HoverChord(value) => {
+                self.minsweeper.set_hover_chord(value)
+            }
+            ChangeNeighbourHighlight(modifier) => {
+                self.minsweeper.set_neighbour_highlight(modifier)
+            }
+            ChangeChordDelay(delay) => {
+                self.minsweeper.set_chord_delay(delay)
+            }
+            ChangeHoverChordDelay(delay) => {
+                self.minsweeper.set_hover_chord_delay(delay)
+            }
+            FlagMode(value) => {
+                self.minsweeper.set_flag_mode(value)
+            }
+            LongPressFlag(value) => {
+                self.minsweeper.set_long_press_flag(value)
+            }
+            SolverPreview(value) => {
+                self.minsweeper.set_solver_preview(value)
+            }
+            ChangeStepPreview(delay) => {
+                self.minsweeper.set_step_preview(delay)
+            }
+            ApplyMouseBindings => {
+                self.minsweeper.set_mouse_bindings(self.settings_menu.settings().mouse_bindings())
+            }
+            ProgressRing(value) => {
+                self.minsweeper.set_progress_ring(value)
+            }
+            ShowProgress(value) => {
+                self.minsweeper.set_show_progress(value)
+            }
+            RiskShading(value) => {
+                self.minsweeper.set_risk_shading(value)
+            }
+            AnimateReveals(value) => {
+                self.minsweeper.set_animate_reveals(value)
+            }
+            ReduceMotion(value) => {
+                self.minsweeper.set_reduce_motion(value)
+            }
+            DescribeCells(value) => {
+                self.minsweeper.set_describe_cells(value)
+            }
+            StatusLine(value) => {
+                self.minsweeper.set_status_line(value)
+            }
+            SolverExplanations(value) => {
+                self.minsweeper.set_solver_explanations(value)
+            }
+            AlwaysOnTop(value) => {
+                return self.update(WindowMessage::AlwaysOnTop(value).into())
+            }
+            Sound(_) | ChangeVolume(_) => {
+                sound::set_volume(self.settings_menu.settings().volume())
+            }
+            ChangeCellScale(cell_scale) => {
+                self.minsweeper.set_cell_scale(cell_scale)
+            }
+            ChangeBoardRotation(rotation) => {
+                self.minsweeper.set_rotation(rotation);
+                if let Some(arena) = &mut self.arena {
+                    arena.rival_mut().set_rotation(rotation);
+                }
+            }
+            ChangeCounterMode(counter_mode) => {
+                self.minsweeper.set_counter_mode(counter_mode)
+            }
+            StreamerOverlay(_) | OverlaySeed(_) | ChangeOverlayOpacity(_) => {
+                self.minsweeper.set_overlay(self.settings_menu.settings().overlay())
+            }
+            StreamerMode(value) => {
+                privacy::set_streamer_mode(value);
+                self.minsweeper.set_streamer_mode(value)
+            }
+            ShowProvenance(value) => {
+                return self.minsweeper.set_show_provenance(value)
+                        .map(Into::into)
+            }
+            ChangeLossRevealDelay(delay) => {
+                self.minsweeper.set_loss_reveal_delay(delay)
+            }
+            Forgiving(_) => {
+                let forgiving = self.settings_menu.settings().forgiving() && matches!(self.mode, Mode::Normal);
+                self.minsweeper.set_forgiving(forgiving)
+            }
+            MistakeGuard(_) => {
+                let mistake_guard = self.settings_menu.settings().mistake_guard() && matches!(self.mode, Mode::Normal);
+                self.minsweeper.set_mistake_guard(mistake_guard)
+            }
+            IdlePause(_) | ChangeIdleThreshold(_) => {
+                self.minsweeper.set_idle_pause(self.settings_menu.settings().idle_pause())
+            }
+            GameOverDialog(value) => {
+                self.minsweeper.set_game_over_dialog(value)
+            }
+            SilentAuto(value) => {
+                self.minsweeper.set_silent_auto(value)
+            }
+            Statistics => {
+                return self.statistics.update(stats::Message::Dialog(true))
+                        .map(Into::into)
+            }
+            SpectatorWindow => {
+                let open = self.detach(Detached::Spectator);
+                self.minsweeper.set_spectated(true);
+                return open
+            }
+            Achievements => {
+                return self.statistics.update(stats::Message::Achievements(true))
+                        .map(Into::into)
+            }
+            ChangeSeed(seed) => {
+                self.abandon_game();
+                self.minsweeper.set_seed(seed);
+                return Task::done(minsweeper::Message::Restart)
+                        .map(Into::into)
+            }
+            Storage => {
+                return self.storage.update(storage::Message::Dialog(true))
+                        .map(Into::into)
+            }
+            Imported(_, Ok(_)) => {
+                let settings = self.settings_menu.settings();
+                sound::set_volume(settings.volume());
+                i18n::set_language(settings.language());
+                logging::set_level(settings.log_level());
+                privacy::set_streamer_mode(settings.streamer_mode());
+                self.abandon_game();
+                self.minsweeper = make_game(self.settings_menu.settings(), self.texture(), self.portrait(), self.spectating(), self.power, self.clock.clone());
+                return Task::done(minsweeper::Message::Restart)
+                        .map(Into::into)
+            }
+            ExportSettings => {
+                let json = self.settings_menu.settings().to_pretty_json();
+                return self.exporter.export(export::Request::new("minsweeper-settings.json", ("JSON", &["json"]),
+                        move |writer| writer.write_all(json.as_bytes())))
+                        .map(Into::into)
+            }
+            ToggleExperiment(..) | DisableExperiment(_) => {
+                self.apply_experiments()
+            }
+            ToggleLatencyOverlay => {
+                return Task::done(Message::ToggleDebugOverlay)
+            }
+            OpenBoard => {
+                return self.board_importer.update(board_text::Message::Pick)
+                        .map(Into::into)
+            }
+            Blitz => {
+                return self.update(blitz::Message::Start.into())
+            }
+            EditBoard => {
+                self.abandon_game();
+                self.minsweeper = make_game(self.settings_menu.settings(), self.texture(), self.portrait(), self.spectating(), self.power, self.clock.clone());
+                self.minsweeper.edit([]);
+                self.reset_arena();
+            }
+            BrowseBoards => {
+                return self.update(saved_boards::Message::Browse.into())
+            }
+            SaveBoard(name) => {
+                let Some(dialect) = board_text::dialect(name) else { return Task::none() };
+                let Some(layout) = self.minsweeper.layout() else {
+                    return self.exporter.notify(t!("no-layout-yet"))
+                            .map(Into::into)
+                };
+                let text = dialect.write(&board_text::Layout::from_gamestate(&layout));
+                return self.exporter.export(export::Request::new("board.txt", ("Board", &["txt", "csv"]),
+                        move |writer| writer.write_all(text.as_bytes())))
                         .map(Into::into)
             }
+            InstallTexturePack => {
+                return self.installer.update(texture::install::Message::Pick)
+                        .map(Into::into)
+            }
+            TestExternalSolver => {
+                return self.settings_menu.test_external_solver(self.minsweeper.gamestate())
+                        .map(Into::into)
+            }
+            CopySeed => {
+                return iced::clipboard::write(format!("{:#x}", self.minsweeper.seed()))
+            }
+            CopyLogs => {
+                return iced::clipboard::write(self.settings_menu.logs().to_string())
+            }
+            ChangeLogLevel(level) => {
+                logging::set_level(level)
+            }
+            _ => {}
         }
+        Task::none()
     }
 
+    /// tears down whatever belongs to experiments that were just turned off
+    fn apply_experiments(&mut self) {
+        let experiments = self.settings_menu.settings().experiments();
+        if !experiments.enabled(experiments::LATENCY_OVERLAY) && self.debug_overlay {
+            self.debug_overlay = false;
+            metrics::set_enabled(false);
+            self.minsweeper.reset_latency();
+        }
+        self.minsweeper.set_canvas_board(experiments.enabled(experiments::CANVAS_BOARD));
+    }
+
+    fn apply_auto(&mut self) {
+        let settings = self.settings_menu.settings();
+        self.minsweeper.set_auto(match self.mode {
+            Mode::Normal => settings.auto_for(self.power),
+            Mode::Blitz(_) => None,
+        });
+        self.minsweeper.set_throttled(settings.throttle(self.power).throttled);
+        if let Some(arena) = &mut self.arena {
+            arena.rival_mut().set_auto(Some(settings.arena_auto(self.power)));
+        }
+    }
+
+    fn detach(&mut self, view: Detached) -> Task<Message> {
+        if let Some(id) = self.windows.find(view) {
+            return window::gain_focus(id)
+        }
+
+        let placement = self.placements.get(&view).copied();
+        let (id, open) = window::open(window::Settings {
+            size: placement.map_or(view.size(), |placement| placement.size()),
+            position: placement.map(|placement| placement.position()).unwrap_or_default(),
+            ..window_settings()
+        });
+        self.windows.detach(id, view);
+        match placement {
+            // the monitor it was on might not be there anymore
+            Some(_) => open.then(|id| window::monitor_size(id)
+                    .map(move |monitor| Message::PlaceWindow(id, monitor))),
+            None => open.discard(),
+        }
+    }
+
+    /// where the window was last time, for putting it back
+    fn placement(&self, id: window::Id) -> Option<geometry::Geometry> {
+        match self.windows.view(id) {
+            Some(view) => self.placements.get(&view).copied(),
+            None => self.geometry,
+        }
+    }
+
+    /// replaces the game with one on `layout`, from a file or the editor
+    fn play_board(&mut self, layout: board_text::Layout) -> Task<Message> {
+        self.abandon_game();
+        self.minsweeper = make_game_with_size(self.settings_menu.settings(), layout.size, self.texture(), self.portrait(), self.spectating(), self.power, self.clock.clone());
+        self.reset_arena();
+        self.minsweeper.play_layout(minsweeper::layout_from_mines(layout.size, &layout.mines))
+                .map(Into::into)
+    }
+
+    /// a new rival for the player's board, or none if the arena is off or a blitz is on
+    fn reset_arena(&mut self) {
+        let settings = self.settings_menu.settings();
+        self.arena = (settings.arena() && matches!(self.mode, Mode::Normal)).then(|| {
+            let mut rival = make_game_with_size(settings, self.minsweeper.size(), self.texture(), self.portrait(), false, self.power, self.clock.clone());
+            rival.set_auto(Some(settings.arena_auto(self.power)));
+            rival.set_game_over_dialog(false);
+            // the solver only ever gets stuck without flags
+            rival.set_no_flags(false);
+            arena::Arena::new(rival)
+        });
+    }
+
+    fn sync_arena(&mut self) -> Task<Message> {
+        match &mut self.arena {
+            Some(arena) => arena.sync(&self.minsweeper)
+                    .map(Message::Rival),
+            None => Task::none(),
+        }
+    }
+
+    /// keeps a blitz's countdown stopped while the game is paused, and ends it once time is up
+    fn sync_blitz(&mut self) -> Task<Message> {
+        let Mode::Blitz(blitz) = &mut self.mode else { return Task::none() };
+        let now = self.clock.now();
+        blitz.set_paused(self.minsweeper.paused(), now);
+        if blitz.is_over() || !blitz.remaining(now).is_zero() {
+            return Task::none()
+        }
+        let best = self.statistics.blitz_best();
+        let _ = self.statistics.update(stats::Message::RecordBlitz(blitz.cleared()));
+        blitz.finish(best);
+        let _ = self.minsweeper.update(minsweeper::Message::ClearPressed);
+        self.update(dialog::Message::Open(dialog::DialogId::BlitzResults).into())
+    }
+
+    /// whether the main window is taller than it's wide, boards can be turned to suit
+    fn portrait(&self) -> bool {
+        self.size.is_some_and(|size| size.height > size.width)
+    }
+
+    fn spectating(&self) -> bool {
+        self.windows.find(Detached::Spectator).is_some()
+    }
+
+    fn sync_portrait(&mut self) {
+        let portrait = self.portrait();
+        self.minsweeper.set_portrait(portrait);
+        if let Some(arena) = &mut self.arena {
+            arena.rival_mut().set_portrait(portrait);
+        }
+    }
+
+    fn abandon_game(&mut self) {
+        // a blitz is over as soon as its board is replaced with anything else
+        self.mode = Mode::Normal;
+        // the summary belongs to the game being replaced
+        self.dialogs.close(dialog::DialogId::GameOver);
+        if let Some(game_over) = self.minsweeper.abandon() {
+            let _ = self.statistics.update(stats::Message::Record(game_over));
+        }
+    }
+
+
+    fn change_textures(&mut self, texture: texture::Texture) {
+        self.minsweeper.change_textures(texture);
+        if let Some(arena) = &mut self.arena {
+            arena.change_textures(texture);
+        }
+    }
+
+    /// the texture in use, which depends on the system appearance when following it
+    fn texture(&self) -> texture::Texture {
+        self.settings_menu.settings().texture_for(self.system_theme)
+    }
+
+    /// the menus and dialogs, which match the board unless another theme was picked for them
+    fn theme(&self, _id: window::Id) -> Theme {
+        self.settings_menu.settings().app_theme().resolve(self.texture())
+    }
+
+    /// the whole window sits on the texture's background, not just the board
+    fn style(&self, theme: &Theme) -> theme::Style {
+        theme::Style {
+            background_color: self.texture().manifest().background,
+            text_color: theme.palette().text,
+        }
+    }
+
+    /// the player's board, and the rival's beside it under the race banner in the arena
+    fn fitting_size(&self) -> iced::Size {
+        let board = self.minsweeper.fitting_size();
+        if let Mode::Blitz(_) = self.mode {
+            return iced::Size::new(board.width, board.height + blitz::BANNER_HEIGHT)
+        }
+        match &self.arena {
+            Some(arena) => {
+                let rival = arena.rival().fitting_size();
+                iced::Size::new(board.width + rival.width, board.height.max(rival.height) + arena::BANNER_HEIGHT)
+            }
+            None => board,
+        }
+    }
+
+    /// the board works in the scaled units too, so fitting cells to the window doesn't scale them
+    /// a second time
+    fn scale_factor(&self, _id: window::Id) -> f32 {
+        self.settings_menu.settings().ui_scale()
+    }
+
+    fn title(&self, id: window::Id) -> String {
+        match self.windows.view(id) {
+            Some(Detached::Statistics) => t!("title-statistics").to_string(),
+            Some(Detached::Achievements) => t!("title-achievements").to_string(),
+            Some(Detached::Spectator) => t!("title-spectator", game = self.minsweeper.title()),
+            None => t!("title", game = self.minsweeper.title()),
+        }
+    }
 
     fn subscriptions(&self) -> Subscription<Message> {
-        iced::event::listen()
-                .filter_map(|e| if let Event::Mouse(mouse::Event::ButtonReleased(e)) = e {
-                    Some(minsweeper::Message::MouseRelease(e).into())
-                } else {
-                    None
+        Subscription::batch([
+            iced::event::listen()
+                    .filter_map(|e| match e {
+                        Event::Mouse(mouse::Event::ButtonReleased(e)) => Some(minsweeper::Message::MouseRelease(e).into()),
+                        // minimising takes the focus too, and a window coming up over this one
+                        // takes the cursor, either way nothing on the board is held anymore
+                        Event::Touch(touch::Event::FingerLifted { .. } | touch::Event::FingerLost { .. })
+                        | Event::Window(window::Event::Unfocused)
+                        | Event::Mouse(mouse::Event::CursorLeft) => Some(minsweeper::Message::ClearPressed.into()),
+                        Event::Window(window::Event::FileDropped(path))
+                                if path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("zip")) =>
+                            Some(texture::install::Message::Open(Some(path)).into()),
+                        _ => None
+                    }),
+            window::close_requests()
+                    .map(Message::CloseRequested),
+            window::events()
+                    .filter_map(|(id, e)| matches!(e, window::Event::Opened { .. } | window::Event::Moved(_) | window::Event::Resized(_))
+                            .then_some(Message::WindowChanged(id, e))),
+            iced::system::theme_changes()
+                    .map(Message::SystemTheme),
+            Subscription::run(solvers::external::failures)
+                    .map(Message::SolverFailed),
+            // stopped by switching away from the pack, it's dropped with the subscription
+            match self.texture() {
+                texture::Texture::Pack(id) => Subscription::run_with(id, texture::pack::watch)
+                        .map(Message::PackReloaded),
+                _ => Subscription::none(),
+            },
+            iced::time::every(journal::FLUSH_INTERVAL)
+                    .map(|_| minsweeper::Message::FlushJournal.into()),
+            if self.settings_menu.settings().throttle_on_battery() {
+                iced::time::every(power::POLL_INTERVAL)
+                        .map(|_| Message::PollPower)
+            } else {
+                Subscription::none()
+            },
+            if self.minsweeper.awaiting_frame() {
+                window::frames()
+                        .map(|at| minsweeper::Message::Frame(at).into())
+            } else {
+                Subscription::none()
+            },
+            match &self.mode {
+                Mode::Blitz(blitz) if !blitz.is_over() => iced::time::every(Duration::from_secs(1))
+                        .map(|_| blitz::Message::Tick.into()),
+                _ => Subscription::none(),
+            },
+            if self.arena.as_ref().is_some_and(|arena| arena.rival().awaiting_frame()) {
+                window::frames()
+                        .map(|at| Message::Rival(minsweeper::Message::Frame(at)))
+            } else {
+                Subscription::none()
+            },
+            if self.minsweeper.over_flagged() {
+                iced::time::every(minsweeper::OVER_FLAG_BLINK)
+                        .map(|_| minsweeper::Message::Repaint.into())
+            } else {
+                Subscription::none()
+            },
+            // the same tick looks for a game that's been left alone
+            if self.minsweeper.timed() || self.minsweeper.watching_idle() {
+                iced::time::every(Duration::from_secs(1))
+                        .map(|_| minsweeper::Message::Repaint.into())
+            } else {
+                Subscription::none()
+            },
+            // the board's scrollable takes the wheel first, ctrl+scroll has to be seen anyway
+            if self.minsweeper.zoomable() {
+                iced::event::listen_with(|event, _status, _window| match event {
+                    Event::Mouse(mouse::Event::WheelScrolled { delta }) =>
+                        Some(minsweeper::Message::Wheel(delta).into()),
+                    _ => None,
                 })
+            } else {
+                Subscription::none()
+            },
+            // a blocking dialog on top keeps game shortcuts from reaching the board, and so does any
+            // flagged one, their number fields don't capture the keys typed into them
+            keyboard::listen()
+                    .with(self.dialogs.blocking() || self.dialogs().next().is_some())
+                    .filter_map(|(blocking, e)| match e {
+                        keyboard::Event::ModifiersChanged(modifiers) =>
+                            Some(minsweeper::Message::Modifiers(modifiers).into()),
+                        // a focused text field takes its keys before they'd get here
+                        keyboard::Event::KeyPressed { key, modifiers, .. } => shortcuts::find(&key, modifiers)
+                                .filter(|shortcut| !(blocking && shortcut.for_game()))
+                                .map(shortcut),
+                        _ => None
+                    }),
+        ])
     }
 
-    pub fn view(&self) -> Element<'_, Message> {
+    pub fn view(&self, id: window::Id) -> Element<'_, Message> {
+        if let Some(detached) = self.windows.view(id) {
+            let content: Element<_> = match detached {
+                Detached::Statistics => Element::from(widget::column![
+                    widget::scrollable(self.statistics.content()),
+                    widget::row![
+                        widget::button(t!("statistics-reset-all")).on_press(stats::Message::ResetAll),
+                        widget::button(t!("statistics-export")).on_press(stats::Message::Export),
+                    ].spacing(10),
+                ].spacing(10)).map(Into::into),
+                Detached::Achievements => Element::from(widget::scrollable(self.statistics.achievements_content()))
+                        .map(Into::into),
+                // it fills its window and has its own background, and it's never sent any input
+                Detached::Spectator => return self.minsweeper.spectator_view().map(Into::into),
+            };
+            return widget::container(content)
+                    .padding(10)
+                    .into()
+        }
+
+        let manifest = self.texture().manifest();
         let base = widget::column![
-            self.settings_menu.view().map(Into::into),
-            self.minsweeper.view().map(Into::into),
+            widget::container(self.settings_menu.view(manifest).map(Into::into))
+                    .width(Length::Fill)
+                    .style(move |_| widget::container::Style::default().background(manifest.header)),
+            match (&self.mode, &self.arena) {
+                (Mode::Blitz(blitz), _) => Element::from(widget::column![
+                    blitz.banner(self.texture(), self.clock.now()).map(Into::into),
+                    self.minsweeper.view().map(Into::into),
+                ]),
+                (Mode::Normal, Some(arena)) => Element::from(widget::column![
+                    arena.banner().map(Into::into),
+                    widget::row![
+                        self.minsweeper.view().map(Into::into),
+                        arena.rival().view().map(Message::Rival),
+                    ],
+                ]),
+                (Mode::Normal, None) => self.minsweeper.view().map(Into::into),
+            },
         ];
+        let base = widget::stack![base]
+                .push(self.exporter.toast().map(|toast| widget::container(toast.map(Into::into))
+                        .padding(20)
+                        .width(Length::Fill)
+                        .height(Length::Fill)
+                        .align_x(Horizontal::Right)
+                        .align_y(Vertical::Bottom)))
+                .push(self.debug_overlay.then(|| widget::container(widget::container(widget::text(self.minsweeper.latency_summary())
+                                .size(12))
+                                .padding(6)
+                                .style(widget::container::rounded_box))
+                        .padding(20)
+                        .width(Length::Fill)
+                        .height(Length::Fill)
+                        .align_x(Horizontal::Left)
+                        .align_y(Vertical::Bottom)));
         self.process_dialog(base)
     }
 
+    /// the dialogs on the stack go above the rest, a click outside the top one dismisses it
     pub fn process_dialog<'a>(&self, content: impl Into<Element<'a, Message>>) -> Element<'a, Message> {
         let mut view = content.into();
         for dialog in self.dialogs() {
-            view = iced_dialog::dialog(true, view, dialog)
+            view = iced_dialog::dialog(true, view, dialog.content)
                     .into();
         }
+        for id in self.dialogs.ordered() {
+            if let Some(dialog) = self.dialog(id) {
+                // at a big ui scale a dialog can be taller than the window
+                view = iced_dialog::dialog(true, view, widget::scrollable(dialog))
+                        .on_press(dialog::Message::Dismiss.into())
+                        .into();
+            }
+        }
         view
     }
 
-    pub fn dialogs<'a>(&self) -> impl Iterator<Item = Element<'a, Message>> {
+    fn dialog<'a>(&self, id: dialog::DialogId) -> Option<Element<'a, Message>> {
+        match id {
+            dialog::DialogId::Resume => Some(widget::column![
+                widget::text(t!("resume-title")),
+                widget::row![
+                    widget::button(t!("resume-discard")).on_press(Message::Resume(false)),
+                    widget::button(t!("resume")).on_press(Message::Resume(true)),
+                ].spacing(10),
+            ].spacing(10).into()),
+            dialog::DialogId::Crash => self.crash.as_ref().map(|report| widget::column![
+                widget::text(t!("crash-title")),
+                widget::scrollable(widget::text(privacy::redact(report)).font(iced::Font::MONOSPACE).size(12))
+                        .height(300)
+                        .width(600),
+                widget::row![widget::button(t!("crash-copy")).on_press(Message::CopyCrashReport)]
+                        .extend(match self.recovered {
+                            Some(_) => vec![
+                                widget::button(t!("resume-discard")).on_press(Message::Resume(false)).into(),
+                                widget::button(t!("resume")).on_press(Message::Resume(true)).into(),
+                            ],
+                            None => vec![widget::button(t!("ok")).on_press(Message::DismissCrash).into()],
+                        })
+                        .spacing(10),
+            ].spacing(10).into()),
+            dialog::DialogId::CustomSize | dialog::DialogId::Seed | dialog::DialogId::MouseBindings
+                    | dialog::DialogId::SolverChain | dialog::DialogId::ExternalSolver | dialog::DialogId::Logs
+                    | dialog::DialogId::Shortcuts => self.settings_menu.dialog(id)
+                    .map(|e| e.map(Into::into)),
+            dialog::DialogId::ExternalSolverFailed => self.solver_failure.as_ref().map(|failure| widget::column![
+                widget::text(t!("external-failed-title")),
+                widget::text(privacy::redact(failure)),
+                widget::button(t!("ok")).on_press(dialog::Message::Close(id).into()),
+            ].spacing(10).into()),
+            dialog::DialogId::GameOver | dialog::DialogId::Undo | dialog::DialogId::MistakeGuard => self.minsweeper.dialog(id)
+                    .map(|e| e.map(Into::into)),
+            dialog::DialogId::SaveBoard | dialog::DialogId::LoadBoard => self.saved_boards.dialog(id)
+                    .map(|e| e.map(Into::into)),
+            dialog::DialogId::BlitzResults => match &self.mode {
+                Mode::Blitz(blitz) => blitz.dialog(id)
+                        .map(|e| e.map(Into::into)),
+                Mode::Normal => None,
+            },
+        }
+    }
+
+    pub fn dialogs<'a>(&self) -> impl Iterator<Item = dialog::Flagged<'a, Message>> {
         let mut vec = vec![];
 
         vec.append(&mut self.settings_menu.dialogs()
-                .map(|e| e.map(Into::into)).collect());
+                .map(|flagged| flagged.map(Into::into)).collect());
+        vec.append(&mut self.statistics.dialogs()
+                .map(|flagged| flagged.map(Into::into)).collect());
+        vec.append(&mut self.storage.dialogs(self.settings_menu.settings().retention())
+                .map(|flagged| flagged.map(Into::into)).collect());
+        vec.append(&mut self.exporter.dialogs()
+                .map(|flagged| flagged.map(Into::into)).collect());
+        vec.append(&mut self.installer.dialogs()
+                .map(|flagged| flagged.map(Into::into)).collect());
+        vec.append(&mut self.board_importer.dialogs()
+                .map(|flagged| flagged.map(Into::into)).collect());
 
         vec.into_iter()
     }
 }
 
-fn make_game(settings: &settings_menu::Settings) -> minsweeper::MinsweeperGame {
-    minsweeper::MinsweeperGame::new(settings.size(), settings.solver(), settings.texture(), settings.auto().cloned(), settings.flag_chord(), settings.hover_chord())
-}
\ No newline at end of file
+/// the message a shortcut from [`shortcuts::BINDINGS`] sends
+fn shortcut(shortcut: shortcuts::Shortcut) -> Message {
+    use shortcuts::Shortcut;
+
+    match shortcut {
+        Shortcut::NewGame => minsweeper::Message::Restart.into(),
+        Shortcut::RestartSameBoard => minsweeper::Message::RestartSameBoard.into(),
+        Shortcut::Pause => minsweeper::Message::Pause.into(),
+        Shortcut::Step => minsweeper::Message::Step.into(),
+        Shortcut::Beginner => settings_menu::Message::ChangeSize(ConventionalSize::Beginner.size()).into(),
+        Shortcut::Intermediate => settings_menu::Message::ChangeSize(ConventionalSize::Intermediate.size()).into(),
+        Shortcut::Expert => settings_menu::Message::ChangeSize(ConventionalSize::Expert.size()).into(),
+        Shortcut::CloseDialog => Message::CloseDialog,
+        Shortcut::Submit => Message::Submit,
+        Shortcut::UiScaleUp => settings_menu::Message::NudgeUiScale(1).into(),
+        Shortcut::UiScaleDown => settings_menu::Message::NudgeUiScale(-1).into(),
+        Shortcut::UiScaleReset => settings_menu::Message::ChangeUiScale(1.0).into(),
+        Shortcut::Fullscreen => WindowMessage::ToggleFullscreen.into(),
+        Shortcut::DebugOverlay => Message::ToggleDebugOverlay,
+        Shortcut::Quit => Message::Quit,
+    }
+}
+
+fn make_game(settings: &settings_menu::Settings, texture: texture::Texture, portrait: bool, spectated: bool, power: power::PowerSource, clock: clock::SharedClock) -> minsweeper::MinsweeperGame {
+    make_game_with_size(settings, settings.size(), texture, portrait, spectated, power, clock)
+}
+
+fn make_game_with_size(settings: &settings_menu::Settings, size: BoardSize, texture: texture::Texture, portrait: bool, spectated: bool, power: power::PowerSource, clock: clock::SharedClock) -> minsweeper::MinsweeperGame {
+    let mut game = minsweeper::MinsweeperGame::new(size, settings.opening(), settings.solver(), settings.seed(), texture,
+                                                    clock);
+    game.set_auto(settings.auto_for(power));
+    game.set_throttled(settings.throttle(power).throttled);
+    game.set_flag_chord(settings.flag_chord());
+    game.set_drag_flag(settings.drag_flag());
+    game.set_hover_chord(settings.hover_chord());
+    game.set_chord_delay(settings.chord_delay());
+    game.set_hover_chord_delay(settings.hover_chord_delay());
+    game.set_neighbour_highlight(settings.neighbour_highlight());
+    game.set_mouse_bindings(settings.mouse_bindings());
+    game.set_flag_mode(settings.flag_mode());
+    game.set_long_press_flag(settings.long_press_flag());
+    game.set_solver_preview(settings.solver_preview());
+    game.set_step_preview(settings.step_preview());
+    game.set_progress_ring(settings.progress_ring());
+    game.set_show_progress(settings.show_progress());
+    game.set_risk_shading(settings.risk_shading());
+    game.set_animate_reveals(settings.animate_reveals());
+    game.set_reduce_motion(settings.reduce_motion());
+    game.set_describe_cells(settings.describe_cells());
+    game.set_status_line(settings.status_line());
+    game.set_solver_explanations(settings.solver_explanations());
+    game.set_cell_scale(settings.cell_scale());
+    game.set_counter_mode(settings.counter_mode());
+    game.set_rotation(settings.board_rotation());
+    game.set_portrait(portrait);
+    game.set_spectated(spectated);
+    let _ = game.set_show_provenance(settings.show_provenance());
+    game.set_loss_reveal_delay(settings.loss_reveal_delay());
+    game.set_game_over_dialog(settings.show_game_over_dialog());
+    game.set_silent_auto(settings.silent_auto());
+    game.set_forgiving(settings.forgiving());
+    game.set_mistake_guard(settings.mistake_guard());
+    game.set_idle_pause(settings.idle_pause());
+    game.set_overlay(settings.overlay());
+    game.set_streamer_mode(settings.streamer_mode());
+    game.set_no_flags(settings.no_flags());
+    game.set_rules(settings.rules());
+    game.set_canvas_board(settings.experiments().enabled(experiments::CANVAS_BOARD));
+    game
+}
@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+#[cfg(test)]
+mod tests;
+
+/// always on in debug builds, release builds only measure while the overlay is open
+static ENABLED: AtomicBool = AtomicBool::new(cfg!(debug_assertions));
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled || cfg!(debug_assertions), Ordering::Relaxed);
+}
+
+/// buckets per doubling, so a bucket's bounds are about 19% apart
+const SUBDIVISIONS: u32 = 4;
+/// 1µs up to about 18 minutes
+const BUCKETS: usize = 30 * SUBDIVISIONS as usize;
+
+/// a streaming histogram with logarithmic buckets, quantiles come back as the upper bound of the
+/// bucket they fall in so they're never optimistic
+#[derive(Clone, Debug)]
+pub struct Histogram {
+    buckets: [u64; BUCKETS],
+    count: u64,
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self { buckets: [0; BUCKETS], count: 0 }
+    }
+}
+
+impl Histogram {
+    pub fn record(&mut self, value: Duration) {
+        let micros = value.as_micros().max(1) as f64;
+        let bucket = (micros.log2() * SUBDIVISIONS as f64) as usize;
+        self.buckets[bucket.min(BUCKETS - 1)] += 1;
+        self.count += 1;
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// `quantile` in 0..=1, `None` until something was recorded
+    pub fn quantile(&self, quantile: f64) -> Option<Duration> {
+        if self.count == 0 {
+            return None
+        }
+        let rank = ((quantile.clamp(0.0, 1.0) * self.count as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+        let bucket = self.buckets.iter()
+                .position(|count| {
+                    seen += count;
+                    seen >= rank
+                })
+                .unwrap_or(BUCKETS - 1);
+        Some(Duration::from_secs_f64(2f64.powf((bucket + 1) as f64 / SUBDIVISIONS as f64) / 1_000_000.0))
+    }
+}
+
+/// time from an input being handled to the first frame drawn after its effect was applied
+///
+/// an input gets a gesture id when it starts, lands once the game has applied it, and is measured
+/// at the next frame
+#[derive(Debug, Default)]
+pub struct Latency {
+    next: u64,
+    started: HashMap<u64, Instant>,
+    landed: Vec<u64>,
+    histogram: Histogram,
+}
+
+impl Latency {
+    /// `None` while metrics are off
+    pub fn begin(&mut self) -> Option<u64> {
+        if !enabled() {
+            return None
+        }
+        self.next += 1;
+        self.started.insert(self.next, Instant::now());
+        Some(self.next)
+    }
+
+    pub fn land(&mut self, gesture: u64) {
+        if self.started.contains_key(&gesture) {
+            self.landed.push(gesture);
+        }
+    }
+
+    /// whether anything is waiting for a frame, so the frame subscription only runs when needed
+    pub fn waiting(&self) -> bool {
+        !self.landed.is_empty()
+    }
+
+    pub fn frame(&mut self, at: Instant) {
+        for gesture in self.landed.drain(..) {
+            if let Some(started) = self.started.remove(&gesture) {
+                self.histogram.record(at.saturating_duration_since(started));
+            }
+        }
+    }
+
+    /// drops the inputs that haven't landed, the ones that have are still measured at the next
+    /// frame
+    pub fn prune(&mut self) {
+        let landed = &self.landed;
+        self.started.retain(|gesture, _| landed.contains(gesture));
+    }
+
+    /// inputs that were dropped before landing, e.g. by a restart
+    pub fn forget(&mut self) {
+        self.started.clear();
+        self.landed.clear();
+    }
+
+    pub fn summary(&self) -> String {
+        let format = |quantile| self.histogram.quantile(quantile)
+                .map(|value| format!("{:.1} ms", value.as_secs_f64() * 1000.0))
+                .unwrap_or_else(|| "–".to_string());
+        format!("input → frame  p50 {}  p95 {}  p99 {}  ({} inputs)",
+            format(0.5), format(0.95), format(0.99), self.histogram.count())
+    }
+}
@@ -0,0 +1,62 @@
+use super::*;
+use crate::minsweeper::tests::{Harness, OPENING};
+use crate::minsweeper::Message;
+use iced::mouse;
+
+#[test]
+fn pruning_keeps_only_what_landed() {
+    set_enabled(true);
+    let mut latency = Latency::default();
+    let dropped = latency.begin().unwrap();
+    let landed = latency.begin().unwrap();
+    latency.land(landed);
+    latency.prune();
+    assert!(!latency.started.contains_key(&dropped));
+    assert!(latency.started.contains_key(&landed));
+
+    latency.frame(Instant::now());
+    assert!(latency.started.is_empty());
+    assert_eq!(latency.histogram.count(), 1);
+}
+
+#[test]
+fn quantiles_never_come_back_below_what_was_recorded() {
+    let mut histogram = Histogram::default();
+    assert_eq!(histogram.quantile(0.5), None);
+    for millis in 1..=100 {
+        histogram.record(Duration::from_millis(millis));
+    }
+    assert!(histogram.quantile(0.5).unwrap() >= Duration::from_millis(50));
+    assert!(histogram.quantile(1.0).unwrap() >= Duration::from_millis(100));
+}
+
+#[test]
+fn a_click_restarted_away_before_its_frame_isnt_measured() {
+    set_enabled(true);
+    let mut harness = Harness::new();
+    harness.click(OPENING);
+    assert!(harness.game.latency().waiting(), "the click landed and waits for its frame");
+
+    harness.send(Message::RestartSameBoard);
+    harness.send(Message::Frame(Instant::now()));
+    assert!(!harness.game.latency().waiting());
+    assert!(harness.game.latency().started.is_empty());
+    assert_eq!(harness.game.latency().histogram.count(), 0);
+}
+
+#[test]
+fn a_click_from_a_stale_generation_is_dropped() {
+    set_enabled(true);
+    let mut harness = Harness::new();
+    // the click starts measuring before it's applied, the restart lands first
+    let stale = harness.press(OPENING, mouse::Button::Left);
+    harness.send(Message::RestartSameBoard);
+    harness.drain(stale);
+    assert!(!harness.game.latency().waiting());
+    assert!(harness.game.latency().started.is_empty());
+
+    // the next game's clicks are measured as usual
+    harness.click(OPENING);
+    harness.send(Message::Frame(Instant::now()));
+    assert_eq!(harness.game.latency().histogram.count(), 1);
+}
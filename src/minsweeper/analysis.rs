@@ -0,0 +1,163 @@
+use crate::clock::SharedClock;
+use crate::minsweeper::{MinsweeperType, Provenance};
+use futures_util::future::{AbortHandle, BoxFuture};
+use iced::Task;
+use minsweeper_rs::board::Point;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[cfg(test)]
+mod tests;
+
+/// every kind of background work that reads the board and hands something back to draw
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Kind {
+    /// who revealed each cell, for marking solver cells once the game is over
+    Provenance,
+    /// every mine and wrong flag after a loss, held back by the loss reveal delay
+    LossReveal,
+}
+
+#[derive(Clone, Debug)]
+pub enum Analysis {
+    Provenance(HashMap<Point, Provenance>),
+    LossReveal(HashMap<Point, minsweeper_rs::Cell>),
+}
+
+/// what a kind does when it's issued again or when the board changes under it
+#[derive(Copy, Clone, Debug)]
+pub struct Policy {
+    /// how long to wait before computing, issuing again inside the window restarts it so a burst
+    /// of changes only computes once
+    pub debounce: Duration,
+    /// whether a board change should start it over, otherwise it's only cancelled
+    pub reissue_on_board_change: bool,
+}
+
+/// a finished analysis, tagged with the board generation it was computed for
+#[derive(Clone, Debug)]
+pub struct Delivery {
+    kind: Kind,
+    generation: u64,
+    analysis: Analysis,
+}
+
+type Compute = Arc<dyn Fn(MinsweeperType) -> BoxFuture<'static, Option<Analysis>> + Send + Sync>;
+
+struct Slot {
+    policy: Policy,
+    compute: Compute,
+    enabled: bool,
+    handle: Option<AbortHandle>,
+}
+
+/// owns the in flight work for every analysis kind, turning a kind off or changing the board
+/// cancels whatever it was doing and anything computed for an older board is dropped here instead
+/// of by each feature
+pub struct AnalysisScheduler {
+    slots: HashMap<Kind, Slot>,
+    generation: u64,
+    clock: SharedClock,
+}
+
+impl AnalysisScheduler {
+
+    pub fn new(clock: SharedClock) -> Self {
+        Self {
+            slots: HashMap::new(),
+            generation: 0,
+            clock,
+        }
+    }
+
+    /// kinds start out disabled
+    pub fn register(&mut self, kind: Kind, policy: Policy,
+                    compute: impl Fn(MinsweeperType) -> BoxFuture<'static, Option<Analysis>> + Send + Sync + 'static) {
+        self.slots.insert(kind, Slot {
+            policy,
+            compute: Arc::new(compute),
+            enabled: false,
+            handle: None,
+        });
+    }
+
+    pub fn set_debounce(&mut self, kind: Kind, debounce: Duration) {
+        if let Some(slot) = self.slots.get_mut(&kind) {
+            slot.policy.debounce = debounce;
+        }
+    }
+
+    /// turning a kind on computes it straight away, turning it off cancels it
+    pub fn set_enabled(&mut self, kind: Kind, enabled: bool, game: &MinsweeperType) -> Task<Delivery> {
+        let Some(slot) = self.slots.get_mut(&kind) else { return Task::none() };
+        let was_enabled = std::mem::replace(&mut slot.enabled, enabled);
+        if !enabled {
+            cancel(slot);
+            Task::none()
+        } else if !was_enabled {
+            self.issue(kind, game)
+        } else {
+            Task::none()
+        }
+    }
+
+    /// (re)computes a kind if it's enabled, anything already running for it is cancelled
+    pub fn issue(&mut self, kind: Kind, game: &MinsweeperType) -> Task<Delivery> {
+        let generation = self.generation;
+        let Some(slot) = self.slots.get_mut(&kind).filter(|slot| slot.enabled) else { return Task::none() };
+        cancel(slot);
+
+        let compute = slot.compute.clone();
+        let debounce = slot.policy.debounce;
+        let game = game.clone();
+        let sleep = (!debounce.is_zero()).then(|| self.clock.sleep(debounce));
+        let (abortable, handle) = futures_util::future::abortable(async move {
+            if let Some(sleep) = sleep {
+                sleep.await;
+            }
+            compute(game).await
+        });
+        slot.handle = Some(handle);
+
+        Task::future(abortable)
+                .then(move |result| match result {
+                    Ok(Some(analysis)) => Task::done(Delivery { kind, generation, analysis }),
+                    _ => Task::none(),
+                })
+    }
+
+    /// everything computed so far is for a board that no longer exists
+    pub fn board_changed(&mut self, game: &MinsweeperType) -> Task<Delivery> {
+        self.generation += 1;
+        let reissue = self.slots.iter_mut()
+                .filter_map(|(kind, slot)| {
+                    cancel(slot);
+                    (slot.enabled && slot.policy.reissue_on_board_change).then_some(*kind)
+                })
+                .collect::<Vec<_>>();
+        Task::batch(reissue.into_iter().map(|kind| self.issue(kind, game)))
+    }
+
+    /// the analysis, unless it's for an older board or its kind was turned off in the meantime
+    pub fn accept(&mut self, delivery: Delivery) -> Option<Analysis> {
+        let slot = self.slots.get_mut(&delivery.kind)?;
+        if delivery.generation != self.generation || !slot.enabled {
+            return None
+        }
+        slot.handle = None;
+        Some(delivery.analysis)
+    }
+}
+
+fn cancel(slot: &mut Slot) {
+    if let Some(handle) = slot.handle.take() {
+        handle.abort();
+    }
+}
+
+impl Drop for AnalysisScheduler {
+    fn drop(&mut self) {
+        self.slots.values_mut().for_each(cancel);
+    }
+}
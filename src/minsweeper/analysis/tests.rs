@@ -0,0 +1,166 @@
+use super::*;
+use crate::clock::TestClock;
+use crate::minsweeper::game::Game;
+use futures_util::StreamExt;
+use iced_runtime::task::into_stream;
+use iced_runtime::Action;
+use minsweeper_rs::board::BoardSize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::runtime::Runtime;
+
+const DEBOUNCE: Duration = Duration::from_millis(20);
+
+struct Fixture {
+    scheduler: AnalysisScheduler,
+    game: MinsweeperType,
+    runtime: Runtime,
+    /// how many times each kind was computed
+    computed: Arc<[AtomicUsize; 2]>,
+}
+
+impl Fixture {
+    fn new() -> Self {
+        let computed = Arc::new([AtomicUsize::new(0), AtomicUsize::new(0)]);
+        let mut scheduler = AnalysisScheduler::new(Arc::new(TestClock::new()));
+        for (index, kind, policy) in [
+            (0, Kind::Provenance, Policy { debounce: Duration::ZERO, reissue_on_board_change: true }),
+            (1, Kind::LossReveal, Policy { debounce: DEBOUNCE, reissue_on_board_change: false }),
+        ] {
+            let computed = computed.clone();
+            scheduler.register(kind, policy, move |_| {
+                computed[index].fetch_add(1, Ordering::Relaxed);
+                Box::pin(async move {
+                    Some(match kind {
+                        Kind::Provenance => Analysis::Provenance(HashMap::new()),
+                        Kind::LossReveal => Analysis::LossReveal(HashMap::new()),
+                    })
+                })
+            });
+        }
+        Self {
+            scheduler,
+            game: Arc::new(Game::new(BoardSize::new(5, 3, 2).unwrap())),
+            runtime: tokio::runtime::Builder::new_current_thread()
+                    .enable_time()
+                    .build()
+                    .unwrap(),
+            computed,
+        }
+    }
+
+    fn enable(&mut self, kind: Kind, enabled: bool) -> Task<Delivery> {
+        let _context = self.runtime.enter();
+        self.scheduler.set_enabled(kind, enabled, &self.game)
+    }
+
+    fn issue(&mut self, kind: Kind) -> Task<Delivery> {
+        let _context = self.runtime.enter();
+        self.scheduler.issue(kind, &self.game)
+    }
+
+    fn board_changed(&mut self) -> Task<Delivery> {
+        let _context = self.runtime.enter();
+        self.scheduler.board_changed(&self.game)
+    }
+
+    /// what the tasks deliver once they've all run to the end
+    fn run(&self, tasks: impl IntoIterator<Item = Task<Delivery>>) -> Vec<Delivery> {
+        let Some(stream) = into_stream(Task::batch(tasks)) else { return vec![] };
+        self.runtime.block_on(stream.filter_map(|action| async move {
+            match action {
+                Action::Output(delivery) => Some(delivery),
+                _ => None,
+            }
+        }).collect())
+    }
+
+    fn computed(&self, kind: Kind) -> usize {
+        self.computed[match kind {
+            Kind::Provenance => 0,
+            Kind::LossReveal => 1,
+        }].load(Ordering::Relaxed)
+    }
+}
+
+fn kinds(deliveries: &[Delivery]) -> Vec<Kind> {
+    deliveries.iter().map(|delivery| delivery.kind).collect()
+}
+
+#[test]
+fn turning_a_kind_on_computes_it_once() {
+    let mut fixture = Fixture::new();
+    let first = fixture.enable(Kind::Provenance, true);
+    let again = fixture.enable(Kind::Provenance, true);
+
+    let deliveries = fixture.run([first, again]);
+    assert_eq!(kinds(&deliveries), [Kind::Provenance]);
+    assert_eq!(fixture.computed(Kind::Provenance), 1);
+    assert!(deliveries.into_iter().all(|delivery| fixture.scheduler.accept(delivery).is_some()));
+}
+
+#[test]
+fn disabled_kinds_compute_nothing() {
+    let mut fixture = Fixture::new();
+    let issued = fixture.issue(Kind::Provenance);
+    let changed = fixture.board_changed();
+
+    assert!(fixture.run([issued, changed]).is_empty());
+    assert_eq!(fixture.computed(Kind::Provenance), 0);
+}
+
+#[test]
+fn issuing_again_inside_the_debounce_only_computes_the_last() {
+    let mut fixture = Fixture::new();
+    let first = fixture.enable(Kind::LossReveal, true);
+    let second = fixture.issue(Kind::LossReveal);
+    let third = fixture.issue(Kind::LossReveal);
+
+    assert_eq!(kinds(&fixture.run([first, second, third])), [Kind::LossReveal]);
+    assert_eq!(fixture.computed(Kind::LossReveal), 1);
+}
+
+#[test]
+fn turning_a_kind_off_cancels_it() {
+    let mut fixture = Fixture::new();
+    let enabled = fixture.enable(Kind::LossReveal, true);
+    let disabled = fixture.enable(Kind::LossReveal, false);
+
+    assert!(fixture.run([enabled, disabled]).is_empty());
+    assert_eq!(fixture.computed(Kind::LossReveal), 0);
+}
+
+#[test]
+fn what_was_computed_before_turning_off_is_dropped() {
+    let mut fixture = Fixture::new();
+    let enabled = fixture.enable(Kind::Provenance, true);
+    let deliveries = fixture.run([enabled]);
+    let _ = fixture.enable(Kind::Provenance, false);
+
+    assert_eq!(deliveries.len(), 1);
+    assert!(deliveries.into_iter().all(|delivery| fixture.scheduler.accept(delivery).is_none()));
+}
+
+#[test]
+fn what_was_computed_for_an_older_board_is_dropped() {
+    let mut fixture = Fixture::new();
+    let enabled = fixture.enable(Kind::Provenance, true);
+    let stale = fixture.run([enabled]);
+    let changed = fixture.board_changed();
+    let fresh = fixture.run([changed]);
+
+    assert!(stale.into_iter().all(|delivery| fixture.scheduler.accept(delivery).is_none()));
+    assert_eq!(kinds(&fresh), [Kind::Provenance]);
+    assert!(fresh.into_iter().all(|delivery| fixture.scheduler.accept(delivery).is_some()));
+}
+
+#[test]
+fn a_board_change_only_starts_over_the_kinds_that_ask_to() {
+    let mut fixture = Fixture::new();
+    let provenance = fixture.enable(Kind::Provenance, true);
+    let loss_reveal = fixture.enable(Kind::LossReveal, true);
+    let changed = fixture.board_changed();
+
+    assert_eq!(kinds(&fixture.run([provenance, loss_reveal, changed])), [Kind::Provenance]);
+    assert_eq!(fixture.computed(Kind::Provenance), 1);
+    assert_eq!(fixture.computed(Kind::LossReveal), 0);
+}
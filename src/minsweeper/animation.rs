@@ -0,0 +1,106 @@
+use crate::minsweeper::Message;
+use iced::widget::canvas::{Frame, Geometry, Program};
+use iced::{mouse, Color, Point as Position, Rectangle, Renderer, Size, Theme};
+use std::time::{Duration, Instant};
+
+const CONFETTI: Duration = Duration::from_millis(1500);
+const CONFETTI_PIECES: usize = 80;
+/// in board heights per second squared, the pieces are thrown up and fall back through the board
+const GRAVITY: f32 = 1.6;
+const PIECE_SIZE: f32 = 6.0;
+const COLORS: [Color; 5] = [
+    Color::from_rgb(0.94, 0.33, 0.31),
+    Color::from_rgb(0.98, 0.78, 0.25),
+    Color::from_rgb(0.36, 0.78, 0.42),
+    Color::from_rgb(0.31, 0.6, 0.94),
+    Color::from_rgb(0.72, 0.45, 0.9),
+];
+
+const SHAKE: Duration = Duration::from_millis(400);
+/// how far the board swings either way at the start, in pixels
+const SHAKE_AMPLITUDE: f32 = 8.0;
+const SHAKE_SWINGS: f32 = 5.0;
+
+/// the burst over a won board or the shake of a lost one, both only work out where things are
+/// from how long they've been going so a dropped frame just skips ahead
+#[derive(Debug)]
+pub struct AnimationState {
+    started: Instant,
+    kind: Kind,
+}
+
+#[derive(Debug)]
+enum Kind {
+    Confetti(Vec<Piece>),
+    Shake,
+}
+
+/// where a piece starts and how it's thrown, in fractions of the board's size
+#[derive(Debug)]
+struct Piece {
+    origin: (f32, f32),
+    velocity: (f32, f32),
+    color: Color,
+}
+
+impl AnimationState {
+    pub fn confetti(now: Instant) -> Self {
+        let pieces = (0..CONFETTI_PIECES)
+                .map(|i| Piece {
+                    origin: (0.3 + rand::random::<f32>() * 0.4, 0.6),
+                    velocity: ((rand::random::<f32>() - 0.5) * 0.8, -0.6 - rand::random::<f32>() * 0.9),
+                    color: COLORS[i % COLORS.len()],
+                })
+                .collect();
+        Self { started: now, kind: Kind::Confetti(pieces) }
+    }
+
+    pub fn shake(now: Instant) -> Self {
+        Self { started: now, kind: Kind::Shake }
+    }
+
+    pub fn finished(&self, now: Instant) -> bool {
+        let duration = match self.kind {
+            Kind::Confetti(_) => CONFETTI,
+            Kind::Shake => SHAKE,
+        };
+        now.saturating_duration_since(self.started) >= duration
+    }
+
+    /// how far the board is pushed sideways, dying down as the shake ends
+    pub fn offset(&self, now: Instant) -> f32 {
+        let Kind::Shake = self.kind else { return 0.0 };
+        let progress = (now.saturating_duration_since(self.started).as_secs_f32() / SHAKE.as_secs_f32()).min(1.0);
+        SHAKE_AMPLITUDE * (1.0 - progress) * (progress * SHAKE_SWINGS * std::f32::consts::TAU).sin()
+    }
+
+    /// the burst drawn over the board, `None` while shaking
+    pub fn overlay(&self, now: Instant) -> Option<Confetti<'_>> {
+        let Kind::Confetti(pieces) = &self.kind else { return None };
+        Some(Confetti { pieces, elapsed: now.saturating_duration_since(self.started) })
+    }
+}
+
+/// draws and nothing else, so clicks go through to whatever is under it
+pub struct Confetti<'a> {
+    pieces: &'a [Piece],
+    elapsed: Duration,
+}
+
+impl Program<Message> for Confetti<'_> {
+    type State = ();
+
+    fn draw(&self, _state: &(), renderer: &Renderer, _theme: &Theme, bounds: Rectangle, _cursor: mouse::Cursor) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let t = self.elapsed.as_secs_f32();
+        // fades over the last third
+        let alpha = ((1.0 - t / CONFETTI.as_secs_f32()) * 3.0).clamp(0.0, 1.0);
+        for piece in self.pieces {
+            let x = piece.origin.0 + piece.velocity.0 * t;
+            let y = piece.origin.1 + piece.velocity.1 * t + GRAVITY * t * t / 2.0;
+            frame.fill_rectangle(Position::new(x * bounds.width, y * bounds.height),
+                    Size::new(PIECE_SIZE, PIECE_SIZE), Color { a: alpha, ..piece.color });
+        }
+        vec![frame.into_geometry()]
+    }
+}
@@ -0,0 +1,46 @@
+use minsweeper_rs::{CellType, GameState};
+use std::time::Duration;
+
+/// the fewest left clicks that clear the board: one per opening, one per number that doesn't
+/// border an opening
+pub fn bbbv(layout: &GameState) -> usize {
+    let size = layout.board.size();
+    let width = size.width().get();
+    let index = |(x, y): (usize, usize)| y * width + x;
+    let mut cleared = vec![false; width * size.height().get()];
+    let mut count = 0;
+
+    for point in size.points() {
+        if layout.board[point].cell_type != CellType::Safe(0) || cleared[index(point)] {
+            continue
+        }
+        count += 1;
+        cleared[index(point)] = true;
+        let mut stack = vec![point];
+        while let Some(point) = stack.pop() {
+            for neighbour in size.neighbours(point) {
+                if cleared[index(neighbour)] {
+                    continue
+                }
+                cleared[index(neighbour)] = true;
+                if layout.board[neighbour].cell_type == CellType::Safe(0) {
+                    stack.push(neighbour);
+                }
+            }
+        }
+    }
+
+    count + size.points()
+            .filter(|point| matches!(layout.board[*point].cell_type, CellType::Safe(_)) && !cleared[index(*point)])
+            .count()
+}
+
+/// 3BV per second of a won game
+pub fn rate(bbbv: usize, elapsed: Duration) -> f64 {
+    bbbv as f64 / elapsed.as_secs_f64().max(0.001)
+}
+
+/// how many of the clicks were needed, 100% is a perfect game
+pub fn efficiency(bbbv: usize, clicks: usize) -> f64 {
+    bbbv as f64 / clicks.max(1) as f64 * 100.0
+}
@@ -0,0 +1,315 @@
+use crate::minsweeper::cell::{Cell, HighlightKind};
+use crate::minsweeper::grid::Grid;
+use crate::minsweeper::minimap::{self, Minimap};
+use crate::minsweeper::{cell, Message, Provenance, MAX_ZOOMED_CELL, MIN_ZOOMED_CELL};
+use crate::texture::Texture;
+use iced::widget::canvas::{Action, Cache, Event, Frame, Geometry, Path, Program, Stroke, Text};
+use iced::{keyboard, mouse, window, Point as Position, Rectangle, Renderer, Size, Theme, Vector};
+use iced_core::svg::Svg;
+use minsweeper_rs::board::Point;
+use minsweeper_rs::GameState;
+use std::collections::HashMap;
+
+/// boards with more cells than this are drawn on a canvas instead of as a widget per cell
+pub const CANVAS_THRESHOLD: usize = 2500;
+
+const ZOOM_STEP: f32 = 1.1;
+const LINE_HEIGHT: f32 = 40.0;
+
+/// draws only the cells intersecting the viewport and does its own hit testing, emitting the same
+/// cell messages the per cell widgets would
+pub struct Board<'a> {
+    cells: &'a Grid<Cell>,
+    gamestate: GameState,
+    texture: Texture,
+    provenance: HashMap<Point, Provenance>,
+    cell_size: f32,
+    skeleton: bool,
+    risk_shading: bool,
+    /// columns are drawn as rows, hit testing turns them back so messages carry the board's points
+    transposed: bool,
+    /// only scrolls and zooms, nothing is sent to the game
+    read_only: bool,
+    /// a minimap goes in the corner while the board doesn't fit, drawn again when this changes
+    minimap: Option<u64>,
+}
+
+pub struct View {
+    scroll: Vector,
+    zoom: f32,
+    hovered: Option<Point>,
+    modifiers: keyboard::Modifiers,
+    minimap: Cache,
+    minimap_drawn: std::cell::Cell<Option<u64>>,
+}
+
+impl Default for View {
+    fn default() -> Self {
+        Self {
+            scroll: Vector::ZERO,
+            zoom: 1.0,
+            hovered: None,
+            modifiers: keyboard::Modifiers::default(),
+            minimap: Cache::default(),
+            minimap_drawn: Default::default(),
+        }
+    }
+}
+
+/// where the minimap sits in the canvas and what it shows
+struct MinimapArea {
+    bounds: Rectangle,
+    /// pixels of minimap per cell
+    scale: f32,
+    /// what of the board is in view, in cells
+    viewport: Rectangle,
+}
+
+impl<'a> Board<'a> {
+    pub fn new(cells: &'a Grid<Cell>, gamestate: GameState, texture: Texture, provenance: HashMap<Point, Provenance>, cell_size: f32, skeleton: bool, risk_shading: bool) -> Self {
+        Self { cells, gamestate, texture, provenance, cell_size, skeleton, risk_shading, transposed: false, read_only: false, minimap: None }
+    }
+
+    pub fn transposed(mut self, transposed: bool) -> Self {
+        self.transposed = transposed;
+        self
+    }
+
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// `version` changes whenever the board does
+    pub fn minimap(mut self, version: u64) -> Self {
+        self.minimap = Some(version);
+        self
+    }
+
+    /// cells across and down as drawn
+    fn dimensions(&self) -> (usize, usize) {
+        match self.transposed {
+            false => (self.cells.width(), self.cells.height()),
+            true => (self.cells.height(), self.cells.width()),
+        }
+    }
+
+    /// the board's point for the cell drawn at `(x, y)`, or the other way around
+    fn turned(&self, (x, y): Point) -> Point {
+        if self.transposed { (y, x) } else { (x, y) }
+    }
+
+    /// size of a cell on screen and where the board's top left corner sits inside the canvas,
+    /// boards smaller than the canvas are centred and larger ones are offset by the scroll
+    fn layout(&self, view: &View, bounds: Rectangle) -> (f32, Vector) {
+        let cell_size = self.cell_size * view.zoom;
+        let (width, height) = self.dimensions();
+        let axis = |board: f32, available: f32, scroll: f32| if board <= available {
+            (available - board) / 2.0
+        } else {
+            -scroll.clamp(0.0, board - available)
+        };
+        (cell_size, Vector::new(
+            axis(cell_size * width as f32, bounds.width, view.scroll.x),
+            axis(cell_size * height as f32, bounds.height, view.scroll.y),
+        ))
+    }
+
+    /// the minimap in the bottom right corner, if there is one and the board doesn't fit
+    fn minimap_area(&self, view: &View, bounds: Rectangle) -> Option<MinimapArea> {
+        self.minimap?;
+        let (cell_size, origin) = self.layout(view, bounds);
+        let (width, height) = self.dimensions();
+        let board = Size::new(width as f32 * cell_size, height as f32 * cell_size);
+        if board.width <= bounds.width && board.height <= bounds.height {
+            return None
+        }
+        let size = Minimap::size(width, height);
+        Some(MinimapArea {
+            bounds: Rectangle::new(Position::new(bounds.width - size.width - 10.0, bounds.height - size.height - 10.0), size),
+            scale: size.width / width as f32,
+            viewport: Rectangle::new(
+                Position::new(-origin.x.min(0.0) / cell_size, -origin.y.min(0.0) / cell_size),
+                Size::new(bounds.width.min(board.width) / cell_size, bounds.height.min(board.height) / cell_size)),
+        })
+    }
+
+    fn hit(&self, view: &View, bounds: Rectangle, position: Position) -> Option<Point> {
+        if self.minimap_area(view, bounds).is_some_and(|minimap| minimap.bounds.contains(position)) {
+            return None
+        }
+        let (cell_size, origin) = self.layout(view, bounds);
+        let x = (position.x - origin.x) / cell_size;
+        let y = (position.y - origin.y) / cell_size;
+        (x >= 0.0 && y >= 0.0)
+                .then(|| self.turned((x as usize, y as usize)))
+                .filter(|point| self.cells.get(*point).is_some())
+    }
+
+    fn clamp_scroll(&self, view: &mut View, bounds: Rectangle) {
+        let cell_size = self.cell_size * view.zoom;
+        let (width, height) = self.dimensions();
+        view.scroll.x = view.scroll.x.clamp(0.0, (cell_size * width as f32 - bounds.width).max(0.0));
+        view.scroll.y = view.scroll.y.clamp(0.0, (cell_size * height as f32 - bounds.height).max(0.0));
+    }
+}
+
+impl Program<Message> for Board<'_> {
+    type State = View;
+
+    fn update(&self, view: &mut View, event: &Event, bounds: Rectangle, cursor: mouse::Cursor) -> Option<Action<Message>> {
+        if let Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) = event {
+            view.modifiers = *modifiers;
+            return None
+        }
+        // the game lets go of everything when the window goes away, the next move finds the
+        // hovered cell again
+        if let Event::Window(window::Event::Unfocused) = event {
+            view.hovered = None;
+            view.modifiers = keyboard::Modifiers::default();
+            return None
+        }
+
+        let position = cursor.position_in(bounds);
+        let hovered = position.and_then(|position| self.hit(view, bounds, position));
+
+        match event {
+            Event::Mouse(mouse::Event::CursorMoved { .. } | mouse::Event::CursorLeft) if !self.read_only => {
+                if hovered == view.hovered {
+                    return None
+                }
+                let from = std::mem::replace(&mut view.hovered, hovered);
+                Some(Action::publish(Message::Hover(from, hovered)))
+            }
+            Event::Mouse(mouse::Event::WheelScrolled { delta }) => {
+                let position = position?;
+                let (x, y) = match *delta {
+                    mouse::ScrollDelta::Lines { x, y } => (x * LINE_HEIGHT, y * LINE_HEIGHT),
+                    mouse::ScrollDelta::Pixels { x, y } => (x, y),
+                };
+
+                if view.modifiers.command() {
+                    let (cell_size, origin) = self.layout(view, bounds);
+                    // keep the board position under the cursor fixed while zooming
+                    let anchor = Vector::new(position.x - origin.x, position.y - origin.y) * (1.0 / cell_size);
+                    // cells stay between the same sizes the scrollable board zooms between
+                    view.zoom = (view.zoom * ZOOM_STEP.powf(y / LINE_HEIGHT)).clamp(
+                        MIN_ZOOMED_CELL.min(self.cell_size) / self.cell_size,
+                        MAX_ZOOMED_CELL.max(self.cell_size) / self.cell_size);
+                    let cell_size = self.cell_size * view.zoom;
+                    view.scroll = Vector::new(anchor.x * cell_size - position.x, anchor.y * cell_size - position.y);
+                } else if view.modifiers.shift() {
+                    view.scroll -= Vector::new(y, x);
+                } else {
+                    view.scroll -= Vector::new(x, y);
+                }
+                self.clamp_scroll(view, bounds);
+
+                Some(Action::request_redraw().and_capture())
+            }
+            // a click on the minimap centres the board on where it landed
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left))
+                    if let Some(position) = position
+                    && let Some(minimap) = self.minimap_area(view, bounds)
+                    && minimap.bounds.contains(position) => {
+                let cell_size = self.cell_size * view.zoom;
+                let centre = (position - minimap.bounds.position()) * (cell_size / minimap.scale);
+                view.scroll = centre - Vector::new(bounds.width / 2.0, bounds.height / 2.0);
+                self.clamp_scroll(view, bounds);
+                Some(Action::request_redraw().and_capture())
+            }
+            Event::Mouse(mouse::Event::ButtonPressed(button)) if !self.read_only => {
+                if !matches!(button, mouse::Button::Left | mouse::Button::Middle | mouse::Button::Right) {
+                    return None
+                }
+                Some(Action::publish(Message::Cell((hovered?, cell::Message::SelfPress(*button)))).and_capture())
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(button @ (mouse::Button::Left | mouse::Button::Middle | mouse::Button::Right))) if !self.read_only => {
+                Some(Action::publish(Message::Cell((hovered?, cell::Message::SelfRelease(*button)))))
+            }
+            _ => None
+        }
+    }
+
+    fn draw(&self, view: &View, renderer: &Renderer, _theme: &Theme, bounds: Rectangle, _cursor: mouse::Cursor) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let (cell_size, origin) = self.layout(view, bounds);
+        let (width, height) = self.dimensions();
+
+        let visible = |origin: f32, available: f32, count: usize|
+            ((-origin / cell_size).floor().max(0.0) as usize)
+                    ..(((available - origin) / cell_size).ceil().max(0.0) as usize).min(count);
+
+        let accent = self.texture.manifest().accent;
+        let skeleton = self.texture.get_cell_asset(minsweeper_rs::Cell::EMPTY, false);
+
+        let columns = visible(origin.x, bounds.width, width);
+        let rows = visible(origin.y, bounds.height, height);
+
+        for y in rows {
+            for x in columns.clone() {
+                let point = self.turned((x, y));
+                let cell = &self.cells[point];
+                let area = Rectangle::new(
+                    Position::new(origin.x + x as f32 * cell_size, origin.y + y as f32 * cell_size),
+                    iced::Size::new(cell_size, cell_size));
+
+                if self.skeleton {
+                    frame.draw_svg(area, &skeleton);
+                    continue
+                }
+
+                let (handle, risk) = self.texture.get_shaded_cell_asset(self.gamestate.board[point], cell.is_armed(), self.risk_shading);
+                if let Some(tint) = risk {
+                    frame.fill_rectangle(area.position(), area.size(), tint);
+                }
+                frame.draw_svg(area, Svg::new(handle).opacity(if cell.highlighted { 0.5 } else { 1.0 }));
+
+                if cell.excess {
+                    frame.fill_rectangle(area.position(), area.size(), cell::EXCESS_FLAG_TINT);
+                }
+
+                match cell.highlight {
+                    Some(HighlightKind::Source) => frame.stroke(&Path::rectangle(area.position(), area.size()),
+                            Stroke::default().with_color(accent).with_width(2.0)),
+                    Some(highlight) => if let Some(tint) = highlight.tint() {
+                        frame.fill_rectangle(area.position(), area.size(), tint);
+                    },
+                    None => {}
+                }
+
+                if let Some(Provenance::Solver(_)) = self.provenance.get(&point) {
+                    let radius = (cell_size / 10.0).max(1.0);
+                    frame.fill(&Path::circle(
+                        Position::new(area.x + area.width - radius * 2.0, area.y + radius * 2.0), radius), accent);
+                }
+
+                if let Some(operation) = cell.preview {
+                    frame.stroke(&Path::rectangle(area.position(), area.size()),
+                            Stroke::default().with_color(accent).with_width(2.0));
+                    frame.fill_text(Text {
+                        content: cell::preview_label(operation).to_string(),
+                        position: Position::new(area.x + 3.0, area.y + 2.0),
+                        color: accent,
+                        size: (cell_size / 3.0).max(6.0).into(),
+                        ..Default::default()
+                    });
+                }
+            }
+        }
+
+        let Some(minimap) = self.minimap_area(view, bounds) else { return vec![frame.into_geometry()] };
+        if view.minimap_drawn.replace(self.minimap) != self.minimap {
+            view.minimap.clear();
+        }
+        let cells = view.minimap.draw(renderer, bounds.size(), |frame| {
+            frame.translate(Vector::new(minimap.bounds.x, minimap.bounds.y));
+            minimap::draw_cells(frame, &self.gamestate, minimap.scale, self.transposed);
+        });
+        let mut outline = Frame::new(renderer, bounds.size());
+        outline.translate(Vector::new(minimap.bounds.x, minimap.bounds.y));
+        minimap::draw_viewport(&mut outline, minimap.viewport, minimap.scale, accent);
+
+        vec![frame.into_geometry(), cells, outline.into_geometry()]
+    }
+}
@@ -1,20 +1,34 @@
 use std::fmt::{Debug, Formatter};
-use crate::minsweeper::MinsweeperType;
+use crate::i18n::t;
+use crate::minsweeper::{Click, MouseAction, MouseBindings, Provenance};
 use crate::texture::Texture;
-use iced::widget::{mouse_area, svg};
-use iced::{mouse, Element};
+use iced::widget::{container, mouse_area, stack, svg, text, Space};
+use iced::{mouse, Background, Border, Color, Element, Length};
+use iced_core::alignment::{Horizontal, Vertical};
 use minsweeper_rs::solver::Operation;
-use std::sync::atomic::{AtomicBool, Ordering};
+use minsweeper_rs::{CellState, CellType};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Instant;
 
 pub struct Cell {
-    game: MinsweeperType,
     pub texture: Texture,
     point: minsweeper_rs::board::Point,
     pub hovering: bool,
     pub pressed: bool,
     pub force: bool,
-    pub revealing: Arc<AtomicBool>
+    pub pending: bool,
+    pub highlighted: bool,
+    /// the flag that just took the counter below zero
+    pub excess: bool,
+    /// part of the solver step being shown before it's played
+    pub preview: Option<Operation>,
+    /// what this cell is to the number the highlight key is held over
+    pub highlight: Option<HighlightKind>,
+    /// revealed in the game but drawn covered until then, so a big opening spreads out from the click
+    pub reveal_at: Option<Instant>,
+    /// how many clicks that reveal around here haven't landed yet
+    pub revealing: Arc<AtomicUsize>
 }
 
 impl Debug for Cell {
@@ -24,9 +38,31 @@ impl Debug for Cell {
     }
 }
 
+/// over a flag that was one too many for the mines, faint so the flag still shows
+pub const EXCESS_FLAG_TINT: Color = Color::from_rgba(1.0, 0.1, 0.1, 0.3);
+
+/// how a cell takes part in the number being highlighted
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum HighlightKind {
+    /// the number itself, outlined
+    Source,
+    Flagged,
+    Unknown,
+}
+
+impl HighlightKind {
+    /// drawn over the cell, the number is only outlined so it stays readable
+    pub fn tint(self) -> Option<Color> {
+        match self {
+            HighlightKind::Source => None,
+            HighlightKind::Flagged => Some(Color::from_rgba(0.2, 0.8, 0.3, 0.4)),
+            HighlightKind::Unknown => Some(Color::from_rgba(1.0, 0.85, 0.1, 0.4)),
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub enum Message {
-    Press(mouse::Button),
     Release(mouse::Button),
     SelfPress(mouse::Button),
     SelfRelease(mouse::Button),
@@ -37,26 +73,23 @@ pub enum Message {
 }
 
 impl Message {
-    pub fn to_action(&self) -> Option<Vec<Operation>> {
+    /// the click this ends up as once the game applies it, if any
+    pub fn click(&self, point: minsweeper_rs::board::Point, bindings: &MouseBindings, flag_mode: bool) -> Option<Click> {
         match self {
-            Message::SelfPress(mouse::Button::Right) => Some(vec![Operation::Flag]),
-            Message::SelfRelease(mouse::Button::Left) => Some(vec![Operation::Reveal, Operation::Chord]),
+            Message::SelfPress(button) if bindings.action(*button) == Some(MouseAction::Flag) => Some(Click::Right(point)),
+            Message::SelfRelease(button) if flag_mode && bindings.action(*button) == Some(MouseAction::Reveal) =>
+                Some(Click::Right(point)),
+            Message::SelfRelease(button) if matches!(bindings.action(*button), Some(MouseAction::Reveal | MouseAction::Chord)) =>
+                Some(Click::Left(point)),
             _ => None
         }
     }
-
-    pub fn is_left_click(&self) -> bool {
-        matches!(self, Message::SelfRelease(mouse::Button::Left))
-    }
-    pub fn is_right_click(&self) -> bool {
-        matches!(self, Message::SelfPress(mouse::Button::Right))
-    }
 }
 
 impl Cell {
 
-    pub fn new(point: minsweeper_rs::board::Point, texture: Texture, game: MinsweeperType) -> Self {
-        Self { game, texture, point, hovering: false, pressed: false, force: false, revealing: Default::default() }
+    pub fn new(point: minsweeper_rs::board::Point, texture: Texture) -> Self {
+        Self { texture, point, hovering: false, pressed: false, force: false, pending: false, highlighted: false, excess: false, preview: None, highlight: None, reveal_at: None, revealing: Default::default() }
     }
 
     // pub fn update(&mut self, message: Message) {
@@ -96,21 +129,106 @@ impl Cell {
     // }
 
     pub fn is_down(&self) -> bool {
-        (self.pressed && self.hovering) || self.revealing.load(Ordering::Relaxed)
+        (self.pressed && self.hovering) || self.revealing.load(Ordering::Relaxed) > 0
     }
 
-    fn is_armed(&self) -> bool {
-        self.is_down() || self.force
+    pub fn is_armed(&self) -> bool {
+        self.is_down() || self.force || self.pending
     }
 
     // fn subscriptions() -> Subscription<Message> {
     //
     // }
 
-    pub fn view(&self) -> Element<'_, Message> {
-        mouse_area(svg(svg::Handle::from_memory(
-            self.texture.get_cell_asset(self.game.blocking_gamestate().board[self.point], self.is_armed()))))
-                .on_press(Message::Press(mouse::Button::Left))
+    pub fn view(&self, cell: minsweeper_rs::Cell, provenance: Option<Provenance>, risk_shading: bool) -> Element<'_, Message> {
+        let (handle, risk) = self.texture.get_shaded_cell_asset(cell, self.is_armed(), risk_shading);
+        let cell = svg(handle)
+                .opacity(if self.highlighted { 0.5 } else { 1.0 });
+        let fill = |tint: Color| container(Space::new())
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .style(move |_| container::Style {
+                    background: Some(Background::Color(tint)),
+                    ..Default::default()
+                });
+
+        // the risk shading goes under the asset so the number is drawn over it untouched, a flag
+        // too many blinks over everything
+        let cell: Element<_> = match risk {
+            Some(tint) => stack![fill(tint), cell].into(),
+            None => cell.into(),
+        };
+        let cell: Element<_> = if self.excess {
+            stack![cell, fill(EXCESS_FLAG_TINT)].into()
+        } else {
+            cell
+        };
+
+        // solver cells get a small dot in the corner, player cells stay unmarked
+        let content: Element<_> = match provenance {
+            Some(Provenance::Solver(_)) => {
+                let accent = self.texture.manifest().accent;
+                stack![
+                    cell,
+                    container(container(Space::new())
+                            .width(6)
+                            .height(6)
+                            .style(move |_| container::Style {
+                                background: Some(Background::Color(accent)),
+                                border: Border::default().rounded(3),
+                                ..Default::default()
+                            }))
+                            .padding(2)
+                            .width(Length::Fill)
+                            .height(Length::Fill)
+                            .align_x(Horizontal::Right)
+                            .align_y(Vertical::Top),
+                ].into()
+            }
+            _ => cell,
+        };
+
+        let content: Element<_> = match self.highlight {
+            Some(highlight) => {
+                let accent = self.texture.manifest().accent;
+                stack![
+                    content,
+                    container(Space::new())
+                            .width(Length::Fill)
+                            .height(Length::Fill)
+                            .style(move |_| container::Style {
+                                background: highlight.tint().map(Background::Color),
+                                border: match highlight {
+                                    HighlightKind::Source => Border::default().color(accent).width(2),
+                                    _ => Border::default(),
+                                },
+                                ..Default::default()
+                            }),
+                ].into()
+            }
+            None => content,
+        };
+
+        let content: Element<_> = match self.preview {
+            Some(operation) => {
+                let accent = self.texture.manifest().accent;
+                stack![
+                    content,
+                    container(text(preview_label(operation)).size(10).color(accent))
+                            .padding(1)
+                            .width(Length::Fill)
+                            .height(Length::Fill)
+                            .style(move |_| container::Style {
+                                border: Border::default().color(accent).width(2),
+                                ..Default::default()
+                            }),
+                ].into()
+            }
+            None => content,
+        };
+
+        mouse_area(content)
+                .on_press(Message::SelfPress(mouse::Button::Left))
                 .on_middle_press(Message::SelfPress(mouse::Button::Middle))
                 .on_right_press(Message::SelfPress(mouse::Button::Right))
                 .on_release(Message::SelfRelease(mouse::Button::Left))
@@ -120,4 +238,67 @@ impl Cell {
                 .on_exit(Message::Exit)
                 .into()
     }
-}
\ No newline at end of file
+
+    /// a covered cell standing in while the board is being restored, it only passes on clicks
+    /// for them to be queued until the board is there
+    pub fn skeleton(&self) -> Element<'_, Message> {
+        mouse_area(svg(self.texture.get_cell_asset(minsweeper_rs::Cell::EMPTY, false)))
+                .on_press(Message::SelfPress(mouse::Button::Left))
+                .on_middle_press(Message::SelfPress(mouse::Button::Middle))
+                .on_right_press(Message::SelfPress(mouse::Button::Right))
+                .on_release(Message::SelfRelease(mouse::Button::Left))
+                .on_middle_release(Message::SelfRelease(mouse::Button::Middle))
+                .on_right_release(Message::SelfRelease(mouse::Button::Right))
+                .into()
+    }
+}
+
+/// marks what a previewed solver step does to a cell
+pub fn preview_label(operation: Operation) -> &'static str {
+    match operation {
+        Operation::Reveal => "R",
+        Operation::Chord => "C",
+        Operation::Flag => "F",
+    }
+}
+
+/// spreadsheet style, a letter for the column counting from A and the row counting from 1
+pub fn coordinate((column, row): minsweeper_rs::board::Point) -> String {
+    let mut letters = vec![];
+    let mut column = column + 1;
+    while column > 0 {
+        column -= 1;
+        letters.push((b'A' + (column % 26) as u8) as char);
+        column /= 26;
+    }
+    letters.iter().rev().collect::<String>() + &(row + 1).to_string()
+}
+
+/// the cell's coordinate and what's on it, `flags` being how many of its neighbours are flagged
+pub fn status(point: minsweeper_rs::board::Point, cell: minsweeper_rs::Cell, flags: usize) -> String {
+    let state = match (cell.cell_state, cell.cell_type) {
+        (CellState::Flagged, _) => t!("status-flagged").to_string(),
+        (CellState::Unknown, _) => t!("status-hidden").to_string(),
+        (CellState::Revealed, CellType::Mine) => t!("status-mine").to_string(),
+        (CellState::Revealed, CellType::Safe(0)) => t!("status-empty").to_string(),
+        (CellState::Revealed, CellType::Safe(number)) => t!("status-number", count = number, flags = flags),
+        (CellState::Revealed, CellType::Unknown) => t!("status-hidden").to_string(),
+    };
+    t!("status-cell", coordinate = coordinate(point), state = state)
+}
+
+/// what a cell is in words, for the status line screen readers follow
+pub fn describe(point: minsweeper_rs::board::Point, cell: minsweeper_rs::Cell) -> String {
+    let (column, row) = point;
+    let state = match (cell.cell_state, cell.cell_type) {
+        (CellState::Flagged, _) => t!("cell-flagged").to_string(),
+        (CellState::Unknown, CellType::Mine) => t!("cell-hidden-mine").to_string(),
+        (CellState::Unknown, _) => t!("cell-hidden").to_string(),
+        (CellState::Revealed, CellType::Mine) => t!("cell-mine").to_string(),
+        (CellState::Revealed, CellType::Safe(0)) => t!("cell-revealed-empty").to_string(),
+        (CellState::Revealed, CellType::Safe(1)) => t!("cell-revealed-one").to_string(),
+        (CellState::Revealed, CellType::Safe(number)) => t!("cell-revealed-number", count = number),
+        (CellState::Revealed, CellType::Unknown) => t!("cell-revealed").to_string(),
+    };
+    t!("cell-description", row = row + 1, column = column + 1, state = state)
+}
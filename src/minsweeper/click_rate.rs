@@ -0,0 +1,52 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// how far back clicks count towards the rate
+const WINDOW: Duration = Duration::from_secs(5);
+/// more clicks than this in [`WINDOW`] aren't humanly possible, the oldest make room
+const CAPACITY: usize = 128;
+
+/// the times of the latest clicks for the overlay's clicks per second, sampled on the timer's tick
+/// so the number doesn't flicker with every click
+#[derive(Debug)]
+pub struct ClickRate {
+    recent: VecDeque<Instant>,
+    total: usize,
+    rate: f32,
+}
+
+impl Default for ClickRate {
+    fn default() -> Self {
+        Self { recent: VecDeque::with_capacity(CAPACITY), total: 0, rate: 0.0 }
+    }
+}
+
+impl ClickRate {
+    pub fn click(&mut self, at: Instant) {
+        if self.recent.len() == CAPACITY {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(at);
+        self.total += 1;
+    }
+
+    pub fn sample(&mut self, now: Instant) {
+        while self.recent.front().is_some_and(|at| now.saturating_duration_since(*at) > WINDOW) {
+            self.recent.pop_front();
+        }
+        self.rate = self.recent.len() as f32 / WINDOW.as_secs_f32();
+    }
+
+    /// per second, as of the last [`sample`](Self::sample)
+    pub fn rate(&self) -> f32 {
+        self.rate
+    }
+
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
@@ -0,0 +1,256 @@
+use crate::i18n::t;
+use crate::minsweeper::SolverType;
+use iced::{keyboard, mouse};
+use minsweeper_rs::board::{BoardSize, ConventionalSize};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Display, Formatter};
+use std::time::Duration;
+
+// what the game needs to be told from outside, kept free of the settings and persistence layers
+// so the board can be driven without them
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum CellScale {
+    #[default]
+    Fit,
+    Fixed(u16),
+    Native,
+}
+
+impl CellScale {
+    /// the cell svgs are drawn on a 160 unit canvas
+    const NATIVE_SIZE: f32 = 160.0;
+
+    pub fn fixed_size(self) -> Option<f32> {
+        match self {
+            CellScale::Fit => None,
+            CellScale::Fixed(size) => Some(size as f32),
+            CellScale::Native => Some(Self::NATIVE_SIZE),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+}
+
+impl MouseButton {
+    pub const ALL: [MouseButton; 3] = [MouseButton::Left, MouseButton::Right, MouseButton::Middle];
+}
+
+impl From<MouseButton> for mouse::Button {
+    fn from(button: MouseButton) -> Self {
+        match button {
+            MouseButton::Left => mouse::Button::Left,
+            MouseButton::Right => mouse::Button::Right,
+            MouseButton::Middle => mouse::Button::Middle,
+        }
+    }
+}
+
+impl Display for MouseButton {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            MouseButton::Left => "Left",
+            MouseButton::Right => "Right",
+            MouseButton::Middle => "Middle",
+        })
+    }
+}
+
+/// the key that, held over a number, shows which cells count towards it
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum HighlightModifier {
+    Alt,
+    Shift,
+    Control,
+}
+
+impl HighlightModifier {
+    pub const ALL: [HighlightModifier; 3] = [HighlightModifier::Alt, HighlightModifier::Shift, HighlightModifier::Control];
+
+    pub fn held(self, modifiers: keyboard::Modifiers) -> bool {
+        match self {
+            HighlightModifier::Alt => modifiers.alt(),
+            HighlightModifier::Shift => modifiers.shift(),
+            HighlightModifier::Control => modifiers.control(),
+        }
+    }
+}
+
+impl Display for HighlightModifier {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            HighlightModifier::Alt => "Alt",
+            HighlightModifier::Shift => "Shift",
+            HighlightModifier::Control => "Ctrl",
+        })
+    }
+}
+
+/// what a button does to a cell, flagging happens on press and the others on release
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum MouseAction {
+    Reveal,
+    Flag,
+    /// only chords numbers, never reveals a hidden cell
+    Chord,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct MouseBindings {
+    pub reveal: MouseButton,
+    pub flag: MouseButton,
+    pub chord: Option<MouseButton>,
+}
+
+impl Default for MouseBindings {
+    fn default() -> Self {
+        Self {
+            reveal: MouseButton::Left,
+            flag: MouseButton::Right,
+            chord: None,
+        }
+    }
+}
+
+impl MouseBindings {
+    pub fn action(&self, button: mouse::Button) -> Option<MouseAction> {
+        if button == self.reveal.into() {
+            Some(MouseAction::Reveal)
+        } else if button == self.flag.into() {
+            Some(MouseAction::Flag)
+        } else if self.chord.is_some_and(|chord| button == chord.into()) {
+            Some(MouseAction::Chord)
+        } else {
+            None
+        }
+    }
+
+    /// why these bindings can't be used, every action needs a button of its own
+    pub fn problem(&self) -> Option<&'static str> {
+        if self.reveal == self.flag {
+            Some("reveal and flag can't share a button")
+        } else if self.chord.is_some_and(|chord| chord == self.reveal || chord == self.flag) {
+            Some("chord needs a button that isn't used yet")
+        } else {
+            None
+        }
+    }
+}
+
+/// keeps playing the solver's moves after every click
+#[derive(Clone)]
+pub struct AutoPlay {
+    /// `None` uses the game's own solver
+    pub solver: Option<SolverType>,
+    pub delay: Duration,
+    pub guess: AutoGuess,
+    /// how long a finished game stays up before auto starts the next one, `None` leaves it up
+    pub restart: Option<Duration>,
+}
+
+impl Debug for AutoPlay {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "AutoPlay {{ delay: {:?}, guess: {:?}, restart: {:?} }}", self.delay, self.guess, self.restart)
+    }
+}
+
+/// the streamer overlay in the board's corner
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Overlay {
+    pub seed: bool,
+    /// of its background and text, 0 to 1
+    pub opacity: f32,
+}
+
+/// what auto does once the solver has nothing certain left and the game isn't over
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum AutoGuess {
+    /// stops, leaving the rest to the player
+    #[default]
+    Never,
+    /// reveals the first covered corner, or failing that edge, where openings are likeliest
+    Corner,
+    /// reveals the covered cell least likely to be a mine
+    LowestProbability,
+}
+
+impl AutoGuess {
+    pub const ALL: [AutoGuess; 3] = [AutoGuess::Never, AutoGuess::Corner, AutoGuess::LowestProbability];
+}
+
+impl Display for AutoGuess {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            AutoGuess::Never => t!("auto-guess-never"),
+            AutoGuess::Corner => t!("auto-guess-corner"),
+            AutoGuess::LowestProbability => t!("auto-guess-lowest-probability"),
+        })
+    }
+}
+
+/// what the counter left of the face counts down
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum CounterMode {
+    /// mines less flags
+    #[default]
+    Mines,
+    /// safe cells still covered
+    SafeCells,
+    /// mines left of the face and safe cells right of it
+    Both,
+}
+
+impl CounterMode {
+    pub const ALL: [CounterMode; 3] = [CounterMode::Mines, CounterMode::SafeCells, CounterMode::Both];
+}
+
+impl Display for CounterMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            CounterMode::Mines => t!("counter-mines"),
+            CounterMode::SafeCells => t!("counter-safe-cells"),
+            CounterMode::Both => t!("counter-both"),
+        })
+    }
+}
+
+/// whether the board is shown turned on its side, columns as rows, for windows taller than wide
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum BoardRotation {
+    #[default]
+    Off,
+    Portrait,
+    /// turned whenever the window is taller than it is wide
+    Auto,
+}
+
+impl BoardRotation {
+    pub const ALL: [BoardRotation; 3] = [BoardRotation::Off, BoardRotation::Portrait, BoardRotation::Auto];
+}
+
+impl Display for BoardRotation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            BoardRotation::Off => t!("rotation-off"),
+            BoardRotation::Portrait => t!("rotation-portrait"),
+            BoardRotation::Auto => t!("rotation-auto"),
+        })
+    }
+}
+
+pub fn size_label(size: BoardSize) -> String {
+    if size == ConventionalSize::Beginner.size() {
+        "Beginner".to_string()
+    } else if size == ConventionalSize::Intermediate.size() {
+        "Intermediate".to_string()
+    } else if size == ConventionalSize::Expert.size() {
+        "Expert".to_string()
+    } else {
+        format!("Custom {}×{}, {} mines", size.width(), size.height(), size.mines())
+    }
+}
@@ -0,0 +1,133 @@
+use crate::i18n::t;
+use crate::minsweeper::cell::{coordinate, HighlightKind};
+use minsweeper_rs::board::Point;
+use minsweeper_rs::solver::{Action, Operation, Reason};
+use minsweeper_rs::{CellState, CellType, GameState};
+
+/// how many explanations the panel holds on to, the oldest go first
+pub const KEPT: usize = 50;
+/// a move that does more than this many of one thing only names the first few cells
+const NAMED: usize = 3;
+
+/// why the solver made a move, one line in the explanations panel
+#[derive(Clone, Debug)]
+pub struct Explanation {
+    pub text: String,
+    /// lit up while the line is hovered, what the reason points at outlined and what the move
+    /// did tinted
+    pub cells: Vec<(Point, HighlightKind)>,
+}
+
+/// the `actions` of a move worked out from `gamestate`. the solver's own reason is used when it
+/// gives one, otherwise each action gets the simplest rule that covers it
+pub fn explain(actions: &[Action], reason: Option<&Reason>, gamestate: &GameState) -> Explanation {
+    let mut actions = actions.to_vec();
+    actions.sort_by_key(|action| (action.point.1, action.point.0));
+
+    let mut cells = actions.iter()
+            .map(|action| (action.point, match action.operation {
+                Operation::Flag => HighlightKind::Flagged,
+                Operation::Reveal | Operation::Chord => HighlightKind::Unknown,
+            }))
+            .collect::<Vec<_>>();
+    let referenced = |point: Point, cells: &mut Vec<(Point, HighlightKind)>| {
+        if !cells.iter().any(|(cell, _)| *cell == point) {
+            cells.push((point, HighlightKind::Source));
+        }
+    };
+
+    let reason = match reason {
+        Some(reason) => {
+            let mut related = reason.related.iter().copied().collect::<Vec<_>>();
+            related.sort_by_key(|point| (point.1, point.0));
+            for point in related {
+                referenced(point, &mut cells);
+            }
+            reason.logic.to_string()
+        }
+        None => {
+            let mut sources = vec![];
+            let mut reasons = vec![];
+            for action in &actions {
+                let Some((source, reason)) = rule(*action, gamestate) else { continue };
+                if !sources.contains(&source) {
+                    sources.push(source);
+                    reasons.push(reason);
+                }
+            }
+            for source in sources {
+                referenced(source, &mut cells);
+            }
+            if reasons.is_empty() {
+                t!("explain-no-rule").to_string()
+            } else {
+                reasons.join(t!("explain-separator"))
+            }
+        }
+    };
+
+    Explanation {
+        text: t!("explain-line", actions = describe(&actions), reason = reason),
+        cells,
+    }
+}
+
+/// the number that forces `action` and what it says, if a single number does
+fn rule(action: Action, gamestate: &GameState) -> Option<(Point, String)> {
+    let size = gamestate.board.size();
+    let number = |point: Point| match gamestate.board[point] {
+        minsweeper_rs::Cell { cell_state: CellState::Revealed, cell_type: CellType::Safe(number @ 1..) } => Some(number as usize),
+        _ => None,
+    };
+    let around = |point: Point, state: CellState| size.neighbours(point)
+            .filter(|around| gamestate.board[*around].cell_state == state)
+            .count();
+    // every mine around it is flagged, so whatever else is covered is safe
+    let satisfied = |point: Point| number(point)
+            .filter(|number| around(point, CellState::Flagged) == *number)
+            .map(|number| (point, t!("explain-satisfied", cell = coordinate(point), count = number)));
+
+    match action.operation {
+        Operation::Chord => satisfied(action.point),
+        Operation::Reveal => size.neighbours(action.point).find_map(satisfied),
+        // as many covered cells around it as it has mines, so every one of them is a mine
+        Operation::Flag => size.neighbours(action.point).find_map(|point| number(point)
+                .filter(|number| around(point, CellState::Flagged) + around(point, CellState::Unknown) == *number)
+                .map(|number| (point, match number {
+                    1 => t!("explain-forced-one", cell = coordinate(point)),
+                    number => t!("explain-forced", cell = coordinate(point), count = number),
+                }))),
+    }
+}
+
+/// what the move does, in the order reveals, chords, flags
+fn describe(actions: &[Action]) -> String {
+    [Operation::Reveal, Operation::Chord, Operation::Flag].into_iter()
+            .filter_map(|operation| {
+                let points = actions.iter()
+                        .filter(|action| action.operation == operation)
+                        .map(|action| coordinate(action.point))
+                        .collect::<Vec<_>>();
+                let cells = match points.len() {
+                    0 => return None,
+                    1..=NAMED => points.join(", "),
+                    more => t!("explain-more", cells = points[..NAMED].join(", "), count = more - NAMED),
+                };
+                Some(match operation {
+                    Operation::Reveal => t!("explain-reveal", cells = cells),
+                    Operation::Chord => t!("explain-chord", cells = cells),
+                    Operation::Flag => t!("explain-flag", cells = cells),
+                })
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+}
+
+/// a reveal auto made once the solver had nothing certain, `risk` being how likely a mine it was
+pub fn guessed(point: Point, risk: f32) -> Explanation {
+    Explanation {
+        text: t!("explain-line", actions = describe(&[Action::new(point, Operation::Reveal)]),
+                reason = t!("explain-guess", percent = format!("{:.0}", risk * 100.0))),
+        cells: vec![(point, HighlightKind::Unknown)],
+    }
+}
@@ -0,0 +1,419 @@
+use crate::minsweeper::rules::{MineOutcome, RuleStack};
+use crate::minsweeper::SolverType;
+use minsweeper_rs::board::{Board, BoardSize, Point};
+use minsweeper_rs::minsweeper::SetMinsweeperGame;
+use minsweeper_rs::solver::{GameResult, Solver};
+use minsweeper_rs::{Cell, CellState, CellType, GameState, GameStatus, Minsweeper};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use tokio::sync::{Mutex, RwLock};
+
+pub struct Game {
+    size: BoardSize,
+    state: RwLock<State>,
+    generate_lock: Mutex<()>,
+}
+
+struct State {
+    seed: u64,
+    epoch: u64,
+    phase: Phase,
+    clicks: Vec<(Click, Provenance)>,
+    provenance: HashMap<Point, Provenance>,
+    rules: RuleStack,
+    /// a rule ended the game before the board did, like a time trial running out
+    ended: bool,
+    /// the game from just before the player's click that lost it
+    undo: Option<Snapshot>,
+}
+
+struct Snapshot {
+    game: SetMinsweeperGame,
+    clicks: usize,
+    provenance: HashMap<Point, Provenance>,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Click {
+    Left(Point),
+    Right(Point),
+}
+
+/// who applied an action, kept per cell for the cells it revealed or flagged
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Provenance {
+    Player,
+    Solver(u32),
+}
+
+#[derive(Clone, Debug)]
+pub struct History {
+    pub epoch: u64,
+    pub seed: u64,
+    pub layout: GameState,
+    pub clicks: Vec<(Click, Provenance)>,
+}
+
+enum Phase {
+    Never,
+    Waiting(SolverType),
+    Playing {
+        layout: GameState,
+        game: SetMinsweeperGame,
+    },
+}
+
+impl Game {
+
+    pub fn new(size: BoardSize) -> Self {
+        Self {
+            size,
+            state: RwLock::new(State {
+                seed: 0,
+                epoch: 0,
+                phase: Phase::Never,
+                clicks: vec![],
+                provenance: HashMap::new(),
+                rules: RuleStack::default(),
+                ended: false,
+                undo: None,
+            }),
+            generate_lock: Default::default(),
+        }
+    }
+
+    pub async fn start_with_solver(&self, solver: SolverType, seed: u64) -> GameState {
+        let mut state = self.state.write().await;
+        state.seed = seed;
+        state.epoch += 1;
+        state.phase = Phase::Waiting(solver);
+        state.ended = false;
+        state.clicks.clear();
+        state.provenance.clear();
+        state.undo = None;
+        self.player_gamestate(&state)
+    }
+
+    pub async fn start_with_layout(&self, layout: GameState, seed: u64) -> GameState {
+        let mut state = self.state.write().await;
+        self.begin_layout(&mut state, layout, seed)
+    }
+
+    /// [`start_with_layout`](Self::start_with_layout) for outside a runtime, where nothing else
+    /// can be holding the game
+    #[cfg(test)]
+    pub fn blocking_start_with_layout(&self, layout: GameState, seed: u64) -> GameState {
+        let mut state = self.state.blocking_write();
+        self.begin_layout(&mut state, layout, seed)
+    }
+
+    fn begin_layout(&self, state: &mut State, layout: GameState, seed: u64) -> GameState {
+        state.seed = seed;
+        state.epoch += 1;
+        state.ended = false;
+        state.clicks.clear();
+        state.provenance.clear();
+        state.undo = None;
+        state.phase = Phase::Playing {
+            game: SetMinsweeperGame::new(layout.clone()),
+            layout,
+        };
+        self.player_gamestate(state)
+    }
+
+    pub fn blocking_set_rules(&self, rules: RuleStack) {
+        self.state.blocking_write().rules = rules;
+    }
+
+    /// loses the game where it stands, for when a rule says it's over but the board doesn't, so
+    /// nothing else plays on
+    pub fn blocking_end(&self) {
+        let mut state = self.state.blocking_write();
+        state.ended = true;
+        state.undo = None;
+    }
+
+    pub fn blocking_layout(&self) -> Option<GameState> {
+        match &self.state.blocking_read().phase {
+            Phase::Playing { layout, .. } => Some(layout.clone()),
+            _ => None
+        }
+    }
+
+    pub fn blocking_history(&self) -> Option<History> {
+        let state = self.state.blocking_read();
+        match &state.phase {
+            Phase::Playing { layout, game } if game.gamestate().status == GameStatus::Playing && !state.ended => Some(History {
+                epoch: state.epoch,
+                seed: state.seed,
+                layout: layout.clone(),
+                clicks: state.clicks.clone(),
+            }),
+            _ => None
+        }
+    }
+
+    /// clicks that did something, the player's and the solver's apart
+    pub fn blocking_clicks_by(&self) -> (usize, usize) {
+        let state = self.state.blocking_read();
+        let player = state.clicks.iter()
+                .filter(|(_, by)| *by == Provenance::Player)
+                .count();
+        (player, state.clicks.len() - player)
+    }
+
+    /// whether a flag went down at any point, even one that came off again
+    pub fn blocking_flagged(&self) -> bool {
+        self.state.blocking_read().clicks.iter()
+                .any(|(click, _)| matches!(click, Click::Right(_)))
+    }
+
+    pub fn blocking_opening(&self) -> Option<Point> {
+        self.state.blocking_read().clicks.iter()
+                .find_map(|(click, _)| match click {
+                    Click::Left(point) => Some(*point),
+                    Click::Right(_) => None,
+                })
+    }
+
+    /// the last click that revealed anything and who made it, on a finished board the one that
+    /// ended it
+    pub fn blocking_last_reveal(&self) -> Option<(Point, Provenance)> {
+        self.state.blocking_read().clicks.iter()
+                .rev()
+                .find_map(|(click, by)| match click {
+                    Click::Left(point) => Some((*point, *by)),
+                    Click::Right(_) => None,
+                })
+    }
+
+    /// whether the game was lost by a player's click that can be taken back
+    pub fn blocking_can_undo(&self) -> bool {
+        self.state.blocking_read().undo.is_some()
+    }
+
+    /// puts the board back how it was before the click that lost it, flags and all
+    pub fn blocking_undo(&self) -> bool {
+        let mut state = self.state.blocking_write();
+        let State { phase, clicks, provenance, undo, .. } = &mut *state;
+        let (Phase::Playing { game, .. }, Some(snapshot)) = (phase, undo.take()) else { return false };
+        *game = snapshot.game;
+        clicks.truncate(snapshot.clicks);
+        *provenance = snapshot.provenance;
+        true
+    }
+
+    pub fn blocking_provenance(&self) -> HashMap<Point, Provenance> {
+        self.state.blocking_read().provenance.clone()
+    }
+
+    pub fn blocking_seed(&self) -> u64 {
+        self.state.blocking_read().seed
+    }
+
+    pub async fn gamestate(&self) -> GameState {
+        self.player_gamestate(&*self.state.read().await)
+    }
+
+    pub fn blocking_gamestate(&self) -> GameState {
+        self.player_gamestate(&self.state.blocking_read())
+    }
+
+    fn player_gamestate(&self, state: &State) -> GameState {
+        match &state.phase {
+            Phase::Never => GameState::new(GameStatus::Never, unknown_board(self.size), 0),
+            Phase::Waiting(_) => GameState::new(GameStatus::Playing, unknown_board(self.size),
+                                                self.size.mines().get() as isize),
+            Phase::Playing { layout, game } => {
+                let gamestate = game.gamestate();
+                let status = match gamestate.status {
+                    GameStatus::Playing if state.ended => GameStatus::Lost,
+                    status => status,
+                };
+                if status == GameStatus::Playing {
+                    return gamestate.clone()
+                }
+
+                let mut board = layout.board.clone();
+                for point in self.size.points() {
+                    board[point].cell_state = gamestate.board[point].cell_state;
+                }
+                GameState::new(status, board, gamestate.remaining_mines)
+            }
+        }
+    }
+
+    async fn generate(&self, point: Point) {
+        let _guard = self.generate_lock.lock().await;
+        let state = self.state.read().await;
+        let Phase::Waiting(solver) = &state.phase else { return };
+        let solver = solver.clone();
+        let seed = state.seed;
+        let epoch = state.epoch;
+        drop(state);
+
+        let layout = generate_solvable(self.size, seed, &solver, point).await;
+
+        let mut state = self.state.write().await;
+        if state.epoch == epoch {
+            state.phase = Phase::Playing {
+                game: SetMinsweeperGame::new(layout.clone()),
+                layout,
+            };
+        }
+    }
+
+    pub async fn left_click(&self, point: Point, by: Provenance) -> Result<GameState, GameState> {
+        self.generate(point).await;
+
+        let mut state = self.state.write().await;
+        let State { phase, clicks, provenance, rules, undo, ended, .. } = &mut *state;
+        let (Phase::Playing { game, layout }, false) = (phase, *ended) else {
+            return Err(self.player_gamestate(&state))
+        };
+        let before = game.clone();
+        let mut result = Minsweeper::left_click(game, point).is_ok();
+
+        if game.gamestate().status == GameStatus::Lost {
+            let mines = self.size.points()
+                    .filter(|point| layout.board[*point].cell_type == CellType::Mine
+                            && game.gamestate().board[*point].cell_state == CellState::Revealed
+                            && before.gamestate().board[*point].cell_state != CellState::Revealed)
+                    .collect::<Vec<_>>();
+
+            if !mines.is_empty() && mines.iter().all(|mine| rules.on_mine_revealed(*mine) == MineOutcome::Continue) {
+                *game = before.clone();
+                for mine in mines {
+                    if Minsweeper::set_flagged(game, mine, true).is_ok() {
+                        clicks.push((Click::Right(mine), by));
+                    }
+                }
+                result = Minsweeper::left_click(game, point).is_ok();
+            }
+        }
+
+        if result && by == Provenance::Player && game.gamestate().status == GameStatus::Lost {
+            *undo = Some(Snapshot {
+                game: before.clone(),
+                clicks: clicks.len(),
+                provenance: provenance.clone(),
+            });
+        }
+        if result {
+            clicks.push((Click::Left(point), by));
+        }
+        attribute(provenance, &before, game, by);
+
+        let gamestate = self.player_gamestate(&state);
+        if result { Ok(gamestate) } else { Err(gamestate) }
+    }
+
+    pub async fn right_click(&self, point: Point, by: Provenance) -> Result<GameState, GameState> {
+        self.set_flagged(point, by, None).await
+    }
+
+    /// flags `point` if it's covered, unlike a right click it never takes a flag off
+    pub async fn flag(&self, point: Point, by: Provenance) -> Result<GameState, GameState> {
+        self.set_flagged(point, by, Some(true)).await
+    }
+
+    /// `None` toggles the flag, the change is kept as a right click either way
+    async fn set_flagged(&self, point: Point, by: Provenance, flagged: Option<bool>) -> Result<GameState, GameState> {
+        let mut state = self.state.write().await;
+        let State { phase, clicks, provenance, ended, .. } = &mut *state;
+        let (Phase::Playing { game, .. }, false) = (phase, *ended) else {
+            return Err(self.player_gamestate(&state))
+        };
+        let before = game.clone();
+        let changes = flagged.is_none_or(|flagged| flagged != (game.gamestate().board[point].cell_state == CellState::Flagged));
+        let result = changes && Minsweeper::right_click(game, point).is_ok();
+        if result {
+            clicks.push((Click::Right(point), by));
+        }
+        attribute(provenance, &before, game, by);
+
+        let gamestate = self.player_gamestate(&state);
+        if result { Ok(gamestate) } else { Err(gamestate) }
+    }
+
+    pub async fn click(&self, click: Click, by: Provenance) -> Result<GameState, GameState> {
+        match click {
+            Click::Left(point) => self.left_click(point, by).await,
+            Click::Right(point) => self.right_click(point, by).await,
+        }
+    }
+}
+
+fn attribute(provenance: &mut HashMap<Point, Provenance>, before: &SetMinsweeperGame, after: &SetMinsweeperGame, by: Provenance) {
+    let size = after.gamestate().board.size();
+    for point in size.points() {
+        let state = after.gamestate().board[point].cell_state;
+        if state == before.gamestate().board[point].cell_state {
+            continue
+        }
+        match state {
+            CellState::Unknown => provenance.remove(&point),
+            CellState::Revealed | CellState::Flagged => provenance.insert(point, by),
+        };
+    }
+}
+
+fn unknown_board(size: BoardSize) -> Board {
+    Board::new(size, Cell::new(CellType::Unknown, CellState::Unknown))
+}
+
+fn generate_layout(size: BoardSize, rng: &mut impl Rng) -> GameState {
+    let mut mines = vec![];
+    let mut placed = HashSet::new();
+    while mines.len() < size.mines().get() {
+        let point = (rng.random_range(0..size.width().get()),
+                     rng.random_range(0..size.height().get()));
+
+        if placed.insert(point) {
+            mines.push(point);
+        }
+    }
+
+    layout_from_mines(size, &mines)
+}
+
+pub fn layout_from_mines(size: BoardSize, mines: &[Point]) -> GameState {
+    let mut board = Board::empty(size);
+
+    for point in mines {
+        board[*point] = Cell::new(CellType::Mine, CellState::Unknown);
+    }
+
+    for point in size.points() {
+        if board[point].cell_type == CellType::Mine {
+            for neighbour in size.neighbours(point) {
+                if let CellType::Safe(number) = board[neighbour].cell_type {
+                    board[neighbour].cell_type = CellType::Safe(number + 1);
+                }
+            }
+        }
+    }
+
+    GameState::new(GameStatus::Playing, board, mines.len() as isize)
+}
+
+/// the first layout from `seed` that `solver` wins from `point`. layouts are tried in the same
+/// order for a seed, but which one is taken depends on the click as well, so a seed only gives the
+/// same board again when it's opened the same way
+async fn generate_solvable(size: BoardSize, seed: u64, solver: &SolverType, point: Point) -> GameState {
+    let mut rng = StdRng::seed_from_u64(seed);
+    loop {
+        let layout = generate_layout(size, &mut rng);
+
+        let mut game = SetMinsweeperGame::new(layout.clone());
+        Minsweeper::reveal(&mut game, point)
+                .expect("should always be able to successfully reveal");
+
+        if solver.solve_game(&mut game) == GameResult::Won {
+            return layout
+        }
+        tokio::task::yield_now().await;
+    }
+}
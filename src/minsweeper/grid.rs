@@ -1,42 +1,91 @@
 use minsweeper_rs::board::Point;
-use std::iter::Flatten;
 use std::ops::{Index, IndexMut};
+use std::slice::{Chunks, Iter, IterMut};
 use std::vec::IntoIter;
 
+#[cfg(test)]
+mod tests;
+
+/// a width × height grid stored row by row, iteration always goes left to right then top to bottom
 #[derive(Clone, Debug)]
 pub struct Grid<E> {
-    grid: Vec<Vec<E>>,
+    grid: Vec<E>,
     width: usize,
     height: usize
 }
 
 impl<E> Grid<E> {
-    const fn from_2d_vec(vec: Vec<Vec<E>>, width: usize, height: usize) -> Self<> {
+    pub fn new(width: usize, height: usize, generator: impl Fn(Point) -> E) -> Self<> {
         Self {
-            grid: vec,
+            grid: (0..height)
+                    .flat_map(|y| (0..width).map(move |x| (x, y)))
+                    .map(generator)
+                    .collect(),
             width,
             height
         }
     }
 
-    pub fn new(width: usize, height: usize, generator: impl Fn(Point) -> E) -> Self<> {
-        let mut columns = vec![];
-        for x in 0..width {
-            let mut column = vec![];
-            for y in 0..height {
-                column.push(generator((x, y)));
-            }
-            columns.push(column);
-        }
-        Self::from_2d_vec(columns, width, height)
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn offset(&self, (x, y): Point) -> Option<usize> {
+        (x < self.width && y < self.height).then_some(y * self.width + x)
+    }
+
+    fn point(&self, offset: usize) -> Point {
+        (offset % self.width, offset / self.width)
     }
-    
-    pub fn iter(&self) -> impl Iterator<Item = &E> {
-        self.into_iter()
+
+    pub fn get(&self, point: Point) -> Option<&E> {
+        self.offset(point).map(|offset| &self.grid[offset])
     }
 
-    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut E> {
-        self.into_iter()
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut E> {
+        self.offset(point).map(|offset| &mut self.grid[offset])
+    }
+
+    pub fn iter(&self) -> Iter<'_, E> {
+        self.grid.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, E> {
+        self.grid.iter_mut()
+    }
+
+    pub fn enumerate(&self) -> impl Iterator<Item = (Point, &E)> {
+        self.grid.iter()
+                .enumerate()
+                .map(|(offset, e)| (self.point(offset), e))
+    }
+
+    /// like [`enumerate`](Self::enumerate) but down each column in turn when `transposed`, the
+    /// order the cells are laid out in with the grid turned on its side
+    pub fn laid_out(&self, transposed: bool) -> impl Iterator<Item = (Point, &E)> {
+        let (width, height) = (self.width, self.height);
+        let points: Box<dyn Iterator<Item = Point>> = match transposed {
+            false => Box::new((0..height).flat_map(move |y| (0..width).map(move |x| (x, y)))),
+            true => Box::new((0..width).flat_map(move |x| (0..height).map(move |y| (x, y)))),
+        };
+        points.map(|point| (point, &self[point]))
+    }
+
+    pub fn rows(&self) -> Chunks<'_, E> {
+        // chunks panics on 0, an empty grid just has no rows
+        self.grid.chunks(self.width.max(1))
+    }
+
+    pub fn map<T>(self, f: impl FnMut(E) -> T) -> Grid<T> {
+        Grid {
+            grid: self.grid.into_iter().map(f).collect(),
+            width: self.width,
+            height: self.height
+        }
     }
 }
 
@@ -45,44 +94,63 @@ impl<E> Index<Point> for Grid<E> {
     type Output = E;
 
     fn index(&self, index: Point) -> &Self::Output {
-        &self.grid[index.0][index.1]
+        match self.offset(index) {
+            Some(offset) => &self.grid[offset],
+            None => panic!("{:?} is out of bounds for a {}x{} grid", index, self.width, self.height),
+        }
     }
 }
 
 impl<E> IndexMut<Point> for Grid<E> {
     fn index_mut(&mut self, index: Point) -> &mut Self::Output {
-        &mut self.grid[index.0][index.1]
+        match self.offset(index) {
+            Some(offset) => &mut self.grid[offset],
+            None => panic!("{:?} is out of bounds for a {}x{} grid", index, self.width, self.height),
+        }
+    }
+}
+
+/// collects `(point, element)` pairs in any order, the grid is sized to fit the largest point and
+/// every point has to be present exactly once
+impl<E> FromIterator<(Point, E)> for Grid<E> {
+    fn from_iter<T: IntoIterator<Item = (Point, E)>>(iter: T) -> Self {
+        let mut elements = iter.into_iter().collect::<Vec<_>>();
+        let width = elements.iter().map(|((x, _), _)| x + 1).max().unwrap_or(0);
+        let height = elements.iter().map(|((_, y), _)| y + 1).max().unwrap_or(0);
+        elements.sort_by_key(|((x, y), _)| (*y, *x));
+        elements.dedup_by_key(|(point, _)| *point);
+        assert_eq!(elements.len(), width * height, "every point of the grid needs an element");
+
+        Self {
+            grid: elements.into_iter().map(|(_, e)| e).collect(),
+            width,
+            height
+        }
     }
 }
 
 impl<E> IntoIterator for Grid<E> {
     type Item = E;
-    type IntoIter = Flatten<IntoIter<Vec<E>>>;
+    type IntoIter = IntoIter<E>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.grid
-                .into_iter()
-                .flatten()
+        self.grid.into_iter()
     }
 }
 impl<'a, E> IntoIterator for &'a Grid<E> {
     type Item = &'a E;
-    type IntoIter = Flatten<std::slice::Iter<'a, Vec<E>>>;
+    type IntoIter = Iter<'a, E>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.grid
-                .iter()
-                .flatten()
+        self.grid.iter()
     }
 }
 
 impl<'a, E> IntoIterator for &'a mut Grid<E> {
     type Item = &'a mut E;
-    type IntoIter = Flatten<std::slice::IterMut<'a, Vec<E>>>;
+    type IntoIter = IterMut<'a, E>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.grid
-                .iter_mut()
-                .flatten()
+        self.grid.iter_mut()
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,47 @@
+use super::*;
+
+fn numbered() -> Grid<usize> {
+    Grid::new(3, 2, |(x, y)| y * 10 + x)
+}
+
+#[test]
+fn a_grid_is_stored_row_by_row() {
+    let grid = numbered();
+    assert_eq!((grid.width(), grid.height()), (3, 2));
+    assert_eq!(grid.iter().copied().collect::<Vec<_>>(), [0, 1, 2, 10, 11, 12]);
+    assert_eq!(grid.rows().map(<[usize]>::to_vec).collect::<Vec<_>>(), [vec![0, 1, 2], vec![10, 11, 12]]);
+    assert_eq!(grid[(2, 1)], 12);
+    assert_eq!(grid.get((3, 0)), None);
+    assert_eq!(grid.get((0, 2)), None);
+}
+
+#[test]
+fn a_grid_knows_where_each_element_is() {
+    let grid = numbered();
+    assert!(grid.enumerate().all(|((x, y), e)| *e == y * 10 + x));
+    let turned = grid.laid_out(true).map(|(point, _)| point).collect::<Vec<_>>();
+    assert_eq!(turned, [(0, 0), (0, 1), (1, 0), (1, 1), (2, 0), (2, 1)]);
+    assert!(grid.laid_out(false).map(|(point, _)| point).eq(grid.enumerate().map(|(point, _)| point)));
+}
+
+#[test]
+fn a_grid_collects_from_points_in_any_order() {
+    let grid = numbered().laid_out(true)
+            .map(|(point, e)| (point, *e))
+            .collect::<Grid<_>>();
+    assert_eq!(grid.iter().copied().collect::<Vec<_>>(), [0, 1, 2, 10, 11, 12]);
+    assert_eq!(grid.map(|e| e * 2)[(1, 1)], 22);
+}
+
+#[test]
+#[should_panic(expected = "every point of the grid needs an element")]
+fn a_grid_with_a_hole_in_it_is_refused() {
+    let _ = [((0, 0), 0), ((1, 1), 1)].into_iter().collect::<Grid<_>>();
+}
+
+#[test]
+fn an_empty_grid_has_no_rows() {
+    let grid = Grid::new(0, 0, |_| ());
+    assert_eq!(grid.rows().count(), 0);
+    assert_eq!(grid.iter().count(), 0);
+}
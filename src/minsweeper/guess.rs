@@ -0,0 +1,82 @@
+use crate::minsweeper::AutoGuess;
+use minsweeper_rs::board::Point;
+use minsweeper_rs::{CellState, CellType, GameState};
+use std::collections::HashMap;
+
+/// where auto clicks once nothing is certain and how likely that is to be a mine, `None` if it
+/// isn't to guess or there's nothing covered left
+pub fn pick(mode: AutoGuess, gamestate: &GameState) -> Option<(Point, f32)> {
+    let risks = risks(gamestate);
+    let point = match mode {
+        AutoGuess::Never => return None,
+        AutoGuess::Corner => {
+            let size = gamestate.board.size();
+            let (right, bottom) = (size.width().get() - 1, size.height().get() - 1);
+            let covered = |point: &Point| risks.contains_key(point);
+            [(0, 0), (right, 0), (0, bottom), (right, bottom)].into_iter()
+                    .find(covered)
+                    .or_else(|| size.points()
+                            .filter(|(x, y)| *x == 0 || *y == 0 || *x == right || *y == bottom)
+                            .find(covered))
+                    // the edges are all open, anywhere will do
+                    .or_else(|| size.points().find(covered))?
+        }
+        AutoGuess::LowestProbability => gamestate.board.size().points()
+                .filter_map(|point| risks.get(&point).map(|risk| (point, *risk)))
+                // the first of the safest in reading order, so the same board always gets the same guess
+                .fold(None, |best: Option<(Point, f32)>, (point, risk)| match best {
+                    Some((_, lowest)) if lowest <= risk => best,
+                    _ => Some((point, risk)),
+                })?
+                .0,
+    };
+    Some((point, risks[&point]))
+}
+
+/// the chance each covered cell is a mine. a cell next to numbers takes the worst of what's left
+/// of their mines spread over their covered neighbours, anywhere else gets an even share of the
+/// mines no number accounts for. not exact, the numbers' overlaps aren't worked through, but
+/// it's quick and never calls a cell safer than its neighbours say
+pub fn risks(gamestate: &GameState) -> HashMap<Point, f32> {
+    let board = &gamestate.board;
+    let size = board.size();
+    let around = |point: Point, state: CellState| size.neighbours(point)
+            .filter(|around| board[*around].cell_state == state)
+            .count();
+
+    let mut frontier = HashMap::new();
+    for point in size.points() {
+        let cell = board[point];
+        let CellType::Safe(number @ 1..) = cell.cell_type else { continue };
+        if cell.cell_state != CellState::Revealed {
+            continue
+        }
+        let covered = around(point, CellState::Unknown);
+        if covered == 0 {
+            continue
+        }
+        let risk = (number as usize).saturating_sub(around(point, CellState::Flagged)) as f32 / covered as f32;
+        for neighbour in size.neighbours(point).filter(|around| board[*around].cell_state == CellState::Unknown) {
+            let worst = frontier.entry(neighbour).or_insert(0.0_f32);
+            *worst = worst.max(risk);
+        }
+    }
+
+    let covered = size.points()
+            .filter(|point| board[*point].cell_state == CellState::Unknown)
+            .collect::<Vec<_>>();
+    let flags = size.points()
+            .filter(|point| board[*point].cell_state == CellState::Flagged)
+            .count();
+    // what the numbers are expected to hold is taken out of what's left for everywhere else
+    let expected = frontier.values().sum::<f32>();
+    let elsewhere = covered.len() - frontier.len();
+    let density = match elsewhere {
+        0 => 0.0,
+        elsewhere => ((size.mines().get().saturating_sub(flags)) as f32 - expected).max(0.0) / elsewhere as f32,
+    };
+
+    covered.into_iter()
+            .map(|point| (point, frontier.get(&point).copied().unwrap_or(density).clamp(0.0, 1.0)))
+            .collect()
+}
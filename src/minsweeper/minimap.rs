@@ -0,0 +1,108 @@
+use crate::minsweeper::Message;
+use iced::widget::canvas::{Action, Cache, Event, Frame, Geometry, Path, Program, Stroke};
+use iced::{mouse, Color, Point as Position, Rectangle, Renderer, Size, Theme};
+use minsweeper_rs::{CellState, CellType, GameState};
+use std::cell::Cell;
+
+/// the longer side of the minimap, the shorter one keeps the board's proportions
+pub const MINIMAP_SIZE: f32 = 120.0;
+
+const UNKNOWN: Color = Color::from_rgb(0.55, 0.55, 0.55);
+const REVEALED: Color = Color::from_rgb(0.85, 0.85, 0.85);
+const FLAGGED: Color = Color::from_rgb(0.85, 0.2, 0.2);
+const MINE: Color = Color::BLACK;
+
+/// the whole board a block per cell with the part that's in view outlined, a click scrolls the
+/// board to centre on where it landed
+pub struct Minimap<'a> {
+    gamestate: &'a GameState,
+    /// changes whenever the board does, the cells are only drawn again when it does
+    version: u64,
+    /// what of the board is in view, in cells
+    viewport: Rectangle,
+    accent: Color,
+    /// drawn turned on its side like the board, `viewport` is already
+    transposed: bool,
+}
+
+#[derive(Default)]
+pub struct State {
+    cells: Cache,
+    drawn: Cell<Option<u64>>,
+}
+
+impl<'a> Minimap<'a> {
+    pub fn new(gamestate: &'a GameState, version: u64, viewport: Rectangle, accent: Color, transposed: bool) -> Self {
+        Self { gamestate, version, viewport, accent, transposed }
+    }
+
+    /// how big the minimap is for a board of this many cells across and down
+    pub fn size(width: usize, height: usize) -> Size {
+        let scale = MINIMAP_SIZE / width.max(height) as f32;
+        Size::new(width as f32 * scale, height as f32 * scale)
+    }
+
+    fn scale(&self, bounds: Rectangle) -> f32 {
+        let size = self.gamestate.board.size();
+        bounds.width / if self.transposed { size.height() } else { size.width() }.get() as f32
+    }
+}
+
+impl Program<Message> for Minimap<'_> {
+    type State = State;
+
+    fn update(&self, _state: &mut State, event: &Event, bounds: Rectangle, cursor: mouse::Cursor) -> Option<Action<Message>> {
+        let Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) = event else { return None };
+        let position = cursor.position_in(bounds)?;
+        let scale = self.scale(bounds);
+        let centre = Position::new(position.x / scale, position.y / scale);
+        let corner = Position::new(centre.x - self.viewport.width / 2.0, centre.y - self.viewport.height / 2.0);
+        Some(Action::publish(Message::ScrollTo(corner.x, corner.y)).and_capture())
+    }
+
+    fn mouse_interaction(&self, _state: &State, bounds: Rectangle, cursor: mouse::Cursor) -> mouse::Interaction {
+        if cursor.is_over(bounds) {
+            mouse::Interaction::Pointer
+        } else {
+            mouse::Interaction::None
+        }
+    }
+
+    fn draw(&self, state: &State, renderer: &Renderer, _theme: &Theme, bounds: Rectangle, _cursor: mouse::Cursor) -> Vec<Geometry> {
+        if state.drawn.replace(Some(self.version)) != Some(self.version) {
+            state.cells.clear();
+        }
+        let scale = self.scale(bounds);
+        let cells = state.cells.draw(renderer, bounds.size(), |frame| {
+            draw_cells(frame, self.gamestate, scale, self.transposed);
+        });
+
+        let mut frame = Frame::new(renderer, bounds.size());
+        draw_viewport(&mut frame, self.viewport, scale, self.accent);
+
+        vec![cells, frame.into_geometry()]
+    }
+}
+
+/// a block `scale` pixels wide for every cell, from the frame's origin
+pub fn draw_cells(frame: &mut Frame, gamestate: &GameState, scale: f32, transposed: bool) {
+    for point in gamestate.board.size().points() {
+        let cell = gamestate.board[point];
+        let color = match (cell.cell_state, cell.cell_type) {
+            (CellState::Flagged, _) => FLAGGED,
+            (CellState::Revealed, CellType::Mine) => MINE,
+            (CellState::Revealed, _) => REVEALED,
+            (CellState::Unknown, _) => UNKNOWN,
+        };
+        let (x, y) = if transposed { (point.1, point.0) } else { point };
+        frame.fill_rectangle(Position::new(x as f32 * scale, y as f32 * scale),
+                Size::new(scale, scale), color);
+    }
+}
+
+/// the outline of `viewport`, in cells, over what [`draw_cells`] drew
+pub fn draw_viewport(frame: &mut Frame, viewport: Rectangle, scale: f32, accent: Color) {
+    frame.stroke(&Path::rectangle(Position::new(viewport.x * scale, viewport.y * scale),
+            Size::new(viewport.width * scale, viewport.height * scale)),
+            Stroke::default().with_color(accent).with_width(1.5));
+}
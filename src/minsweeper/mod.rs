@@ -1,44 +1,270 @@
+mod analysis;
+mod animation;
+pub mod bbbv;
+mod board;
 mod cell;
+mod click_rate;
+mod config;
+mod explain;
+mod game;
 mod grid;
+mod guess;
+mod minimap;
+pub mod pathology;
 mod restart;
-
+pub mod rules;
+pub mod tasks;
+#[cfg(test)]
+pub(crate) mod tests;
+
+use crate::board_text::Layout;
+use crate::clock::SharedClock;
+use crate::crash;
+use crate::dialog;
+use crate::dialog::DialogId;
+use crate::i18n::t;
+use crate::journal::Journal;
+use crate::metrics::Latency;
+use crate::minsweeper::analysis::{Analysis, AnalysisScheduler, Kind, Policy};
+use crate::minsweeper::animation::AnimationState;
+use crate::minsweeper::cell::HighlightKind;
+use crate::minsweeper::click_rate::ClickRate;
+use crate::minsweeper::explain::Explanation;
 use crate::minsweeper::restart::RestartButton;
-use crate::settings_menu::Auto;
+use crate::minsweeper::rules::{RuleContext, RuleStack};
+use crate::sound;
+use crate::sound::Sound;
+use crate::stats::format_duration;
 use crate::texture::{Border, Texture};
 use derive_more::From;
 use formatx::formatx;
-use futures_util::future::AbortHandle;
-use iced::widget::{container, responsive, row, svg, Grid, Row, Svg};
-use iced::{widget, Element, Task};
+use futures_util::future::{select, Either};
+use futures_util::{FutureExt, StreamExt};
+use iced::widget::{button, canvas, container, mouse_area, progress_bar, responsive, row, scrollable, svg, text, tooltip, Grid, Row, Svg};
+use iced::widget::scrollable::AbsoluteOffset;
+use iced::{keyboard, widget, Element, Task};
 use iced_core::alignment::{Horizontal, Vertical};
 use iced_core::{mouse, Background, ContentFit, Length, Padding, Size};
 use minsweeper_rs::board::{BoardSize, Point};
-use minsweeper_rs::minsweeper::nonblocking::AsyncMinsweeperGame;
-use minsweeper_rs::solver::{Move, Operation, Solver};
-use minsweeper_rs::{CellState, CellType, GameStatus};
-use std::collections::HashMap;
+use minsweeper_rs::solver::{Action, Operation, Solver};
+use minsweeper_rs::{CellState, CellType, GameState, GameStatus};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt::{Debug, Formatter};
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::pin::pin;
 use std::sync::Arc;
-use std::time::Duration;
-use tokio::sync::Mutex;
-use uuid::Uuid;
+use std::time::{Duration, Instant};
+use tasks::Tasks;
+use tracing::Instrument;
+use tokio::sync::watch;
+
+pub use config::{size_label, AutoGuess, AutoPlay, BoardRotation, CellScale, CounterMode, HighlightModifier, MouseAction, MouseBindings, MouseButton, Overlay};
+pub use game::{layout_from_mines, Click, Game, History, Provenance};
 
-pub type MinsweeperType = Arc<AsyncMinsweeperGame<SolverType, FnType, FnType>>;
+pub type MinsweeperType = Arc<game::Game>;
 pub type SolverType = Arc<dyn Solver + Send + Sync>;
-pub type FnType = fn();
+
+/// a number that was just hover chorded isn't chorded again when the cursor comes back this soon
+const HOVER_CHORD_COOLDOWN: Duration = Duration::from_millis(500);
+/// how long a solver step is shown before it's played, unless it waits to be confirmed
+pub const DEFAULT_STEP_PREVIEW: Duration = Duration::from_secs(1);
+/// how long a press has to be held before it flags
+const LONG_PRESS: Duration = Duration::from_millis(400);
+/// how long the header says flags are off after a flag is tried in no flags mode
+const FLAG_REFUSAL: Duration = Duration::from_millis(1500);
+/// the counter goes dim and back this often while there are more flags than mines
+pub const OVER_FLAG_BLINK: Duration = Duration::from_millis(250);
+/// how long the flag that was one too many stays tinted
+const EXCESS_FLAG: Duration = Duration::from_secs(1);
+
+/// how big cells get when the window is resized to fit a board whose cells fit the window
+const FIT_CELL_SIZE: f32 = 32.0;
+/// the counter, face and buttons above the board don't get any narrower than this
+const HEADER_WIDTH: f32 = 560.0;
+const HEADER_HEIGHT: f32 = 70.0;
+/// how small and big zooming makes cells, a fixed size above the largest can still zoom out
+const MIN_ZOOMED_CELL: f32 = 8.0;
+const MAX_ZOOMED_CELL: f32 = 96.0;
+const ZOOM_STEP: f32 = 1.1;
+/// how many pixels of a touchpad scroll count as one wheel notch
+const ZOOM_LINE: f32 = 40.0;
+
+/// how long each ring of an animated reveal takes to show, the whole thing is kept between
+/// [`REVEAL_MIN`] and [`REVEAL_MAX`]
+const REVEAL_RING: Duration = Duration::from_millis(20);
+const REVEAL_MIN: Duration = Duration::from_millis(150);
+const REVEAL_MAX: Duration = Duration::from_millis(300);
+/// the line describing the hovered cell and the last move
+const DESCRIPTION_HEIGHT: f32 = 24.0;
+/// the line under the board with the hovered cell and the flag count
+const STATUS_LINE_HEIGHT: f32 = 24.0;
+/// the solver explanations panel beside the board
+const EXPLANATIONS_WIDTH: f32 = 260.0;
+/// the bar under the header showing how much of the board is cleared
+const PROGRESS_HEIGHT: f32 = 4.0;
 
 pub struct MinsweeperGame {
     game: MinsweeperType,
     size: BoardSize,
-    solver: SolverType,
+    mode: Mode,
+    /// what boards are generated with, it settles what the first click is guaranteed
+    opening: SolverType,
+    /// what steps and auto ask for moves, nothing gets played for the player without one
+    solver: Option<SolverType>,
+    seed: Option<u64>,
     texture: Texture,
-    auto: Option<Auto>,
+    auto: Option<AutoPlay>,
+    /// auto is running slower than configured to save battery
+    throttled: bool,
     flag_chord: bool,
+    mouse_bindings: MouseBindings,
+    /// taps flag instead of revealing, for touchscreens where there's no right click
+    flag_mode: bool,
+    long_press_flag: bool,
+    /// the press that flags its cell if it's still held once [`LONG_PRESS`] is up
+    long_press: Option<(u64, Point)>,
+    long_press_id: u64,
     hover_chord: bool,
+    /// how long the cursor has to stay on a number before it's hover chorded
+    hover_chord_delay: Duration,
+    hover_id: u64,
+    /// the last hover chord, so sweeping back over it doesn't fire it again right away
+    hover_chorded: Option<(Point, Instant)>,
+    /// dragging with the flag button held flags every covered cell it passes over
+    drag_flag: bool,
+    /// the button a flag drag is going on with
+    flag_drag: Option<mouse::Button>,
+    /// holding this over a number shows what counts towards it, `None` turns that off
+    neighbour_highlight: Option<HighlightModifier>,
+    modifiers: keyboard::Modifiers,
+    hovered: Option<Point>,
+    /// the number whose neighbours are highlighted
+    highlighting: Option<Point>,
+    chord_delay: Duration,
+    /// a solver step plays itself once its preview is up, otherwise it waits for a second press
+    solver_preview: bool,
+    step_preview: Duration,
+    step: Option<PendingStep>,
+    /// bumped by every click so a step worked out for an older board is thrown away
+    step_id: u64,
+    /// the solver had nothing for the last step
+    step_stuck: bool,
+    /// why the solver made its last few moves, oldest first
+    explanations: VecDeque<Explanation>,
+    solver_explanations: bool,
+    /// the explanations panel is folded down to its button
+    explanations_collapsed: bool,
+    /// the explanation being hovered, its cells are lit up
+    explaining: Option<usize>,
+    chord: Option<PendingChord>,
+    chord_id: u64,
+    /// of the board being played, worked out in the background once the mines are placed
+    bbbv: Option<usize>,
+    measuring: bool,
+    /// bumped on restart so a 3BV for the previous board is thrown away
+    board_id: u64,
+    progress_ring: bool,
+    show_progress: bool,
+    /// the share of safe cells revealed as of the last repaint
+    progress: f32,
+    /// safe cells still covered, counted with the progress
+    safe_left: usize,
+    counter_mode: CounterMode,
+    rotation: BoardRotation,
+    /// the window is taller than it is wide, for [`BoardRotation::Auto`]
+    portrait: bool,
+    risk_shading: bool,
+    animate_reveals: bool,
+    /// some cell has a `reveal_at` still to come
+    animating: bool,
+    /// no confetti on a win or shake on a loss
+    reduce_motion: bool,
+    animation: Option<AnimationState>,
+    describe_cells: bool,
+    status_line: bool,
+    overlay: Option<Overlay>,
+    /// the hovered cell is left out of the description and status line
+    streamer_mode: bool,
+    click_rate: ClickRate,
+    /// the board as of the last repaint, what the status line, the minimap and the spectator
+    /// window read from
+    snapshot: Option<GameState>,
+    /// a spectator window is open on this game
+    spectated: bool,
+    /// bumped whenever the snapshot is taken so the minimap knows to draw it again
+    snapshot_version: u64,
+    /// the board the last announcement was worked out against
+    announced: Option<GameState>,
+    announcement: Option<String>,
+    cell_scale: CellScale,
+    /// how much ctrl+scroll has scaled the fixed cell size by
+    zoom: f32,
+    scroll_id: widget::Id,
+    /// where the scrollable board is scrolled to, in pixels
+    scroll: AbsoluteOffset,
+    show_provenance: bool,
+    loss_reveal_delay: Duration,
+    /// what every mine and wrong flag looks like after a loss, drawn over the board instead of
+    /// whatever the game reports
+    loss_overlay: Option<HashMap<Point, minsweeper_rs::Cell>>,
+    /// who revealed what, only computed once the game is over and solver cells are marked
+    provenance: Option<HashMap<Point, Provenance>>,
+    analysis: AnalysisScheduler,
+    game_over_dialog: bool,
+    silent_auto: bool,
+    game_over: Option<GameOver>,
     cells: grid::Grid<cell::Cell>,
-    handles: Arc<Mutex<HashMap<Uuid, AbortHandle>>>,
+    tasks: Tasks,
     autoing: Arc<AtomicBool>,
+    status: GameStatus,
+    remaining_mines: isize,
+    finished: Option<Duration>,
+    started: Option<Instant>,
+    /// when the current pause began, the board is hidden and the clock stopped until it ends
+    paused_at: Option<Instant>,
+    /// tells a running auto solver to hold still
+    pause: watch::Sender<bool>,
+    /// a game nobody has touched for this long pauses itself, `None` if it never does
+    idle_pause: Option<Duration>,
+    last_input: Instant,
+    /// the pause is the idle one, any input ends it
+    idled: bool,
+    steps: Arc<AtomicU32>,
+    /// the cells auto revealed without knowing they were safe
+    guesses: Arc<AtomicU32>,
+    /// boards auto has played back to back since it started restarting them by itself
+    auto_restart: Option<AutoRestartSession>,
+    /// bumped to call off a pending auto restart
+    auto_restart_id: u64,
+    retry: bool,
+    /// a loss can be taken back instead of ending the game
+    forgiving: bool,
+    undos: u32,
+    /// the undo dialog is up and the loss hasn't been settled either way
+    offering_undo: bool,
+    /// a reveal on a cell the numbers around it say is a mine asks first
+    mistake_guard: bool,
+    /// the cell the guard is asking about
+    guarding: Option<Point>,
+    /// reveals the guard talked the player out of
+    guarded: u32,
+    /// nothing gets flagged, not by the player, a flag chord or the solver
+    no_flags: bool,
+    /// a flag was just tried and the header is saying why nothing happened
+    flag_refused: Option<u64>,
+    flag_refusal_id: u64,
+    /// the last cell the player flagged, tinted if it took the counter further below zero
+    last_flagged: Option<Point>,
+    excess_flag: Option<(Point, u64)>,
+    excess_flag_id: u64,
+    journal: Journal,
+    rules: RuleStack,
+    hydrating: Option<f32>,
+    queued: Vec<Click>,
+    latency: Latency,
+    /// draw on the canvas whatever the board size
+    canvas_board: bool,
+    clock: SharedClock,
 }
 
 impl Debug for MinsweeperGame {
@@ -50,33 +276,324 @@ impl Debug for MinsweeperGame {
 #[derive(Clone, Debug, From)]
 pub enum Message {
     Restart,
+    RestartSameBoard,
     Cell((Point, cell::Message)),
     MouseRelease(mouse::Button),
+    /// a finger was lifted or the window lost focus mid gesture, no release or exit is coming
+    /// for whatever it was holding
+    ClearPressed,
     Repaint,
+    GameOver(GameOver),
+    FlushJournal,
+    CommitChord(u64),
+    #[from(skip)]
+    Pause,
+    #[from(skip)]
+    Measured(u64, Option<usize>),
+    /// the cursor stayed on a number long enough to chord it
+    #[from(skip)]
+    HoverChord(u64, Point),
+    /// a press was held long enough to flag its cell
+    #[from(skip)]
+    LongPress(u64, Point),
+    /// the tap mode button was clicked, the settings decide what happens
+    ToggleFlagMode,
+    /// asks the solver for one move, or plays the one being shown
+    #[from(skip)]
+    Step,
+    #[from(skip)]
+    StepFound(u64, Option<(Vec<Action>, Explanation)>),
+    /// the preview of a step is up
+    #[from(skip)]
+    StepDue(u64),
+    /// the wait after an unattended game is up, the next board starts unless something
+    /// interrupted in the meantime
+    #[from(skip)]
+    AutoRestart(u64),
+    /// a board auto restarted by itself has started, auto opens it
+    #[from(skip)]
+    AutoOpening(u64),
+    /// why auto made a move on the board with this id
+    #[from(skip)]
+    Explained(u64, Explanation),
+    /// the line of the explanations panel under the cursor
+    #[from(skip)]
+    HoverExplanation(Option<usize>),
+    CollapseExplanations,
+    CancelChord,
+    CloseGameOver,
+    /// the undo dialog's answers to a forgiven loss
+    Undo,
+    AcceptDefeat,
+    /// the mistake guard's answers, reveal the mine after all or leave it be
+    RevealAnyway,
+    KeepCovered,
+    /// the keyboard modifiers changed, for the neighbour highlight
+    #[from(skip)]
+    Modifiers(keyboard::Modifiers),
+    /// the note about flags being off has been up long enough
+    #[from(skip)]
+    FlagRefusalOver(u64),
+    /// the flag that was one too many has been tinted long enough
+    #[from(skip)]
+    ExcessFlagOver(u64),
+    /// the game over dialog's share buttons, the app has the clipboard and the exporter
+    CopyBoard,
+    ExportBoardImage,
+    Dialog(dialog::Message),
+    /// how much of a resumed game has been replayed, for the board it was resumed on
+    Hydrating(u64, f32),
+    #[from(skip)]
+    Hydrated(u64),
+    /// the hovered cell changed on the canvas board, exits are sent before enters
+    Hover(Option<Point>, Option<Point>),
+    /// the mouse moved or clicked over a board that paused itself
+    Awake,
+    /// the mouse wheel turned anywhere in the window, it zooms a scrollable board while ctrl is held
+    #[from(skip)]
+    Wheel(mouse::ScrollDelta),
+    #[from(skip)]
+    Scrolled(AbsoluteOffset),
+    /// the minimap was clicked, scrolls the board's top left corner to this cell
+    #[from(skip)]
+    ScrollTo(f32, f32),
+    Analysis(analysis::Delivery),
+    /// a click was applied to the game, carries its gesture id while latency is measured
+    #[from(skip)]
+    Landed(u64),
+    /// a frame is being drawn
+    Frame(Instant),
+    /// a click on the board with this id landed, with the board from before it
+    #[from(skip)]
+    Revealed(u64, Point, GameState),
+    /// the editor's buttons, all but clearing are up to the app
+    ClearMines,
+    PlayEdited,
+    SaveEdited,
+    BrowseBoards,
+    /// a reveal that comes from the app rather than the mouse, a watched board still takes it
+    #[from(skip)]
+    Reveal(Point),
+}
+
+impl Message {
+    /// whether this came from someone using the board rather than from the game itself
+    pub fn is_input(&self) -> bool {
+        matches!(self, Message::Restart | Message::RestartSameBoard | Message::Cell(_) | Message::MouseRelease(_)
+                | Message::ClearPressed | Message::CommitChord(_) | Message::Pause | Message::HoverChord(..)
+                | Message::LongPress(..) | Message::ToggleFlagMode | Message::Step | Message::CancelChord
+                | Message::CloseGameOver | Message::Undo | Message::AcceptDefeat
+                | Message::RevealAnyway | Message::KeepCovered | Message::CopyBoard | Message::ExportBoardImage | Message::Hover(..) | Message::Awake
+                | Message::ClearMines | Message::PlayEdited | Message::SaveEdited | Message::BrowseBoards)
+    }
+}
+
+/// what clicks on the board do
+#[derive(Debug)]
+enum Mode {
+    Play,
+    /// a board is being put together to be played later, clicks place and take away mines and
+    /// nothing is timed or solved
+    Edit(HashSet<Point>),
+    /// someone else is playing, the user can look but nothing they do reaches the board
+    Watch,
+}
+
+/// the tally of an unattended run, shown over the board while it goes on
+#[derive(Copy, Clone, Debug, Default)]
+struct AutoRestartSession {
+    attempted: u32,
+    won: u32,
+}
+
+/// a move the solver found, shown on the board before it's played
+#[derive(Debug)]
+struct PendingStep {
+    id: u64,
+    actions: Vec<Action>,
+}
+
+/// a chord waiting out the confirmation flash, it either commits once the delay passes or is
+/// cancelled by esc or by leaving the cell
+#[derive(Debug)]
+struct PendingChord {
+    id: u64,
+    point: Point,
+    flags: Vec<Point>,
+    reveals: Vec<Point>,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct GameOver {
+    pub size: BoardSize,
+    pub status: GameStatus,
+    pub elapsed: Duration,
+    pub clicks: usize,
+    /// the clicks the player made, without the solver's
+    pub player_clicks: usize,
+    pub bbbv: Option<usize>,
+    pub seed: u64,
+    pub revealed_by_player: usize,
+    pub revealed_by_solver: usize,
+    /// the solver made at least one move
+    pub auto: bool,
+    pub retry: bool,
+    /// losses that were taken back along the way
+    pub undos: u32,
+    /// certain mines the mistake guard kept covered
+    pub guarded: u32,
+    /// reveals auto made when nothing was certain
+    pub guesses: u32,
+    pub no_flags: bool,
+    /// a flag was placed at some point
+    pub flagged: bool,
+    pub eligible: bool,
+}
+
+impl GameOver {
+    /// the game was saved from a loss at least once
+    pub fn assisted(&self) -> bool {
+        self.undos > 0 || self.guarded > 0
+    }
+
+    /// the solver revealed or flagged something, whoever made the move that ended it
+    pub fn solver_played(&self) -> bool {
+        self.auto || self.revealed_by_solver > 0
+    }
 }
 
 impl MinsweeperGame {
 
-    pub fn new(size: BoardSize, solver: SolverType, texture: Texture, auto: Option<Auto>, flag_chord: bool, hover_chord: bool) -> Self {
-        let game = AsyncMinsweeperGame::new(size,
-                                                                      (|| {}) as fn(), (|| {}) as fn());
-        let game = Arc::new(game);
+    pub fn new(size: BoardSize, opening: SolverType, solver: Option<SolverType>, seed: Option<u64>, texture: Texture,
+               clock: SharedClock) -> Self {
+        let game = Arc::new(game::Game::new(size));
         let cells = grid::Grid::new(size.width().get(), size.height().get(),
-                                    |point| cell::Cell::new(point, texture, game.clone()));
+                                    |point| cell::Cell::new(point, texture));
         Self {
             game,
             size,
+            mode: Mode::Play,
+            opening,
             solver,
+            seed,
             texture,
-            auto,
-            flag_chord,
-            hover_chord,
+            auto: None,
+            throttled: false,
+            flag_chord: false,
+            mouse_bindings: MouseBindings::default(),
+            flag_mode: false,
+            long_press_flag: false,
+            long_press: None,
+            long_press_id: 0,
+            hover_chord: false,
+            hover_chord_delay: Duration::ZERO,
+            hover_id: 0,
+            hover_chorded: None,
+            drag_flag: false,
+            flag_drag: None,
+            neighbour_highlight: None,
+            modifiers: keyboard::Modifiers::default(),
+            hovered: None,
+            highlighting: None,
+            chord_delay: Duration::ZERO,
+            solver_preview: true,
+            step_preview: DEFAULT_STEP_PREVIEW,
+            step: None,
+            step_id: 0,
+            step_stuck: false,
+            explanations: VecDeque::new(),
+            solver_explanations: false,
+            explanations_collapsed: false,
+            explaining: None,
+            chord: None,
+            chord_id: 0,
+            bbbv: None,
+            measuring: false,
+            board_id: 0,
+            progress_ring: false,
+            show_progress: false,
+            progress: 0.0,
+            safe_left: size.width().get() * size.height().get() - size.mines().get(),
+            counter_mode: CounterMode::default(),
+            rotation: BoardRotation::default(),
+            portrait: false,
+            risk_shading: false,
+            animate_reveals: false,
+            animating: false,
+            reduce_motion: false,
+            animation: None,
+            describe_cells: false,
+            status_line: false,
+            overlay: None,
+            streamer_mode: false,
+            click_rate: ClickRate::default(),
+            snapshot: None,
+            spectated: false,
+            snapshot_version: 0,
+            announced: None,
+            announcement: None,
+            cell_scale: CellScale::Fit,
+            zoom: 1.0,
+            scroll_id: widget::Id::unique(),
+            scroll: AbsoluteOffset::default(),
+            show_provenance: false,
+            loss_reveal_delay: Duration::ZERO,
+            loss_overlay: None,
+            provenance: None,
+            analysis: make_scheduler(size, clock.clone()),
+            game_over_dialog: true,
+            silent_auto: false,
+            game_over: None,
             cells,
-            handles: Default::default(),
+            tasks: Tasks::default(),
             autoing: Default::default(),
+            status: GameStatus::Never,
+            remaining_mines: size.mines().get() as isize,
+            finished: None,
+            started: None,
+            paused_at: None,
+            idle_pause: None,
+            last_input: clock.now(),
+            idled: false,
+            pause: watch::Sender::new(false),
+            steps: Default::default(),
+            guesses: Default::default(),
+            auto_restart: None,
+            auto_restart_id: 0,
+            retry: false,
+            forgiving: false,
+            undos: 0,
+            offering_undo: false,
+            mistake_guard: false,
+            guarding: None,
+            guarded: 0,
+            no_flags: false,
+            flag_refused: None,
+            flag_refusal_id: 0,
+            last_flagged: None,
+            excess_flag: None,
+            excess_flag_id: 0,
+            journal: Journal::default(),
+            rules: RuleStack::default(),
+            hydrating: None,
+            queued: vec![],
+            latency: Latency::default(),
+            canvas_board: false,
+            clock,
         }
     }
 
+    /// a game already being played on `layout`, set up without the task
+    /// [`play_layout`](Self::play_layout) needs so nothing has to run before it takes clicks, and
+    /// without a journal so it never touches the user's
+    #[cfg(test)]
+    pub fn with_layout(layout: GameState, seed: u64, solver: SolverType, texture: Texture, clock: SharedClock) -> Self {
+        let mut game = Self::new(layout.board.size(), solver.clone(), Some(solver), Some(seed), texture, clock);
+        game.journal = Journal::off();
+        game.game.blocking_start_with_layout(layout, seed);
+        game
+    }
+
     pub fn change_textures(&mut self, texture: Texture) {
         self.texture = texture;
         for cell in &mut self.cells {
@@ -84,29 +601,383 @@ impl MinsweeperGame {
         }
     }
 
-    pub fn set_auto(&mut self, auto: Option<Auto>) {
+    pub fn set_seed(&mut self, seed: Option<u64>) {
+        self.seed = seed;
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.game.blocking_seed()
+    }
+
+    pub fn set_auto(&mut self, auto: Option<AutoPlay>) {
         self.auto = auto;
+        if self.auto.as_ref().and_then(|auto| auto.restart).is_none() {
+            self.stop_auto_restart();
+        }
+    }
+
+    /// ends an unattended run and calls off the restart it has waiting
+    pub fn stop_auto_restart(&mut self) {
+        self.auto_restart_id += 1;
+        if let Some(session) = self.auto_restart.take() {
+            tracing::info!(attempted = session.attempted, won = session.won, "auto restarting stopped");
+        }
+    }
+
+    /// counts `game_over` towards the unattended run and waits to start the next board, if auto
+    /// played it and is set to restart
+    fn schedule_auto_restart(&mut self, game_over: &GameOver) -> Task<Message> {
+        let Some(delay) = self.auto.as_ref().and_then(|auto| auto.restart) else { return Task::none() };
+        // once the run has started every board is auto's, the player has kept their hands off
+        if !(game_over.auto || self.auto_restart.is_some()) || !matches!(self.mode, Mode::Play) {
+            return Task::none()
+        }
+        let session = self.auto_restart.get_or_insert_default();
+        session.attempted += 1;
+        if game_over.status == GameStatus::Won {
+            session.won += 1;
+        }
+
+        self.auto_restart_id += 1;
+        let id = self.auto_restart_id;
+        // a restart cancels everything the game has going, this wait included
+        Task::future(self.tasks.run(self.clock.sleep(delay)))
+                .then(move |waited| match waited {
+                    Some(()) => Task::done(Message::AutoRestart(id)),
+                    None => Task::none(),
+                })
+    }
+
+    /// on battery the cosmetic animations are let go too, they're back once it's off
+    pub fn set_throttled(&mut self, throttled: bool) {
+        self.throttled = throttled;
+        if throttled {
+            self.stop_reveals();
+            self.animation = None;
+        }
     }
 
     pub fn set_flag_chord(&mut self, flag_chord: bool) {
         self.flag_chord = flag_chord;
     }
 
+    pub fn set_mouse_bindings(&mut self, mouse_bindings: MouseBindings) {
+        self.mouse_bindings = mouse_bindings;
+    }
+
+    pub fn set_flag_mode(&mut self, flag_mode: bool) {
+        self.flag_mode = flag_mode;
+    }
+
+    pub fn set_long_press_flag(&mut self, long_press_flag: bool) {
+        self.long_press_flag = long_press_flag;
+        self.long_press = None;
+    }
+
     pub fn set_hover_chord(&mut self, hover_chord: bool) {
         self.hover_chord = hover_chord;
     }
 
+    pub fn set_drag_flag(&mut self, drag_flag: bool) {
+        self.drag_flag = drag_flag;
+        self.flag_drag = None;
+    }
+
+    pub fn set_neighbour_highlight(&mut self, neighbour_highlight: Option<HighlightModifier>) {
+        self.neighbour_highlight = neighbour_highlight;
+        self.highlight(self.hovered);
+    }
+
+    pub fn set_idle_pause(&mut self, idle_pause: Option<Duration>) {
+        self.idle_pause = idle_pause;
+        self.last_input = self.clock.now();
+    }
+
+    pub fn set_hover_chord_delay(&mut self, hover_chord_delay: Duration) {
+        self.hover_chord_delay = hover_chord_delay;
+    }
+
+    pub fn set_solver_preview(&mut self, solver_preview: bool) {
+        self.solver_preview = solver_preview;
+    }
+
+    pub fn set_step_preview(&mut self, step_preview: Duration) {
+        self.step_preview = step_preview;
+    }
+
+    pub fn set_chord_delay(&mut self, chord_delay: Duration) {
+        self.chord_delay = chord_delay;
+    }
+
+    pub fn set_progress_ring(&mut self, progress_ring: bool) {
+        self.progress_ring = progress_ring;
+    }
+
+    pub fn set_counter_mode(&mut self, counter_mode: CounterMode) {
+        self.counter_mode = counter_mode;
+    }
+
+    pub fn set_rotation(&mut self, rotation: BoardRotation) {
+        self.rotation = rotation;
+    }
+
+    pub fn set_portrait(&mut self, portrait: bool) {
+        self.portrait = portrait;
+    }
+
+    /// whether the board is shown turned on its side. only the drawing is, every `Point` the game
+    /// deals in stays the board's own
+    fn transposed(&self) -> bool {
+        match self.rotation {
+            BoardRotation::Off => false,
+            BoardRotation::Portrait => true,
+            BoardRotation::Auto => self.portrait,
+        }
+    }
+
+    /// how many cells across and down the board shows
+    fn shown_dimensions(&self) -> (usize, usize) {
+        let (width, height) = (self.size.width().get(), self.size.height().get());
+        if self.transposed() { (height, width) } else { (width, height) }
+    }
+
+    /// where `point` shows on screen in cells, turning it back works the same way
+    fn shown(&self, (x, y): Point) -> Point {
+        if self.transposed() { (y, x) } else { (x, y) }
+    }
+
+    pub fn set_show_progress(&mut self, show_progress: bool) {
+        self.show_progress = show_progress;
+    }
+
+    pub fn set_risk_shading(&mut self, risk_shading: bool) {
+        self.risk_shading = risk_shading;
+    }
+
+    pub fn set_animate_reveals(&mut self, animate_reveals: bool) {
+        self.animate_reveals = animate_reveals;
+        if !animate_reveals {
+            self.stop_reveals();
+        }
+    }
+
+    pub fn set_reduce_motion(&mut self, reduce_motion: bool) {
+        self.reduce_motion = reduce_motion;
+        if reduce_motion {
+            self.stop_reveals();
+            self.animation = None;
+        }
+    }
+
+    pub fn set_status_line(&mut self, status_line: bool) {
+        self.status_line = status_line;
+        self.take_snapshot();
+    }
+
+    pub fn set_overlay(&mut self, overlay: Option<Overlay>) {
+        self.overlay = overlay;
+    }
+
+    pub fn set_spectated(&mut self, spectated: bool) {
+        self.spectated = spectated;
+        self.take_snapshot();
+    }
+
+    pub fn set_streamer_mode(&mut self, streamer_mode: bool) {
+        self.streamer_mode = streamer_mode;
+    }
+
+    /// clicks per second as the overlay shows it, and every click this game
+    pub fn click_rate(&self) -> (f32, usize) {
+        (self.click_rate.rate(), self.click_rate.total())
+    }
+
+    pub fn set_solver_explanations(&mut self, solver_explanations: bool) {
+        self.solver_explanations = solver_explanations;
+        self.hover_explanation(None);
+    }
+
+    pub fn set_describe_cells(&mut self, describe_cells: bool) {
+        self.describe_cells = describe_cells;
+        self.announcement = None;
+        self.announced = describe_cells.then(|| self.game.blocking_gamestate());
+    }
+
+    pub fn set_cell_scale(&mut self, cell_scale: CellScale) {
+        self.cell_scale = cell_scale;
+        self.zoom = 1.0;
+        self.take_snapshot();
+    }
+
+    /// the board is drawn as widgets in a scrollable at a fixed cell size, which is what zooms
+    /// and gets a minimap from here, the canvas board zooms and draws its minimap itself
+    pub fn zoomable(&self) -> bool {
+        self.cell_scale.fixed_size().is_some() && !self.on_canvas()
+    }
 
-    pub fn points(&self) -> impl Iterator<Item = Point> {
-        (0..self.size.height().into())
-                .flat_map(|y| (0..self.size.width().into())
-                        .map(move |x| (x, y)))
+    fn on_canvas(&self) -> bool {
+        self.canvas_board || self.size.width().get() * self.size.height().get() > board::CANVAS_THRESHOLD
     }
 
+    /// the fixed cell size with the zoom applied
+    fn fixed_cell_size(&self) -> Option<f32> {
+        self.cell_scale.fixed_size().map(|size| size * self.zoom)
+    }
+
+    fn take_snapshot(&mut self) {
+        self.snapshot = (self.status_line || self.zoomable() || self.spectated).then(|| self.game.blocking_gamestate());
+        self.snapshot_version += 1;
+    }
+
+    pub fn set_show_provenance(&mut self, show_provenance: bool) -> Task<Message> {
+        self.show_provenance = show_provenance;
+        if !show_provenance {
+            self.provenance = None;
+        }
+        self.sync_analyses()
+    }
+
+    pub fn set_loss_reveal_delay(&mut self, loss_reveal_delay: Duration) {
+        self.loss_reveal_delay = loss_reveal_delay;
+        self.analysis.set_debounce(Kind::LossReveal, loss_reveal_delay);
+    }
+
+    pub fn set_game_over_dialog(&mut self, game_over_dialog: bool) {
+        self.game_over_dialog = game_over_dialog;
+    }
+
+    pub fn set_forgiving(&mut self, forgiving: bool) {
+        self.forgiving = forgiving;
+    }
+
+    pub fn set_mistake_guard(&mut self, mistake_guard: bool) {
+        self.mistake_guard = mistake_guard;
+    }
+
+    pub fn set_no_flags(&mut self, no_flags: bool) {
+        self.no_flags = no_flags;
+    }
+
+    pub fn set_silent_auto(&mut self, silent_auto: bool) {
+        self.silent_auto = silent_auto;
+    }
+
+    pub fn set_rules(&mut self, rules: RuleStack) {
+        self.game.blocking_set_rules(rules.clone());
+        self.rules = rules;
+    }
+
+    /// there are more flags than mines, the counter blinks until there aren't
+    pub fn over_flagged(&self) -> bool {
+        matches!(self.mode, Mode::Play) && self.status == GameStatus::Playing && !self.paused() && self.remaining_mines < 0
+    }
+
+    pub fn timed(&self) -> bool {
+        (self.rules.timed() || self.status_line || self.overlay.is_some()) && self.status == GameStatus::Playing && !self.paused()
+    }
+
+    pub fn title(&self) -> String {
+        if let Mode::Edit(mines) = &self.mode {
+            return t!("editor-title", mines = mines.len())
+        }
+        match self.status {
+            GameStatus::Playing if self.started.is_some() => t!("mines-left", count = self.remaining_mines),
+            GameStatus::Won => match self.finished {
+                Some(elapsed) => t!("title-won-in", seconds = elapsed.as_secs()),
+                None => t!("you-win").to_string(),
+            },
+            GameStatus::Lost => t!("title-lost").to_string(),
+            _ => size_label(self.size),
+        }
+    }
+
+    pub fn paused(&self) -> bool {
+        self.paused_at.is_some()
+    }
+
+    /// only a game that's being played can be paused, unpausing always works
+    pub fn set_paused(&mut self, paused: bool) {
+        match self.paused_at {
+            None if paused && self.status == GameStatus::Playing && self.started.is_some() => {
+                let _ = self.update(Message::ClearPressed);
+                self.paused_at = Some(self.clock.now());
+            }
+            Some(paused_at) if !paused => {
+                // the pause is left out of the game time by moving the start forward by as long
+                self.started = self.started.map(|started| started + self.clock.elapsed(paused_at));
+                self.paused_at = None;
+                self.idled = false;
+                self.last_input = self.clock.now();
+            }
+            _ => return,
+        }
+        self.pause.send_replace(paused);
+    }
+
+    /// whether the game could pause itself for want of input, the clock tick checks while it could
+    pub fn watching_idle(&self) -> bool {
+        self.idle_pause.is_some() && matches!(self.mode, Mode::Play) && self.status == GameStatus::Playing
+                && !self.paused() && self.auto.is_none() && self.auto_restart.is_none()
+    }
+
+    /// pauses a game that's gone without input for longer than the idle threshold
+    fn check_idle(&mut self) {
+        let Some(threshold) = self.idle_pause else { return };
+        if self.watching_idle() && self.clock.elapsed(self.last_input) >= threshold {
+            tracing::info!(idle = ?threshold, "pausing an idle game");
+            self.set_paused(true);
+            self.idled = self.paused();
+        }
+    }
+
+    /// time played since `started`, a pause in progress doesn't count
+    fn played(&self, started: Instant) -> Duration {
+        self.paused_at
+                .unwrap_or_else(|| self.clock.now())
+                .saturating_duration_since(started)
+    }
+
+    fn rule_context(&self) -> RuleContext {
+        RuleContext {
+            elapsed: self.started
+                    .map(|started| self.played(started))
+                    .or(self.finished)
+                    .unwrap_or_default(),
+        }
+    }
+
+    fn status(&self) -> GameStatus {
+        self.rules.status(&self.game.blocking_gamestate(), &self.rule_context())
+    }
+
+
     pub fn update(&mut self, message: Message) -> Task<Message> {
+        if matches!(self.mode, Mode::Watch) && message.is_input() {
+            return Task::none()
+        }
+        // only a hand on the board ends an unattended run, a mouse passing over it doesn't
+        if message.is_input() && !matches!(message, Message::Hover(..)
+                | Message::Cell((_, cell::Message::Enter | cell::Message::Exit))) {
+            self.stop_auto_restart();
+        }
+        if message.is_input() {
+            self.last_input = self.clock.now();
+            // whatever woke the game up only does that, the board wasn't showing to aim at
+            if self.idled {
+                self.set_paused(false);
+                return Task::none()
+            }
+        }
         match message {
+            Message::Cell((point, e)) if self.editing() => return self.edit_cell(point, e),
             Message::Cell((point, e)) => {
-                if self.game.blocking_gamestate().status != GameStatus::Playing {
+                if self.hydrating.is_some() {
+                    if let Some(click) = e.click(point, &self.mouse_bindings, self.flag_mode) {
+                        self.queued.push(click);
+                    }
+                    return Task::none();
+                }
+                if self.status() != GameStatus::Playing {
                     return Task::none();
                 }
                 // self.cells[point].update(e.clone());
@@ -115,31 +986,713 @@ impl MinsweeperGame {
                 //
                 // }
 
-                return task
-            }
-            Message::MouseRelease(button) => for cell in self.size.clone().points() {
-                let _ = self.update_cell(cell, cell::Message::Release(button));
-            },
-            Message::Restart => {
-                for (_, handle) in self.handles.blocking_lock().iter() {
-                    handle.abort();
+                return task
+            }
+            Message::MouseRelease(button) => {
+                self.long_press = None;
+                if self.flag_drag == Some(button) {
+                    self.flag_drag = None;
+                }
+                for cell in self.size.clone().points() {
+                    let _ = self.update_cell(cell, cell::Message::Release(button));
+                }
+            }
+            // clicks that already went through keep running, only what's still held is let go
+            Message::ClearPressed => {
+                self.end_chord();
+                self.long_press = None;
+                self.flag_drag = None;
+                let _ = self.update(Message::MouseRelease(mouse::Button::Left));
+                for cell in self.cells.iter_mut() {
+                    cell.pressed = false;
+                    cell.hovering = false;
+                    cell.force = false;
+                }
+            }
+            Message::Restart => return self.restart(None),
+            Message::RestartSameBoard => {
+                let layout = self.game.blocking_layout();
+                return self.restart(layout)
+            }
+            Message::Repaint => {
+                self.tasks.repainted();
+                self.check_idle();
+                self.click_rate.sample(self.clock.now());
+                let revealed = self.revealed_safe();
+                self.safe_left = self.safe_cells() - revealed;
+                self.progress = revealed as f32 / self.safe_cells() as f32;
+                // flags might have come or gone around the highlighted number
+                self.highlight(self.highlighting);
+                if self.snapshot.is_some() {
+                    self.take_snapshot();
+                }
+                let journal = self.flush_journal();
+                self.announce_changes();
+                let excess = self.flag_excess();
+                return Task::batch([self.measure(), excess, journal, self.check_game_over()])
+            }
+            Message::Measured(board_id, bbbv) => {
+                if board_id == self.board_id {
+                    self.bbbv = bbbv;
+                }
+            }
+            Message::Landed(gesture) => {
+                self.latency.land(gesture);
+                return self.update(Message::Repaint)
+            }
+            Message::Frame(at) => {
+                self.latency.frame(at);
+                self.advance_reveals();
+                if self.animation.as_ref().is_some_and(|animation| animation.finished(self.clock.now())) {
+                    self.animation = None;
+                }
+            }
+            Message::Revealed(board_id, origin, before) => {
+                if board_id == self.board_id {
+                    self.animate_reveals_from(origin, &before);
+                }
+            }
+            Message::FlushJournal => return self.flush_journal(),
+            Message::CommitChord(id) => {
+                if self.chord.as_ref().is_some_and(|chord| chord.id == id)
+                        && let Some(chord) = self.end_chord() {
+                    return self.left_click(chord.point)
+                }
+            }
+            Message::CancelChord => {
+                self.end_chord();
+            }
+            // a board being edited has no clock to stop
+            Message::Pause if self.editing() => {}
+            Message::Pause => self.set_paused(!self.paused()),
+            Message::HoverChord(id, point) => {
+                if id == self.hover_id && self.cells.get(point).is_some_and(|cell| cell.hovering)
+                        && self.chord_preview(point).is_some() {
+                    self.hover_chorded = Some((point, self.clock.now()));
+                    return self.left_click(point)
+                }
+            }
+            Message::LongPress(id, point) => {
+                if self.long_press.is_some_and(|(pressing, _)| pressing == id) {
+                    self.long_press = None;
+                    // so letting go doesn't reveal it as well
+                    if let Some(cell) = self.cells.get_mut(point) {
+                        cell.pressed = false;
+                    }
+                    return self.right_click(point)
+                }
+            }
+            Message::ClearMines => {
+                if let Mode::Edit(mines) = &mut self.mode {
+                    mines.clear();
+                }
+            }
+            Message::Reveal(point) => return self.left_click(point),
+            Message::ToggleFlagMode | Message::CopyBoard | Message::ExportBoardImage
+                    | Message::PlayEdited | Message::SaveEdited | Message::BrowseBoards => {}
+            Message::Step => return self.step(),
+            Message::StepFound(id, found) => {
+                if id != self.step_id {
+                    return Task::none()
+                }
+                let Some((actions, explanation)) = found else {
+                    self.step_stuck = true;
+                    return Task::none()
+                };
+                self.explained(explanation);
+                for action in &actions {
+                    if let Some(cell) = self.cells.get_mut(action.point) {
+                        cell.preview = Some(action.operation);
+                    }
+                }
+                self.step = Some(PendingStep { id, actions });
+                if self.solver_preview {
+                    return Task::future(self.clock.sleep(self.step_preview))
+                            .map(move |_| Message::StepDue(id))
+                }
+            }
+            Message::StepDue(id) => {
+                if self.step.as_ref().is_some_and(|step| step.id == id) {
+                    return self.play_step()
+                }
+            }
+            Message::Explained(board_id, explanation) => {
+                if board_id == self.board_id {
+                    self.explained(explanation);
+                }
+            }
+            Message::HoverExplanation(index) => self.hover_explanation(index),
+            Message::AutoRestart(id) => {
+                if id != self.auto_restart_id || self.auto_restart.is_none() {
+                    return Task::none()
+                }
+                let restart = self.restart(None);
+                return restart.chain(Task::done(Message::AutoOpening(self.board_id)))
+            }
+            Message::AutoOpening(board_id) => {
+                if board_id != self.board_id || self.auto_restart.is_none() {
+                    return Task::none()
+                }
+                // auto only picks up from a click, the solver makes this one so it isn't put
+                // down to the player
+                let middle = (self.size.width().get() / 2, self.size.height().get() / 2);
+                let by = Provenance::Solver(self.steps.fetch_add(1, Ordering::Relaxed) + 1);
+                return self.left_click_by(middle, by)
+            }
+            Message::CollapseExplanations => {
+                self.hover_explanation(None);
+                self.explanations_collapsed = !self.explanations_collapsed;
+            }
+            Message::GameOver(game_over) => {
+                // nobody's watching for a dialog, the next board is on its way
+                if self.game_over_dialog && matches!(game_over.status, GameStatus::Won | GameStatus::Lost)
+                        && !(self.silent_auto && game_over.auto) && self.auto_restart.is_none() {
+                    self.game_over = Some(game_over);
+                    return Task::done(dialog::Message::Open(DialogId::GameOver).into())
+                }
+            }
+            // the input it is already woke the game
+            Message::Awake => {}
+            Message::Hover(from, to) => {
+                let exit = from.map(|point| self.update(Message::Cell((point, cell::Message::Exit))));
+                let enter = to.map(|point| self.update(Message::Cell((point, cell::Message::Enter))));
+                return Task::batch(exit.into_iter().chain(enter))
+            }
+            Message::Wheel(delta) => return self.zoom_by(delta),
+            Message::Scrolled(offset) => self.scroll = offset,
+            Message::ScrollTo(x, y) => {
+                let cell_size = self.fixed_cell_size().unwrap_or(FIT_CELL_SIZE);
+                return self.scroll_to(AbsoluteOffset { x: x * cell_size, y: y * cell_size })
+            }
+            Message::Analysis(delivery) => match self.analysis.accept(delivery) {
+                Some(Analysis::Provenance(provenance)) => self.provenance = Some(provenance),
+                Some(Analysis::LossReveal(overlay)) => self.loss_overlay = Some(overlay),
+                None => {}
+            },
+            Message::CloseGameOver => {
+                self.game_over = None;
+                return Task::done(dialog::Message::Close(DialogId::GameOver).into())
+            }
+            Message::Undo => {
+                if !self.offering_undo {
+                    return Task::none()
+                }
+                self.offering_undo = false;
+                if self.game.blocking_undo() {
+                    self.undos += 1;
+                    self.announce(t!("announce-undone").to_string());
+                }
+                return Task::done(dialog::Message::Close(DialogId::Undo).into())
+                        .chain(Task::done(Message::Repaint))
+            }
+            Message::RevealAnyway => {
+                let Some(point) = self.guarding.take() else { return Task::none() };
+                return Task::done(dialog::Message::Close(DialogId::MistakeGuard).into())
+                        .chain(self.left_click(point))
+            }
+            Message::KeepCovered => {
+                if self.guarding.take().is_some() {
+                    self.guarded += 1;
+                }
+                return Task::done(dialog::Message::Close(DialogId::MistakeGuard).into())
+            }
+            Message::Modifiers(modifiers) => {
+                self.modifiers = modifiers;
+                self.highlight(self.hovered);
+            }
+            Message::FlagRefusalOver(id) => {
+                if self.flag_refused == Some(id) {
+                    self.flag_refused = None;
+                }
+            }
+            Message::ExcessFlagOver(id) => {
+                if self.excess_flag.is_some_and(|(_, excess)| excess == id) {
+                    self.clear_excess_flag();
+                }
+            }
+            Message::AcceptDefeat => {
+                if !self.offering_undo {
+                    return Task::none()
+                }
+                self.offering_undo = false;
+                let status = self.status();
+                return Task::done(dialog::Message::Close(DialogId::Undo).into())
+                        .chain(self.settle(status))
+            }
+            Message::Dialog(_) => {}
+            // from a board that's been restarted since
+            Message::Hydrating(board_id, _) | Message::Hydrated(board_id) if board_id != self.board_id => {}
+            Message::Hydrating(_, progress) => self.hydrating = Some(progress),
+            Message::Hydrated(_) => {
+                self.hydrating = None;
+                // the restored board isn't news
+                self.announced = None;
+                let tasks = std::mem::take(&mut self.queued).into_iter()
+                        .map(|click| match click {
+                            Click::Left(point) => self.left_click(point),
+                            Click::Right(point) => self.right_click(point),
+                        })
+                        .collect::<Vec<_>>();
+                return Task::batch(tasks).chain(Task::done(Message::Repaint))
+            }
+        }
+        Task::none()
+    }
+
+
+    #[tracing::instrument(skip_all, fields(retry = layout.is_some()))]
+    fn restart(&mut self, layout: Option<GameState>) -> Task<Message> {
+        if self.editing() {
+            self.mode = Mode::Play;
+        }
+        self.set_paused(false);
+        self.last_input = self.clock.now();
+        self.board_id += 1;
+        self.bbbv = None;
+        self.measuring = false;
+        self.progress = 0.0;
+        self.safe_left = self.safe_cells();
+        self.tasks.cancel_all();
+        self.end_chord();
+        self.clear_step();
+        self.hover_explanation(None);
+        self.explanations.clear();
+        self.stop_reveals();
+        self.animation = None;
+        self.announced = None;
+        self.announcement = None;
+        self.game_over = None;
+        self.offering_undo = false;
+        self.undos = 0;
+        self.guarding = None;
+        self.guarded = 0;
+        self.last_flagged = None;
+        self.clear_excess_flag();
+        self.flag_drag = None;
+        self.loss_overlay = None;
+        self.provenance = None;
+        self.hydrating = None;
+        self.queued.clear();
+        self.latency.forget();
+
+        let closed = Task::done(dialog::Message::Close(DialogId::GameOver).into())
+                .chain(Task::done(dialog::Message::Close(DialogId::Undo).into()))
+                .chain(Task::done(dialog::Message::Close(DialogId::MistakeGuard).into()));
+        let abandoned = self.abandon()
+                .map(|game_over| Task::done(Message::GameOver(game_over)))
+                .unwrap_or_else(Task::none);
+        self.status = GameStatus::Never;
+        let analyses = Task::batch([self.sync_analyses(), self.analysis.board_changed(&self.game).map(Message::Analysis)]);
+        self.finished = None;
+        self.steps.store(0, Ordering::Relaxed);
+        self.guesses.store(0, Ordering::Relaxed);
+        self.retry = layout.is_some();
+        self.journal.clear();
+        self.click_rate.reset();
+
+        let game = self.game.clone();
+        let opening = self.opening.clone();
+        let seed = match layout {
+            Some(_) => self.game.blocking_seed(),
+            None => self.seed.unwrap_or_else(rand::random),
+        };
+        tracing::info!(size = %size_label(self.size), seed, "starting a game");
+        closed.chain(abandoned).chain(analyses).chain(Task::future(async move {
+            match layout {
+                Some(layout) => game.start_with_layout(layout, seed).await,
+                None => game.start_with_solver(opening, seed).await,
+            }
+        }.in_current_span()).map(|_| Message::Repaint))
+    }
+
+    /// starts over on a board that was made elsewhere
+    pub fn play_layout(&mut self, layout: GameState) -> Task<Message> {
+        self.restart(Some(layout))
+    }
+
+    /// opens the editor on this board with `mines` placed, meant for a game that hasn't started
+    pub fn edit(&mut self, mines: impl IntoIterator<Item = Point>) {
+        self.mode = Mode::Edit(mines.into_iter().collect());
+    }
+
+    /// leaves the board to be played by whatever sends it [`Message::Reveal`] and its auto player
+    pub fn watch(&mut self) {
+        self.mode = Mode::Watch;
+    }
+
+    pub fn editing(&self) -> bool {
+        matches!(self.mode, Mode::Edit(_))
+    }
+
+    /// the board in the editor, `None` if there isn't one or it has no mines or no safe cells
+    pub fn edited(&self) -> Option<Layout> {
+        let Mode::Edit(mines) = &self.mode else { return None };
+        let mut mines = mines.iter().copied().collect::<Vec<_>>();
+        mines.sort_by_key(|(x, y)| (*y, *x));
+        Layout::new(self.size.width().get(), self.size.height().get(), mines).ok()
+    }
+
+    /// a press places or takes away a mine, hovering still describes the cell
+    fn edit_cell(&mut self, point: Point, message: cell::Message) -> Task<Message> {
+        let Mode::Edit(mines) = &mut self.mode else { return Task::none() };
+        let Some(cell) = self.cells.get_mut(point) else { return Task::none() };
+        match message {
+            // a mine that's there is taken away, otherwise one goes in
+            cell::Message::SelfPress(_) if !mines.remove(&point) => {
+                mines.insert(point);
+            }
+            cell::Message::Enter => cell.hovering = true,
+            cell::Message::Exit => cell.hovering = false,
+            _ => {}
+        }
+        Task::none()
+    }
+
+    /// the board as the player sees it
+    pub fn gamestate(&self) -> GameState {
+        self.game.blocking_gamestate()
+    }
+
+    /// where the mines are, `None` until the first click placed them
+    pub fn layout(&self) -> Option<GameState> {
+        self.game.blocking_layout()
+    }
+
+    /// the first cell revealed, `None` until there is one
+    pub fn opening(&self) -> Option<Point> {
+        self.game.blocking_opening()
+    }
+
+    /// as of the last repaint
+    pub fn game_status(&self) -> GameStatus {
+        self.status
+    }
+
+    pub fn size(&self) -> BoardSize {
+        self.size
+    }
+
+    pub fn resume(&mut self, layout: GameState, seed: u64, clicks: Vec<(Click, Provenance)>) -> Task<Message> {
+        self.status = GameStatus::Never;
+        self.finished = None;
+        self.started = Some(self.clock.now());
+
+        self.hydrating = Some(0.0);
+        self.queued.clear();
+
+        // replaying a long game in one go stalls the first frame, so the clicks are applied a
+        // chunk at a time while a skeleton board is shown
+        const CHUNK_SIZE: usize = 256;
+        let total = clicks.len().max(1);
+        let game = self.game.clone();
+        let board_id = self.board_id;
+        let stream = futures_util::stream::unfold(Some((Some(layout), clicks.into_iter(), 0)), move |state| {
+            let game = game.clone();
+            async move {
+                let (layout, mut clicks, done) = state?;
+                if let Some(layout) = layout {
+                    game.start_with_layout(layout, seed).await;
+                }
+
+                let chunk = clicks.by_ref().take(CHUNK_SIZE).collect::<Vec<_>>();
+                // only a replay that got to the end says so, one that was cancelled just stops
+                if chunk.is_empty() {
+                    return Some((Message::Hydrated(board_id), None))
+                }
+                for (click, by) in &chunk {
+                    let _ = game.click(*click, *by).await;
+                }
+                tokio::task::yield_now().await;
+
+                let done = done + chunk.len();
+                Some((Message::Hydrating(board_id, done as f32 / total as f32), Some((None, clicks, done))))
+            }
+        });
+
+        Task::stream(stream.take_until(self.tasks.cancelled()))
+    }
+
+    fn flush_journal(&mut self) -> Task<Message> {
+        // only the game the user plays is recovered after a crash
+        if self.hydrating.is_some() || matches!(self.mode, Mode::Watch) {
+            return Task::none()
+        }
+        let history = self.game.blocking_history();
+        crash::remember(history.as_ref());
+        let Some(history) = history else { return Task::none() };
+        if !self.journal.should_flush(&history, self.clock.now()) {
+            return Task::none()
+        }
+        // the records are small, it's waiting for them to reach the disk that takes a while
+        let file = match self.journal.write(&history, self.clock.now()).and_then(|()| self.journal.sync_handle()) {
+            Ok(Some(file)) => file,
+            Ok(None) => return Task::none(),
+            Err(e) => {
+                tracing::error!("failed to write journal: {}", e);
+                return Task::none()
+            }
+        };
+        Task::future(tokio::task::spawn_blocking(move || file.sync_data()))
+                .then(|result| {
+                    if let Err(e) = result.unwrap_or_else(|e| Err(std::io::Error::other(e))) {
+                        tracing::error!("failed to sync journal: {}", e);
+                    }
+                    Task::none()
+                })
+    }
+
+    pub fn abandon(&mut self) -> Option<GameOver> {
+        let started = self.started.take()?;
+        (self.status == GameStatus::Playing).then(|| self.game_over(GameStatus::Playing, self.played(started)))
+    }
+
+    fn game_over(&self, status: GameStatus, elapsed: Duration) -> GameOver {
+        let gamestate = self.game.blocking_gamestate();
+        let provenance = self.game.blocking_provenance();
+        // any move of the solver's, a flag or a step that was followed included, makes it the
+        // solver's game as far as records go
+        let auto = provenance.values().any(|by| matches!(by, Provenance::Solver(_)));
+        let (mut revealed_by_player, mut revealed_by_solver) = (0, 0);
+        for (point, by) in provenance {
+            if gamestate.board[point].cell_state == CellState::Revealed {
+                match by {
+                    Provenance::Player => revealed_by_player += 1,
+                    Provenance::Solver(_) => revealed_by_solver += 1,
+                }
+            }
+        }
+
+        let (player_clicks, solver_clicks) = self.game.blocking_clicks_by();
+        // a game won on its first click can end before the background count comes back
+        let bbbv = self.bbbv.or_else(|| self.game.blocking_layout().map(|layout| bbbv::bbbv(&layout)));
+
+        GameOver {
+            size: self.size,
+            status,
+            elapsed,
+            clicks: player_clicks + solver_clicks,
+            player_clicks,
+            bbbv,
+            seed: self.game.blocking_seed(),
+            revealed_by_player,
+            revealed_by_solver,
+            auto,
+            retry: self.retry,
+            undos: self.undos,
+            guarded: self.guarded,
+            guesses: self.guesses.load(Ordering::Relaxed),
+            no_flags: self.no_flags,
+            flagged: self.game.blocking_flagged(),
+            eligible: self.rules.records_eligible(),
+        }
+    }
+
+    /// counts the 3BV off the ui thread once, as soon as there's a layout to count
+    fn measure(&mut self) -> Task<Message> {
+        if self.bbbv.is_some() || self.measuring {
+            return Task::none()
+        }
+        let Some(layout) = self.game.blocking_layout() else { return Task::none() };
+        self.measuring = true;
+        let board_id = self.board_id;
+        Task::future(tokio::task::spawn_blocking(move || bbbv::bbbv(&layout)))
+                .map(move |bbbv| Message::Measured(board_id, bbbv.ok()))
+    }
+
+    /// a loss that can be undone stays open until the player picks undo or accepts it
+    fn can_undo(&self) -> bool {
+        self.forgiving && !self.rules.timed() && matches!(self.mode, Mode::Play) && self.game.blocking_can_undo()
+    }
+
+    fn check_game_over(&mut self) -> Task<Message> {
+        if self.offering_undo {
+            return Task::none()
+        }
+        let status = self.status();
+        if self.status == GameStatus::Playing && status == GameStatus::Lost && self.can_undo() {
+            self.offering_undo = true;
+            self.remaining_mines = self.remaining_mines();
+            return Task::done(dialog::Message::Open(DialogId::Undo).into())
+        }
+        self.settle(status)
+    }
+
+    /// ends the game once it's won or lost, whatever else checking it found
+    fn settle(&mut self, status: GameStatus) -> Task<Message> {
+        let previous = std::mem::replace(&mut self.status, status);
+        self.remaining_mines = self.remaining_mines();
+        if matches!(status, GameStatus::Won | GameStatus::Lost) {
+            self.journal.clear();
+        }
+        if previous == GameStatus::Playing && matches!(status, GameStatus::Won | GameStatus::Lost)
+                && let Some(started) = self.started.take() {
+            self.tasks.cancel_all();
+            // the clicks that were just called off never land
+            self.latency.prune();
+            // lost to a rule, like the time running out, on a board that's still being played
+            if status == GameStatus::Lost && self.game.blocking_gamestate().status == GameStatus::Playing {
+                self.game.blocking_end();
+            }
+            sound::play(match status {
+                GameStatus::Won => Sound::Fanfare,
+                _ => Sound::Explosion,
+            });
+            if !self.reduce_motion && !self.throttled {
+                let now = self.clock.now();
+                self.animation = Some(match status {
+                    GameStatus::Won => AnimationState::confetti(now),
+                    _ => AnimationState::shake(now),
+                });
+            }
+            let elapsed = self.played(started);
+            self.finished = Some(elapsed);
+            self.announce(match status {
+                GameStatus::Won => t!("announce-won", seconds = elapsed.as_secs()),
+                _ => t!("announce-lost").to_string(),
+            });
+            let game_over = self.game_over(status, elapsed);
+            let restart = self.schedule_auto_restart(&game_over);
+            return Task::batch([Task::done(Message::GameOver(game_over)), self.sync_analyses(), restart])
+        }
+        Task::none()
+    }
+
+    /// says what changed on the board since the last announcement, a repaint that changed
+    /// nothing leaves the last one up
+    fn announce_changes(&mut self) {
+        if !self.describe_cells {
+            return
+        }
+        let gamestate = self.game.blocking_gamestate();
+        let Some(before) = self.announced.replace(gamestate.clone()) else { return };
+
+        let (mut revealed, mut flagged, mut unflagged) = (0, 0, 0);
+        for point in self.size.points() {
+            match (before.board[point].cell_state, gamestate.board[point].cell_state) {
+                (CellState::Revealed, _) => {}
+                (_, CellState::Revealed) => revealed += 1,
+                (CellState::Unknown, CellState::Flagged) => flagged += 1,
+                (CellState::Flagged, CellState::Unknown) => unflagged += 1,
+                _ => {}
+            }
+        }
+
+        let changes = [
+            (revealed > 0).then(|| match revealed {
+                1 => t!("announce-revealed-one").to_string(),
+                _ => t!("announce-revealed", count = revealed),
+            }),
+            (flagged > 0).then(|| match flagged {
+                1 => t!("announce-flagged-one").to_string(),
+                _ => t!("announce-flagged", count = flagged),
+            }),
+            (unflagged > 0).then(|| match unflagged {
+                1 => t!("announce-unflagged-one").to_string(),
+                _ => t!("announce-unflagged", count = unflagged),
+            }),
+        ].into_iter().flatten().collect::<Vec<_>>();
+        if !changes.is_empty() {
+            self.announcement = Some(changes.join(", "));
+        }
+    }
+
+    /// adds to what the last repaint announced, a game ending comes right after the move that
+    /// ended it
+    fn announce(&mut self, event: String) {
+        if !self.describe_cells {
+            return
+        }
+        self.announcement = Some(match self.announcement.take() {
+            Some(announcement) => t!("announce-then", first = announcement, then = event),
+            None => event,
+        });
+    }
+
+    /// the hovered cell and the last announcement, only worked out for the one cell under the
+    /// cursor so big boards don't pay for it
+    fn description(&self) -> String {
+        let hovered = self.cells.enumerate()
+                .filter(|_| !self.streamer_mode)
+                .find(|(_, cell)| cell.hovering)
+                .map(|(point, _)| cell::describe(point, self.displayed_gamestate().board[point]));
+        [hovered, self.announcement.clone()].into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+                .join(t!("description-separator"))
+    }
+
+    /// the hovered cell and the flags against the mines, or the board's size and time when
+    /// nothing is hovered
+    fn status_text(&self) -> String {
+        let Some(snapshot) = &self.snapshot else { return String::new() };
+        let board = &snapshot.board;
+        let flags = self.size.points()
+                .filter(|point| board[*point].cell_state == CellState::Flagged)
+                .count();
+        let flags = t!("status-flags", flags = flags, mines = self.size.mines());
+        let hovered = match self.hovered.filter(|point| !self.streamer_mode && self.cells.get(*point).is_some()) {
+            Some(point) => cell::status(point, board[point], self.size.neighbours(point)
+                    .filter(|around| board[*around].cell_state == CellState::Flagged)
+                    .count()),
+            None => t!("status-board", width = self.size.width(), height = self.size.height(),
+                    mines = self.size.mines(), time = format_duration(self.rule_context().elapsed)),
+        };
+        [hovered, flags].join(t!("description-separator"))
+    }
+
+    /// turns each analysis on or off to match the game and settings
+    fn sync_analyses(&mut self) -> Task<Message> {
+        let finished = matches!(self.status, GameStatus::Won | GameStatus::Lost);
+        Task::batch([
+            self.analysis.set_enabled(Kind::Provenance, self.show_provenance && finished, &self.game),
+            self.analysis.set_enabled(Kind::LossReveal, self.status == GameStatus::Lost, &self.game),
+        ]).map(Message::Analysis)
+    }
+
+    /// the board as it should be drawn, after a loss nothing but what was clicked shows until the
+    /// overlay is in
+    pub fn displayed_gamestate(&self) -> GameState {
+        if let Mode::Edit(mines) = &self.mode {
+            return edited_gamestate(self.size, mines)
+        }
+        let mut gamestate = self.game.blocking_gamestate();
+        if self.animating {
+            let now = self.clock.now();
+            for (point, cell) in self.cells.enumerate() {
+                if cell.reveal_at.is_some_and(|at| at > now) {
+                    gamestate.board[point] = minsweeper_rs::Cell::new(CellType::Unknown, CellState::Unknown);
                 }
-                self.handles.blocking_lock().clear();
+            }
+        }
+        if gamestate.status != GameStatus::Lost {
+            return gamestate
+        }
 
-                let game = self.game.clone();
-                let solver = self.solver.clone();
-                return Task::future(async move {
-                    game.start_with_solver(solver).await
-                }).map(|_| Message::Repaint)
+        for point in self.size.points() {
+            let cell = &mut gamestate.board[point];
+            match self.loss_overlay.as_ref().and_then(|overlay| overlay.get(&point)) {
+                Some(overlay) => *cell = *overlay,
+                None if self.loss_overlay.is_none() && cell.cell_state != CellState::Revealed => {
+                    cell.cell_type = CellType::Unknown
+                }
+                None => {}
             }
-            Message::Repaint => {}
         }
-        Task::none()
+        gamestate
+    }
+
+    fn left_click(&mut self, point: Point) -> Task<Message> {
+        self.left_click_by(point, Provenance::Player)
     }
 
-    fn left_click(&self, point: Point) -> Task<Message> {
+    fn left_click_by(&mut self, point: Point, by: Provenance) -> Task<Message> {
+        let by = self.following_step(point, false, by);
+        if self.started.is_none() {
+            self.started = Some(self.clock.now());
+        }
         let cell = &self.cells[point];
-        let revealings = if matches!(self.game.blocking_gamestate().board[point].cell_type, CellType::Safe(_)) {
+        let gamestate = self.game.blocking_gamestate();
+        let revealings = if matches!(gamestate.board[point].cell_type, CellType::Safe(_)) {
             self.size.neighbours(point)
                     .map(|point| self.cells[point].revealing.clone())
                     .collect()
@@ -148,161 +1701,472 @@ impl MinsweeperGame {
         };
 
         for revealing in &revealings {
-            revealing.store(true, Ordering::Relaxed);
+            revealing.fetch_add(1, Ordering::Relaxed);
         }
 
         let game = self.game.clone();
-        let flag_chord = self.flag_chord;
+        let flag_chord = self.flag_chord && !self.no_flags;
+        let landed = self.landed();
+        let tasks = self.tasks.clone();
+        let board_id = self.board_id;
+        let before = (self.animate_reveals && !self.reduce_motion && !self.throttled).then_some(gamestate);
+
+        let mut task = Task::future(self.tasks.run(async move {
+            left_click(&game, point, flag_chord, by).await
+        }.instrument(tracing::debug_span!("left click", x = point.0, y = point.1)))).then(move |_| {
+            for revealing in &revealings {
+                revealing.fetch_sub(1, Ordering::Relaxed);
+            }
+            // ahead of the repaint so the opening never shows all at once
+            match &before {
+                Some(before) => Task::done(Message::Revealed(board_id, point, before.clone()))
+                        .chain(repaint(&tasks, landed.clone())),
+                None => repaint(&tasks, landed.clone()),
+            }
+        });
 
-        let mut handles_lock = self.handles.blocking_lock();
+        if let Some(auto) = &self.auto
+                && let Some(solver) = auto.solver.clone().or(self.solver.clone())
+                && !self.autoing.fetch_or(true, Ordering::Relaxed) {
+            task = task.chain(self.auto_task(solver, auto.delay, auto.guess))
+        }
 
-        let (abortable, handle) = futures_util::future::abortable(async move {
-            left_click(&game, point, flag_chord).await
-        });
-        let task = Task::future(abortable);
-        let uuid = Uuid::new_v4();
-
-        let handles = self.handles.clone();
-        let mut task = task.then(move |_| {
-            let handles = handles.clone();
-            let revealings = revealings.clone();
-            Task::future(async move {
-                for revealing in revealings {
-                    revealing.store(false, Ordering::Relaxed);
-                }
-                let mut handles = handles.lock().await;
-                handles.remove(&uuid);
-            })
-        });
+        task
+    }
+
+    /// what a click sends once the game has applied it
+    fn landed(&mut self) -> Message {
+        match self.latency.begin() {
+            Some(gesture) => Message::Landed(gesture),
+            None => Message::Repaint,
+        }
+    }
 
+    /// whether a click landed and the frame showing it hasn't been drawn yet, or a reveal or the
+    /// end of game animation is still going
+    pub fn awaiting_frame(&self) -> bool {
+        self.latency.waiting() || self.animating || self.animation.is_some()
+    }
 
-        handles_lock.insert(uuid, handle);
+    /// covers what the click at `origin` revealed and uncovers it again ring by ring going out
+    fn animate_reveals_from(&mut self, origin: Point, before: &GameState) {
+        let after = self.game.blocking_gamestate();
+        let revealed = self.size.points()
+                .filter(|point| before.board[*point].cell_state != CellState::Revealed
+                        && after.board[*point].cell_state == CellState::Revealed)
+                .collect::<HashSet<_>>();
+        if revealed.len() < 2 {
+            return
+        }
 
+        // through what was revealed, so it follows the opening around corners
+        let mut distances = HashMap::new();
+        let mut queue = VecDeque::new();
+        if revealed.contains(&origin) {
+            distances.insert(origin, 0);
+            queue.push_back(origin);
+        } else {
+            // a chord, the number itself was already showing
+            for neighbour in self.size.neighbours(origin).filter(|point| revealed.contains(point)) {
+                distances.insert(neighbour, 1);
+                queue.push_back(neighbour);
+            }
+        }
+        while let Some(point) = queue.pop_front() {
+            let distance = distances[&point];
+            for neighbour in self.size.neighbours(point) {
+                if revealed.contains(&neighbour) && !distances.contains_key(&neighbour) {
+                    distances.insert(neighbour, distance + 1);
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+        for point in &revealed {
+            distances.entry(*point).or_insert_with(|| point.0.abs_diff(origin.0).max(point.1.abs_diff(origin.1)) as u32);
+        }
 
-        drop(handles_lock);
-        if let Some(auto) = &self.auto && !self.autoing.fetch_or(true, Ordering::Relaxed) {
-            let solver = auto.solver()
-                    .map(Into::into)
-                    .unwrap_or(self.solver.clone());
-            let game = self.game.clone();
-            let handles = self.handles.clone();
-            let delay = auto.delay();
-            let autoing = self.autoing.clone();
+        let rings = distances.values().copied().max().unwrap_or(0).max(1);
+        let total = (REVEAL_RING * rings).clamp(REVEAL_MIN, REVEAL_MAX);
+        let now = self.clock.now();
+        for (point, distance) in distances {
+            self.cells[point].reveal_at = Some(now + total * distance / rings);
+        }
+        self.animating = true;
+    }
 
+    /// forgets the reveals whose time has come, and stops asking for frames once they all have
+    fn advance_reveals(&mut self) {
+        if !self.animating {
+            return
+        }
+        let now = self.clock.now();
+        let mut waiting = false;
+        for cell in self.cells.iter_mut() {
+            match cell.reveal_at {
+                Some(at) if at <= now => cell.reveal_at = None,
+                Some(_) => waiting = true,
+                None => {}
+            }
+        }
+        self.animating = waiting;
+    }
 
-            task = task.then(move |_| Self::auto_task(solver.clone(), game.clone(), handles.clone(), delay, autoing.clone()).map(|_| ()))
+    fn stop_reveals(&mut self) {
+        for cell in self.cells.iter_mut() {
+            cell.reveal_at = None;
         }
+        self.animating = false;
+    }
+
+    pub fn reset_latency(&mut self) {
+        self.latency = Latency::default();
+    }
+
+    pub fn set_canvas_board(&mut self, canvas_board: bool) {
+        self.canvas_board = canvas_board;
+        self.take_snapshot();
+    }
+
+    pub fn latency_summary(&self) -> String {
+        self.latency.summary()
+    }
 
-        task.map(|_| Message::Repaint)
+    #[cfg(test)]
+    pub fn latency(&self) -> &Latency {
+        &self.latency
     }
 
-    fn auto_task(solver: SolverType, game: MinsweeperType, handles: Arc<Mutex<HashMap<Uuid, AbortHandle>>>, delay: Duration, autoing: Arc<AtomicBool>) -> Task<Message> {
+    fn auto_task(&self, solver: SolverType, delay: Duration, guess: AutoGuess) -> Task<Message> {
+        let game = self.game.clone();
+        let tasks = self.tasks.clone();
+        let autoing = self.autoing.clone();
+        let steps = self.steps.clone();
+        let guesses = self.guesses.clone();
+        let no_flags = self.no_flags;
+        let pause = self.pause.subscribe();
+        let clock = self.clock.clone();
+        let board_id = self.board_id;
         #[derive(Debug)]
         enum Phase {
-            Start, SolveNext(Uuid), End(Uuid)
+            Start, SolveNext, End
         }
+        let repaints = self.tasks.clone();
+        // a restart ends the run wherever it's at, waiting between steps included
+        let cancelled = self.tasks.cancelled().shared();
         Task::stream(futures_util::stream::unfold(Phase::Start, move |phase| {
-            let handles = handles.clone();
+            let tasks = tasks.clone();
+            let cancelled = cancelled.clone();
             let game = game.clone();
             let solver = solver.clone();
             let autoing = autoing.clone();
+            let steps = steps.clone();
+            let guesses = guesses.clone();
+            let clock = clock.clone();
+            let mut pause = pause.clone();
             async move {
-                let handles = handles.clone();
-                let game = game.clone();
-                if let Phase::SolveNext(uuid) | Phase::End(uuid) = phase {
-                    handles.lock().await.remove(&uuid);
+                if matches!(phase, Phase::End) {
+                    autoing.store(false, Ordering::Relaxed);
+                    return None
                 }
 
-                if matches!(phase, Phase::SolveNext(_)) {
-                    tokio::time::sleep(delay).await;
-                }
+                let step = async move {
+                    if matches!(phase, Phase::SolveNext) {
+                        clock.sleep(delay).await;
+                    }
+                    // a pause holds the solver between steps, never in the middle of one
+                    let _ = pause.wait_for(|paused| !paused).await;
 
-                let phase = if matches!(phase, Phase::Start | Phase::SolveNext(_)) {
-                    let uuid = Uuid::new_v4();
-                    let (abortable, handle) = futures_util::future::abortable(async move {
+                    tasks.run(async move {
                         let gamestate = game.gamestate().await;
-                        let Some(Move { actions, .. }) = solver.solve(&gamestate) else {
-                            return false
+                        // a guess that hit a mine ends the run here rather than solving a lost board
+                        if matches!(gamestate.status, GameStatus::Won | GameStatus::Lost) {
+                            return None
+                        }
+                        // an external solver blocks on its process for as long as it takes to answer
+                        let found = {
+                            let gamestate = gamestate.clone();
+                            tokio::task::spawn_blocking(move || solved(&solver, &gamestate, no_flags))
+                                    .await
+                                    .ok()
+                                    .flatten()
                         };
+                        let (actions, explanation) = match found {
+                            Some(found) => found,
+                            None => {
+                                let Some((point, risk)) = guess::pick(guess, &gamestate)
+                                        .filter(|_| gamestate.status == GameStatus::Playing) else {
+                                    tracing::debug!("the solver found no move it could play, auto is done");
+                                    return None
+                                };
+                                guesses.fetch_add(1, Ordering::Relaxed);
+                                tracing::info!(x = point.0, y = point.1, risk, ?guess, "nothing certain left, auto guessed");
+                                (vec![Action::new(point, Operation::Reveal)], explain::guessed(point, risk))
+                            }
+                        };
+                        let by = Provenance::Solver(steps.fetch_add(1, Ordering::Relaxed) + 1);
+                        tracing::debug!(?by, actions = actions.len(), "auto step");
 
                         for action in actions {
-                            match action.operation {
-                                Operation::Reveal | Operation::Chord => left_click(&game, action.point, false).await,
-                                Operation::Flag => right_click(&game, action.point).await,
-                            }
+                            let _ = match action.operation {
+                                Operation::Reveal | Operation::Chord => left_click(&game, action.point, false, by).await,
+                                Operation::Flag => right_click(&game, action.point, by).await,
+                            };
                         }
 
-                        true
-                    });
-                    handles.lock().await.insert(uuid, handle);
-
-                    match abortable.await {
-                        Ok(true) => Some(Phase::SolveNext(uuid)),
-                        _ => Some(Phase::End(uuid))
-                    }
-                } else {
-                    autoing.store(false, Ordering::Relaxed);
-                    None
+                        Some(explanation)
+                    }.instrument(tracing::debug_span!("auto step"))).await
                 };
 
-                phase.map(|phase| ((), phase))
+                // cancelled goes first, the step only takes its place in `tasks` once it's polled,
+                // which is after the restart that should have ended it
+                match select(cancelled, pin!(step)).await {
+                    Either::Right((Some(Some(explanation)), _)) => Some((Some(explanation), Phase::SolveNext)),
+                    _ => Some((None, Phase::End)),
+                }
             }
-        })).map(|_| Message::Repaint)
+        })).then(move |explanation| match explanation {
+            Some(explanation) => Task::done(Message::Explained(board_id, explanation))
+                    .chain(repaint(&repaints, Message::Repaint)),
+            None => repaint(&repaints, Message::Repaint),
+        })
+    }
+
+    fn chord_preview(&self, point: Point) -> Option<(Vec<Point>, Vec<Point>)> {
+        let gamestate = self.game.blocking_gamestate();
+        let cell = gamestate.board[point];
+        let CellType::Safe(number @ 1..) = cell.cell_type else { return None };
+        if cell.cell_state != CellState::Revealed {
+            return None
+        }
+
+        let flags = self.size.neighbours(point)
+                .filter(|point| gamestate.board[*point].cell_state == CellState::Flagged)
+                .collect::<Vec<_>>();
+        let reveals = self.size.neighbours(point)
+                .filter(|point| gamestate.board[*point].cell_state == CellState::Unknown)
+                .collect::<Vec<_>>();
+
+        (flags.len() == number as usize && !reveals.is_empty())
+                .then_some((flags, reveals))
+    }
+
+    fn begin_chord(&mut self, point: Point, flags: Vec<Point>, reveals: Vec<Point>) -> Task<Message> {
+        self.end_chord();
+        for flag in &flags {
+            self.cells[*flag].highlighted = true;
+        }
+        for reveal in &reveals {
+            self.cells[*reveal].pending = true;
+        }
+
+        self.chord_id += 1;
+        let id = self.chord_id;
+        self.chord = Some(PendingChord { id, point, flags, reveals });
+
+        let delay = self.chord_delay;
+        Task::future(self.clock.sleep(delay))
+                .map(move |_| Message::CommitChord(id))
+    }
+
+    /// waits out the hover delay before chording, and only for numbers whose flags are all placed
+    fn hover_chord(&mut self, point: Point) -> Task<Message> {
+        if !self.hover_chord || self.chord_preview(point).is_none() {
+            return Task::none()
+        }
+        if self.hover_chorded.is_some_and(|(chorded, at)| chorded == point && self.clock.elapsed(at) < HOVER_CHORD_COOLDOWN) {
+            return Task::none()
+        }
+
+        self.hover_id += 1;
+        let id = self.hover_id;
+        Task::future(self.clock.sleep(self.hover_chord_delay))
+                .map(move |_| Message::HoverChord(id, point))
     }
 
-    fn right_click(&self, point: Point) -> Task<Message> {
+    fn begin_long_press(&mut self, point: Point) -> Task<Message> {
+        self.long_press_id += 1;
+        let id = self.long_press_id;
+        self.long_press = Some((id, point));
+        Task::future(self.clock.sleep(LONG_PRESS))
+                .map(move |_| Message::LongPress(id, point))
+    }
+
+    /// the first press asks the solver, a press while its move is shown plays it right away
+    fn step(&mut self) -> Task<Message> {
+        if self.step.is_some() {
+            return self.play_step()
+        }
+        let Some(solver) = self.solver.clone() else { return Task::none() };
+        if self.editing() || self.paused() || self.hydrating.is_some() || self.status() != GameStatus::Playing {
+            return Task::none()
+        }
+        self.clear_step();
+        let id = self.step_id;
+        let gamestate = self.game.blocking_gamestate();
+        let no_flags = self.no_flags;
+        // external solvers take their time
+        Task::future(tokio::task::spawn_blocking(move || solved(&solver, &gamestate, no_flags)))
+                .map(move |found| Message::StepFound(id, found.ok().flatten()))
+    }
+
+    fn play_step(&mut self) -> Task<Message> {
+        // the step stays shown for once the game's resumed
+        if self.paused() || self.status() != GameStatus::Playing {
+            return Task::none()
+        }
+        let Some(step) = self.clear_step() else { return Task::none() };
         let game = self.game.clone();
+        let by = Provenance::Solver(self.steps.fetch_add(1, Ordering::Relaxed) + 1);
+        let landed = self.landed();
+        let tasks = self.tasks.clone();
+
+        Task::future(self.tasks.run(async move {
+            for action in step.actions {
+                let _ = match action.operation {
+                    Operation::Reveal | Operation::Chord => left_click(&game, action.point, false, by).await,
+                    Operation::Flag => right_click(&game, action.point, by).await,
+                };
+            }
+        }.instrument(tracing::debug_span!("solver step")))).then(move |_| repaint(&tasks, landed.clone()))
+    }
 
-        let mut handles_lock = self.handles.blocking_lock();
+    /// takes the step off the board, a move the player makes from it was the solver's hint so
+    /// it's put down to the solver
+    fn following_step(&mut self, point: Point, flag: bool, by: Provenance) -> Provenance {
+        let hinted = self.clear_step().is_some_and(|step| step.actions.iter()
+                .any(|action| action.point == point && matches!(action.operation, Operation::Flag) == flag));
+        match by {
+            Provenance::Player if hinted => Provenance::Solver(self.steps.fetch_add(1, Ordering::Relaxed) + 1),
+            by => by,
+        }
+    }
 
-        let (abortable, handle) = futures_util::future::abortable(async move {
-            right_click(&game, point).await
-        });
-        let task = Task::future(abortable);
-        let uuid = Uuid::new_v4();
-
-        let handles = self.handles.clone();
-        let task = task.then(move |_| {
-            let handles = handles.clone();
-            Task::future(async move {
-                let handles = handles.lock().await;
-                let Some(handle) = handles.get(&uuid) else { return };
-                handle.abort();
-            })
-        });
+    /// takes the step off the board and forgets any that's still being worked out
+    fn clear_step(&mut self) -> Option<PendingStep> {
+        self.step_id += 1;
+        self.step_stuck = false;
+        let step = self.step.take()?;
+        for action in &step.actions {
+            if let Some(cell) = self.cells.get_mut(action.point) {
+                cell.preview = None;
+            }
+        }
+        Some(step)
+    }
 
+    fn end_chord(&mut self) -> Option<PendingChord> {
+        let chord = self.chord.take()?;
+        for flag in &chord.flags {
+            self.cells[*flag].highlighted = false;
+        }
+        for reveal in &chord.reveals {
+            self.cells[*reveal].pending = false;
+        }
+        Some(chord)
+    }
 
-        handles_lock.insert(uuid, handle);
+    fn right_click(&mut self, point: Point) -> Task<Message> {
+        if self.no_flags {
+            self.flag_refusal_id += 1;
+            let id = self.flag_refusal_id;
+            self.flag_refused = Some(id);
+            return Task::future(self.clock.sleep(FLAG_REFUSAL))
+                    .map(move |_| Message::FlagRefusalOver(id))
+        }
+        let by = self.following_step(point, true, Provenance::Player);
+        self.last_flagged = Some(point);
+        let game = self.game.clone();
+        let landed = self.landed();
+        let tasks = self.tasks.clone();
 
-        task.map(|_| Message::Repaint)
+        Task::future(self.tasks.run(async move {
+            right_click(&game, point, by).await
+        }.instrument(tracing::debug_span!("right click", x = point.0, y = point.1)))).then(move |_| repaint(&tasks, landed.clone()))
     }
 
 
+    fn drag_flag_over(&mut self, point: Point) -> Task<Message> {
+        let by = self.following_step(point, true, Provenance::Player);
+        self.last_flagged = Some(point);
+        let game = self.game.clone();
+        let landed = self.landed();
+        let tasks = self.tasks.clone();
+
+        Task::future(self.tasks.run(async move {
+            if game.flag(point, by).await.is_ok() {
+                sound::play(Sound::Flag);
+            }
+        }.instrument(tracing::debug_span!("drag flag", x = point.0, y = point.1)))).then(move |_| repaint(&tasks, landed.clone()))
+    }
+
     pub fn update_cell(&mut self, point: Point, message: cell::Message) -> Task<Message> {
-        let cell = &mut self.cells[point];
+        if self.paused() {
+            return Task::none()
+        }
+        match message {
+            cell::Message::Enter => self.hovered = Some(point),
+            cell::Message::Exit if self.hovered == Some(point) => self.hovered = None,
+            _ => {}
+        }
+        if matches!(message, cell::Message::Enter | cell::Message::Exit) {
+            self.highlight(self.hovered);
+        }
+        // the canvas board can still be reporting points from before a size change
+        let Some(cell) = self.cells.get_mut(point) else { return Task::none() };
         match message {
-            cell::Message::Press(_button) => {
-                if self.game.blocking_gamestate().board[point].cell_state == CellState::Unknown
-                        || matches!(self.game.blocking_gamestate().board[point].cell_type, CellType::Safe(1..)) {
-                    cell.pressed = true;
-                }
-            }
             cell::Message::Release(_button) => {
                 cell.pressed = false;
             }
             cell::Message::SelfPress(button) => {
-                if self.game.blocking_gamestate().board[point].cell_state == CellState::Unknown
-                        || matches!(self.game.blocking_gamestate().board[point].cell_type, CellType::Safe(1..)) {
-                    cell.pressed = true;
+                if matches!(self.status, GameStatus::Never | GameStatus::Playing) {
+                    self.click_rate.click(self.clock.now());
                 }
-
-                if matches!(button, mouse::Button::Right) {
-                    cell.pressed = false;
-                    return self.right_click(point);
+                let gamestate = self.game.blocking_gamestate();
+                let number = matches!(gamestate.board[point].cell_type, CellType::Safe(1..));
+                let hidden = gamestate.board[point].cell_state != CellState::Revealed;
+                match self.mouse_bindings.action(button) {
+                    Some(MouseAction::Flag) => {
+                        // only a press that flags starts a drag, taking a flag off stays a single click
+                        if self.drag_flag && !self.no_flags && gamestate.board[point].cell_state == CellState::Unknown {
+                            self.flag_drag = Some(button);
+                        }
+                        return self.right_click(point)
+                    }
+                    // flagged cells too, a tap takes their flag off
+                    Some(MouseAction::Reveal) if self.flag_mode => cell.pressed = hidden,
+                    Some(MouseAction::Reveal) => {
+                        if gamestate.board[point].cell_state == CellState::Unknown || number {
+                            cell.pressed = true;
+                        }
+                        if self.long_press_flag && hidden {
+                            return self.begin_long_press(point)
+                        }
+                    }
+                    Some(MouseAction::Chord) if number => cell.pressed = true,
+                    _ => {}
                 }
             }
             cell::Message::SelfRelease(button) => {
-                if cell.pressed && matches!(button, mouse::Button::Left) {
+                self.long_press = None;
+                if self.flag_drag == Some(button) {
+                    self.flag_drag = None;
+                }
+                let action = self.mouse_bindings.action(button);
+                if cell.pressed && self.flag_mode && action == Some(MouseAction::Reveal) {
+                    cell.pressed = false;
+                    return self.right_click(point)
+                }
+                if cell.pressed && matches!(action, Some(MouseAction::Reveal | MouseAction::Chord)) {
+                    if !self.chord_delay.is_zero()
+                            && let Some((flags, reveals)) = self.chord_preview(point) {
+                        return self.begin_chord(point, flags, reveals)
+                    }
+                    if self.mistake_guard && self.status == GameStatus::Playing
+                            && certainly_mine(&self.game.blocking_gamestate(), point) {
+                        self.cells[point].pressed = false;
+                        self.guarding = Some(point);
+                        return Task::done(dialog::Message::Open(DialogId::MistakeGuard).into())
+                    }
                     return self.left_click(point)
                 }
                 cell.pressed = false;
@@ -310,24 +2174,36 @@ impl MinsweeperGame {
             cell::Message::ForceArmed(value) => {
                 cell.force = value;
             }
-            cell::Message::Revealing(value) => {
-                cell.revealing.store(value, Ordering::Relaxed);
+            cell::Message::Revealing(true) => {
+                cell.revealing.fetch_add(1, Ordering::Relaxed);
+            }
+            cell::Message::Revealing(false) => {
+                let _ = cell.revealing.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |count| count.checked_sub(1));
             }
             cell::Message::Enter => {
                 cell.hovering = true;
-                if self.hover_chord && matches!(self.game.blocking_gamestate().board[point].cell_type, CellType::Safe(n) if n > 0) {
-                    return self.left_click(point);
+                // sweeping back over flags leaves them be
+                if self.flag_drag.is_some() {
+                    return self.drag_flag_over(point)
                 }
+                return self.hover_chord(point)
             }
             cell::Message::Exit => {
-                cell.hovering = false
+                cell.hovering = false;
+                if self.long_press.is_some_and(|(_, pressing)| pressing == point) {
+                    self.long_press = None;
+                }
+                if self.chord.as_ref().is_some_and(|chord| chord.point == point) {
+                    self.end_chord();
+                    return Task::none()
+                }
             }
         }
 
 
-        if matches!(message, cell::Message::Press(_) | cell::Message::Release(_) | cell::Message::SelfPress(_) | cell::Message::SelfRelease(_) | cell::Message::Enter | cell::Message::Exit) {
+        if matches!(message, cell::Message::Release(_) | cell::Message::SelfPress(_) | cell::Message::SelfRelease(_) | cell::Message::Enter | cell::Message::Exit) {
             let down = cell.is_down();
-            if (cell.pressed || matches!(message, cell::Message::Press(_) | cell::Message::Release(_) | cell::Message::SelfPress(_) | cell::Message::SelfRelease(_)))
+            if (cell.pressed || matches!(message, cell::Message::Release(_) | cell::Message::SelfPress(_) | cell::Message::SelfRelease(_)))
                     && matches!(self.game.blocking_gamestate().board[point].cell_type, CellType::Safe(_)) {
                 for neighbour in self.size.clone().neighbours(point) {
                     let _ = self.update_cell(neighbour, cell::Message::ForceArmed(down));
@@ -337,6 +2213,62 @@ impl MinsweeperGame {
         Task::none()
     }
 
+    /// tints what counts towards the number at `point` while the highlight key is held, and
+    /// takes the tint off whatever was highlighted before
+    fn highlight(&mut self, point: Option<Point>) {
+        if let Some(previous) = self.highlighting.take() {
+            for around in std::iter::once(previous).chain(self.size.neighbours(previous)) {
+                self.cells[around].highlight = None;
+            }
+        }
+        let held = self.neighbour_highlight.is_some_and(|modifier| modifier.held(self.modifiers));
+        let Some(point) = point.filter(|point| held && !self.editing() && self.cells.get(*point).is_some()) else { return };
+        let gamestate = self.game.blocking_gamestate();
+        let cell = gamestate.board[point];
+        if cell.cell_state != CellState::Revealed || !matches!(cell.cell_type, CellType::Safe(1..)) {
+            return
+        }
+        self.cells[point].highlight = Some(HighlightKind::Source);
+        for around in self.size.neighbours(point) {
+            self.cells[around].highlight = match gamestate.board[around].cell_state {
+                CellState::Flagged => Some(HighlightKind::Flagged),
+                CellState::Unknown => Some(HighlightKind::Unknown),
+                CellState::Revealed => None,
+            };
+        }
+        self.highlighting = Some(point);
+    }
+
+    /// adds `explanation` to the end of the panel, the oldest goes once there are too many
+    fn explained(&mut self, explanation: Explanation) {
+        if self.explanations.len() >= explain::KEPT {
+            // every line moves up one, the lit up cells would be for the wrong one
+            self.hover_explanation(None);
+            self.explanations.pop_front();
+        }
+        self.explanations.push_back(explanation);
+    }
+
+    /// lights up the cells the explanation at `index` is about, and puts out the ones lit up before
+    fn hover_explanation(&mut self, index: Option<usize>) {
+        if let Some(previous) = self.explaining.take().and_then(|index| self.explanations.get(index)) {
+            for (point, _) in &previous.cells {
+                if let Some(cell) = self.cells.get_mut(*point) {
+                    cell.highlight = None;
+                }
+            }
+            // whatever the highlight key was showing comes back
+            self.highlight(self.highlighting);
+        }
+        let Some(explanation) = index.and_then(|index| self.explanations.get(index)) else { return };
+        for (point, kind) in &explanation.cells {
+            if let Some(cell) = self.cells.get_mut(*point) {
+                cell.highlight = Some(*kind);
+            }
+        }
+        self.explaining = index;
+    }
+
     // fn subscriptions() -> Subscription<Message> {
     //
     // }
@@ -348,46 +2280,480 @@ impl MinsweeperGame {
                 row![
                     self.border(Border::LeftRight)
                         .height(32),
-                    container(self.number_display(self.remaining_mines(), self.remaining_mine_digit()))
+                    container(match self.counter_mode {
+                        CounterMode::SafeCells if !self.editing() => self.safe_cell_counter().0.into(),
+                        CounterMode::Both if !self.editing() => self.framed(self.mine_counter()),
+                        _ => self.mine_counter().0.into(),
+                    })
                         .padding(Padding::default().horizontal(10)),
-                    Element::new(RestartButton::new(self.texture, self.game.blocking_gamestate().status, self.any_revealing(), Message::Restart)),
-                ].align_y(Vertical::Center)
+                    Element::new(RestartButton::new(self.texture, self.status(), self.any_revealing(),
+                        self.progress_ring.then_some(self.progress), Message::Restart)
+                        .width(70)
+                        .height(70)
+                        .padding(6)
+                        .clip(true)
+                        .style(button::text)),
+                ].push((self.counter_mode == CounterMode::Both && !self.editing())
+                    .then(|| container(self.framed(self.safe_cell_counter()))
+                        .padding(Padding::default().horizontal(10))))
+                .extend(match self.mode {
+                    Mode::Play => self.play_controls(),
+                    Mode::Edit(_) => self.editor_controls(),
+                    Mode::Watch => vec![container(text(t!("watching")))
+                        .padding(Padding::default().horizontal(10))
+                        .into()],
+                })
+                .push(self.hydrating.map(|progress| container(text(t!("restoring", percent = format!("{:.0}", progress * 100.0))))
+                    .padding(Padding::default().horizontal(10))))
+                .push((self.throttled && self.auto.is_some()).then(|| container(text(t!("auto-throttled")))
+                    .padding(Padding::default().horizontal(10))))
+                .align_y(Vertical::Center)
             ).width(Length::Fill).align_x(Horizontal::Center),
+            self.progress_shown().then(|| progress_bar(0.0..=1.0, self.progress)
+                .girth(PROGRESS_HEIGHT)
+                .style(match self.status {
+                    GameStatus::Won => progress_bar::success,
+                    GameStatus::Lost => progress_bar::danger,
+                    _ => progress_bar::primary,
+                })),
+            self.describe_cells.then(|| container(text(self.description()))
+                .height(DESCRIPTION_HEIGHT)
+                .width(Length::Fill)
+                .align_x(Horizontal::Center)),
             responsive(|size|
                 row![
                     self.border(Border::LeftRight)
                             .height(size.height),
-                    responsive(|size|
-
-                        container(Grid::from_iter(self.points()
-                            .map(|point| (point, &self.cells[point]))
-                            .map(|(point, e)| e.view()
-                                .map(move |message| Message::Cell((point, message)))))
-                            .columns(self.size.width().get())
-                            .width(self.cell_size(size) * self.size.width().get() as f32))
-                        .width(Length::Fill)
-                        .height(Length::Fill)
-                        .align_x(Horizontal::Center)
-                        .align_y(Vertical::Center)
-                        .into()),
+                    responsive(|size| {
+                        let provenance = self.provenance.clone().unwrap_or_default();
+                        // nothing of the board shows while paused so it can't be studied
+                        let board: Element<_> = if self.idled {
+                            mouse_area(container(text(t!("idle-paused")))
+                                    .center(Length::Fill))
+                                    .on_press(Message::Awake)
+                                    .on_move(|_| Message::Awake)
+                                    .into()
+                        } else if self.paused() {
+                            text(t!("paused")).into()
+                        } else if self.on_canvas() {
+                            let cell_size = self.cell_scale.fixed_size()
+                                    .unwrap_or_else(|| self.cell_size(size));
+                            canvas(board::Board::new(&self.cells, self.displayed_gamestate(), self.texture,
+                                    provenance, cell_size, self.hydrating.is_some(), self.risk_shading)
+                                    .transposed(self.transposed())
+                                    .minimap(self.snapshot_version))
+                                    .width(Length::Fill)
+                                    .height(Length::Fill)
+                                    .into()
+                        } else {
+                            let gamestate = self.displayed_gamestate();
+                            let (columns, _) = self.shown_dimensions();
+                            let grid = match self.hydrating {
+                                None => Grid::from_iter(self.cells.laid_out(self.transposed())
+                                    .map(|(point, e)| e.view(gamestate.board[point], provenance.get(&point).copied(), self.risk_shading)
+                                        .map(move |message| Message::Cell((point, message))))),
+                                Some(_) => Grid::from_iter(self.cells.laid_out(self.transposed())
+                                    .map(|(point, e)| e.skeleton()
+                                        .map(move |message| Message::Cell((point, message))))),
+                            }.columns(columns);
+
+                            match self.fixed_cell_size() {
+                                None => grid.width(self.cell_size(size) * columns as f32)
+                                        .into(),
+                                Some(cell_size) => {
+                                    let board = scrollable(grid.width(cell_size * columns as f32))
+                                            .id(self.scroll_id.clone())
+                                            .on_scroll(|viewport| Message::Scrolled(viewport.absolute_offset()))
+                                            .direction(scrollable::Direction::Both {
+                                                vertical: Default::default(),
+                                                horizontal: Default::default(),
+                                            });
+                                    match self.minimap(size, cell_size) {
+                                        Some(minimap) => widget::stack![board, container(minimap)
+                                                .padding(10)
+                                                .width(Length::Fill)
+                                                .height(Length::Fill)
+                                                .align_x(Horizontal::Right)
+                                                .align_y(Vertical::Bottom)]
+                                                .into(),
+                                        None => board.into(),
+                                    }
+                                }
+                            }
+                        };
+
+                        let now = self.clock.now();
+                        let offset = self.animation.as_ref().map_or(0.0, |animation| animation.offset(now));
+                        let board = container(board)
+                            .padding(Padding::default().left(offset.max(0.0)).right((-offset).max(0.0)))
+                            .width(Length::Fill)
+                            .height(Length::Fill)
+                            .align_x(Horizontal::Center)
+                            .align_y(Vertical::Center);
+                        let mut layers = widget::stack![board];
+                        if let Some(confetti) = self.animation.as_ref().and_then(|animation| animation.overlay(now)) {
+                            layers = layers.push(canvas(confetti)
+                                    .width(Length::Fill)
+                                    .height(Length::Fill));
+                        }
+                        if let Some(overlay) = self.overlay {
+                            layers = layers.push(self.overlay(overlay));
+                        }
+                        if let Some(session) = &self.auto_restart {
+                            layers = layers.push(container(container(text(t!("auto-restart-tally",
+                                    won = session.won, attempted = session.attempted)).size(14))
+                                    .padding(6)
+                                    .style(container::rounded_box))
+                                    .padding(10)
+                                    .width(Length::Fill)
+                                    .height(Length::Fill)
+                                    .align_x(Horizontal::Right)
+                                    .align_y(Vertical::Top));
+                        }
+                        layers.into()
+                    }),
                     self.border(Border::LeftRight)
                             .height(size.height),
-                ].into()
+                ].push(self.explanations_panel())
+                .into()
             ),
-
+            self.status_line.then(|| container(text(self.status_text()))
+                .height(STATUS_LINE_HEIGHT)
+                .width(Length::Fill)
+                .align_x(Horizontal::Center)
+                .align_y(Vertical::Center)),
         ]).style(|_theme| container::Style {
-            background: Some(Background::Color(self.texture.get_background_colour())),
+            background: Some(Background::Color(self.texture.manifest().background)),
             ..Default::default()
         }).into()
     }
 
+    /// the board read only, for a window of its own that only watches. drawn from the snapshot so
+    /// it never takes the game's locks itself
+    pub fn spectator_view(&self) -> Element<'_, Message> {
+        // all from the last repaint, the game itself isn't locked to draw a second window
+        let remaining = self.snapshot.as_ref()
+                .map_or(self.size.mines().get() as isize, |snapshot| self.mines_left(snapshot));
+        let length = self.remaining_mine_digit();
+        let header = row![
+            self.framed((self.number_display(remaining, length, false), length)),
+            svg(self.texture.get_restart_button(self.status, false, false))
+                    .width(58)
+                    .height(58),
+            self.framed(self.safe_cell_counter()),
+        ].spacing(10).align_y(Vertical::Center);
+        let status = [self.title(), format_duration(self.rule_context().elapsed)].join(t!("description-separator"));
+
+        let board: Element<_> = match &self.snapshot {
+            // nothing of the board shows while paused here either
+            _ if self.paused() => text(t!("paused")).into(),
+            Some(snapshot) => responsive(move |size| {
+                let (width, height) = self.shown_dimensions();
+                let cell_size = f32::min(size.width / width as f32, size.height / height as f32);
+                canvas(board::Board::new(&self.cells, snapshot.clone(), self.texture, HashMap::new(), cell_size, false, self.risk_shading)
+                        .transposed(self.transposed())
+                        .read_only(true))
+                        .width(Length::Fill)
+                        .height(Length::Fill)
+                        .into()
+            }).into(),
+            None => text(t!("spectator-waiting")).into(),
+        };
+
+        container(widget::column![
+            container(header).width(Length::Fill).align_x(Horizontal::Center),
+            container(board).width(Length::Fill).height(Length::Fill).center(Length::Fill),
+            container(text(status)).width(Length::Fill).align_x(Horizontal::Center),
+        ].spacing(10).padding(10))
+                .style(|_theme| container::Style {
+                    background: Some(Background::Color(self.texture.manifest().background)),
+                    ..Default::default()
+                })
+                .into()
+    }
+
+    /// big time and click counts in the corner for recordings, only ever drawn on top of the board
+    /// so the image export never has it
+    fn overlay(&self, overlay: Overlay) -> Element<'_, Message> {
+        let (rate, total) = self.click_rate();
+        let opacity = overlay.opacity;
+        container(container(widget::column![
+            text(format_duration(self.rule_context().elapsed))
+                    .size(36)
+                    .font(iced::Font::MONOSPACE),
+            text(t!("overlay-clicks", rate = format!("{:.1}", rate), total = total))
+                    .size(16),
+        ].push(overlay.seed.then(|| text(t!("overlay-seed", seed = self.seed())).size(12))))
+                .padding(8)
+                .style(move |theme| {
+                    let style = container::rounded_box(theme);
+                    container::Style {
+                        background: style.background.map(|background| background.scale_alpha(opacity)),
+                        text_color: Some(theme.palette().text.scale_alpha(opacity)),
+                        ..style
+                    }
+                }))
+                .padding(10)
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .align_x(Horizontal::Left)
+                .align_y(Vertical::Top)
+                .into()
+    }
+
+    /// why the solver made its last few moves, newest first, hovering a line lights up its cells
+    fn explanations_panel(&self) -> Option<Element<'_, Message>> {
+        if !self.solver_explanations || !matches!(self.mode, Mode::Play) {
+            return None
+        }
+        if self.explanations_collapsed {
+            return Some(container(button(text(t!("explanations-expand")))
+                    .on_press(Message::CollapseExplanations)
+                    .style(button::secondary))
+                    .padding(10)
+                    .into())
+        }
+
+        let lines: Element<_> = if self.explanations.is_empty() {
+            text(t!("explanations-empty")).size(14).into()
+        } else {
+            widget::Column::with_children(self.explanations.iter().enumerate().rev()
+                    .map(|(index, explanation)| {
+                        let hovered = self.explaining == Some(index);
+                        mouse_area(container(text(explanation.text.as_str()).size(14))
+                                .padding(4)
+                                .width(Length::Fill)
+                                .style(move |theme| if hovered { container::rounded_box(theme) } else { container::Style::default() }))
+                                .on_enter(Message::HoverExplanation(Some(index)))
+                                .on_exit(Message::HoverExplanation(None))
+                                .into()
+                    }))
+                    .spacing(2)
+                    .into()
+        };
+
+        Some(container(widget::column![
+            row![
+                text(t!("explanations-title")).width(Length::Fill),
+                button(text(t!("explanations-collapse")).size(14))
+                        .on_press(Message::CollapseExplanations)
+                        .style(button::text),
+            ].align_y(Vertical::Center),
+            scrollable(lines),
+        ].spacing(8))
+                .width(EXPLANATIONS_WIDTH)
+                .height(Length::Fill)
+                .padding(10)
+                .style(container::rounded_box)
+                .into())
+    }
+
+    /// the tap mode, step and replay buttons and whatever the rules add
+    fn play_controls(&self) -> Vec<Element<'_, Message>> {
+        let mut controls: Vec<Element<_>> = vec![
+            container(tooltip(
+                button(svg(self.texture.get_tap_mode(self.flag_mode))
+                        .width(32)
+                        .height(32))
+                    .on_press(Message::ToggleFlagMode)
+                    .padding(2)
+                    .style(button::text),
+                container(text(if self.flag_mode { t!("taps-flag") } else { t!("taps-reveal") }))
+                    .padding(10)
+                    .style(container::rounded_box),
+                tooltip::Position::Bottom))
+                .padding(Padding::default().horizontal(10))
+                .into(),
+            container(tooltip(
+                button(if self.step.is_some() { t!("play-step") } else { t!("step") })
+                        .on_press_maybe((self.solver.is_some() && !self.editing()).then_some(Message::Step)),
+                container(text(if self.step_stuck { t!("step-stuck") } else { t!("step-explained") }))
+                    .padding(10)
+                    .style(container::rounded_box),
+                tooltip::Position::Bottom))
+                .into(),
+            container(tooltip(
+                button(t!("replay")).on_press(Message::RestartSameBoard),
+                container(text(t!("replay-explained")))
+                    .padding(10)
+                    .style(container::rounded_box),
+                tooltip::Position::Bottom))
+                .padding(Padding::default().horizontal(10))
+                .into(),
+        ];
+        controls.extend(self.rules.header_extras(&self.rule_context())
+            .into_iter()
+            .chain((self.undos > 0).then(|| t!("undos-so-far", count = self.undos)))
+            .chain(self.no_flags.then(|| match self.flag_refused {
+                Some(_) => t!("no-flags-refused").to_string(),
+                None => t!("no-flags-badge").to_string(),
+            }))
+            .map(|extra| container(text(extra))
+                .padding(Padding::default().horizontal(10))
+                .into()));
+        controls
+    }
+
+    /// what the header has instead of the play buttons while a board is being edited
+    fn editor_controls(&self) -> Vec<Element<'_, Message>> {
+        let playable = self.edited().is_some();
+        vec![
+            container(tooltip(
+                button(t!("editor-play")).on_press_maybe(playable.then_some(Message::PlayEdited)),
+                container(text(if playable { t!("editor-play-explained") } else { t!("editor-unplayable") }))
+                    .padding(10)
+                    .style(container::rounded_box),
+                tooltip::Position::Bottom))
+                .padding(Padding::default().horizontal(10))
+                .into(),
+            button(t!("editor-clear")).on_press(Message::ClearMines).into(),
+            container(button(t!("editor-save")).on_press_maybe(playable.then_some(Message::SaveEdited)))
+                .padding(Padding::default().horizontal(10))
+                .into(),
+            button(t!("load-board")).on_press(Message::BrowseBoards).into(),
+        ]
+    }
+
+    /// the game over summary and the undo offer, opened through the [`dialog::DialogStack`]
+    pub fn dialog<'a>(&self, id: DialogId) -> Option<Element<'a, Message>> {
+        if id == DialogId::MistakeGuard {
+            return self.guarding.map(|_| widget::column![
+                text(t!("mistake-guard-title")),
+                row![
+                    button(t!("mistake-guard-keep")).on_press(Message::KeepCovered),
+                    button(t!("mistake-guard-reveal")).on_press(Message::RevealAnyway),
+                ].spacing(10),
+            ].spacing(10).into())
+        }
+        if id == DialogId::Undo {
+            return self.offering_undo.then(|| widget::column![
+                text(t!("undo-title")),
+                (self.undos > 0).then(|| text(t!("undos-so-far", count = self.undos))),
+                row![
+                    button(t!("accept-defeat")).on_press(Message::AcceptDefeat),
+                    button(t!("undo")).on_press(Message::Undo),
+                ].spacing(10),
+            ].spacing(10).into())
+        }
+        let game_over = self.game_over.as_ref().filter(|_| id == DialogId::GameOver)?;
+        Some(
+            widget::column![
+                text(match game_over.status {
+                    GameStatus::Won => t!("you-win"),
+                    _ => t!("game-over"),
+                }),
+                text(t!("game-over-time", time = format_duration(game_over.elapsed))),
+                text(t!("game-over-clicks", clicks = game_over.player_clicks)),
+                game_over.bbbv.map(|bbbv| text(t!("game-over-bbbv", bbbv = bbbv))),
+                game_over.bbbv.filter(|_| game_over.status == GameStatus::Won)
+                        .map(|bbbv| text(t!("game-over-bbbv-rate", rate = format!("{:.2}", bbbv::rate(bbbv, game_over.elapsed))))),
+                // the solver's clicks would make anyone look efficient
+                game_over.bbbv.filter(|_| game_over.status == GameStatus::Won && !game_over.auto)
+                        .map(|bbbv| text(t!("game-over-efficiency", percent = format!("{:.0}", bbbv::efficiency(bbbv, game_over.player_clicks))))),
+                game_over.auto.then(|| {
+                    let total = (game_over.revealed_by_player + game_over.revealed_by_solver) as f32;
+                    text(t!("game-over-shares",
+                        player = format!("{:.0}", game_over.revealed_by_player as f32 / total * 100.0),
+                        solver = format!("{:.0}", game_over.revealed_by_solver as f32 / total * 100.0)))
+                }),
+                (game_over.undos > 0).then(|| text(t!("game-over-undos", count = game_over.undos))),
+                (game_over.guarded > 0).then(|| text(t!("game-over-guarded", count = game_over.guarded))),
+                text(t!("game-over-seed", seed = format!("{:#x}", game_over.seed))),
+                row![
+                    button(t!("new-game")).on_press(Message::Restart),
+                    button(t!("replay-board")).on_press(Message::RestartSameBoard),
+                    button(t!("close")).on_press(Message::CloseGameOver),
+                ].spacing(10),
+                row![
+                    button(t!("copy-board")).on_press(Message::CopyBoard),
+                    button(t!("export-board-image")).on_press(Message::ExportBoardImage),
+                ].spacing(10),
+            ].spacing(10).into()
+        )
+    }
+
+    /// how much room the game needs to show the whole board at its cell size, or at
+    /// [`FIT_CELL_SIZE`] when cells fit the window
+    pub fn fitting_size(&self) -> Size {
+        let cell_size = self.fixed_cell_size().unwrap_or(FIT_CELL_SIZE);
+        // the borders are drawn at a fifth of their svg size, see `border`
+        let border = 120.0 / 5.0;
+        let description = if self.describe_cells { DESCRIPTION_HEIGHT } else { 0.0 };
+        let progress = if self.progress_shown() { PROGRESS_HEIGHT } else { 0.0 };
+        let status_line = if self.status_line { STATUS_LINE_HEIGHT } else { 0.0 };
+        let (width, height) = self.shown_dimensions();
+        Size::new(
+            f32::max(cell_size * width as f32 + border * 2.0, HEADER_WIDTH),
+            HEADER_HEIGHT + progress + description + cell_size * height as f32 + status_line,
+        )
+    }
+
     fn cell_size(&self, size: Size) -> f32 {
-        f32::min(size.width / self.size.width().get() as f32, size.height / self.size.height().get() as f32)
+        let (width, height) = self.shown_dimensions();
+        f32::min(size.width / width as f32, size.height / height as f32)
+    }
+
+    /// the whole board small with the part in view outlined, only once it doesn't all fit in
+    /// `available`
+    fn minimap(&self, available: Size, cell_size: f32) -> Option<Element<'_, Message>> {
+        let (width, height) = self.shown_dimensions();
+        let board = Size::new(width as f32 * cell_size, height as f32 * cell_size);
+        if board.width <= available.width && board.height <= available.height {
+            return None
+        }
+        let viewport = iced::Rectangle::new(
+            iced::Point::new(self.scroll.x / cell_size, self.scroll.y / cell_size),
+            Size::new(available.width.min(board.width) / cell_size, available.height.min(board.height) / cell_size));
+        let size = minimap::Minimap::size(width, height);
+        Some(canvas(minimap::Minimap::new(self.snapshot.as_ref()?, self.snapshot_version, viewport,
+                self.texture.manifest().accent, self.transposed()))
+                .width(size.width)
+                .height(size.height)
+                .into())
+    }
+
+    /// ctrl+scroll scales the cells about the one under the cursor, so it stays put on screen
+    fn zoom_by(&mut self, delta: mouse::ScrollDelta) -> Task<Message> {
+        let Some(fixed) = self.cell_scale.fixed_size() else { return Task::none() };
+        if !self.modifiers.command() || !self.zoomable() || self.paused() {
+            return Task::none()
+        }
+        let lines = match delta {
+            mouse::ScrollDelta::Lines { y, .. } => y,
+            mouse::ScrollDelta::Pixels { y, .. } => y / ZOOM_LINE,
+        };
+        let old = fixed * self.zoom;
+        let new = (old * ZOOM_STEP.powf(lines)).clamp(MIN_ZOOMED_CELL, MAX_ZOOMED_CELL.max(fixed));
+        if new == old {
+            return Task::none()
+        }
+        self.zoom = new / fixed;
+
+        let anchor = self.hovered
+                .map(|point| self.shown(point))
+                .map_or((0.0, 0.0), |(x, y)| (x as f32 + 0.5, y as f32 + 0.5));
+        let on_screen = (anchor.0 * old - self.scroll.x, anchor.1 * old - self.scroll.y);
+        self.scroll_to(AbsoluteOffset {
+            x: (anchor.0 * new - on_screen.0).max(0.0),
+            y: (anchor.1 * new - on_screen.1).max(0.0),
+        })
+    }
+
+    fn scroll_to(&mut self, offset: AbsoluteOffset) -> Task<Message> {
+        // the scrollable reports back where it ended up, this is only until it does
+        self.scroll = offset;
+        widget::operation::scroll_to(self.scroll_id.clone(), offset)
     }
 
 
     fn remaining_mines(&self) -> isize {
-        let gamestate = self.game.blocking_gamestate();
+        if let Mode::Edit(mines) = &self.mode {
+            return mines.len() as isize
+        }
+        self.mines_left(&self.game.blocking_gamestate())
+    }
+
+    fn mines_left(&self, gamestate: &GameState) -> isize {
         match gamestate.status {
             GameStatus::Playing | GameStatus::Lost => gamestate.remaining_mines,
             GameStatus::Won => 0,
@@ -397,20 +2763,95 @@ impl MinsweeperGame {
 
     fn remaining_mine_digit(&self) -> usize {
         let size = self.size;
+        if self.editing() {
+            return (size.width().get() * size.height().get()).to_string().len()
+        }
         usize::max(size.mines().to_string().len(),
             (size.mines().get() as isize - size.width().get() as isize * size.height().get() as isize).to_string().len())
     }
 
-    fn number_display(&'_ self, number: isize, length: usize) -> Row<'_, Message> {
-        const NUMBER_SIZE_MULTIPLIER: u32 = 2;
-        row(formatx!(format!("{{:0{}}}", length).as_str(), number)
-                .expect("number display should never fail")
-                .chars()
-                .map(|c|
-                        svg(svg::Handle::from_memory(self.texture.get_digit(c)))
-                                .width(13 * NUMBER_SIZE_MULTIPLIER)
-                                .height(23 * NUMBER_SIZE_MULTIPLIER)
-                                .into()))
+    fn mine_counter(&self) -> (Row<'_, Message>, usize) {
+        let length = self.remaining_mine_digit();
+        (self.number_display(self.remaining_mines(), length, self.counter_dimmed()), length)
+    }
+
+    fn safe_cell_counter(&self) -> (Row<'_, Message>, usize) {
+        let length = self.safe_cells().to_string().len();
+        (self.number_display(self.safe_left as isize, length, false), length)
+    }
+
+    /// a counter `length` digits long in the texture's counter border, the way the classic layout
+    /// has one either side of the face
+    fn framed<'a>(&'a self, (counter, length): (Row<'a, Message>, usize)) -> Element<'a, Message> {
+        let width = 13 * NUMBER_SIZE_MULTIPLIER * length as u32;
+        let height = 23 * NUMBER_SIZE_MULTIPLIER;
+        let edge = 2;
+        row![
+            self.border(Border::CounterLeft).width(edge).height(height + 2 * edge),
+            widget::column![
+                self.border(Border::CounterTop).width(width).height(edge),
+                counter,
+                self.border(Border::CounterBottom).width(width).height(edge),
+            ],
+            self.border(Border::CounterRight).width(edge).height(height + 2 * edge),
+        ].into()
+    }
+
+    fn number_display(&'_ self, number: isize, length: usize, dimmed: bool) -> Row<'_, Message> {
+        digit_display(self.texture, number, length, if dimmed { 0.35 } else { 1.0 })
+    }
+
+    /// every other blink while there are more flags than mines
+    fn counter_dimmed(&self) -> bool {
+        self.over_flagged() && self.started.is_some_and(|started| {
+            let blinks = self.clock.now().saturating_duration_since(started).as_millis() / OVER_FLAG_BLINK.as_millis();
+            blinks % 2 == 1
+        })
+    }
+
+    /// catches the counter up with the board, tinting the player's last flag for a moment if it
+    /// took the counter further below zero
+    fn flag_excess(&mut self) -> Task<Message> {
+        let previous = self.remaining_mines;
+        self.remaining_mines = self.remaining_mines();
+        if self.remaining_mines == previous {
+            return Task::none()
+        }
+        let Some(point) = self.last_flagged.take() else { return Task::none() };
+        if self.editing() || self.remaining_mines >= 0 || self.remaining_mines > previous
+                || self.game.blocking_gamestate().board[point].cell_state != CellState::Flagged {
+            return Task::none()
+        }
+        self.clear_excess_flag();
+        self.excess_flag_id += 1;
+        let id = self.excess_flag_id;
+        self.excess_flag = Some((point, id));
+        self.cells[point].excess = true;
+        Task::future(self.clock.sleep(EXCESS_FLAG))
+                .map(move |_| Message::ExcessFlagOver(id))
+    }
+
+    fn clear_excess_flag(&mut self) {
+        if let Some((point, _)) = self.excess_flag.take()
+                && let Some(cell) = self.cells.get_mut(point) {
+            cell.excess = false;
+        }
+    }
+
+    /// there's nothing to clear on a board in the editor
+    fn progress_shown(&self) -> bool {
+        self.show_progress && !self.editing()
+    }
+
+    fn safe_cells(&self) -> usize {
+        self.size.width().get() * self.size.height().get() - self.size.mines().get()
+    }
+
+    /// flagged cells count as covered whether or not there's a mine under them
+    fn revealed_safe(&self) -> usize {
+        self.game.blocking_gamestate().board.iter()
+                .filter(|cell| cell.cell_state == CellState::Revealed && matches!(cell.cell_type, CellType::Safe(_)))
+                .count()
     }
 
     fn any_revealing(&self) -> bool {
@@ -420,7 +2861,7 @@ impl MinsweeperGame {
     }
 
     fn border(&'_ self, border: Border) -> Svg<'_> {
-        let svg = svg(svg::Handle::from_memory(self.texture.get_border(border)));
+        let svg = svg(self.texture.get_border(border));
 
         let (width, height) = match border {
             Border::TopLeft | Border::TopBottom | Border::TopRight | Border::BottomLeft | Border::BottomRight
@@ -435,8 +2876,18 @@ impl MinsweeperGame {
     }
 }
 
-async fn left_click(game: &MinsweeperType, point: Point, flag_chord: bool) {
+/// what lands is shown by the next repaint, so it's only sent when there isn't one on its way
+fn repaint(tasks: &Tasks, landed: Message) -> Task<Message> {
+    match landed {
+        Message::Repaint if !tasks.claim_repaint() => Task::none(),
+        landed => Task::done(landed),
+    }
+}
+
+/// the board once the click and any flags it put down went in, `None` if none of it changed anything
+async fn left_click(game: &MinsweeperType, point: Point, flag_chord: bool, by: Provenance) -> Option<GameState> {
     let gamestate = game.gamestate().await;
+    let mut flagged = None;
     if flag_chord
             && let CellType::Safe(n) = gamestate.board[point].cell_type
             && n as usize == gamestate.board.size()
@@ -445,21 +2896,158 @@ async fn left_click(game: &MinsweeperType, point: Point, flag_chord: bool) {
                     .count() {
         for point in gamestate.board.size()
                 .neighbours(point).filter(|point| matches!(gamestate.board[*point].cell_state, CellState::Unknown)) {
-            right_click(game, point).await;
+            flagged = right_click(game, point, by).await.or(flagged);
+        }
+    }
+
+    match game.left_click(point, by).await {
+        Ok(gamestate) if gamestate.status == GameStatus::Playing => {
+            sound::play(Sound::Reveal);
+            Some(gamestate)
+        }
+        Ok(gamestate) => {
+            tracing::debug!(status = ?gamestate.status, "the click ended the game");
+            Some(gamestate)
+        }
+        Err(_) => {
+            tracing::trace!("the click changed nothing");
+            flagged
         }
     }
+}
+
+/// the board once the flag went in or came off, `None` if it didn't
+async fn right_click(game: &MinsweeperType, point: Point, by: Provenance) -> Option<GameState> {
+    match game.right_click(point, by).await {
+        Ok(gamestate) => {
+            sound::play(Sound::Flag);
+            Some(gamestate)
+        }
+        Err(_) => {
+            tracing::trace!("the flag changed nothing");
+            None
+        }
+    }
+}
+
+fn make_scheduler(size: BoardSize, clock: SharedClock) -> AnalysisScheduler {
+    let mut scheduler = AnalysisScheduler::new(clock);
+    scheduler.register(Kind::Provenance, Policy { debounce: Duration::ZERO, reissue_on_board_change: true },
+            |game| Box::pin(async move {
+                tokio::task::spawn_blocking(move || game.blocking_provenance())
+                        .await
+                        .ok()
+                        .map(Analysis::Provenance)
+            }));
+    scheduler.register(Kind::LossReveal, Policy { debounce: Duration::ZERO, reissue_on_board_change: false },
+            move |game| Box::pin(async move {
+                tokio::task::spawn_blocking(move || loss_overlay(size, &game))
+                        .await
+                        .ok()
+                        .flatten()
+                        .map(Analysis::LossReveal)
+            }));
+    scheduler
+}
+
+/// every mine and every wrong flag, as they should look once the game is lost. only the mine the
+/// losing click set off is drawn blown up, even when a chord revealed more than one
+fn loss_overlay(size: BoardSize, game: &MinsweeperType) -> Option<HashMap<Point, minsweeper_rs::Cell>> {
+    let gamestate = game.blocking_gamestate();
+    let layout = game.blocking_layout()?;
+    let exploded = |point: &Point| layout.board[*point].cell_type == CellType::Mine
+            && gamestate.board[*point].cell_state == CellState::Revealed;
+    let blast = game.blocking_last_reveal()
+            .and_then(|(point, _)| std::iter::once(point)
+                    .chain(size.neighbours(point))
+                    .find(exploded));
+
+    Some(size.points()
+            .filter_map(|point| {
+                let cell_type = layout.board[point].cell_type;
+                let cell_state = match (cell_type, gamestate.board[point].cell_state) {
+                    (CellType::Mine, CellState::Revealed) if blast == Some(point) => CellState::Revealed,
+                    (CellType::Mine, CellState::Revealed) => CellState::Unknown,
+                    (CellType::Mine, CellState::Unknown) => CellState::Unknown,
+                    (CellType::Safe(_), CellState::Flagged) => CellState::Flagged,
+                    _ => return None
+                };
+                Some((point, minsweeper_rs::Cell::new(cell_type, cell_state)))
+            })
+            .collect())
+}
+
+/// how much bigger the counter digits are drawn than their svgs' 13 by 23
+const NUMBER_SIZE_MULTIPLIER: u32 = 2;
+
+/// `number` in the texture's counter digits, padded with zeroes to `length`. a negative number
+/// starts with the texture's minus
+pub fn digit_display<'a, M: 'a>(texture: Texture, number: isize, length: usize, opacity: f32) -> Row<'a, M> {
+    row(formatx!(format!("{{:0{}}}", length).as_str(), number)
+            .expect("number display should never fail")
+            .chars()
+            .map(|c|
+                    svg(texture.get_digit(c))
+                            .width(13 * NUMBER_SIZE_MULTIPLIER)
+                            .height(23 * NUMBER_SIZE_MULTIPLIER)
+                            .opacity(opacity)
+                            .into()))
+}
+
+/// `actions` with the flags left out when they aren't allowed
+fn without_flags(actions: impl IntoIterator<Item = Action>, no_flags: bool) -> Vec<Action> {
+    actions.into_iter()
+            .filter(|action| !(no_flags && matches!(action.operation, Operation::Flag)))
+            .collect()
+}
+
+/// the solver's next move with what of it can be played and why, `None` if there's nothing to
+/// play, a move that was all flags would only be found again next time
+fn solved(solver: &SolverType, gamestate: &GameState, no_flags: bool) -> Option<(Vec<Action>, Explanation)> {
+    let found = solver.solve(gamestate)?;
+    let actions = without_flags(found.actions.iter().copied(), no_flags);
+    if actions.is_empty() {
+        return None
+    }
+    let explanation = explain::explain(&actions, found.reason.as_ref(), gamestate);
+    Some((actions, explanation))
+}
 
-    _ = game.left_click(point).await;
+/// whether some revealed number next to `point` has exactly as many covered cells around it as
+/// its number, so every one of them, `point` included, has to be a mine
+///
+/// only the numbers touching `point` are looked at so it costs nothing to ask on every click
+fn certainly_mine(gamestate: &GameState, point: Point) -> bool {
+    let size = gamestate.board.size();
+    if gamestate.board[point].cell_state != CellState::Unknown {
+        return false
+    }
+    size.neighbours(point)
+            .any(|neighbour| {
+                let cell = gamestate.board[neighbour];
+                let CellType::Safe(number @ 1..) = cell.cell_type else { return false };
+                cell.cell_state == CellState::Revealed && size.neighbours(neighbour)
+                        .filter(|around| gamestate.board[*around].cell_state != CellState::Revealed)
+                        .count() == number as usize
+            })
 }
 
-async fn right_click(game: &MinsweeperType, point: Point) {
-    _ = game.right_click(point).await;
+/// the editor's board, every safe cell showing its number and the mines left covered
+fn edited_gamestate(size: BoardSize, mines: &HashSet<Point>) -> GameState {
+    let mut gamestate = layout_from_mines(size, &mines.iter().copied().collect::<Vec<_>>());
+    for point in size.points() {
+        let cell = &mut gamestate.board[point];
+        if cell.cell_type != CellType::Mine {
+            cell.cell_state = CellState::Revealed;
+        }
+    }
+    gamestate
 }
 
 impl Drop for MinsweeperGame {
     fn drop(&mut self) {
-        for (_, handle) in self.handles.blocking_lock().iter() {
-            handle.abort();
-        }
+        // the journal stays, a game closed on purpose is told apart from a crash by the clean
+        // exit marker and not by it being gone
+        self.tasks.cancel_all();
     }
 }
\ No newline at end of file
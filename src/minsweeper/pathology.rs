@@ -0,0 +1,53 @@
+use std::fmt::{Display, Formatter};
+
+#[cfg(test)]
+mod tests;
+
+/// above this share of mines nearly every safe cell touches several mines, logic runs dry right
+/// after the opening and generating a board the solver can finish without guessing stops
+/// terminating in practice
+pub const MAX_DENSITY: f32 = 0.6;
+
+/// a board that's technically valid but not much of a game
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Pathology {
+    /// every cell but one is a mine so the first click is the whole game
+    SingleSafeCell,
+    /// more than [`MAX_DENSITY`] of the board is mines
+    Dense,
+    /// one cell wide, every number only sees two neighbours so any 1 between two unknowns is a
+    /// coin flip
+    Strip,
+}
+
+impl Pathology {
+    /// whether boards like this can realistically be generated for solvers that must finish the
+    /// game without guessing
+    pub fn allows_no_guess(self) -> bool {
+        !matches!(self, Pathology::Dense)
+    }
+}
+
+impl Display for Pathology {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Pathology::SingleSafeCell => "trivial board — only one safe cell",
+            Pathology::Dense => "extremely high density — most games will require guessing",
+            Pathology::Strip => "single row — most games will come down to guessing",
+        })
+    }
+}
+
+/// the most severe thing wrong with a board of this size, if anything
+pub fn assess(width: usize, height: usize, mines: usize) -> Option<Pathology> {
+    let cells = width * height;
+    if cells > 1 && mines + 1 == cells {
+        Some(Pathology::SingleSafeCell)
+    } else if mines as f32 > cells as f32 * MAX_DENSITY {
+        Some(Pathology::Dense)
+    } else if cells > 1 && width.min(height) == 1 {
+        Some(Pathology::Strip)
+    } else {
+        None
+    }
+}
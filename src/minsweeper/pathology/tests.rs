@@ -0,0 +1,30 @@
+use super::*;
+
+#[test]
+fn ordinary_boards_are_fine() {
+    assert_eq!(assess(9, 9, 10), None);
+    assert_eq!(assess(30, 16, 99), None);
+    assert_eq!(assess(1, 1, 0), None);
+}
+
+#[test]
+fn the_most_severe_pathology_wins() {
+    assert_eq!(assess(3, 3, 8), Some(Pathology::SingleSafeCell));
+    assert_eq!(assess(1, 10, 9), Some(Pathology::SingleSafeCell));
+    assert_eq!(assess(10, 1, 7), Some(Pathology::Dense));
+    assert_eq!(assess(10, 1, 3), Some(Pathology::Strip));
+    assert_eq!(assess(1, 10, 3), Some(Pathology::Strip));
+}
+
+#[test]
+fn density_is_only_too_high_past_the_limit() {
+    assert_eq!(assess(10, 10, 60), None);
+    assert_eq!(assess(10, 10, 61), Some(Pathology::Dense));
+}
+
+#[test]
+fn only_dense_boards_rule_out_no_guess() {
+    assert!(!Pathology::Dense.allows_no_guess());
+    assert!(Pathology::SingleSafeCell.allows_no_guess());
+    assert!(Pathology::Strip.allows_no_guess());
+}
@@ -1,52 +1,131 @@
 use crate::texture::Texture;
-use iced::widget::button::{Catalog, Status};
+use iced::advanced::graphics::geometry::Renderer as _;
+use iced::widget::button::{Catalog, Status, Style, StyleFn};
+use iced::widget::canvas::{path, Frame, Path, Stroke};
 use iced::widget::svg;
-use iced::Element;
+use iced::{Element, Radians};
 use iced_core::widget::{tree, Operation, Tree};
-use iced_core::{layout, mouse, overlay, renderer, touch, window, Background, Clipboard, Color, Event, Layout, Length, Padding, Rectangle, Renderer, Shell, Size, Vector, Widget};
+use iced_core::{layout, mouse, overlay, renderer, touch, window, Background, Clipboard, Color, Event, Layout, Length, Padding, Point, Rectangle, Renderer, Shell, Size, Vector, Widget};
 use minsweeper_rs::GameStatus;
+use std::f32::consts::{FRAC_PI_2, TAU};
 
-pub struct RestartButton<Message: Clone> {
+const RING_WIDTH: f32 = 3.0;
+
+pub struct RestartButton<'a, Message: Clone> {
     texture: Texture,
     game_status: GameStatus,
     revealing: bool,
+    progress: Option<f32>,
     on_press: Message,
     width: Length,
     height: Length,
+    padding: Padding,
     clip: bool,
+    class: <iced::Theme as Catalog>::Class<'a>,
     status: Option<Status>,
 }
 
-impl<Message: Clone> RestartButton<Message> {
+impl<'a, Message: Clone> RestartButton<'a, Message> {
 
-    pub fn new(texture: Texture, game_status: GameStatus, revealing: bool, on_press: Message) -> Self {
+    pub fn new(texture: Texture, game_status: GameStatus, revealing: bool, progress: Option<f32>, on_press: Message) -> Self {
         Self {
             texture,
             game_status,
             revealing,
+            progress,
             on_press,
             width: Length::Fixed(50.0),
             height: Length::Fixed(50.0),
+            padding: Padding::ZERO,
             clip: false,
+            class: <iced::Theme as Catalog>::default(),
             status: None,
         }
     }
 
+    pub fn width(mut self, width: impl Into<Length>) -> Self {
+        self.width = width.into();
+        self
+    }
+
+    pub fn height(mut self, height: impl Into<Length>) -> Self {
+        self.height = height.into();
+        self
+    }
+
+    /// room between the edge and the face, the progress ring is drawn in it
+    pub fn padding(mut self, padding: impl Into<Padding>) -> Self {
+        self.padding = padding.into();
+        self
+    }
+
+    pub fn clip(mut self, clip: bool) -> Self {
+        self.clip = clip;
+        self
+    }
+
+    pub fn style(mut self, style: impl Fn(&iced::Theme, Status) -> Style + 'a) -> Self {
+        self.class = Box::new(style) as StyleFn<'a, iced::Theme>;
+        self
+    }
+
+    /// the face fills whatever is left inside the padding
     fn content(&'_ self) -> Element<'_, Message> {
-        svg(svg::Handle::from_memory(
-            self.texture.get_restart_button(self.game_status, self.status.map(|e| e == Status::Pressed).unwrap_or_default(), self.revealing)))
-                .width(Length::Fixed(50.0))
-                .height(Length::Fixed(50.0))
+        svg(self.texture.get_restart_button(self.game_status, self.status.map(|e| e == Status::Pressed).unwrap_or_default(), self.revealing))
+                .width(Length::Fill)
+                .height(Length::Fill)
                 .into()
     }
 }
 
+impl<Message: Clone> RestartButton<'_, Message> {
+    fn draw_ring(&self, renderer: &mut iced::Renderer, bounds: Rectangle, progress: f32) {
+        let Some((start_angle, end_angle)) = ring_angles(progress) else { return };
+
+        let mut frame = Frame::new(renderer, bounds.size());
+        let center = Point::new(bounds.width / 2.0, bounds.height / 2.0);
+        let radius = f32::min(bounds.width, bounds.height) / 2.0 - RING_WIDTH / 2.0;
+
+        let path = if end_angle.0 - start_angle.0 >= TAU {
+            Path::circle(center, radius)
+        } else {
+            Path::new(|builder| builder.arc(path::Arc { center, radius, start_angle, end_angle }))
+        };
+        frame.stroke(&path, Stroke::default()
+                .with_color(ring_colour(self.texture, self.game_status))
+                .with_width(RING_WIDTH));
+
+        renderer.with_translation(Vector::new(bounds.x, bounds.y), |renderer| {
+            renderer.draw_geometry(frame.into_geometry());
+        });
+    }
+}
+
+fn ring_angles(progress: f32) -> Option<(Radians, Radians)> {
+    let progress = progress.clamp(0.0, 1.0);
+    if progress == 0.0 {
+        return None
+    }
+    let start = -FRAC_PI_2;
+    Some((Radians(start), Radians(start + progress * TAU)))
+}
+
+fn ring_colour(texture: Texture, game_status: GameStatus) -> Color {
+    match game_status {
+        GameStatus::Lost => Color {
+            a: 0.5,
+            ..Color::from_rgb(0.5, 0.5, 0.5)
+        },
+        _ => texture.manifest().accent,
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 struct State {
     is_pressed: bool,
 }
 
-impl<Message: Clone> Widget<Message, iced::Theme, iced::Renderer> for RestartButton<Message> {
+impl<Message: Clone> Widget<Message, iced::Theme, iced::Renderer> for RestartButton<'_, Message> {
     fn tag(&self) -> tree::Tag {
         tree::Tag::of::<State>()
     }
@@ -80,7 +159,7 @@ impl<Message: Clone> Widget<Message, iced::Theme, iced::Renderer> for RestartBut
             limits,
             self.width,
             self.height,
-            Padding::new(0.0),
+            self.padding,
             |limits| {
                 self.content().as_widget_mut().layout(
                     &mut tree.children[0],
@@ -203,8 +282,7 @@ impl<Message: Clone> Widget<Message, iced::Theme, iced::Renderer> for RestartBut
     ) {
         let bounds = layout.bounds();
         let content_layout = layout.children().next().unwrap();
-        let style =
-                theme.style(&iced::Theme::default(), self.status.unwrap_or(Status::Disabled));
+        let style = theme.style(&self.class, self.status.unwrap_or(Status::Disabled));
 
         if style.background.is_some()
                 || style.border.width > 0.0
@@ -240,6 +318,10 @@ impl<Message: Clone> Widget<Message, iced::Theme, iced::Renderer> for RestartBut
             cursor,
             &viewport,
         );
+
+        if let Some(progress) = self.progress {
+            self.draw_ring(renderer, bounds, progress);
+        }
     }
 
     fn mouse_interaction(
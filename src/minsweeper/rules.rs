@@ -0,0 +1,177 @@
+use crate::stats::format_duration;
+use minsweeper_rs::board::Point;
+use minsweeper_rs::{GameState, GameStatus};
+use std::fmt::{Debug, Display, Formatter};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[cfg(test)]
+mod tests;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum MineOutcome {
+    Continue,
+    Lose,
+}
+
+#[derive(Copy, Clone, Debug, Default)]
+pub struct RuleContext {
+    pub elapsed: Duration,
+}
+
+/// a gameplay variant that hooks into the few points where variants differ from classic play
+///
+/// every hook defaults to classic behaviour so rules only override what they change
+pub trait GameRules: Debug + Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// names of rules this one can't be stacked with
+    fn conflicts(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    fn map_status(&self, status: GameStatus, _context: &RuleContext) -> GameStatus {
+        status
+    }
+
+    /// `None` leaves the decision to later rules in the stack
+    fn on_mine_revealed(&self, _point: Point) -> Option<MineOutcome> {
+        None
+    }
+
+    fn check_alternate_win(&self, _gamestate: &GameState) -> bool {
+        false
+    }
+
+    fn records_eligible(&self) -> bool {
+        true
+    }
+
+    fn header_extras(&self, _context: &RuleContext) -> Option<String> {
+        None
+    }
+
+    /// whether the status depends on time and needs to be rechecked while playing
+    fn timed(&self) -> bool {
+        false
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RuleConflict {
+    pub rule: &'static str,
+    pub existing: &'static str,
+}
+
+impl Display for RuleConflict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} can't be combined with {}", self.rule, self.existing)
+    }
+}
+
+/// rules applied in push order, the empty stack plays classic minesweeper
+///
+/// statuses are mapped through every rule in order, the first rule with an opinion on a revealed
+/// mine decides it, any rule can declare an alternate win, and records only count if every rule
+/// allows it
+#[derive(Clone, Debug, Default)]
+pub struct RuleStack {
+    rules: Vec<Arc<dyn GameRules>>,
+}
+
+impl RuleStack {
+
+    pub fn push(&mut self, rule: Arc<dyn GameRules>) -> Result<(), RuleConflict> {
+        if let Some(existing) = self.rules.iter()
+                .find(|existing| existing.conflicts().contains(&rule.name())
+                        || rule.conflicts().contains(&existing.name())) {
+            return Err(RuleConflict { rule: rule.name(), existing: existing.name() })
+        }
+        self.rules.push(rule);
+        Ok(())
+    }
+
+    pub fn status(&self, gamestate: &GameState, context: &RuleContext) -> GameStatus {
+        let status = if gamestate.status == GameStatus::Playing
+                && self.rules.iter().any(|rule| rule.check_alternate_win(gamestate)) {
+            GameStatus::Won
+        } else {
+            gamestate.status
+        };
+
+        self.rules.iter()
+                .fold(status, |status, rule| rule.map_status(status, context))
+    }
+
+    pub fn on_mine_revealed(&self, point: Point) -> MineOutcome {
+        self.rules.iter()
+                .find_map(|rule| rule.on_mine_revealed(point))
+                .unwrap_or(MineOutcome::Lose)
+    }
+
+    pub fn records_eligible(&self) -> bool {
+        self.rules.iter()
+                .all(|rule| rule.records_eligible())
+    }
+
+    pub fn header_extras(&self, context: &RuleContext) -> Vec<String> {
+        self.rules.iter()
+                .filter_map(|rule| rule.header_extras(context))
+                .collect()
+    }
+
+    pub fn timed(&self) -> bool {
+        self.rules.iter()
+                .any(|rule| rule.timed())
+    }
+}
+
+/// hitting a mine flags it instead of ending the game, games don't count towards statistics
+#[derive(Debug)]
+pub struct Practice;
+
+impl GameRules for Practice {
+    fn name(&self) -> &'static str {
+        "Practice"
+    }
+
+    fn conflicts(&self) -> &'static [&'static str] {
+        &["Time Trial"]
+    }
+
+    fn on_mine_revealed(&self, _point: Point) -> Option<MineOutcome> {
+        Some(MineOutcome::Continue)
+    }
+
+    fn records_eligible(&self) -> bool {
+        false
+    }
+}
+
+/// the game is lost once the time limit runs out
+#[derive(Debug)]
+pub struct TimeTrial {
+    pub limit: Duration,
+}
+
+impl GameRules for TimeTrial {
+    fn name(&self) -> &'static str {
+        "Time Trial"
+    }
+
+    fn map_status(&self, status: GameStatus, context: &RuleContext) -> GameStatus {
+        if status == GameStatus::Playing && context.elapsed >= self.limit {
+            GameStatus::Lost
+        } else {
+            status
+        }
+    }
+
+    fn header_extras(&self, context: &RuleContext) -> Option<String> {
+        Some(format!("{} left", format_duration(self.limit.saturating_sub(context.elapsed))))
+    }
+
+    fn timed(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,83 @@
+use super::*;
+use crate::minsweeper::layout_from_mines;
+use minsweeper_rs::board::BoardSize;
+
+/// turns one status into another, named so a stack can hold more than one
+#[derive(Debug)]
+struct Mapping(&'static str, GameStatus, GameStatus);
+
+impl GameRules for Mapping {
+    fn name(&self) -> &'static str {
+        self.0
+    }
+
+    fn map_status(&self, status: GameStatus, _context: &RuleContext) -> GameStatus {
+        if status == self.1 { self.2 } else { status }
+    }
+}
+
+/// says `outcome` about every mine
+#[derive(Debug)]
+struct Mines(&'static str, Option<MineOutcome>);
+
+impl GameRules for Mines {
+    fn name(&self) -> &'static str {
+        self.0
+    }
+
+    fn on_mine_revealed(&self, _point: Point) -> Option<MineOutcome> {
+        self.1
+    }
+}
+
+fn stack(rules: impl IntoIterator<Item = Arc<dyn GameRules>>) -> RuleStack {
+    let mut stack = RuleStack::default();
+    for rule in rules {
+        stack.push(rule).unwrap();
+    }
+    stack
+}
+
+fn playing() -> GameState {
+    let mut gamestate = layout_from_mines(BoardSize::new(3, 3, 1).unwrap(), &[(0, 0)]);
+    gamestate.status = GameStatus::Playing;
+    gamestate
+}
+
+#[test]
+fn statuses_are_mapped_through_the_rules_in_push_order() {
+    let win: Arc<dyn GameRules> = Arc::new(Mapping("win", GameStatus::Playing, GameStatus::Won));
+    let lose: Arc<dyn GameRules> = Arc::new(Mapping("lose", GameStatus::Won, GameStatus::Lost));
+    let context = RuleContext::default();
+
+    assert_eq!(stack([win.clone(), lose.clone()]).status(&playing(), &context), GameStatus::Lost);
+    assert_eq!(stack([lose, win]).status(&playing(), &context), GameStatus::Won);
+    assert_eq!(RuleStack::default().status(&playing(), &context), GameStatus::Playing);
+}
+
+#[test]
+fn the_first_rule_with_an_opinion_on_a_mine_decides_it() {
+    let undecided: Arc<dyn GameRules> = Arc::new(Mines("undecided", None));
+    let forgiving: Arc<dyn GameRules> = Arc::new(Mines("forgiving", Some(MineOutcome::Continue)));
+    let strict: Arc<dyn GameRules> = Arc::new(Mines("strict", Some(MineOutcome::Lose)));
+
+    assert_eq!(stack([undecided.clone(), forgiving.clone(), strict.clone()]).on_mine_revealed((0, 0)), MineOutcome::Continue);
+    assert_eq!(stack([strict, forgiving]).on_mine_revealed((0, 0)), MineOutcome::Lose);
+    assert_eq!(stack([undecided]).on_mine_revealed((0, 0)), MineOutcome::Lose, "nobody deciding is classic play");
+}
+
+#[test]
+fn conflicting_rules_are_refused_whichever_comes_first() {
+    let time_trial = || Arc::new(TimeTrial { limit: Duration::from_secs(60) });
+
+    let mut stack = RuleStack::default();
+    stack.push(Arc::new(Practice)).unwrap();
+    let conflict = stack.push(time_trial()).unwrap_err();
+    assert_eq!((conflict.rule, conflict.existing), ("Time Trial", "Practice"));
+
+    let mut stack = RuleStack::default();
+    stack.push(time_trial()).unwrap();
+    let conflict = stack.push(Arc::new(Practice)).unwrap_err();
+    assert_eq!((conflict.rule, conflict.existing), ("Practice", "Time Trial"));
+    assert!(stack.timed() && stack.records_eligible(), "the refused rule wasn't added");
+}
@@ -0,0 +1,89 @@
+use futures_util::future::{select, Either};
+use std::future::Future;
+use std::pin::pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::{watch, Semaphore};
+
+/// how many clicks and solver steps can be applying at once, the rest wait their turn in order
+const MAX_IN_FLIGHT: usize = 32;
+
+/// the futures a game has going, capped so a burst of clicks can't flood the runtime, and all
+/// cancelled at once by bumping a generation rather than by taking a lock on the ui thread
+#[derive(Clone)]
+pub struct Tasks {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    permits: Semaphore,
+    generation: watch::Sender<u64>,
+    /// a repaint was sent and hasn't been handled yet, it'll show whatever lands before then too
+    repaint_pending: AtomicBool,
+}
+
+impl Default for Tasks {
+    fn default() -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                permits: Semaphore::new(MAX_IN_FLIGHT),
+                generation: watch::Sender::new(0),
+                repaint_pending: AtomicBool::new(false),
+            }),
+        }
+    }
+}
+
+impl Tasks {
+    /// runs `future` once there's room for it, `None` if [`cancel_all`](Self::cancel_all) got to it
+    /// first
+    pub fn run<F: Future>(&self, future: F) -> impl Future<Output = Option<F::Output>> + use<F> {
+        let inner = self.inner.clone();
+        let cancelled = self.cancelled();
+        async move {
+            let run = pin!(async {
+                let _permit = inner.permits.acquire().await.ok()?;
+                Some(future.await)
+            });
+            // cancelled goes first, a click that'd finish on its first poll still never lands on
+            // the game that replaced its own
+            match select(pin!(cancelled), run).await {
+                Either::Right((output, _)) => output,
+                Either::Left(_) => {
+                    tracing::debug!("a task was cancelled before it finished");
+                    None
+                }
+            }
+        }
+    }
+
+    /// resolves once everything started before now is cancelled
+    pub fn cancelled(&self) -> impl Future<Output = ()> + use<> {
+        let mut generation = self.inner.generation.subscribe();
+        let started = *generation.borrow_and_update();
+        async move {
+            let _ = generation.wait_for(|generation| *generation != started).await;
+        }
+    }
+
+    pub fn cancel_all(&self) {
+        self.inner.generation.send_modify(|generation| *generation += 1);
+        tracing::debug!(in_flight = self.in_flight(), "cancelling every task");
+    }
+
+    /// how many are running or holding their place to
+    pub fn in_flight(&self) -> usize {
+        MAX_IN_FLIGHT - self.inner.permits.available_permits()
+    }
+
+    /// whether something that just landed should ask for a repaint, `false` if one is already on
+    /// its way
+    pub fn claim_repaint(&self) -> bool {
+        !self.inner.repaint_pending.swap(true, Ordering::AcqRel)
+    }
+
+    /// the pending repaint is being handled, whatever lands next needs one of its own
+    pub fn repainted(&self) {
+        self.inner.repaint_pending.store(false, Ordering::Release);
+    }
+}
@@ -0,0 +1,947 @@
+use super::*;
+use crate::achievements::{Achievement, Achievements};
+use crate::clock::TestClock;
+use iced_runtime::task::into_stream;
+use iced_runtime::Action as RuntimeAction;
+use minsweeper_rs::solver::mia::MiaSolver;
+use tokio::runtime::Runtime;
+
+/// ```text
+/// . . M 2 .
+/// . . M 2 .
+/// . . 1 1 .
+/// ```
+/// a click on the right edge opens the two right columns and nothing else
+const WIDTH: usize = 5;
+const HEIGHT: usize = 3;
+const MINES: [Point; 2] = [(2, 0), (2, 1)];
+pub const OPENING: Point = (4, 1);
+
+fn layout() -> GameState {
+    layout_from_mines(BoardSize::new(WIDTH, HEIGHT, MINES.len()).unwrap(), &MINES)
+}
+
+/// a game driven by messages alone, the tasks it hands back are run to the end on a runtime of
+/// its own with whatever they output fed back in as it comes, the way the app would. updates
+/// happen in the runtime's context like iced's do, but never on it, where the game's blocking
+/// locks can't be taken. other modules drive the game with it too
+pub struct Harness {
+    pub game: MinsweeperGame,
+    clock: Arc<TestClock>,
+    runtime: Runtime,
+}
+
+impl Harness {
+    pub fn new() -> Self {
+        Self::with(layout())
+    }
+
+    fn with(layout: GameState) -> Self {
+        let clock = Arc::new(TestClock::new());
+        Self {
+            game: MinsweeperGame::with_layout(layout, 0, Arc::new(MiaSolver::default()), Texture::default(), clock.clone()),
+            clock,
+            runtime: tokio::runtime::Builder::new_current_thread()
+                    .enable_time()
+                    .build()
+                    .unwrap(),
+        }
+    }
+
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        let _context = self.runtime.enter();
+        self.game.update(message)
+    }
+
+    pub fn send(&mut self, message: Message) -> Vec<Message> {
+        let task = self.update(message);
+        self.drain(task)
+    }
+
+    /// runs `task` and everything its messages lead to, giving back every message on the way
+    pub fn drain(&mut self, task: Task<Message>) -> Vec<Message> {
+        self.drain_holding(task, |_| false).0
+    }
+
+    /// [`drain`](Self::drain), except messages `hold` picks are handed back instead of sent, for
+    /// stopping something that'd otherwise go on forever
+    fn drain_holding(&mut self, task: Task<Message>, hold: impl Fn(&Message) -> bool) -> (Vec<Message>, Vec<Message>) {
+        let mut pending = VecDeque::from([task]);
+        let (mut seen, mut held) = (vec![], vec![]);
+        while let Some(task) = pending.pop_front() {
+            let Some(mut stream) = into_stream(task) else { continue };
+            // each message is handled as it comes out, like iced would, before the stream goes on
+            while let Some(action) = self.runtime.block_on(stream.next()) {
+                // the rest are for the window, which there isn't one of
+                let RuntimeAction::Output(message) = action else { continue };
+                if hold(&message) {
+                    held.push(message);
+                    continue
+                }
+                seen.push(message.clone());
+                pending.push_back(self.update(message));
+            }
+        }
+        (seen, held)
+    }
+
+    pub fn press(&mut self, point: Point, button: mouse::Button) -> Task<Message> {
+        let pressed = self.update(Message::Cell((point, cell::Message::SelfPress(button))));
+        self.drain(pressed);
+        self.update(Message::Cell((point, cell::Message::SelfRelease(button))))
+    }
+
+    pub fn click(&mut self, point: Point) {
+        let task = self.press(point, mouse::Button::Left);
+        self.drain(task);
+    }
+
+    fn flag(&mut self, point: Point) {
+        let task = self.press(point, mouse::Button::Right);
+        self.drain(task);
+    }
+
+    fn wait(&self, duration: Duration) {
+        self.clock.advance(duration);
+    }
+
+    fn state(&self, point: Point) -> CellState {
+        self.game.gamestate().board[point].cell_state
+    }
+
+    fn revealing(&self) -> usize {
+        self.game.cells.iter()
+                .map(|cell| cell.revealing.load(Ordering::Relaxed))
+                .sum()
+    }
+}
+
+#[test]
+fn flag_chord_flags_what_a_number_can_only_mean() {
+    let mut harness = Harness::new();
+    harness.game.set_flag_chord(true);
+    harness.click(OPENING);
+    assert_eq!(harness.state((3, 0)), CellState::Revealed);
+
+    harness.click((3, 0));
+    assert_eq!(harness.state((2, 0)), CellState::Flagged);
+    assert_eq!(harness.state((2, 1)), CellState::Flagged);
+    assert_eq!(harness.state((2, 2)), CellState::Unknown);
+    assert_eq!(harness.game.status(), GameStatus::Playing);
+}
+
+#[test]
+fn without_flag_chord_a_number_flags_nothing() {
+    let mut harness = Harness::new();
+    harness.click(OPENING);
+    harness.click((3, 0));
+    assert_eq!(harness.state((2, 0)), CellState::Unknown);
+    assert_eq!(harness.state((2, 1)), CellState::Unknown);
+}
+
+#[test]
+fn hover_chord_only_when_enabled_and_satisfied() {
+    let mut harness = Harness::new();
+    harness.click(OPENING);
+    harness.flag((2, 1));
+
+    // off, hovering a satisfied number does nothing
+    harness.send(Message::Cell(((3, 2), cell::Message::Enter)));
+    harness.send(Message::Cell(((3, 2), cell::Message::Exit)));
+    assert_eq!(harness.state((2, 2)), CellState::Unknown);
+
+    harness.game.set_hover_chord(true);
+    // the 2 only has one of its flags
+    let seen = harness.send(Message::Cell(((3, 0), cell::Message::Enter)));
+    harness.send(Message::Cell(((3, 0), cell::Message::Exit)));
+    assert!(!seen.iter().any(|message| matches!(message, Message::HoverChord(..))));
+    assert_eq!(harness.state((2, 0)), CellState::Unknown);
+
+    let seen = harness.send(Message::Cell(((3, 2), cell::Message::Enter)));
+    assert!(seen.iter().any(|message| matches!(message, Message::HoverChord(..))));
+    assert_eq!(harness.state((2, 2)), CellState::Revealed);
+}
+
+#[test]
+fn hover_chord_left_before_it_fires_does_nothing() {
+    let mut harness = Harness::new();
+    harness.click(OPENING);
+    harness.flag((2, 1));
+    harness.game.set_hover_chord(true);
+
+    let entered = harness.update(Message::Cell(((3, 2), cell::Message::Enter)));
+    harness.send(Message::Cell(((3, 2), cell::Message::Exit)));
+    harness.drain(entered);
+    assert_eq!(harness.state((2, 2)), CellState::Unknown);
+}
+
+#[test]
+fn restart_aborts_reveals_in_flight() {
+    let mut harness = Harness::new();
+    let click = harness.press(OPENING, mouse::Button::Left);
+    harness.send(Message::RestartSameBoard);
+    harness.drain(click);
+
+    assert!(harness.game.gamestate().board.size().points()
+            .all(|point| harness.state(point) == CellState::Unknown));
+    assert_eq!(harness.revealing(), 0);
+}
+
+#[test]
+fn revealing_balances_out() {
+    let mut harness = Harness::new();
+    // a double click lands twice before either is done
+    let first = harness.press(OPENING, mouse::Button::Left);
+    let second = harness.press(OPENING, mouse::Button::Left);
+    assert_eq!(harness.revealing(), 2);
+
+    harness.drain(first);
+    // the second click is still going
+    assert!(harness.game.cells[OPENING].is_down());
+    harness.drain(second);
+    assert_eq!(harness.revealing(), 0);
+    assert!(!harness.game.any_revealing());
+
+    // a number covers what's around it
+    let chord = harness.press((3, 2), mouse::Button::Left);
+    assert_eq!(harness.revealing(), 5);
+    harness.drain(chord);
+    assert_eq!(harness.revealing(), 0);
+}
+
+#[test]
+fn nothing_gets_through_once_the_game_is_over() {
+    let mut harness = Harness::new();
+    harness.click(OPENING);
+    harness.click((2, 0));
+    assert_eq!(harness.game.status(), GameStatus::Lost);
+
+    let points = harness.game.gamestate().board.size().points().collect::<Vec<_>>();
+    let before = points.iter().map(|point| harness.state(*point)).collect::<Vec<_>>();
+    harness.click((0, 0));
+    harness.flag((1, 0));
+    harness.send(Message::Cell(((3, 0), cell::Message::Enter)));
+    assert_eq!(points.iter().map(|point| harness.state(*point)).collect::<Vec<_>>(), before);
+    assert!(!harness.game.any_revealing());
+}
+
+fn blasts(harness: &Harness) -> Vec<Point> {
+    let mut overlay = loss_overlay(harness.game.size, &harness.game.game)
+            .unwrap()
+            .into_iter()
+            .filter(|(_, cell)| cell.cell_state == CellState::Revealed)
+            .map(|(point, _)| point)
+            .collect::<Vec<_>>();
+    overlay.sort_unstable();
+    overlay
+}
+
+#[test]
+fn only_the_mine_that_was_clicked_blows_up() {
+    let mut harness = Harness::new();
+    harness.click(OPENING);
+    harness.click((2, 1));
+    assert_eq!(harness.game.status(), GameStatus::Lost);
+    assert_eq!(blasts(&harness), [(2, 1)]);
+}
+
+#[test]
+fn a_chord_onto_two_mines_blows_up_one() {
+    let mut harness = Harness::new();
+    harness.click((1, 1));
+    harness.flag((0, 0));
+    harness.flag((0, 2));
+    harness.click((1, 1));
+    assert_eq!(harness.game.status(), GameStatus::Lost);
+    assert_eq!(blasts(&harness), [(2, 0)]);
+    let overlay = loss_overlay(harness.game.size, &harness.game.game).unwrap();
+    assert_eq!(overlay[&(2, 1)].cell_state, CellState::Unknown);
+}
+
+async fn started() -> MinsweeperType {
+    let game = Arc::new(game::Game::new(BoardSize::new(WIDTH, HEIGHT, MINES.len()).unwrap()));
+    game.start_with_layout(layout(), 0).await;
+    game
+}
+
+#[tokio::test]
+async fn clicks_say_what_they_changed() {
+    let game = started().await;
+    let opened = left_click(&game, OPENING, false, Provenance::Player).await
+            .expect("the opening changes the board");
+    assert_eq!(opened.board[(3, 0)].cell_state, CellState::Revealed);
+
+    // a number that can't chord
+    assert!(left_click(&game, (3, 0), false, Provenance::Player).await.is_none());
+    assert!(right_click(&game, (3, 0), Provenance::Player).await.is_none());
+
+    let flagged = right_click(&game, (2, 1), Provenance::Player).await
+            .expect("flagging changes the board");
+    assert_eq!(flagged.board[(2, 1)].cell_state, CellState::Flagged);
+}
+
+#[tokio::test]
+async fn a_flag_chord_with_nothing_to_reveal_still_changed_something() {
+    let game = started().await;
+    left_click(&game, OPENING, true, Provenance::Player).await;
+    let chorded = left_click(&game, (3, 0), true, Provenance::Player).await
+            .expect("the flags went in");
+    assert_eq!(chorded.board[(2, 0)].cell_state, CellState::Flagged);
+    assert_eq!(chorded.board[(2, 1)].cell_state, CellState::Flagged);
+}
+
+#[test]
+fn a_step_says_why_and_a_restart_forgets() {
+    let mut harness = Harness::new();
+    harness.click(OPENING);
+    harness.send(Message::Step);
+    assert_eq!(harness.game.explanations.len(), 1);
+
+    harness.send(Message::RestartSameBoard);
+    assert!(harness.game.explanations.is_empty());
+}
+
+#[test]
+fn explanations_keep_the_last_few() {
+    let mut harness = Harness::new();
+    let explanation = Explanation { text: String::new(), cells: vec![] };
+    for _ in 0..explain::KEPT + 5 {
+        harness.send(Message::Explained(harness.game.board_id, explanation.clone()));
+    }
+    assert_eq!(harness.game.explanations.len(), explain::KEPT);
+
+    // from a board that's been restarted since
+    harness.send(Message::RestartSameBoard);
+    harness.send(Message::Explained(harness.game.board_id - 1, explanation));
+    assert!(harness.game.explanations.is_empty());
+}
+
+#[test]
+fn without_a_reason_the_numbers_explain_it() {
+    let mut harness = Harness::new();
+    harness.click(OPENING);
+    let gamestate = harness.game.gamestate();
+    let flags = [Action::new((2, 0), Operation::Flag), Action::new((2, 1), Operation::Flag)];
+    let explanation = explain::explain(&flags, None, &gamestate);
+    assert!(explanation.text.contains("Flag C1, C2"), "{}", explanation.text);
+    assert!(explanation.text.contains("cell D1 shows 2"), "{}", explanation.text);
+    assert!(explanation.cells.contains(&((3, 0), HighlightKind::Source)));
+
+    // nothing around (1, 1) is showing a number
+    let explanation = explain::explain(&[Action::new((1, 1), Operation::Reveal)], None, &gamestate);
+    assert!(explanation.text.contains("no single number"), "{}", explanation.text);
+}
+
+#[test]
+fn hovering_an_explanation_lights_up_its_cells() {
+    let mut harness = Harness::new();
+    harness.click(OPENING);
+    harness.send(Message::Explained(harness.game.board_id, Explanation {
+        text: String::new(),
+        cells: vec![((3, 0), HighlightKind::Source), ((2, 0), HighlightKind::Flagged)],
+    }));
+
+    harness.send(Message::HoverExplanation(Some(0)));
+    assert_eq!(harness.game.cells[(2, 0)].highlight, Some(HighlightKind::Flagged));
+    harness.send(Message::HoverExplanation(None));
+    assert_eq!(harness.game.cells[(2, 0)].highlight, None);
+    assert_eq!(harness.game.cells[(3, 0)].highlight, None);
+}
+
+#[test]
+fn auto_explains_what_it_plays() {
+    let mut harness = Harness::new();
+    harness.game.set_auto(Some(AutoPlay { solver: None, delay: Duration::ZERO, guess: AutoGuess::Never, restart: None }));
+    harness.click(OPENING);
+    assert_eq!(harness.game.status(), GameStatus::Won);
+    assert!(!harness.game.explanations.is_empty());
+}
+
+/// ```text
+/// ? ? 0 0
+/// 1 1 0 0
+/// ```
+/// one mine in either `?`, nothing the numbers say tells them apart
+fn coin_flip(mine: Point) -> Harness {
+    let mut harness = Harness::with(layout_from_mines(BoardSize::new(4, 2, 1).unwrap(), &[mine]));
+    harness.game.set_auto(Some(AutoPlay { solver: None, delay: Duration::ZERO, guess: AutoGuess::LowestProbability, restart: None }));
+    harness
+}
+
+#[test]
+fn auto_guesses_its_way_out() {
+    let mut harness = coin_flip((0, 1));
+    harness.click((3, 0));
+    assert_eq!(harness.game.status(), GameStatus::Won);
+    assert_eq!(harness.game.guesses.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn a_guess_that_loses_ends_auto() {
+    let mut harness = coin_flip((0, 0));
+    harness.click((3, 0));
+    assert_eq!(harness.game.status(), GameStatus::Lost);
+    assert_eq!(harness.game.guesses.load(Ordering::Relaxed), 1);
+    assert!(!harness.game.autoing.load(Ordering::Relaxed));
+}
+
+#[test]
+fn without_guessing_auto_stops_at_the_coin_flip() {
+    let mut harness = coin_flip((0, 0));
+    harness.game.set_auto(Some(AutoPlay { solver: None, delay: Duration::ZERO, guess: AutoGuess::Never, restart: None }));
+    harness.click((3, 0));
+    assert_eq!(harness.game.status(), GameStatus::Playing);
+    assert_eq!(harness.game.guesses.load(Ordering::Relaxed), 0);
+}
+
+#[test]
+fn guesses_go_where_the_numbers_say_least() {
+    let mut harness = Harness::new();
+    harness.click(OPENING);
+    let gamestate = harness.game.gamestate();
+    let risks = guess::risks(&gamestate);
+    assert_eq!(risks[&(2, 0)], 1.0);
+    // both mines are spoken for by the numbers
+    assert_eq!(risks[&(0, 0)], 0.0);
+    assert!(!risks.contains_key(&(3, 0)));
+
+    assert_eq!(guess::pick(AutoGuess::LowestProbability, &gamestate), Some(((0, 0), 0.0)));
+    assert_eq!(guess::pick(AutoGuess::Corner, &gamestate).map(|(point, _)| point), Some((0, 0)));
+    assert_eq!(guess::pick(AutoGuess::Never, &gamestate), None);
+}
+
+fn restarting(message: &Message) -> bool {
+    matches!(message, Message::AutoRestart(_))
+}
+
+#[test]
+fn auto_restart_keeps_going_until_the_board_is_touched() {
+    let mut harness = Harness::new();
+    harness.game.set_auto(Some(AutoPlay { solver: None, delay: Duration::ZERO, guess: AutoGuess::LowestProbability,
+        restart: Some(Duration::ZERO) }));
+    let click = harness.press(OPENING, mouse::Button::Left);
+    let (_, held) = harness.drain_holding(click, restarting);
+    let [next] = held.try_into().expect("a restart is waiting");
+    assert_eq!(harness.game.auto_restart.map(|session| (session.attempted, session.won)), Some((1, 1)));
+
+    // the next board is started and played without anyone clicking
+    let task = harness.update(next);
+    let (_, held) = harness.drain_holding(task, restarting);
+    let [next] = held.try_into().expect("another restart is waiting");
+    assert_eq!(harness.game.auto_restart.map(|session| session.attempted), Some(2));
+
+    // hovering is fine, a click isn't
+    harness.send(Message::Cell(((0, 0), cell::Message::Enter)));
+    assert!(harness.game.auto_restart.is_some());
+    harness.flag((0, 0));
+    assert!(harness.game.auto_restart.is_none());
+    let status = harness.game.status();
+    harness.send(next);
+    assert_eq!(harness.game.status(), status, "the called off restart didn't happen");
+}
+
+#[test]
+fn auto_restart_is_called_off_by_turning_it_off() {
+    let mut harness = Harness::new();
+    harness.game.set_auto(Some(AutoPlay { solver: None, delay: Duration::ZERO, guess: AutoGuess::Never,
+        restart: Some(Duration::ZERO) }));
+    let click = harness.press(OPENING, mouse::Button::Left);
+    let (_, held) = harness.drain_holding(click, restarting);
+    assert_eq!(held.len(), 1);
+
+    harness.game.set_auto(Some(AutoPlay { solver: None, delay: Duration::ZERO, guess: AutoGuess::Never, restart: None }));
+    assert!(harness.game.auto_restart.is_none());
+    for message in held {
+        harness.send(message);
+    }
+    assert_eq!(harness.game.status(), GameStatus::Won);
+}
+
+#[test]
+fn one_flag_too_many_blinks_the_counter_until_it_comes_off() {
+    let mut harness = Harness::new();
+    harness.click(OPENING);
+    harness.flag((0, 0));
+    harness.flag((1, 0));
+    assert!(!harness.game.over_flagged());
+
+    let pressed = harness.update(Message::Cell(((0, 1), cell::Message::SelfPress(mouse::Button::Right))));
+    let (_, held) = harness.drain_holding(pressed, |message| matches!(message, Message::ExcessFlagOver(_)));
+    assert!(harness.game.over_flagged());
+    assert!(harness.game.cells[(0, 1)].excess);
+    assert!(!harness.game.cells[(0, 0)].excess);
+    for message in held {
+        harness.send(message);
+    }
+    assert!(!harness.game.cells[(0, 1)].excess, "the tint wears off");
+
+    harness.flag((0, 1));
+    assert_eq!(harness.state((0, 1)), CellState::Unknown);
+    assert!(!harness.game.over_flagged(), "back to as many flags as mines");
+}
+
+#[test]
+fn a_game_left_alone_pauses_itself_until_it_is_touched() {
+    let mut harness = Harness::new();
+    harness.game.set_idle_pause(Some(Duration::from_secs(60)));
+    harness.click((4, 0));
+    assert_eq!(harness.game.status(), GameStatus::Playing);
+
+    harness.wait(Duration::from_secs(59));
+    harness.send(Message::Repaint);
+    assert!(!harness.game.paused());
+
+    harness.wait(Duration::from_secs(1));
+    harness.send(Message::Repaint);
+    assert!(harness.game.paused());
+    assert!(!harness.game.watching_idle());
+
+    // the time away isn't played
+    harness.wait(Duration::from_secs(600));
+    harness.send(Message::Awake);
+    assert!(!harness.game.paused());
+    let started = harness.game.started.unwrap();
+    assert_eq!(harness.game.played(started), Duration::from_secs(60));
+
+    // a pause the player asked for isn't ended by the mouse passing over
+    harness.send(Message::Pause);
+    harness.send(Message::Awake);
+    assert!(harness.game.paused());
+}
+
+#[test]
+fn the_safe_cell_counter_takes_in_a_whole_opening() {
+    let mut harness = Harness::new();
+    assert_eq!(harness.game.safe_left, WIDTH * HEIGHT - MINES.len());
+    harness.click(OPENING);
+    assert_eq!(harness.game.safe_left, WIDTH * HEIGHT - MINES.len() - 6);
+    // the left columns open up as far as the numbers next to the mines
+    harness.click((0, 0));
+    assert_eq!(harness.game.safe_left, 1);
+    harness.click((2, 2));
+    assert_eq!(harness.game.status(), GameStatus::Won);
+    assert_eq!(harness.game.safe_left, 0);
+}
+
+#[test]
+fn a_flag_that_came_off_still_counts_as_placed() {
+    let won = |flag_first: bool| {
+        let mut harness = Harness::new();
+        harness.click(OPENING);
+        if flag_first {
+            harness.flag((0, 0));
+            harness.flag((0, 0));
+        }
+        harness.click((0, 0));
+        let task = harness.press((2, 2), mouse::Button::Left);
+        harness.drain(task).into_iter()
+                .find_map(|message| match message {
+                    Message::GameOver(game_over) => Some(game_over),
+                    _ => None,
+                })
+                .expect("the game is won")
+    };
+
+    let untouched = won(false);
+    assert!(!untouched.flagged);
+    assert!(Achievements::default().record(&untouched).contains(&Achievement::NoFlagsPlaced));
+
+    let flagged = won(true);
+    assert!(flagged.flagged);
+    assert!(!Achievements::default().record(&flagged).contains(&Achievement::NoFlagsPlaced));
+}
+
+#[test]
+fn a_turned_board_swaps_what_shows_but_not_its_points() {
+    let mut harness = Harness::new();
+    let upright = harness.game.fitting_size();
+
+    harness.game.set_rotation(BoardRotation::Auto);
+    assert_eq!(harness.game.shown_dimensions(), (WIDTH, HEIGHT), "auto only turns it for a portrait window");
+    harness.game.set_portrait(true);
+    assert_eq!(harness.game.shown_dimensions(), (HEIGHT, WIDTH));
+    assert_eq!(harness.game.fitting_size().height, upright.height + (WIDTH - HEIGHT) as f32 * FIT_CELL_SIZE);
+
+    // laid out down the board's columns, each still the cell it always was
+    let laid_out = harness.game.cells.laid_out(true)
+            .map(|(point, _)| point)
+            .take(HEIGHT + 1)
+            .collect::<Vec<_>>();
+    assert_eq!(laid_out, [(0, 0), (0, 1), (0, 2), (1, 0)]);
+    assert_eq!(harness.game.shown((4, 1)), (1, 4));
+
+    harness.click(OPENING);
+    assert_eq!(harness.state(OPENING), CellState::Revealed);
+}
+
+#[test]
+fn the_click_rate_counts_recent_clicks_on_the_tick() {
+    let mut harness = Harness::new();
+    harness.click(OPENING);
+    harness.flag((0, 0));
+    harness.flag((0, 0));
+    // the rate is only worked out on a repaint, the total goes up right away
+    assert_eq!(harness.game.click_rate().1, 3);
+
+    harness.send(Message::Repaint);
+    assert_eq!(harness.game.click_rate(), (3.0 / 5.0, 3));
+
+    // old clicks drop out of the rate but not out of the total
+    harness.wait(Duration::from_secs(6));
+    harness.send(Message::Repaint);
+    assert_eq!(harness.game.click_rate(), (0.0, 3));
+
+    harness.send(Message::Restart);
+    assert_eq!(harness.game.click_rate(), (0.0, 0));
+}
+
+#[test]
+fn a_spectated_game_keeps_its_snapshot_up_to_date() {
+    let mut harness = Harness::new();
+    assert!(harness.game.snapshot.is_none());
+
+    harness.game.set_spectated(true);
+    harness.click(OPENING);
+    let snapshot = harness.game.snapshot.as_ref().expect("the spectator draws from the snapshot");
+    assert_eq!(snapshot.board[OPENING].cell_state, CellState::Revealed);
+
+    harness.game.set_spectated(false);
+    assert!(harness.game.snapshot.is_none());
+}
+
+fn resumed() -> Vec<(Click, Provenance)> {
+    vec![(Click::Left(OPENING), Provenance::Player)]
+}
+
+#[test]
+fn clicks_while_a_resumed_game_is_replayed_land_once_it_is_done() {
+    let mut harness = Harness::new();
+    let hydration = harness.game.resume(layout(), 0, resumed());
+    harness.click((0, 0));
+    harness.flag((2, 2));
+    assert_eq!(harness.game.queued.len(), 2);
+    assert_eq!(harness.state((0, 0)), CellState::Unknown);
+
+    let seen = harness.drain(hydration);
+    assert!(seen.iter().any(|message| matches!(message, Message::Hydrated(_))));
+    assert!(harness.game.hydrating.is_none());
+    assert!(harness.game.queued.is_empty());
+    assert_eq!(harness.state(OPENING), CellState::Revealed);
+    assert_eq!(harness.state((0, 0)), CellState::Revealed);
+    assert_eq!(harness.state((2, 2)), CellState::Flagged);
+}
+
+#[test]
+fn a_restart_while_a_resumed_game_is_replayed_leaves_the_new_board_alone() {
+    let mut harness = Harness::new();
+    let hydration = harness.game.resume(layout(), 0, resumed());
+    harness.click((0, 0));
+    harness.send(Message::RestartSameBoard);
+
+    let seen = harness.drain(hydration);
+    assert!(!seen.iter().any(|message| matches!(message, Message::Hydrated(_))));
+    assert!(harness.game.hydrating.is_none());
+    assert!(harness.game.gamestate().board.size().points()
+            .all(|point| harness.state(point) == CellState::Unknown));
+
+    // progress from the replay that was called off
+    harness.send(Message::Hydrating(harness.game.board_id - 1, 0.5));
+    assert!(harness.game.hydrating.is_none());
+}
+
+#[test]
+fn a_time_trial_that_runs_out_ends_the_board_too() {
+    let mut harness = Harness::new();
+    let mut rules = RuleStack::default();
+    rules.push(Arc::new(rules::TimeTrial { limit: Duration::from_secs(10) })).unwrap();
+    harness.game.set_rules(rules);
+    harness.click(OPENING);
+
+    harness.wait(Duration::from_secs(9));
+    harness.send(Message::Repaint);
+    assert_eq!(harness.game.game_status(), GameStatus::Playing);
+
+    harness.wait(Duration::from_secs(1));
+    let seen = harness.send(Message::Repaint);
+    assert!(seen.iter().any(|message| matches!(message, Message::GameOver(game_over) if game_over.status == GameStatus::Lost)));
+    assert_eq!(harness.game.game_status(), GameStatus::Lost);
+    assert_eq!(harness.game.gamestate().status, GameStatus::Lost, "not just the header thinks it's over");
+    assert!(harness.game.game.blocking_history().is_none(), "there's nothing to resume");
+
+    harness.click((0, 0));
+    assert_eq!(harness.state((0, 0)), CellState::Unknown);
+}
+
+/// an expert board with its mines anywhere but around the top left corner, so a click there opens
+/// it up for the solver
+fn expert() -> GameState {
+    use rand::{Rng, SeedableRng};
+    let size = BoardSize::new(30, 16, 99).unwrap();
+    let mut rng = rand::rngs::StdRng::seed_from_u64(1563);
+    let mut mines = HashSet::new();
+    while mines.len() < size.mines().get() {
+        let point = (rng.random_range(0..size.width().get()), rng.random_range(0..size.height().get()));
+        if point.0 > 2 || point.1 > 2 {
+            mines.insert(point);
+        }
+    }
+    layout_from_mines(size, &mines.into_iter().collect::<Vec<_>>())
+}
+
+#[test]
+fn restarting_under_a_running_auto_solve_leaves_nothing_behind() {
+    const RESTARTS: usize = 100;
+    let (done, finished) = std::sync::mpsc::channel();
+    // a deadlock would hang the test, it fails on the timeout instead
+    std::thread::spawn(move || {
+        let mut harness = Harness::with(expert());
+        harness.game.set_auto(Some(AutoPlay { solver: None, delay: Duration::ZERO, guess: AutoGuess::LowestProbability, restart: None }));
+
+        let mut left_over = vec![];
+        for _ in 0..RESTARTS {
+            let click = harness.press((0, 0), mouse::Button::Left);
+            let mut run = into_stream(click).expect("the click does something");
+            // a few steps in, then the board is pulled out from under it
+            for _ in 0..3 {
+                let Some(RuntimeAction::Output(message)) = harness.runtime.block_on(run.next()) else { break };
+                left_over.push(harness.update(message));
+            }
+            harness.send(Message::RestartSameBoard);
+            left_over.push(Task::run(run, |action| match action {
+                RuntimeAction::Output(message) => message,
+                _ => Message::Repaint,
+            }));
+        }
+        for task in left_over {
+            harness.drain(task);
+        }
+        done.send(harness).ok();
+    });
+
+    let harness = finished.recv_timeout(Duration::from_secs(60)).expect("every restart went through");
+    assert!(!harness.game.autoing.load(Ordering::Relaxed));
+    assert_eq!(harness.game.tasks.in_flight(), 0);
+    assert_eq!(harness.revealing(), 0);
+    assert!(harness.game.gamestate().board.size().points()
+            .all(|point| harness.state(point) == CellState::Unknown));
+}
+
+#[test]
+fn the_editor_neither_pauses_nor_steps() {
+    let mut harness = Harness::new();
+    harness.game.edit(MINES);
+    harness.send(Message::Pause);
+    assert!(!harness.game.paused());
+    harness.send(Message::Step);
+    assert!(harness.game.step.is_none());
+    assert_eq!(harness.game.game.blocking_clicks_by(), (0, 0));
+}
+
+fn seeded(seed: u64, point: Point) -> GameState {
+    let game = game::Game::new(BoardSize::new(9, 9, 10).unwrap());
+    tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+        game.start_with_solver(Arc::new(MiaSolver::default()), seed).await;
+        game.left_click(point, Provenance::Player).await.unwrap();
+    });
+    game.blocking_layout().unwrap()
+}
+
+fn mines(layout: &GameState) -> Vec<Point> {
+    layout.board.size().points()
+            .filter(|point| layout.board[*point].cell_type == CellType::Mine)
+            .collect()
+}
+
+#[test]
+fn a_seed_opened_the_same_way_gives_the_same_board() {
+    for point in [(0, 0), (4, 4), (8, 2)] {
+        let first = seeded(0x5eed, point);
+        assert_eq!(mines(&first), mines(&seeded(0x5eed, point)), "opened at {:?}", point);
+        assert!(!mines(&first).contains(&point));
+    }
+    assert_ne!(mines(&seeded(1, (4, 4))), mines(&seeded(2, (4, 4))));
+}
+
+#[test]
+fn any_solver_move_makes_it_an_auto_game() {
+    let mut harness = Harness::new();
+    harness.click(OPENING);
+    harness.send(Message::Step);
+    harness.send(Message::Step);
+    harness.click((0, 0));
+    harness.click((2, 2));
+    assert_eq!(harness.game.status(), GameStatus::Won);
+    let game_over = harness.game.game_over(GameStatus::Won, Duration::ZERO);
+    assert!(game_over.revealed_by_solver > 0);
+    assert!(game_over.auto, "the player finishing it doesn't make the steps before theirs");
+
+    let mut harness = Harness::new();
+    harness.click(OPENING);
+    harness.click((0, 0));
+    harness.click((2, 2));
+    assert_eq!(harness.game.status(), GameStatus::Won);
+    assert!(!harness.game.game_over(GameStatus::Won, Duration::ZERO).auto);
+
+    let mut harness = Harness::new();
+    harness.game.set_auto(Some(AutoPlay { solver: None, delay: Duration::ZERO, guess: AutoGuess::Never, restart: None }));
+    harness.click(OPENING);
+    assert!(harness.game.game_over(GameStatus::Won, Duration::ZERO).auto);
+}
+
+#[test]
+fn a_move_the_shown_step_suggested_is_put_down_to_the_solver() {
+    let mut harness = Harness::new();
+    harness.game.set_solver_preview(false);
+    harness.click(OPENING);
+    harness.send(Message::Step);
+    let action = harness.game.step.as_ref().expect("the step is shown").actions[0];
+    match action.operation {
+        Operation::Flag => harness.flag(action.point),
+        Operation::Reveal | Operation::Chord => harness.click(action.point),
+    }
+    assert!(harness.game.step.is_none());
+    assert!(matches!(harness.game.game.blocking_provenance().get(&action.point), Some(Provenance::Solver(_))));
+
+    harness.flag((0, 0));
+    assert_eq!(harness.game.game.blocking_provenance().get(&(0, 0)), Some(&Provenance::Player));
+}
+
+#[test]
+fn reveals_only_animate_when_nothing_asks_for_less_motion() {
+    let animated = |setup: fn(&mut MinsweeperGame)| {
+        let mut harness = Harness::new();
+        harness.game.set_animate_reveals(true);
+        setup(&mut harness.game);
+        harness.click(OPENING);
+        harness.game.cells.iter().any(|cell| cell.reveal_at.is_some())
+    };
+    assert!(animated(|_| {}));
+    assert!(!animated(|game| game.set_throttled(true)));
+    assert!(!animated(|game| game.set_reduce_motion(true)));
+}
+
+#[test]
+fn the_timer_runs_on_the_clock_and_stops_for_a_pause() {
+    let mut harness = Harness::new();
+    harness.click(OPENING);
+    harness.wait(Duration::from_secs(3));
+    assert_eq!(harness.game.rule_context().elapsed, Duration::from_secs(3));
+
+    harness.send(Message::Pause);
+    harness.wait(Duration::from_secs(60));
+    harness.send(Message::Pause);
+    harness.wait(Duration::from_secs(2));
+    assert_eq!(harness.game.rule_context().elapsed, Duration::from_secs(5));
+
+    harness.click((0, 0));
+    harness.click((2, 2));
+    harness.wait(Duration::from_secs(10));
+    assert_eq!(harness.game.rule_context().elapsed, Duration::from_secs(5));
+}
+
+#[test]
+fn a_press_held_while_the_window_goes_away_is_let_go() {
+    let mut harness = Harness::new();
+    let pressed = harness.update(Message::Cell(((0, 0), cell::Message::SelfPress(mouse::Button::Left))));
+    harness.drain(pressed);
+    assert!(harness.game.cells[(0, 0)].pressed);
+
+    harness.send(Message::ClearPressed);
+    assert!(harness.game.cells.iter().all(|cell| !cell.pressed && !cell.hovering));
+    // the release after coming back reveals nothing
+    harness.send(Message::Cell(((0, 0), cell::Message::SelfRelease(mouse::Button::Left))));
+    assert_eq!(harness.state((0, 0)), CellState::Unknown);
+}
+
+#[test]
+fn a_flag_drag_cut_off_by_the_window_going_away_flags_nothing_more() {
+    let mut harness = Harness::new();
+    harness.game.set_drag_flag(true);
+    harness.click(OPENING);
+    let pressed = harness.update(Message::Cell(((0, 0), cell::Message::SelfPress(mouse::Button::Right))));
+    harness.drain(pressed);
+    assert_eq!(harness.state((0, 0)), CellState::Flagged);
+
+    harness.send(Message::ClearPressed);
+    harness.send(Message::Cell(((1, 0), cell::Message::Enter)));
+    harness.send(Message::Cell(((1, 1), cell::Message::Enter)));
+    assert_eq!(harness.state((1, 0)), CellState::Unknown);
+    assert_eq!(harness.state((1, 1)), CellState::Unknown);
+
+    harness.click((1, 2));
+    assert_eq!(harness.state((1, 2)), CellState::Revealed);
+    assert_eq!(harness.state((1, 1)), CellState::Unknown);
+}
+
+#[test]
+fn the_progress_ring_follows_the_safe_cells_and_starts_over_on_a_restart() {
+    let mut harness = Harness::new();
+    let safe = (WIDTH * HEIGHT - MINES.len()) as f32;
+    assert_eq!(harness.game.progress, 0.0);
+    harness.click(OPENING);
+    assert_eq!(harness.game.progress, 6.0 / safe);
+    // a flag isn't progress
+    harness.flag((2, 0));
+    assert_eq!(harness.game.progress, 6.0 / safe);
+    harness.click((0, 0));
+    harness.click((2, 2));
+    assert_eq!(harness.game.progress, 1.0);
+
+    harness.send(Message::RestartSameBoard);
+    assert_eq!(harness.game.progress, 0.0);
+}
+
+/// a chord on the 1 under the mines, with the mine next to it flagged, held before it commits
+fn flashing_chord() -> (Harness, Vec<Message>) {
+    let mut harness = Harness::new();
+    harness.game.set_chord_delay(Duration::from_millis(300));
+    harness.click(OPENING);
+    harness.flag((2, 1));
+    let released = harness.press((3, 2), mouse::Button::Left);
+    let (_, held) = harness.drain_holding(released, |message| matches!(message, Message::CommitChord(_)));
+    (harness, held)
+}
+
+#[test]
+fn a_chord_flashes_what_it_stands_on_before_it_goes_in() {
+    let (mut harness, held) = flashing_chord();
+    assert_eq!(held.len(), 1);
+    assert!(harness.game.cells[(2, 1)].highlighted);
+    assert!(harness.game.cells[(2, 2)].pending);
+    assert_eq!(harness.state((2, 2)), CellState::Unknown);
+
+    for message in held {
+        harness.send(message);
+    }
+    assert_eq!(harness.state((2, 2)), CellState::Revealed);
+    assert!(harness.game.cells.iter().all(|cell| !cell.highlighted && !cell.pending));
+}
+
+#[test]
+fn a_chord_called_off_while_flashing_reveals_nothing() {
+    let (mut harness, held) = flashing_chord();
+    harness.send(Message::CancelChord);
+    assert!(harness.game.cells.iter().all(|cell| !cell.highlighted && !cell.pending));
+    for message in held {
+        harness.send(message);
+    }
+    assert_eq!(harness.state((2, 2)), CellState::Unknown);
+}
+
+#[test]
+fn a_shown_step_isnt_played_while_paused() {
+    let mut harness = Harness::new();
+    harness.game.set_solver_preview(false);
+    harness.click(OPENING);
+    harness.send(Message::Step);
+    assert!(harness.game.step.is_some());
+
+    harness.send(Message::Pause);
+    harness.send(Message::Step);
+    assert_eq!(harness.game.game.blocking_clicks_by().1, 0);
+    assert!(harness.game.step.is_some(), "it's still there for after");
+
+    harness.send(Message::Pause);
+    harness.send(Message::Step);
+    assert!(harness.game.game.blocking_clicks_by().1 > 0);
+}
@@ -0,0 +1,115 @@
+use directories::ProjectDirs;
+use std::fs::create_dir_all;
+use std::io;
+use std::path::PathBuf;
+use std::sync::{LazyLock, OnceLock};
+
+/// `None` on systems with no home folder to keep anything in
+static DIRS: LazyLock<Option<ProjectDirs>> = LazyLock::new(||
+        ProjectDirs::from("", "canaryprism", "minsweeper-native-client"));
+
+/// set by `--portable`, everything goes in here instead
+static PORTABLE: OnceLock<PathBuf> = OnceLock::new();
+
+/// what the folder next to the executable is called in portable mode
+const PORTABLE_FOLDER: &str = "minsweeper-data";
+
+/// keeps everything in a folder next to the executable from now on, for running off a usb stick.
+/// has to happen before anything is read or written
+pub fn make_portable() -> io::Result<()> {
+    let executable = std::env::current_exe()?;
+    let folder = executable.parent()
+            .ok_or_else(|| io::Error::other("the executable isn't in a folder"))?
+            .join(PORTABLE_FOLDER);
+    let _ = PORTABLE.set(folder);
+    Ok(())
+}
+
+pub fn portable() -> bool {
+    PORTABLE.get().is_some()
+}
+
+fn project() -> io::Result<&'static ProjectDirs> {
+    DIRS.as_ref().ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "there's no home folder to keep anything in"))
+}
+
+/// `folder`, made first if it isn't there yet
+fn made(folder: PathBuf) -> io::Result<PathBuf> {
+    create_dir_all(&folder)?;
+    Ok(folder)
+}
+
+/// settings, what someone would back up or carry to another computer
+pub fn config_dir() -> io::Result<PathBuf> {
+    made(match PORTABLE.get() {
+        Some(portable) => portable.clone(),
+        None => project()?.config_dir().to_path_buf(),
+    })
+}
+
+/// everything kept that isn't settings
+pub fn data_dir() -> io::Result<PathBuf> {
+    made(match PORTABLE.get() {
+        Some(portable) => portable.clone(),
+        None => project()?.data_dir().to_path_buf(),
+    })
+}
+
+pub fn settings_file() -> io::Result<PathBuf> {
+    Ok(config_dir()?.join("settings.json"))
+}
+
+/// where settings were kept before they moved to [`config_dir`], `None` if that's the same place
+/// or there's nothing to move them from
+pub fn legacy_settings_file() -> Option<PathBuf> {
+    if portable() {
+        return None
+    }
+    let project = DIRS.as_ref()?;
+    (project.data_dir() != project.config_dir())
+            .then(|| project.data_dir().join("settings.json"))
+}
+
+pub fn stats_file() -> io::Result<PathBuf> {
+    Ok(data_dir()?.join("stats.json"))
+}
+
+pub fn window_file() -> io::Result<PathBuf> {
+    Ok(data_dir()?.join("window.json"))
+}
+
+/// where a view moved out into its own window was, one for each kind of view
+pub fn detached_window_file(view: &str) -> io::Result<PathBuf> {
+    Ok(data_dir()?.join(format!("window-{}.json", view)))
+}
+
+/// the game in progress, for recovering it after a crash
+pub fn journal_file() -> io::Result<PathBuf> {
+    Ok(data_dir()?.join("journal.bin"))
+}
+
+/// written on the way out, a journal older than it was from a game that was closed on purpose
+pub fn clean_exit_file() -> io::Result<PathBuf> {
+    Ok(data_dir()?.join("clean-exit"))
+}
+
+/// games that were interrupted and not resumed
+pub fn journals_dir() -> io::Result<PathBuf> {
+    made(data_dir()?.join("journals"))
+}
+
+pub fn logs_dir() -> io::Result<PathBuf> {
+    made(data_dir()?.join("logs"))
+}
+
+pub fn crashes_dir() -> io::Result<PathBuf> {
+    made(data_dir()?.join("crashes"))
+}
+
+pub fn boards_dir() -> io::Result<PathBuf> {
+    made(data_dir()?.join("boards"))
+}
+
+pub fn textures_dir() -> io::Result<PathBuf> {
+    made(data_dir()?.join("textures"))
+}
@@ -0,0 +1,69 @@
+use std::path::Path;
+use std::time::Duration;
+
+#[cfg(test)]
+mod tests;
+
+/// how often the power source is checked while throttling is turned on
+pub const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PowerSource {
+    Ac,
+    Battery,
+    /// the platform doesn't say, treated like being plugged in
+    Unknown,
+}
+
+const POWER_SUPPLY: &str = "/sys/class/power_supply";
+
+/// whether this platform can tell battery from mains at all
+pub fn supported() -> bool {
+    cfg!(target_os = "linux") && Path::new(POWER_SUPPLY).is_dir()
+}
+
+/// reads the power supply class in sysfs, any online mains adapter means ac, otherwise a
+/// discharging battery means battery
+#[cfg(target_os = "linux")]
+pub fn detect() -> PowerSource {
+    let Ok(entries) = std::fs::read_dir(POWER_SUPPLY) else { return PowerSource::Unknown };
+
+    let read = |path: &Path, name: &str| std::fs::read_to_string(path.join(name))
+            .map(|value| value.trim().to_string())
+            .unwrap_or_default();
+
+    let mut discharging = false;
+    for entry in entries.filter_map(Result::ok) {
+        let path = entry.path();
+        match read(&path, "type").as_str() {
+            "Mains" | "USB" if read(&path, "online") == "1" => return PowerSource::Ac,
+            "Battery" if read(&path, "status") == "Discharging" => discharging = true,
+            _ => {}
+        }
+    }
+
+    if discharging { PowerSource::Battery } else { PowerSource::Ac }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn detect() -> PowerSource {
+    PowerSource::Unknown
+}
+
+/// what throttling comes to for one setting, power source and auto delay
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Throttle {
+    /// auto is slowed down and the cosmetic animations are let go
+    pub throttled: bool,
+    /// the auto delay to actually use, only ever raised and only while throttling
+    pub delay: Duration,
+}
+
+/// decides throttling from what it's given alone, the power source is read elsewhere
+pub fn throttle(enabled: bool, source: PowerSource, configured: Duration, minimum: Duration) -> Throttle {
+    let throttled = enabled && source == PowerSource::Battery;
+    Throttle {
+        throttled,
+        delay: if throttled { configured.max(minimum) } else { configured },
+    }
+}
@@ -0,0 +1,35 @@
+use super::*;
+
+const MINIMUM: Duration = Duration::from_millis(200);
+
+#[test]
+fn only_a_battery_with_the_setting_on_throttles() {
+    for enabled in [false, true] {
+        for source in [PowerSource::Ac, PowerSource::Battery, PowerSource::Unknown] {
+            for configured in [Duration::ZERO, MINIMUM, Duration::from_millis(500)] {
+                let throttle = throttle(enabled, source, configured, MINIMUM);
+                let throttled = enabled && source == PowerSource::Battery;
+                assert_eq!(throttle.throttled, throttled, "{} {:?} {:?}", enabled, source, configured);
+                let delay = if throttled { configured.max(MINIMUM) } else { configured };
+                assert_eq!(throttle.delay, delay, "{} {:?} {:?}", enabled, source, configured);
+            }
+        }
+    }
+}
+
+#[test]
+fn throttling_only_ever_raises_the_delay() {
+    let slow = Duration::from_secs(1);
+    assert_eq!(throttle(true, PowerSource::Battery, slow, MINIMUM).delay, slow);
+    assert_eq!(throttle(true, PowerSource::Battery, Duration::ZERO, MINIMUM).delay, MINIMUM);
+}
+
+#[test]
+fn plugging_in_gives_the_configured_delay_back() {
+    let configured = Duration::from_millis(50);
+    let on_battery = throttle(true, PowerSource::Battery, configured, MINIMUM);
+    assert_eq!(on_battery, Throttle { throttled: true, delay: MINIMUM });
+
+    let plugged_in = throttle(true, PowerSource::Ac, configured, MINIMUM);
+    assert_eq!(plugged_in, Throttle { throttled: false, delay: configured });
+}
@@ -0,0 +1,68 @@
+use std::fmt::Display;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+#[cfg(test)]
+mod tests;
+
+/// streamer mode, what's shown on screen is kept free of who's playing
+static STREAMER_MODE: AtomicBool = AtomicBool::new(false);
+
+pub fn streamer_mode() -> bool {
+    STREAMER_MODE.load(Ordering::Relaxed)
+}
+
+pub fn set_streamer_mode(enabled: bool) {
+    STREAMER_MODE.store(enabled, Ordering::Relaxed);
+}
+
+/// `text` as it can be shown, in streamer mode the home folder becomes `~` and the user's name is
+/// taken out wherever else it's left, like in a path outside the home folder
+pub fn redact(text: impl Display) -> String {
+    let text = text.to_string();
+    if !streamer_mode() {
+        return text
+    }
+    let home = directories::BaseDirs::new()
+            .map(|dirs| dirs.home_dir().to_string_lossy().into_owned());
+    let user = std::env::var("USER")
+            .or_else(|_| std::env::var("USERNAME"))
+            .ok();
+    redact_with(&text, home.as_deref(), user.as_deref())
+}
+
+fn redact_with(text: &str, home: Option<&str>, user: Option<&str>) -> String {
+    let mut text = match home.filter(|home| home.len() > 1) {
+        // a home of /home/bob doesn't make /home/bobby ~by
+        Some(home) => replace_bounded(text, home, "~",
+                |_, after| after.is_none_or(std::path::is_separator)),
+        None => text.to_string(),
+    };
+    if let Some(user) = user.filter(|user| !user.is_empty()) {
+        text = replace_word(&text, user, "<user>");
+    }
+    text
+}
+
+/// `word` only where it isn't part of a longer one, a short name shouldn't eat into every path
+fn replace_word(text: &str, word: &str, with: &str) -> String {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    replace_bounded(text, word, with, |before, after| !before.is_some_and(is_word) && !after.is_some_and(is_word))
+}
+
+/// `from` wherever `bounded` accepts the characters on either side of it in the original text
+fn replace_bounded(text: &str, from: &str, with: &str, bounded: impl Fn(Option<char>, Option<char>) -> bool) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut searched = 0;
+    while let Some(index) = text[searched..].find(from).map(|index| searched + index) {
+        let end = index + from.len();
+        out.push_str(&text[searched..index]);
+        if bounded(text[..index].chars().next_back(), text[end..].chars().next()) {
+            out.push_str(with);
+        } else {
+            out.push_str(from);
+        }
+        searched = end;
+    }
+    out.push_str(&text[searched..]);
+    out
+}
@@ -0,0 +1,40 @@
+use super::{redact_with, replace_word};
+
+#[test]
+fn a_word_is_only_replaced_on_its_own() {
+    assert_eq!(replace_word("bob and bobby", "bob", "<user>"), "<user> and bobby");
+    assert_eq!(replace_word("jimbob, bob_2, bob", "bob", "<user>"), "jimbob, bob_2, <user>");
+    assert_eq!(replace_word("/srv/bob/games", "bob", "<user>"), "/srv/<user>/games");
+    assert_eq!(replace_word("bob-bob", "bob", "<user>"), "<user>-<user>");
+}
+
+#[test]
+fn a_word_that_isnt_there_leaves_the_text_alone() {
+    assert_eq!(replace_word("nothing to see", "bob", "<user>"), "nothing to see");
+    assert_eq!(replace_word("", "bob", "<user>"), "");
+}
+
+#[test]
+fn the_home_folder_becomes_a_tilde() {
+    let home = Some("/home/bob");
+    assert_eq!(redact_with("/home/bob/solver.sh", home, None), "~/solver.sh");
+    assert_eq!(redact_with("cd /home/bob", home, None), "cd ~");
+}
+
+#[test]
+fn a_home_folder_only_matches_up_to_a_separator() {
+    assert_eq!(redact_with("/home/bobby/solver.sh", Some("/home/bob"), None), "/home/bobby/solver.sh");
+    assert_eq!(redact_with("/home/bobby/solver.sh", Some("/home/bob"), Some("bob")), "/home/bobby/solver.sh");
+}
+
+#[test]
+fn the_name_is_taken_out_of_paths_outside_home() {
+    assert_eq!(redact_with("/mnt/bob/solver.sh and /home/bob/x", Some("/home/bob"), Some("bob")),
+            "/mnt/<user>/solver.sh and ~/x");
+}
+
+#[test]
+fn a_root_home_or_an_empty_name_redacts_nothing() {
+    assert_eq!(redact_with("/usr/bin/solver", Some("/"), Some("")), "/usr/bin/solver");
+    assert_eq!(redact_with("/usr/bin/solver", None, None), "/usr/bin/solver");
+}
@@ -0,0 +1,211 @@
+use crate::board_text::Layout;
+use crate::dialog;
+use crate::dialog::DialogId;
+use crate::i18n::t;
+use crate::paths;
+use derive_more::From;
+use iced::widget::{button, row, scrollable, text, text_input};
+use iced::{widget, Element, Length, Task};
+use minsweeper_rs::board::Point;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io;
+use std::path::PathBuf;
+
+#[cfg(test)]
+mod tests;
+
+/// characters some file system won't take in a name
+const FORBIDDEN: &str = r#"<>:"/\|?*"#;
+
+/// a board made in the editor as it's kept on disk
+#[derive(Debug, Serialize, Deserialize)]
+struct SavedBoard {
+    width: usize,
+    height: usize,
+    mines: Vec<Point>,
+}
+
+/// boards from the editor, each a json file named after what the user called it
+#[derive(Debug, Default)]
+pub struct SavedBoards {
+    /// the board the save dialog is naming
+    saving: Option<Layout>,
+    name: String,
+    /// what was in the folder when a dialog last opened
+    saved: Vec<String>,
+    error: Option<String>,
+}
+
+#[derive(Clone, Debug, From)]
+pub enum Message {
+    /// asks what to call the board before saving it
+    Save(Layout),
+    #[from(skip)]
+    Name(String),
+    ConfirmSave,
+    Saved(Result<String, String>),
+    /// lists what's saved to pick one to edit
+    Browse,
+    Listed(Vec<String>),
+    #[from(skip)]
+    Load(String),
+    /// the board read back, for the editor
+    Loaded(Result<Layout, String>),
+    Dialog(dialog::Message),
+}
+
+impl SavedBoards {
+
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        match message {
+            Message::Save(layout) => {
+                self.saving = Some(layout);
+                self.error = None;
+                return Task::done(dialog::Message::Open(DialogId::SaveBoard).into())
+                        .chain(list())
+            }
+            Message::Name(name) => self.name = name,
+            Message::ConfirmSave => {
+                let Some(layout) = self.saving.clone() else { return Task::none() };
+                let name = self.name.trim().to_string();
+                if !valid(&name) {
+                    self.error = Some(t!("board-name-invalid").to_string());
+                    return Task::none()
+                }
+                return Task::future(tokio::task::spawn_blocking(move || write(&name, &layout)
+                        .map(|_| name)
+                        .map_err(|e| e.to_string())))
+                        .map(|result| Message::Saved(result.unwrap_or_else(|e| Err(e.to_string()))))
+            }
+            Message::Saved(Ok(_)) => {
+                self.saving = None;
+                return Task::done(dialog::Message::Close(DialogId::SaveBoard).into())
+            }
+            Message::Saved(Err(e)) => self.error = Some(e),
+            Message::Browse => {
+                self.error = None;
+                return Task::done(dialog::Message::Open(DialogId::LoadBoard).into())
+                        .chain(list())
+            }
+            Message::Listed(saved) => self.saved = saved,
+            Message::Load(name) => {
+                return Task::future(tokio::task::spawn_blocking(move || read(&name)))
+                        .map(|result| Message::Loaded(result.unwrap_or_else(|e| Err(e.to_string()))))
+            }
+            Message::Loaded(Ok(_)) => {
+                return Task::done(dialog::Message::Close(DialogId::LoadBoard).into())
+            }
+            Message::Loaded(Err(e)) => self.error = Some(e),
+            Message::Dialog(_) => {}
+        }
+        Task::none()
+    }
+
+    pub fn dialog<'a>(&self, id: DialogId) -> Option<Element<'a, Message>> {
+        match id {
+            DialogId::SaveBoard => Some(self.save_view()),
+            DialogId::LoadBoard => Some(self.load_view()),
+            _ => None,
+        }
+    }
+
+    fn save_view<'a>(&self) -> Element<'a, Message> {
+        let name = self.name.trim();
+        let valid = valid(name);
+        widget::column![
+            text(t!("save-board-title")),
+            text_input(t!("board-name"), &self.name)
+                    .on_input(Message::Name)
+                    .on_submit_maybe(valid.then_some(Message::ConfirmSave)),
+            self.saved.iter().any(|saved| saved == name).then(|| text(t!("board-name-taken"))),
+            self.error.clone().map(text),
+            row![
+                button(t!("cancel")).on_press(dialog::Message::Close(DialogId::SaveBoard).into()),
+                button(t!("save")).on_press_maybe(valid.then_some(Message::ConfirmSave)),
+            ].spacing(10),
+        ].spacing(10).into()
+    }
+
+    fn load_view<'a>(&self) -> Element<'a, Message> {
+        let saved: Element<_> = if self.saved.is_empty() {
+            text(t!("no-saved-boards")).into()
+        } else {
+            scrollable(widget::column(self.saved.iter()
+                    .map(|name| button(text(name.clone()))
+                            .on_press(Message::Load(name.clone()))
+                            .width(Length::Fill)
+                            .style(button::text)
+                            .into())))
+                    .height(Length::Shrink)
+                    .into()
+        };
+        widget::column![
+            text(t!("load-board-title")),
+            saved,
+            self.error.clone().map(text),
+            button(t!("cancel")).on_press(dialog::Message::Close(DialogId::LoadBoard).into()),
+        ].spacing(10).into()
+    }
+}
+
+/// whether `name` can be a file name everywhere
+fn valid(name: &str) -> bool {
+    !name.is_empty() && !name.starts_with('.')
+            && !name.chars().any(|c| c.is_control() || FORBIDDEN.contains(c))
+}
+
+/// where a board called `name` is kept
+fn path(name: &str) -> io::Result<PathBuf> {
+    if !valid(name) {
+        return Err(io::Error::other(t!("board-name-invalid")))
+    }
+    Ok(paths::boards_dir()?.join(format!("{}.json", name)))
+}
+
+fn list() -> Task<Message> {
+    Task::future(tokio::task::spawn_blocking(|| {
+        // nothing saved yet and no folder are the same thing
+        let Ok(entries) = paths::boards_dir().and_then(std::fs::read_dir) else { return vec![] };
+        let mut saved = entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().is_some_and(|extension| extension == "json"))
+                .filter_map(|path| path.file_stem().map(|stem| stem.to_string_lossy().into_owned()))
+                .collect::<Vec<_>>();
+        saved.sort_by_key(|name| name.to_lowercase());
+        saved
+    })).map(|saved| Message::Listed(saved.unwrap_or_default()))
+}
+
+fn write(name: &str, layout: &Layout) -> io::Result<()> {
+    let path = path(name)?;
+    let board = SavedBoard {
+        width: layout.size.width().get(),
+        height: layout.size.height().get(),
+        mines: layout.mines.clone(),
+    };
+    let temp = path.with_extension("json.tmp");
+    let mut file = File::create(&temp)?;
+    serde_json::to_writer(&mut file, &board)?;
+    file.sync_all()?;
+    drop(file);
+
+    std::fs::rename(temp, path)
+}
+
+fn read(name: &str) -> Result<Layout, String> {
+    let invalid = || t!("saved-board-invalid", name = name);
+    if !valid(name) {
+        return Err(invalid())
+    }
+    let file = path(name).and_then(File::open).map_err(|e| e.to_string())?;
+    let board = serde_json::from_reader::<_, SavedBoard>(io::BufReader::new(file))
+            .map_err(|_| invalid())?;
+    layout(board).ok_or_else(invalid)
+}
+
+/// the board a file describes, if it's one that could have been saved
+fn layout(board: SavedBoard) -> Option<Layout> {
+    Layout::checked(board.width, board.height, board.mines)
+}
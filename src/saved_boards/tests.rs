@@ -0,0 +1,26 @@
+use super::*;
+use crate::board_text::MAX_SIDE;
+
+fn board(width: usize, height: usize, mines: Vec<Point>) -> SavedBoard {
+    SavedBoard { width, height, mines }
+}
+
+#[test]
+fn a_saved_board_reads_back() {
+    let layout = layout(board(4, 3, vec![(0, 0), (3, 2)])).unwrap();
+    assert_eq!((layout.size.width().get(), layout.size.height().get()), (4, 3));
+    assert_eq!(layout.mines, [(0, 0), (3, 2)]);
+}
+
+#[test]
+fn an_oversized_board_is_refused() {
+    assert!(layout(board(usize::MAX, usize::MAX, vec![(0, 0)])).is_none());
+    assert!(layout(board(MAX_SIDE + 1, 1, vec![(0, 0)])).is_none());
+}
+
+#[test]
+fn mines_have_to_be_on_the_board_and_apart() {
+    assert!(layout(board(2, 2, vec![(2, 0)])).is_none());
+    assert!(layout(board(2, 2, vec![(1, 1), (1, 1)])).is_none());
+    assert!(layout(board(1, 1, vec![(0, 0), (0, 0)])).is_none());
+}
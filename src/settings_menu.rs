@@ -1,340 +1,2485 @@
-use crate::minsweeper::SolverType;
-use crate::texture::Texture;
-use crate::{settings_menu, DIRS};
+use crate::board_text;
+use crate::dialog;
+use crate::dialog::{DialogId, Flagged};
+use crate::experiments;
+use crate::experiments::{Experiment, Experiments};
+use crate::i18n::{t, Language};
+use crate::launch::Launch;
+use crate::minsweeper;
+use crate::minsweeper::pathology;
+use crate::minsweeper::rules::{GameRules, Practice, RuleConflict, RuleStack, TimeTrial};
+use crate::minsweeper::{AutoGuess, AutoPlay, BoardRotation, CellScale, CounterMode, HighlightModifier, MouseBindings, MouseButton, Overlay, SolverType};
+use crate::power::PowerSource;
+use crate::solvers;
+use crate::solvers::external::ExternalSolver;
+use crate::solvers::{external, ChainedSolver, FirstClick, KnownSolver};
+use crate::storage::Retention;
+use crate::logging::LogLevel;
+use crate::texture::manifest::Manifest;
+use crate::texture::{pack, Texture};
+use crate::texture;
+use crate::share;
+use crate::shortcuts;
+use crate::{export, logging, paths, power, privacy};
 use derive_more::From;
 use iced::widget::*;
-use iced::{widget, Border, Color, Element, Length, Task};
+use iced::{theme, widget, Border, Element, Font, Length, Task};
+use iced_aw::menu::{Item, Menu};
 use iced_aw::{menu, menu_items};
 use iced_aw::{menu_bar, number_input};
 use iced_core::alignment::Vertical;
 use minsweeper_rs::board::{BoardSize, ConventionalSize};
-use minsweeper_rs::solver::mia::{Level, MiaSolver};
-use minsweeper_rs::solver::start::{SafeStart, WinStart, ZeroStart};
-use serde::de::{MapAccess, Visitor};
+use minsweeper_rs::GameState;
+use serde::de::{IgnoredAny, MapAccess, SeqAccess, Visitor};
 use serde::ser::SerializeStruct;
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
-use std::fmt::Formatter;
-use std::fs::{create_dir_all, File};
+use serde_json::Value;
+use std::fmt::{Display, Formatter};
+use std::fs::File;
 use std::io;
-use std::path::PathBuf;
-use std::sync::{Arc, LazyLock};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
-static SETTINGS_PATH: LazyLock<PathBuf> = LazyLock::new(|| {
-    let folder = DIRS.data_dir();
-    if !folder.is_dir() {
-        create_dir_all(folder)
-                .unwrap_or_else(|_| panic!("failed to create directory {:?}", folder))
+#[cfg(test)]
+mod tests;
+
+/// changes still waiting out the save debounce, for the panic hook to write on the way down
+static UNSAVED: Mutex<Option<String>> = Mutex::new(None);
+
+/// changes are written at most this often so dragging a slider doesn't rewrite the file per step
+const SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// the custom size dialog shows the density in red above this
+const HIGH_DENSITY: f32 = 0.35;
+
+/// the highest density the custom size dialog takes in density mode
+const MAX_DENSITY_PERCENT: u32 = 90;
+
+/// how many custom sizes the Size menu remembers
+const RECENT_SIZES: usize = 5;
+
+/// how much of the log the logs dialog shows
+const LOG_TAIL: usize = 200;
+
+/// bumped whenever the layout changes in a way old files need [`migrate`] for
+const CURRENT_VERSION: u32 = 2;
+
+// every field falls back to its default so files from before a field existed still load
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// missing in files from before versioning, which count as 0
+    version: u32,
+    size: SerializableBoardSize,
+    texture: Texture,
+    /// use Light or Dark to match the system instead of `texture`
+    #[serde(default)]
+    follow_system: bool,
+    /// what the menus and dialogs are drawn with
+    #[serde(default)]
+    app_theme: AppTheme,
+    /// what boards are generated to guarantee
+    #[serde(default)]
+    first_click: FirstClick,
+    /// what steps and auto play with, `None` leaves the player without one
+    assist_solver: Option<KnownSolver>,
+    /// whether `assist_solver`, `solver_chain` or `external_solver` is played with
+    #[serde(default)]
+    solver_mode: SolverMode,
+    #[serde(default)]
+    solver_chain: Vec<KnownSolver>,
+    #[serde(default)]
+    external_solver: ExternalSettings,
+    auto: bool,
+    auto_settings: Auto,
+    flag_chord: bool,
+    /// dragging with the flag button flags every covered cell along the way
+    #[serde(default = "enabled")]
+    drag_flag: bool,
+    hover_chord: bool,
+    #[serde(default = "default_hover_chord_delay")]
+    hover_chord_delay: Duration,
+    #[serde(default)]
+    mouse_bindings: MouseBindings,
+    /// taps flag instead of revealing
+    #[serde(default)]
+    flag_mode: bool,
+    /// a solver step plays itself after `step_preview` instead of waiting for a second press
+    #[serde(default = "enabled")]
+    solver_preview: bool,
+    #[serde(default = "default_step_preview")]
+    step_preview: Duration,
+    #[serde(default)]
+    long_press_flag: bool,
+    #[serde(default = "enabled")]
+    progress_ring: bool,
+    /// a bar under the header filling up as the board is cleared
+    #[serde(default = "enabled")]
+    show_progress: bool,
+    #[serde(default)]
+    seed: Option<u64>,
+    #[serde(default)]
+    rules: Vec<KnownRule>,
+    #[serde(default = "default_time_limit")]
+    time_limit: Duration,
+    #[serde(default)]
+    chord_delay: Duration,
+    #[serde(default = "enabled")]
+    show_game_over_dialog: bool,
+    #[serde(default)]
+    silent_auto: bool,
+    #[serde(default)]
+    retention: Retention,
+    #[serde(default = "enabled")]
+    sound_enabled: bool,
+    #[serde(default = "default_volume")]
+    volume: f32,
+    #[serde(default)]
+    cell_scale: CellScale,
+    #[serde(default)]
+    counter_mode: CounterMode,
+    #[serde(default)]
+    board_rotation: BoardRotation,
+    #[serde(default)]
+    show_provenance: bool,
+    #[serde(default)]
+    loss_reveal_delay: Duration,
+    #[serde(default)]
+    throttle_on_battery: bool,
+    #[serde(default = "default_battery_min_delay")]
+    battery_min_delay: Duration,
+    #[serde(default)]
+    experiments: Experiments,
+    /// custom sizes that were played, most recent first
+    #[serde(default)]
+    recent_sizes: Vec<SerializableBoardSize>,
+    #[serde(default)]
+    risk_shading: bool,
+    #[serde(default)]
+    always_on_top: bool,
+    /// big openings spread out from the click instead of appearing at once
+    #[serde(default)]
+    animate_reveals: bool,
+    /// no confetti on a win or shake on a loss
+    #[serde(default)]
+    reduce_motion: bool,
+    /// a line under the header says what the hovered cell is and what the last move did, for
+    /// screen readers and magnifiers that follow text
+    #[serde(default)]
+    describe_cells: bool,
+    /// a line under the board about the hovered cell
+    #[serde(default)]
+    status_line: bool,
+    /// a panel beside the board with why the solver made its last few moves
+    #[serde(default)]
+    solver_explanations: bool,
+    /// everything in the window is drawn this many times bigger, on top of the system's own scale
+    #[serde(default = "default_ui_scale")]
+    ui_scale: f32,
+    #[serde(default)]
+    language: Language,
+    /// how many pixels a cell is in an exported png
+    #[serde(default = "default_image_cell_size")]
+    image_cell_size: u32,
+    /// the custom size dialog asks for a percentage instead of a mine count
+    #[serde(default)]
+    density_mode: bool,
+    /// in percent
+    #[serde(default = "default_custom_density")]
+    custom_density: u32,
+    /// the solver plays a copy of the board next to the player's, first to clear theirs wins
+    #[serde(default)]
+    arena: bool,
+    /// taken off a blitz's countdown for each board lost
+    #[serde(default = "default_blitz_penalty")]
+    blitz_penalty: Duration,
+    /// losing offers to take the click back
+    #[serde(default)]
+    forgiving: bool,
+    /// revealing a cell that's certainly a mine asks first
+    #[serde(default)]
+    mistake_guard: bool,
+    /// flags are off, only kept from one run of the app to the next with `keep_no_flags`
+    #[serde(default)]
+    no_flags: bool,
+    #[serde(default)]
+    keep_no_flags: bool,
+    /// held over a number to see what counts towards it
+    #[serde(default = "default_neighbour_highlight")]
+    neighbour_highlight: Option<HighlightModifier>,
+    /// how much goes into the log file
+    #[serde(default)]
+    log_level: LogLevel,
+    /// a game left alone for `idle_threshold` pauses itself so the time away isn't counted
+    #[serde(default = "enabled")]
+    idle_pause: bool,
+    #[serde(default = "default_idle_threshold")]
+    idle_threshold: Duration,
+    /// time and clicks per second in the board's corner, for recordings
+    #[serde(default)]
+    streamer_overlay: bool,
+    #[serde(default)]
+    overlay_seed: bool,
+    #[serde(default = "default_overlay_opacity")]
+    overlay_opacity: f32,
+    /// keeps the hovered cell, paths and the user's name out of what's on screen
+    #[serde(default)]
+    streamer_mode: bool,
+}
+
+fn default_neighbour_highlight() -> Option<HighlightModifier> {
+    Some(HighlightModifier::Alt)
+}
+
+fn default_idle_threshold() -> Duration {
+    Duration::from_secs(60)
+}
+
+fn default_overlay_opacity() -> f32 {
+    0.8
+}
+
+fn default_blitz_penalty() -> Duration {
+    Duration::from_secs(10)
+}
+
+fn enabled() -> bool {
+    true
+}
+
+fn default_volume() -> f32 {
+    0.7
+}
+
+fn default_time_limit() -> Duration {
+    Duration::from_secs(120)
+}
+
+fn default_custom_density() -> u32 {
+    20
+}
+
+pub const MIN_UI_SCALE: f32 = 0.5;
+pub const MAX_UI_SCALE: f32 = 3.0;
+/// how far ctrl plus and minus move the ui scale
+const UI_SCALE_STEP: f32 = 0.1;
+
+/// any fainter and the overlay can't be read over the board
+const MIN_OVERLAY_OPACITY: f32 = 0.2;
+
+fn default_ui_scale() -> f32 {
+    1.0
+}
+
+fn default_image_cell_size() -> u32 {
+    32
+}
+
+fn default_battery_min_delay() -> Duration {
+    Duration::from_millis(100)
+}
+
+fn default_step_preview() -> Duration {
+    minsweeper::DEFAULT_STEP_PREVIEW
+}
+
+fn default_hover_chord_delay() -> Duration {
+    Duration::from_millis(100)
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            version: CURRENT_VERSION,
+            size: SerializableBoardSize(ConventionalSize::Beginner.size()),
+            texture: Texture::default(),
+            follow_system: false,
+            app_theme: AppTheme::default(),
+            first_click: FirstClick::default(),
+            assist_solver: Some(KnownSolver::default()),
+            solver_mode: SolverMode::default(),
+            solver_chain: vec![],
+            external_solver: ExternalSettings::default(),
+            auto: false,
+            auto_settings: Auto::default(),
+            flag_chord: false,
+            drag_flag: true,
+            hover_chord: false,
+            hover_chord_delay: default_hover_chord_delay(),
+            mouse_bindings: MouseBindings::default(),
+            flag_mode: false,
+            solver_preview: true,
+            step_preview: default_step_preview(),
+            long_press_flag: false,
+            progress_ring: true,
+            show_progress: true,
+            seed: None,
+            rules: vec![],
+            time_limit: default_time_limit(),
+            chord_delay: Duration::ZERO,
+            show_game_over_dialog: true,
+            silent_auto: false,
+            retention: Retention::default(),
+            sound_enabled: true,
+            volume: default_volume(),
+            cell_scale: CellScale::default(),
+            counter_mode: CounterMode::default(),
+            board_rotation: BoardRotation::default(),
+            show_provenance: false,
+            loss_reveal_delay: Duration::ZERO,
+            throttle_on_battery: false,
+            battery_min_delay: default_battery_min_delay(),
+            experiments: Experiments::default(),
+            recent_sizes: vec![],
+            risk_shading: false,
+            always_on_top: false,
+            animate_reveals: false,
+            reduce_motion: false,
+            describe_cells: false,
+            status_line: false,
+            ui_scale: default_ui_scale(),
+            language: Language::default(),
+            image_cell_size: default_image_cell_size(),
+            density_mode: false,
+            custom_density: default_custom_density(),
+            arena: false,
+            forgiving: false,
+            mistake_guard: false,
+            no_flags: false,
+            keep_no_flags: false,
+            neighbour_highlight: default_neighbour_highlight(),
+            log_level: LogLevel::default(),
+            solver_explanations: false,
+            blitz_penalty: default_blitz_penalty(),
+            idle_pause: true,
+            idle_threshold: default_idle_threshold(),
+            streamer_overlay: false,
+            overlay_seed: false,
+            overlay_opacity: default_overlay_opacity(),
+            streamer_mode: false,
+        }
+    }
+}
+
+impl Settings {
+    /// writes to a temporary file and renames it over the old one so a crash mid write can't
+    /// leave a truncated file behind, returns what was written
+    fn save(&self) -> io::Result<String> {
+        Self::write(serde_json::to_string(self)?)
+    }
+
+    #[tracing::instrument(skip_all, err(Display))]
+    fn write(json: String) -> io::Result<String> {
+        let path = paths::settings_file()?;
+        let temp = path.with_extension("json.tmp");
+        let mut file = File::create(&temp)?;
+        file.write_all(json.as_bytes())?;
+        file.sync_all()?;
+        drop(file);
+
+        std::fs::rename(temp, path)?;
+        Ok(json)
+    }
+
+    /// a missing file isn't an error, there's just nothing saved yet. a file that can't be parsed
+    /// is moved aside so the next save doesn't overwrite whatever the user had in it
+    ///
+    /// files from older versions are migrated and written back, files from newer versions load
+    /// whatever this version understands and leave a warning in `warnings`
+    #[tracing::instrument(skip_all, err(Display))]
+    pub fn load(warnings: &mut Vec<SettingsError>) -> Result<Option<Self>, SettingsError> {
+        let mut path = paths::settings_file()
+                .map_err(|source| SettingsError::NoFolder { source })?;
+        if let Some(legacy) = paths::legacy_settings_file()
+                && legacy.exists() && !path.exists() {
+            match move_legacy(&legacy, &path) {
+                Ok(()) => tracing::info!("moved settings from {} to {}", legacy.display(), path.display()),
+                // read where they are, the next save puts them in the new place anyway
+                Err(e) => {
+                    tracing::warn!("failed to move settings from {}: {}", legacy.display(), e);
+                    path = legacy;
+                }
+            }
+        }
+        let file = match File::open(&path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(source) => return Err(SettingsError::Read { path, source }),
+        };
+
+        let mut value: Value = serde_json::from_reader(io::BufReader::new(file))
+                .map_err(|source| unreadable(path.clone(), source))?;
+        let version = value.get("version")
+                .and_then(Value::as_u64)
+                .unwrap_or(0) as u32;
+
+        if version > CURRENT_VERSION {
+            // keep the original around so going back to the newer version loses nothing
+            let copy = path.with_extension(format!("v{}.json", version));
+            let copy = std::fs::copy(&path, &copy)
+                    .is_ok()
+                    .then_some(copy);
+            let (settings, dropped) = Self::salvage(value);
+            warnings.push(SettingsError::Newer { path, version, dropped, copy });
+            return Ok(Some(settings))
+        }
+
+        migrate(&mut value, version);
+        let settings = Self::parse(value, warnings)
+                .map_err(|source| unreadable(path.clone(), source))?;
+        if version < CURRENT_VERSION && let Err(source) = settings.save() {
+            warnings.push(write_error(source));
+        }
+        Ok(Some(settings))
+    }
+
+    /// reads a file already migrated to the current version. a solver or texture this version
+    /// doesn't have is swapped for the default and left in `warnings` rather than losing the whole
+    /// file
+    fn parse(mut value: Value, warnings: &mut Vec<SettingsError>) -> serde_json::Result<Self> {
+        let unknown = [
+            ("/assist_solver", KnownSolver::default().id().into()),
+            ("/solver_chain", Value::Null),
+            ("/auto_settings/solver", Value::Null),
+        ].into_iter()
+                .flat_map(|(pointer, fallback)| solvers::forget_unknown(&mut value, pointer, fallback))
+                .collect::<Vec<_>>();
+        for id in unknown {
+            warnings.push(SettingsError::UnknownSolver { id });
+        }
+        if let Some(name) = texture::forget_unknown(&mut value, "/texture") {
+            warnings.push(SettingsError::UnknownTexture { name });
+        }
+        serde_json::from_value(value)
+    }
+
+    /// takes every field from `value` that parses on its own, returning the names of the ones that
+    /// didn't
+    fn salvage(value: Value) -> (Self, Vec<String>) {
+        let mut settings = serde_json::to_value(Self::default()).unwrap_or_default();
+        let mut dropped = vec![];
+        if let (Value::Object(fields), Value::Object(_)) = (value, &settings) {
+            for (name, field) in fields {
+                if name == "version" {
+                    continue
+                }
+                let mut candidate = settings.clone();
+                candidate[&name] = field;
+                if serde_json::from_value::<Self>(candidate.clone()).is_ok() {
+                    settings = candidate;
+                } else {
+                    dropped.push(name);
+                }
+            }
+        }
+        (serde_json::from_value(settings).unwrap_or_default(), dropped)
+    }
+
+    /// for sharing, the file on disk stays compact
+    pub fn to_pretty_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    pub fn size(&self) -> BoardSize {
+        self.size.0
+    }
+
+    pub fn texture(&self) -> Texture {
+        self.texture
+    }
+
+    /// the texture to draw with while the system appearance is `system`
+    pub fn texture_for(&self, system: theme::Mode) -> Texture {
+        match (self.follow_system, system) {
+            (true, theme::Mode::Light) => Texture::Light,
+            (true, theme::Mode::Dark) => Texture::Dark,
+            _ => self.texture,
+        }
+    }
+
+    pub fn app_theme(&self) -> &AppTheme {
+        &self.app_theme
+    }
+
+    /// what boards are generated with, the first click's guarantee followed by the assistant if it
+    /// only plays no-guess boards
+    pub fn opening(&self) -> SolverType {
+        let assistant = self.solver().filter(|_| self.needs_no_guess());
+        Arc::new(ChainedSolver(self.first_click.solver()
+                .into_iter()
+                .chain(assistant)
+                .collect()))
+    }
+
+    /// the assistant
+    pub fn solver(&self) -> Option<SolverType> {
+        match self.solver_choice() {
+            SolverChoice::Single(solver) => solver.map(Into::into),
+            SolverChoice::Chain => Some(Arc::new(ChainedSolver(self.solver_chain.iter().copied().map(Into::into).collect()))),
+            SolverChoice::External => match &self.external_solver.command {
+                Some(command) => Some(Arc::new(ExternalSolver::new(command.clone(), self.external_solver.timeout))),
+                None => self.assist_solver.map(Into::into),
+            },
+        }
+    }
+
+    /// what's played with, a chain that's empty or an external solver without a program falls back
+    /// to `assist_solver`
+    fn solver_choice(&self) -> SolverChoice {
+        match self.solver_mode {
+            SolverMode::Chain if !self.solver_chain.is_empty() => SolverChoice::Chain,
+            SolverMode::External if self.external_solver.command.is_some() => SolverChoice::External,
+            _ => SolverChoice::Single(self.assist_solver),
+        }
+    }
+
+    /// whether generated boards have to be solvable start to finish
+    fn needs_no_guess(&self) -> bool {
+        match self.solver_choice() {
+            SolverChoice::Single(solver) => solver.is_some_and(KnownSolver::needs_no_guess),
+            SolverChoice::Chain => chain_needs_no_guess(&self.solver_chain),
+            // it only ever gets safe starts
+            SolverChoice::External => false,
+        }
+    }
+
+    pub fn auto(&self) -> Option<&Auto> {
+        if self.auto {
+            Some(&self.auto_settings)
+        } else {
+            None
+        }
+    }
+
+    /// the auto settings with the delay raised to the battery minimum while throttling on battery,
+    /// the player gets no help while racing the solver
+    pub fn auto_for(&self, power: PowerSource) -> Option<AutoPlay> {
+        self.auto().filter(|_| !self.arena).map(|auto| AutoPlay {
+            solver: auto.solver.map(Into::into),
+            delay: self.throttle(power).delay,
+            guess: auto.guess,
+            restart: auto.restart.then_some(auto.restart_delay),
+        })
+    }
+
+    /// how the solver plays its side of the arena, the assistant at the auto delay, or the default
+    /// solver without one
+    pub fn arena_auto(&self, power: PowerSource) -> AutoPlay {
+        AutoPlay {
+            solver: self.solver().is_none().then(|| KnownSolver::default().into()),
+            delay: self.throttle(power).delay,
+            guess: self.auto_settings.guess,
+            // the race is over once a board is
+            restart: None,
+        }
+    }
+
+    pub fn experiments(&self) -> &Experiments {
+        &self.experiments
+    }
+
+    /// throttling for the auto delay as it's set
+    pub fn throttle(&self, power: PowerSource) -> power::Throttle {
+        power::throttle(self.throttle_on_battery, power, self.auto_settings.delay, self.battery_min_delay)
+    }
+
+    pub fn throttle_on_battery(&self) -> bool {
+        self.throttle_on_battery
+    }
+
+    pub fn flag_chord(&self) -> bool {
+        self.flag_chord
+    }
+
+    pub fn drag_flag(&self) -> bool {
+        self.drag_flag
+    }
+
+    pub fn hover_chord(&self) -> bool {
+        self.hover_chord
+    }
+
+    pub fn chord_delay(&self) -> Duration {
+        self.chord_delay
+    }
+
+    pub fn hover_chord_delay(&self) -> Duration {
+        self.hover_chord_delay
+    }
+
+    pub fn mouse_bindings(&self) -> MouseBindings {
+        self.mouse_bindings
+    }
+
+    pub fn flag_mode(&self) -> bool {
+        self.flag_mode
+    }
+
+    pub fn solver_preview(&self) -> bool {
+        self.solver_preview
+    }
+
+    pub fn step_preview(&self) -> Duration {
+        self.step_preview
+    }
+
+    pub fn long_press_flag(&self) -> bool {
+        self.long_press_flag
+    }
+
+    pub fn loss_reveal_delay(&self) -> Duration {
+        self.loss_reveal_delay
+    }
+
+    pub fn risk_shading(&self) -> bool {
+        self.risk_shading
+    }
+
+    pub fn always_on_top(&self) -> bool {
+        self.always_on_top
+    }
+
+    pub fn animate_reveals(&self) -> bool {
+        self.animate_reveals
+    }
+
+    pub fn reduce_motion(&self) -> bool {
+        self.reduce_motion
+    }
+
+    pub fn describe_cells(&self) -> bool {
+        self.describe_cells
+    }
+
+    pub fn status_line(&self) -> bool {
+        self.status_line
+    }
+
+    pub fn solver_explanations(&self) -> bool {
+        self.solver_explanations
+    }
+
+    pub fn language(&self) -> Language {
+        self.language
+    }
+
+    /// one the export offers, a hand edited file can say anything
+    pub fn image_cell_size(&self) -> u32 {
+        if share::IMAGE_CELL_SIZES.contains(&self.image_cell_size) {
+            self.image_cell_size
+        } else {
+            default_image_cell_size()
+        }
+    }
+
+    pub fn arena(&self) -> bool {
+        self.arena
+    }
+
+    pub fn blitz_penalty(&self) -> Duration {
+        self.blitz_penalty
+    }
+
+    /// there's nothing to forgive in a race against the solver
+    pub fn forgiving(&self) -> bool {
+        self.forgiving && !self.arena
+    }
+
+    pub fn mistake_guard(&self) -> bool {
+        self.mistake_guard
+    }
+
+    pub fn no_flags(&self) -> bool {
+        self.no_flags
+    }
+
+    pub fn neighbour_highlight(&self) -> Option<HighlightModifier> {
+        self.neighbour_highlight
+    }
+
+    pub fn log_level(&self) -> LogLevel {
+        self.log_level
+    }
+
+    pub fn idle_pause(&self) -> Option<Duration> {
+        self.idle_pause.then_some(self.idle_threshold)
+    }
+
+    pub fn overlay(&self) -> Option<Overlay> {
+        self.streamer_overlay.then_some(Overlay {
+            seed: self.overlay_seed,
+            opacity: self.overlay_opacity.clamp(MIN_OVERLAY_OPACITY, 1.0),
+        })
+    }
+
+    pub fn streamer_mode(&self) -> bool {
+        self.streamer_mode
+    }
+
+    /// a hand edited settings file can say anything, the window has to stay usable
+    pub fn ui_scale(&self) -> f32 {
+        if self.ui_scale.is_finite() {
+            self.ui_scale.clamp(MIN_UI_SCALE, MAX_UI_SCALE)
+        } else {
+            default_ui_scale()
+        }
+    }
+
+    pub fn progress_ring(&self) -> bool {
+        self.progress_ring
+    }
+
+    pub fn show_progress(&self) -> bool {
+        self.show_progress
+    }
+
+    pub fn show_game_over_dialog(&self) -> bool {
+        self.show_game_over_dialog
+    }
+
+    pub fn silent_auto(&self) -> bool {
+        self.silent_auto
+    }
+
+    pub fn volume(&self) -> f32 {
+        if self.sound_enabled {
+            self.volume
+        } else {
+            0.0
+        }
+    }
+
+    pub fn cell_scale(&self) -> CellScale {
+        self.cell_scale
+    }
+
+    pub fn counter_mode(&self) -> CounterMode {
+        self.counter_mode
+    }
+
+    pub fn board_rotation(&self) -> BoardRotation {
+        self.board_rotation
+    }
+
+    pub fn show_provenance(&self) -> bool {
+        self.show_provenance
+    }
+
+    pub fn retention(&self) -> Retention {
+        self.retention
+    }
+
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    pub fn rules(&self) -> RuleStack {
+        assemble_rules(&self.rules, self.time_limit)
+                .unwrap_or_else(|e| {
+                    tracing::warn!("ignoring rules: {}", e);
+                    RuleStack::default()
+                })
+    }
+}
+
+/// writes whatever changes are still waiting to be saved, for the panic hook, which can't wait on
+/// the lock in case it's the one that panicked holding it
+pub fn save_unsaved() {
+    if let Ok(mut unsaved) = UNSAVED.try_lock()
+            && let Some(json) = unsaved.take() {
+        let _ = Settings::write(json);
+    }
+}
+
+fn assemble_rules(rules: &[KnownRule], time_limit: Duration) -> Result<RuleStack, RuleConflict> {
+    let mut stack = RuleStack::default();
+    for rule in rules {
+        stack.push(rule.make(time_limit))?;
+    }
+    Ok(stack)
+}
+
+#[derive(Debug)]
+pub struct SettingsMenu {
+    settings: Settings,
+    custom_width: usize,
+    custom_height: usize,
+    custom_mines: usize,
+    custom_confirmed: bool,
+    /// what the solver chain dialog shows until it's applied
+    chain_draft: Vec<KnownSolver>,
+    external_draft: ExternalSettings,
+    /// what the last test of the external solver came back with
+    external_test: Option<String>,
+    seed_input: String,
+    /// what the mouse buttons dialog shows until it's applied
+    bindings_draft: MouseBindings,
+    rule_conflict: Option<String>,
+    experiments_dialog: bool,
+    /// an experiment waiting for the user to confirm turning it off
+    disabling: Option<Experiment>,
+    refusal: Option<String>,
+    /// fields the command line changed for this session, with what they were before and what they
+    /// were changed to. those are saved as they were before unless they've been changed since
+    overridden: Vec<(String, Value, Value)>,
+    /// the json last written to disk, `None` if the file doesn't match anything we know of
+    saved: Option<String>,
+    save_scheduled: bool,
+    /// set once saving failed, from then on settings only live in memory
+    save_failed: bool,
+    /// shown one at a time, oldest first
+    errors: Vec<SettingsError>,
+    /// what the logs dialog shows, read when it's opened
+    logs: String,
+}
+
+impl Default for SettingsMenu {
+    fn default() -> Self {
+        let mut errors = vec![];
+        let mut settings = Settings::load(&mut errors)
+                .unwrap_or_else(|e| {
+                    errors.push(e);
+                    None
+                })
+                .unwrap_or_default();
+        // a no flags session is a one off unless asked otherwise
+        if !settings.keep_no_flags {
+            settings.no_flags = false;
+        }
+        let (custom_width, custom_height, custom_mines) = settings.recent_sizes.first()
+                .map(|recent| (recent.0.width().get(), recent.0.height().get(), recent.0.mines().get()))
+                .unwrap_or((10, 10, 10));
+        let mut menu = Self {
+            saved: serde_json::to_string(&settings).ok(),
+            settings,
+            custom_width,
+            custom_height,
+            custom_mines,
+            custom_confirmed: false,
+            chain_draft: vec![],
+            external_draft: ExternalSettings::default(),
+            external_test: None,
+            seed_input: String::new(),
+            bindings_draft: MouseBindings::default(),
+            rule_conflict: None,
+            experiments_dialog: false,
+            disabling: None,
+            refusal: None,
+            overridden: vec![],
+            save_scheduled: false,
+            save_failed: false,
+            errors,
+            logs: String::new(),
+        };
+        menu.apply_density();
+        menu
+    }
+}
+
+#[derive(Clone, Debug, From)]
+pub enum Message {
+    MenuLabel,
+    ChangeSize(BoardSize),
+    ChangeTexture(Texture),
+    ChangeLanguage(Language),
+    ChangeFirstClick(FirstClick),
+    /// the assistant, `None` for none
+    #[from(skip)]
+    ChangeSolver(Option<KnownSolver>),
+    /// plays with the chain that was last put together
+    #[from(skip)]
+    UseSolverChain,
+    #[from(skip)]
+    SolverChainDialog,
+    EditSolverChain(ChainEdit),
+    #[from(skip)]
+    ApplySolverChain,
+    #[from(skip)]
+    UseExternalSolver,
+    #[from(skip)]
+    ExternalSolverDialog,
+    #[from(skip)]
+    PickExternalSolver,
+    #[from(skip)]
+    ExternalSolverPicked(Option<PathBuf>),
+    #[from(skip)]
+    ChangeExternalTimeout(Duration),
+    /// runs the program being configured once against the board being played
+    #[from(skip)]
+    TestExternalSolver,
+    #[from(skip)]
+    ExternalSolverTested(String),
+    #[from(skip)]
+    ApplyExternalSolver,
+    Dialog(dialog::Message),
+    CustomSizeUpdate(usize, usize, usize),
+    #[from(skip)]
+    CustomDensityMode(bool),
+    #[from(skip)]
+    CustomDensity(u32),
+    ConfirmCustomSize,
+    DismissRefusal,
+    FlushSettings,
+    DismissError,
+    OpenSettingsFolder,
+    #[from(skip)]
+    Auto(bool),
+    #[from(skip)]
+    FlagChord(bool),
+    #[from(skip)]
+    DragFlag(bool),
+    #[from(skip)]
+    HoverChord(bool),
+    #[from(skip)]
+    ProgressRing(bool),
+    #[from(skip)]
+    ShowProgress(bool),
+    #[from(skip)]
+    RiskShading(bool),
+    #[from(skip)]
+    AlwaysOnTop(bool),
+    #[from(skip)]
+    AnimateReveals(bool),
+    #[from(skip)]
+    ReduceMotion(bool),
+    #[from(skip)]
+    DescribeCells(bool),
+    #[from(skip)]
+    StatusLine(bool),
+    #[from(skip)]
+    SolverExplanations(bool),
+    #[from(skip)]
+    ChangeUiScale(f32),
+    /// ctrl plus and minus, by this many steps
+    #[from(skip)]
+    NudgeUiScale(i8),
+    #[from(skip)]
+    FollowSystem(bool),
+    ChangeAppTheme(AppTheme),
+    ChangeAutoSolver(Option<KnownSolver>),
+    ChangeAutoDelay(Duration),
+    ChangeAutoGuess(AutoGuess),
+    #[from(skip)]
+    AutoRestart(bool),
+    #[from(skip)]
+    ChangeAutoRestartDelay(Duration),
+    #[from(skip)]
+    Statistics,
+    #[from(skip)]
+    Achievements,
+    #[from(skip)]
+    SpectatorWindow,
+    #[from(skip)]
+    Pause,
+    #[from(skip)]
+    SeedDialog,
+    #[from(skip)]
+    SeedInput(String),
+    ChangeSeed(Option<u64>),
+    #[from(skip)]
+    CopySeed,
+    ToggleRule(KnownRule, bool),
+    #[from(skip)]
+    ChangeTimeLimit(Duration),
+    /// the time limit slider was let go, a running time trial only picks the limit up then
+    #[from(skip)]
+    ApplyTimeLimit,
+    #[from(skip)]
+    DismissRuleConflict,
+    #[from(skip)]
+    ChangeChordDelay(Duration),
+    #[from(skip)]
+    ChangeHoverChordDelay(Duration),
+    #[from(skip)]
+    FlagMode(bool),
+    #[from(skip)]
+    LongPressFlag(bool),
+    #[from(skip)]
+    SolverPreview(bool),
+    #[from(skip)]
+    ChangeStepPreview(Duration),
+    #[from(skip)]
+    Step,
+    /// resizes the main window around the board
+    #[from(skip)]
+    FitWindow,
+    #[from(skip)]
+    Fullscreen,
+    #[from(skip)]
+    MouseBindingsDialog,
+    EditMouseBindings(MouseBindings),
+    #[from(skip)]
+    ApplyMouseBindings,
+    #[from(skip)]
+    GameOverDialog(bool),
+    #[from(skip)]
+    SilentAuto(bool),
+    #[from(skip)]
+    Storage,
+    #[from(skip)]
+    InstallTexturePack,
+    #[from(skip)]
+    ExperimentsDialog(bool),
+    ToggleExperiment(Experiment, bool),
+    /// turns an experiment off after confirming
+    #[from(skip)]
+    DisableExperiment(Experiment),
+    #[from(skip)]
+    CancelDisableExperiment,
+    #[from(skip)]
+    ToggleLatencyOverlay,
+    #[from(skip)]
+    ExportSettings,
+    #[from(skip)]
+    ImportSettings,
+    #[from(skip)]
+    OpenBoard,
+    /// writes the board being played in the named dialect
+    #[from(skip)]
+    SaveBoard(&'static str),
+    #[from(skip)]
+    CopyBoard,
+    #[from(skip)]
+    ExportBoardImage,
+    #[from(skip)]
+    ChangeImageCellSize(u32),
+    /// opens the editor on a blank board of the configured size
+    #[from(skip)]
+    EditBoard,
+    #[from(skip)]
+    Arena(bool),
+    #[from(skip)]
+    Forgiving(bool),
+    #[from(skip)]
+    MistakeGuard(bool),
+    #[from(skip)]
+    IdlePause(bool),
+    #[from(skip)]
+    ChangeIdleThreshold(Duration),
+    #[from(skip)]
+    StreamerOverlay(bool),
+    #[from(skip)]
+    OverlaySeed(bool),
+    #[from(skip)]
+    ChangeOverlayOpacity(f32),
+    #[from(skip)]
+    StreamerMode(bool),
+    #[from(skip)]
+    NoFlags(bool),
+    #[from(skip)]
+    KeepNoFlags(bool),
+    ChangeNeighbourHighlight(Option<HighlightModifier>),
+    ChangeLogLevel(LogLevel),
+    /// the last lines of the log, in a dialog for pasting into bug reports
+    #[from(skip)]
+    ShowLogs,
+    #[from(skip)]
+    CopyLogs,
+    #[from(skip)]
+    Blitz,
+    #[from(skip)]
+    ChangeBlitzPenalty(Duration),
+    #[from(skip)]
+    BrowseBoards,
+    ImportChosen(Option<PathBuf>),
+    Imported(PathBuf, Result<String, ImportError>),
+    ChangeRetention(Retention),
+    #[from(skip)]
+    Sound(bool),
+    ChangeVolume(f32),
+    ChangeCellScale(CellScale),
+    ChangeCounterMode(CounterMode),
+    ChangeBoardRotation(BoardRotation),
+    #[from(skip)]
+    ShowProvenance(bool),
+    #[from(skip)]
+    ChangeLossRevealDelay(Duration),
+    #[from(skip)]
+    ThrottleOnBattery(bool),
+    #[from(skip)]
+    ChangeBatteryMinDelay(Duration),
+}
+
+impl Message {
+    /// whether this only opens, dismisses or saves something instead of changing how the game plays
+    pub fn is_passive(&self) -> bool {
+        matches!(self, Message::MenuLabel | Message::Dialog(_) | Message::FlushSettings
+                | Message::DismissRefusal | Message::DismissError | Message::DismissRuleConflict
+                | Message::OpenSettingsFolder | Message::Statistics | Message::Achievements | Message::SpectatorWindow | Message::Pause | Message::Step | Message::FitWindow | Message::Fullscreen | Message::SeedDialog
+                | Message::MouseBindingsDialog | Message::EditMouseBindings(_) | Message::FlagMode(_)
+                | Message::SolverChainDialog | Message::EditSolverChain(_) | Message::ExternalSolverDialog
+                | Message::PickExternalSolver | Message::ExternalSolverPicked(_) | Message::ChangeExternalTimeout(_)
+                | Message::TestExternalSolver | Message::ExternalSolverTested(_)
+                | Message::SeedInput(_) | Message::CopySeed | Message::Storage | Message::ExperimentsDialog(_)
+                | Message::ExportSettings | Message::ImportSettings | Message::ImportChosen(_)
+                | Message::OpenBoard | Message::SaveBoard(_) | Message::CopyBoard | Message::ExportBoardImage
+                | Message::ChangeImageCellSize(_) | Message::EditBoard | Message::BrowseBoards | Message::Blitz
+                | Message::ChangeBlitzPenalty(_) | Message::InstallTexturePack
+                | Message::ToggleLatencyOverlay | Message::CancelDisableExperiment
+                | Message::ChangeUiScale(_) | Message::NudgeUiScale(_) | Message::ChangeLanguage(_) | Message::ChangeAppTheme(_)
+                | Message::ChangeLogLevel(_) | Message::ShowLogs | Message::CopyLogs)
+    }
+}
+
+impl SettingsMenu {
+
+    /// the saved settings with the command line's on top
+    pub fn with_launch(launch: &Launch) -> Self {
+        let mut menu = Self::default();
+        let clean = !menu.dirty();
+        let before = serde_json::to_value(&menu.settings).unwrap_or_default();
+
+        if let Some(size) = launch.size {
+            menu.settings.size = SerializableBoardSize(size);
+        }
+        if let Some(texture) = launch.texture {
+            menu.settings.texture = texture;
+            menu.settings.follow_system = false;
+        }
+        if let Some(solver) = launch.solver {
+            match FirstClick::replacing(solver.id()) {
+                Some(first_click) => menu.settings.first_click = first_click,
+                None => {
+                    menu.settings.assist_solver = Some(solver);
+                    menu.settings.solver_mode = SolverMode::Single;
+                }
+            }
+        }
+        if launch.auto {
+            menu.settings.auto = true;
+        }
+
+        if launch.save {
+            menu.flush();
+            return menu
+        }
+        if let (Value::Object(before), Ok(Value::Object(after))) = (before, serde_json::to_value(&menu.settings)) {
+            menu.overridden = after.into_iter()
+                    .filter_map(|(name, value)| {
+                        let was = before.get(&name)?;
+                        (*was != value).then(|| (name, was.clone(), value))
+                    })
+                    .collect();
+        }
+        if clean {
+            menu.saved = menu.persisted();
+        }
+        menu
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// why the last size or solver change wasn't applied, while its dialog is still open
+    pub fn refusal(&self) -> Option<&str> {
+        self.refusal.as_deref()
+    }
+
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        match message {
+            Message::ChangeSize(size) => {
+                if let Some(refusal) = no_guess_refusal(size, self.settings.needs_no_guess()) {
+                    self.refusal = Some(refusal);
+                    return Task::none()
+                }
+                self.settings.size = SerializableBoardSize(size);
+                if !conventional(size) {
+                    self.settings.recent_sizes.retain(|recent| recent.0 != size);
+                    self.settings.recent_sizes.insert(0, SerializableBoardSize(size));
+                    self.settings.recent_sizes.truncate(RECENT_SIZES);
+                }
+                return Task::batch([
+                    Task::done(dialog::Message::Close(DialogId::CustomSize).into()),
+                    self.schedule_save(),
+                ])
+            },
+            Message::ChangeTexture(texture) => {
+                self.settings.texture = texture;
+                self.settings.follow_system = false;
+            }
+            Message::FollowSystem(value) => self.settings.follow_system = value,
+            Message::ChangeAppTheme(app_theme) => self.settings.app_theme = app_theme,
+            Message::ChangeFirstClick(first_click) => self.settings.first_click = first_click,
+            Message::ChangeSolver(solver) => {
+                if let Some(refusal) = no_guess_refusal(self.settings.size.0, solver.is_some_and(KnownSolver::needs_no_guess)) {
+                    self.refusal = Some(refusal);
+                    return Task::none()
+                }
+                self.settings.assist_solver = solver;
+                self.settings.solver_mode = SolverMode::Single;
+            }
+            Message::UseSolverChain => {
+                if let Some(refusal) = no_guess_refusal(self.settings.size.0, chain_needs_no_guess(&self.settings.solver_chain)) {
+                    self.refusal = Some(refusal);
+                    return Task::none()
+                }
+                if !self.settings.solver_chain.is_empty() {
+                    self.settings.solver_mode = SolverMode::Chain;
+                }
+            }
+            Message::SolverChainDialog => {
+                self.chain_draft = match self.settings.solver_chain.is_empty() {
+                    true => self.settings.assist_solver.into_iter().collect(),
+                    false => self.settings.solver_chain.clone(),
+                };
+                return Task::done(dialog::Message::Open(DialogId::SolverChain).into())
+            }
+            Message::EditSolverChain(edit) => match edit {
+                ChainEdit::Add(solver) => self.chain_draft.push(solver),
+                ChainEdit::Remove(index) => {
+                    if index < self.chain_draft.len() {
+                        self.chain_draft.remove(index);
+                    }
+                }
+                ChainEdit::MoveUp(index) => {
+                    if (1..self.chain_draft.len()).contains(&index) {
+                        self.chain_draft.swap(index - 1, index);
+                    }
+                }
+            },
+            Message::UseExternalSolver => {
+                if self.settings.external_solver.command.is_some() {
+                    self.settings.solver_mode = SolverMode::External;
+                }
+            }
+            Message::ExternalSolverDialog => {
+                self.external_draft = self.settings.external_solver.clone();
+                self.external_test = None;
+                return Task::done(dialog::Message::Open(DialogId::ExternalSolver).into())
+            }
+            Message::PickExternalSolver => {
+                return Task::future(rfd::AsyncFileDialog::new().pick_file())
+                        .map(|handle| Message::ExternalSolverPicked(handle.map(|handle| handle.path().to_path_buf())))
+            }
+            Message::ExternalSolverPicked(command) => {
+                if command.is_some() {
+                    self.external_draft.command = command;
+                    self.external_test = None;
+                }
+            }
+            Message::ChangeExternalTimeout(timeout) => self.external_draft.timeout = timeout,
+            // needs the board, which the app hands to [`Self::test_external_solver`]
+            Message::TestExternalSolver => {}
+            Message::ExternalSolverTested(result) => self.external_test = Some(result),
+            Message::ApplyExternalSolver => {
+                if self.external_draft.command.is_none() {
+                    return Task::none()
+                }
+                self.settings.external_solver = self.external_draft.clone();
+                self.settings.solver_mode = SolverMode::External;
+                return Task::batch([
+                    Task::done(dialog::Message::Close(DialogId::ExternalSolver).into()),
+                    self.schedule_save(),
+                ])
+            }
+            Message::ApplySolverChain => {
+                if self.chain_problem().is_some() {
+                    return Task::none()
+                }
+                self.settings.solver_chain = self.chain_draft.clone();
+                self.settings.solver_mode = SolverMode::Chain;
+                return Task::batch([
+                    Task::done(dialog::Message::Close(DialogId::SolverChain).into()),
+                    self.schedule_save(),
+                ])
+            }
+            Message::MenuLabel => {},
+            Message::Dialog(_) => {}
+            Message::CustomSizeUpdate(width, height, mines) =>  {
+                self.custom_width = width;
+                self.custom_height = height;
+                self.custom_mines = mines.clamp(1, max_mines(width, height));
+                self.custom_confirmed = false;
+                self.apply_density();
+            },
+            Message::CustomDensityMode(value) => {
+                self.settings.density_mode = value;
+                self.custom_confirmed = false;
+                self.apply_density();
+            }
+            Message::CustomDensity(percent) => {
+                self.settings.custom_density = percent.clamp(1, MAX_DENSITY_PERCENT);
+                self.custom_confirmed = false;
+                self.apply_density();
+            }
+            Message::ConfirmCustomSize => self.custom_confirmed = true,
+            Message::DismissRefusal => self.refusal = None,
+            Message::FlushSettings => {
+                self.save_scheduled = false;
+                self.flush();
+            }
+            Message::DismissError => {
+                if !self.errors.is_empty() {
+                    self.errors.remove(0);
+                }
+            }
+            Message::OpenSettingsFolder => match paths::config_dir() {
+                Ok(folder) => if let Err(e) = export::open_folder(&folder) {
+                    self.errors.push(SettingsError::OpenFolder { path: folder, source: e });
+                }
+                Err(source) => self.errors.push(SettingsError::NoFolder { source }),
+            }
+            Message::Auto(value) => {
+                self.settings.auto = value;
+            }
+            Message::FlagChord(value) => {
+                self.settings.flag_chord = value;
+            }
+            Message::DragFlag(value) => {
+                self.settings.drag_flag = value;
+            }
+            Message::HoverChord(value) => {
+                self.settings.hover_chord = value;
+            }
+            Message::ProgressRing(value) => {
+                self.settings.progress_ring = value;
+            }
+            Message::ShowProgress(value) => {
+                self.settings.show_progress = value;
+            }
+            Message::RiskShading(value) => {
+                self.settings.risk_shading = value;
+            }
+            Message::AlwaysOnTop(value) => {
+                self.settings.always_on_top = value;
+            }
+            Message::AnimateReveals(value) => {
+                self.settings.animate_reveals = value;
+            }
+            Message::ReduceMotion(value) => {
+                self.settings.reduce_motion = value;
+            }
+            Message::DescribeCells(value) => {
+                self.settings.describe_cells = value;
+            }
+            Message::StatusLine(value) => {
+                self.settings.status_line = value;
+            }
+            Message::SolverExplanations(value) => {
+                self.settings.solver_explanations = value;
+            }
+            Message::Arena(value) => {
+                self.settings.arena = value;
+            }
+            Message::Forgiving(value) => {
+                self.settings.forgiving = value;
+            }
+            Message::MistakeGuard(value) => {
+                self.settings.mistake_guard = value;
+            }
+            Message::IdlePause(value) => {
+                self.settings.idle_pause = value;
+            }
+            Message::ChangeIdleThreshold(threshold) => {
+                self.settings.idle_threshold = threshold;
+            }
+            Message::StreamerOverlay(value) => {
+                self.settings.streamer_overlay = value;
+            }
+            Message::OverlaySeed(value) => {
+                self.settings.overlay_seed = value;
+            }
+            Message::ChangeOverlayOpacity(opacity) => {
+                self.settings.overlay_opacity = opacity;
+            }
+            Message::StreamerMode(value) => {
+                self.settings.streamer_mode = value;
+            }
+            Message::NoFlags(value) => {
+                self.settings.no_flags = value;
+            }
+            Message::KeepNoFlags(value) => {
+                self.settings.keep_no_flags = value;
+            }
+            Message::ChangeNeighbourHighlight(modifier) => {
+                self.settings.neighbour_highlight = modifier;
+            }
+            Message::ChangeLogLevel(level) => {
+                self.settings.log_level = level;
+            }
+            Message::ShowLogs => {
+                self.logs = logging::tail(LOG_TAIL)
+                        .unwrap_or_else(|e| t!("logs-unreadable", error = e));
+                return Task::done(dialog::Message::Open(DialogId::Logs).into())
+            }
+            Message::ChangeLanguage(language) => {
+                self.settings.language = language;
+            }
+            Message::ChangeUiScale(scale) => {
+                self.settings.ui_scale = scale.clamp(MIN_UI_SCALE, MAX_UI_SCALE);
+            }
+            Message::NudgeUiScale(steps) => {
+                // rounded so repeated steps don't drift away from the slider's marks
+                let scale = self.settings.ui_scale() + steps as f32 * UI_SCALE_STEP;
+                self.settings.ui_scale = ((scale * 10.0).round() / 10.0).clamp(MIN_UI_SCALE, MAX_UI_SCALE);
+            }
+            Message::ChangeAutoSolver(solver) => {
+                self.settings.auto_settings.solver = solver;
+            }
+            Message::ChangeAutoDelay(delay) => {
+                self.settings.auto_settings.delay = delay;
+            }
+            Message::ChangeAutoGuess(guess) => {
+                self.settings.auto_settings.guess = guess;
+            }
+            Message::AutoRestart(value) => {
+                self.settings.auto_settings.restart = value;
+            }
+            Message::ChangeAutoRestartDelay(delay) => {
+                self.settings.auto_settings.restart_delay = delay;
+            }
+            Message::Statistics | Message::Achievements | Message::SpectatorWindow | Message::Pause | Message::Step | Message::FitWindow | Message::Fullscreen | Message::CopySeed | Message::Storage | Message::InstallTexturePack
+                    | Message::ExportSettings | Message::ImportChosen(None)
+                    | Message::OpenBoard | Message::SaveBoard(_) | Message::CopyBoard | Message::ExportBoardImage
+                    | Message::EditBoard | Message::BrowseBoards | Message::Blitz | Message::CopyLogs => {}
+            Message::ChangeBlitzPenalty(penalty) => {
+                self.settings.blitz_penalty = penalty;
+            }
+            Message::ChangeImageCellSize(cell_size) => {
+                self.settings.image_cell_size = cell_size;
+            }
+            Message::ImportSettings => {
+                return Task::future(rfd::AsyncFileDialog::new()
+                        .add_filter("JSON", &["json"])
+                        .pick_file())
+                        .map(|handle| Message::ImportChosen(handle.map(|handle| handle.path().to_path_buf())))
+            }
+            Message::ImportChosen(Some(path)) => {
+                return Task::future(async move {
+                    let result = tokio::task::spawn_blocking({
+                        let path = path.clone();
+                        move || read_import(&path)
+                    }).await.unwrap_or_else(|e| Err(ImportError::Unreadable(e.to_string())));
+                    Message::Imported(path, result)
+                })
+            }
+            Message::Imported(path, result) => match result.and_then(|json| serde_json::from_str(&json)
+                    .map_err(|e| ImportError::Unreadable(e.to_string()))) {
+                Ok(settings) => {
+                    self.settings = settings;
+                    self.overridden.clear();
+                    self.flush();
+                }
+                Err(error) => self.errors.push(SettingsError::Import { path, error }),
+            },
+            Message::SeedDialog => {
+                self.seed_input = self.settings.seed
+                        .map(|seed| format!("{:#x}", seed))
+                        .unwrap_or_default();
+                return Task::done(dialog::Message::Open(DialogId::Seed).into())
+            }
+            Message::SeedInput(input) => self.seed_input = input,
+            Message::ChangeSeed(seed) => {
+                self.settings.seed = seed;
+                return Task::batch([
+                    Task::done(dialog::Message::Close(DialogId::Seed).into()),
+                    self.schedule_save(),
+                ])
+            }
+            Message::ToggleRule(rule, value) => {
+                let mut rules = self.settings.rules.clone();
+                rules.retain(|e| *e != rule);
+                if value {
+                    rules.push(rule);
+                    rules.sort();
+                }
+                match assemble_rules(&rules, self.settings.time_limit) {
+                    Ok(_) => self.settings.rules = rules,
+                    Err(e) => self.rule_conflict = Some(e.to_string()),
+                }
+            }
+            Message::ChangeTimeLimit(limit) => {
+                self.settings.time_limit = limit;
+            }
+            Message::ApplyTimeLimit => {}
+            Message::DismissRuleConflict => self.rule_conflict = None,
+            Message::ExperimentsDialog(value) => self.experiments_dialog = value,
+            Message::ToggleExperiment(experiment, value) => {
+                if !value && experiment.confirm_disable.is_some() {
+                    self.disabling = Some(experiment);
+                } else {
+                    self.settings.experiments.set(experiment, value);
+                }
+            }
+            Message::DisableExperiment(experiment) => {
+                self.disabling = None;
+                self.settings.experiments.set(experiment, false);
+            }
+            Message::CancelDisableExperiment => self.disabling = None,
+            Message::ToggleLatencyOverlay => {}
+            Message::ChangeChordDelay(delay) => {
+                self.settings.chord_delay = delay;
+            }
+            Message::ChangeHoverChordDelay(delay) => {
+                self.settings.hover_chord_delay = delay;
+            }
+            Message::FlagMode(value) => {
+                self.settings.flag_mode = value;
+            }
+            Message::LongPressFlag(value) => {
+                self.settings.long_press_flag = value;
+            }
+            Message::SolverPreview(value) => {
+                self.settings.solver_preview = value;
+            }
+            Message::ChangeStepPreview(delay) => {
+                self.settings.step_preview = delay;
+            }
+            Message::MouseBindingsDialog => {
+                self.bindings_draft = self.settings.mouse_bindings;
+                return Task::done(dialog::Message::Open(DialogId::MouseBindings).into())
+            }
+            Message::EditMouseBindings(bindings) => self.bindings_draft = bindings,
+            Message::ApplyMouseBindings => {
+                if self.bindings_draft.problem().is_some() {
+                    return Task::none()
+                }
+                self.settings.mouse_bindings = self.bindings_draft;
+                return Task::batch([
+                    Task::done(dialog::Message::Close(DialogId::MouseBindings).into()),
+                    self.schedule_save(),
+                ])
+            }
+            Message::GameOverDialog(value) => {
+                self.settings.show_game_over_dialog = value;
+            }
+            Message::SilentAuto(value) => {
+                self.settings.silent_auto = value;
+            }
+            Message::ChangeRetention(retention) => {
+                self.settings.retention = retention;
+            }
+            Message::Sound(value) => {
+                self.settings.sound_enabled = value;
+            }
+            Message::ChangeVolume(volume) => {
+                self.settings.volume = volume;
+            }
+            Message::ChangeCellScale(cell_scale) => {
+                self.settings.cell_scale = cell_scale;
+            }
+            Message::ChangeCounterMode(counter_mode) => {
+                self.settings.counter_mode = counter_mode;
+            }
+            Message::ChangeBoardRotation(board_rotation) => {
+                self.settings.board_rotation = board_rotation;
+            }
+            Message::ShowProvenance(value) => {
+                self.settings.show_provenance = value;
+            }
+            Message::ChangeLossRevealDelay(delay) => {
+                self.settings.loss_reveal_delay = delay;
+            }
+            Message::ThrottleOnBattery(value) => {
+                self.settings.throttle_on_battery = value;
+            }
+            Message::ChangeBatteryMinDelay(delay) => {
+                self.settings.battery_min_delay = delay;
+            }
+        }
+
+        self.schedule_save()
+    }
+
+    fn dirty(&self) -> bool {
+        self.persisted() != self.saved
+    }
+
+    /// what goes on disk, which leaves out whatever the command line overrode
+    fn persisted(&self) -> Option<String> {
+        if self.overridden.is_empty() {
+            return serde_json::to_string(&self.settings).ok()
+        }
+        let mut value = serde_json::to_value(&self.settings).ok()?;
+        for (name, was, overridden) in &self.overridden {
+            if value.get(name) == Some(overridden) {
+                value[name] = was.clone();
+            }
+        }
+        serde_json::to_string(&value).ok()
+    }
+
+    fn schedule_save(&mut self) -> Task<Message> {
+        if self.save_failed || !self.dirty() {
+            return Task::none()
+        }
+        if let Ok(mut unsaved) = UNSAVED.lock() {
+            *unsaved = self.persisted();
+        }
+        if self.save_scheduled {
+            return Task::none()
+        }
+        self.save_scheduled = true;
+        Task::future(tokio::time::sleep(SAVE_DEBOUNCE))
+                .map(|_| Message::FlushSettings)
+    }
+
+    /// writes pending changes now instead of waiting for the debounce
+    pub fn flush(&mut self) {
+        if self.save_failed || !self.dirty() {
+            return
+        }
+        let Some(json) = self.persisted() else { return };
+        match Settings::write(json) {
+            Ok(json) => {
+                self.saved = Some(json);
+                if let Ok(mut unsaved) = UNSAVED.lock() {
+                    *unsaved = None;
+                }
+            }
+            Err(source) => {
+                self.save_failed = true;
+                self.errors.push(write_error(source));
+            }
+        }
+    }
+
+    /// in density mode the mine count follows the board size
+    fn apply_density(&mut self) {
+        if self.settings.density_mode {
+            let cells = self.custom_width.saturating_mul(self.custom_height);
+            let mines = (cells as f64 * self.settings.custom_density as f64 / 100.0).round() as usize;
+            self.custom_mines = mines.clamp(1, max_mines(self.custom_width, self.custom_height));
+        }
+    }
+
+    /// what Done in the custom size dialog does right now, if it can be pressed
+    pub fn custom_size_action(&self) -> Option<Message> {
+        let size = BoardSize::new(self.custom_width, self.custom_height, self.custom_mines).ok()?;
+        if no_guess_refusal(size, self.settings.needs_no_guess()).is_some() {
+            None
+        } else if pathology::assess(self.custom_width, self.custom_height, self.custom_mines).is_some() && !self.custom_confirmed {
+            Some(Message::ConfirmCustomSize)
+        } else {
+            Some(Message::ChangeSize(size))
+        }
     }
 
-    folder.join("settings.json")
-});
+    /// the bar sits on the texture's header like the board sits on its background, and the menus
+    /// it opens on the background
+    pub fn view(&self, manifest: Manifest) -> Element<'_, Message> {
+        let solver_choice = self.settings.solver_choice();
+        container(menu_bar!(
+            (menu_label(t!("menu-file")), menu!(
+                (menu_button(t!("import-settings"), Message::ImportSettings)),
+                (menu_button(t!("export-settings"), Message::ExportSettings)),
+                (menu_button(t!("open-board"), Message::OpenBoard)),
+                (submenu(t!("save-board-as")), Menu::new(board_text::DIALECTS.iter()
+                        .map(|dialect| Item::new(menu_button(dialect.name(), Message::SaveBoard(dialect.name()))))
+                        .collect())
+                        .max_width(180.0)),
+                (menu_button(t!("copy-board"), Message::CopyBoard)),
+                (menu_button(t!("export-board-image"), Message::ExportBoardImage)),
+                (submenu(t!("image-cell-size")), Menu::new(share::IMAGE_CELL_SIZES.iter()
+                        .map(|cell_size| Item::new(radio(t!("pixels", count = cell_size), *cell_size,
+                                Some(self.settings.image_cell_size), Message::ChangeImageCellSize)
+                                .width(Length::Fill)))
+                        .collect())
+                        .max_width(150.0)),
+            ).max_width(150.0)
+            .close_on_item_click(true)),
+            (menu_label(t!("menu-game")), menu!(
+                (menu_button(t!("statistics"), Message::Statistics)),
+                (menu_button(t!("achievements"), Message::Achievements)),
+                (menu_button(t!("pause"), Message::Pause)),
+                (explained(menu_checkbox(t!("idle-pause"), Message::IdlePause, self.settings.idle_pause),
+                        t!("idle-pause-explained"))),
+                (submenu_maybe(t!("idle-pause-after"), self.settings.idle_pause), menu!(
+                    (text(t!("idle-threshold", seconds = self.settings.idle_threshold.as_secs()))),
+                    (slider(15..=600, self.settings.idle_threshold.as_secs() as u32,
+                            |seconds| Message::ChangeIdleThreshold(Duration::from_secs(seconds as u64)))
+                            .step(15u32)),
+                ).max_width(200.0)),
+                (menu_button(t!("solver-step"), Message::Step)),
+                (menu_button(t!("set-seed"), Message::SeedDialog)),
+                (menu_button(t!("board-editor"), Message::EditBoard)),
+                (menu_button(t!("load-board"), Message::BrowseBoards)),
+                (explained(menu_checkbox(t!("arena"), Message::Arena, self.settings.arena),
+                        t!("arena-explained"))),
+                (explained(menu_checkbox(t!("forgiving"), Message::Forgiving, self.settings.forgiving),
+                        t!("forgiving-explained"))),
+                (explained(menu_checkbox(t!("mistake-guard"), Message::MistakeGuard, self.settings.mistake_guard),
+                        t!("mistake-guard-explained"))),
+                (explained(menu_checkbox(t!("no-flags"), Message::NoFlags, self.settings.no_flags),
+                        t!("no-flags-explained"))),
+                (menu_checkbox(t!("keep-no-flags"), Message::KeepNoFlags, self.settings.keep_no_flags)),
+                (explained(menu_button(t!("blitz"), Message::Blitz), t!("blitz-explained"))),
+                (submenu(t!("blitz-settings")), menu!(
+                    (text(t!("blitz-penalty", seconds = self.settings.blitz_penalty.as_secs()))),
+                    (slider(0..=60, self.settings.blitz_penalty.as_secs() as u32,
+                            |secs| Message::ChangeBlitzPenalty(Duration::from_secs(secs as u64)))
+                            .step(5u32)),
+                ).max_width(150.0)),
+                (submenu(t!("rules")), menu!(
+                    (menu_checkbox(t!("rule-practice"), |value| Message::ToggleRule(KnownRule::Practice, value),
+                            self.settings.rules.contains(&KnownRule::Practice))),
+                    (menu_checkbox(t!("rule-time-trial"), |value| Message::ToggleRule(KnownRule::TimeTrial, value),
+                            self.settings.rules.contains(&KnownRule::TimeTrial))),
+                    (text(t!("time-limit", time = crate::stats::format_duration(self.settings.time_limit)))),
+                    (slider(10..=600, self.settings.time_limit.as_secs() as u32,
+                            |secs| Message::ChangeTimeLimit(Duration::from_secs(secs as u64)))
+                            .on_release(Message::ApplyTimeLimit)
+                            .step(10u32)),
+                ).max_width(150.0)),
+            ).max_width(150.0)
+            .close_on_item_click(true)),
+            (menu_label(t!("menu-size")), Menu::new([
+                Item::new(size_radio(t!("size-beginner"), SizeChoice::Preset(ConventionalSize::Beginner.size()), self.settings.size.0)),
+                Item::new(size_radio(t!("size-intermediate"), SizeChoice::Preset(ConventionalSize::Intermediate.size()), self.settings.size.0)),
+                Item::new(size_radio(t!("size-expert"), SizeChoice::Preset(ConventionalSize::Expert.size()), self.settings.size.0)),
+                Item::new(size_radio(t!("size-custom"), SizeChoice::Custom, self.settings.size.0)),
+            ].into_iter()
+            .chain(self.settings.recent_sizes.iter()
+                    .map(|recent| Item::new(menu_button(
+                        text(t!("size-recent", width = recent.0.width(), height = recent.0.height(), mines = recent.0.mines())),
+                        recent.0))))
+            .collect())
+            .max_width(200.0)
+            .close_on_item_click(true)),
+            (menu_label(t!("menu-theme")), Menu::new([
+                Item::new(menu_checkbox(t!("theme-follow-system"), Message::FollowSystem, self.settings.follow_system)),
+                Item::new(texture_radio(t!("theme-dark"), Texture::Dark, self.settings.texture)),
+                Item::new(texture_radio(t!("theme-light"), Texture::Light, self.settings.texture)),
+                Item::new(texture_radio(t!("theme-gay"), Texture::Gay, self.settings.texture)),
+                Item::new(texture_radio(t!("theme-accessible"), Texture::Accessible, self.settings.texture)),
+            ].into_iter()
+            .chain(pack::installed().into_iter()
+                    .map(|(id, name)| Item::new(texture_radio(name, Texture::Pack(id), self.settings.texture))))
+            .chain([
+                Item::new(menu_button(t!("install-texture-pack"), Message::InstallTexturePack)),
+                Item::with_menu(explained(submenu(t!("app-theme")), t!("app-theme-explained")),
+                        Menu::new(AppTheme::options()
+                                .map(|option| Item::new(app_theme_radio(option, &self.settings.app_theme)))
+                                .collect())
+                                .max_width(200.0)),
+            ])
+            .collect())
+            .max_width(240.0)),
+            (menu_label(t!("menu-solver")), menu!(
+                (explained(submenu(t!("opening")), t!("opening-explained")), Menu::new(FirstClick::ALL.iter()
+                        .map(|first_click| Item::new(menu_radio(first_click.to_string(), *first_click, self.settings.first_click)))
+                        .collect())
+                        .max_width(200.0)),
+                (explained(submenu(t!("assistant")), t!("assistant-explained")), Menu::new([SolverChoice::Single(None)].into_iter()
+                        .chain(solvers::assistants().map(|entry| SolverChoice::Single(Some(entry.into()))))
+                        .map(|choice| Item::new(menu_radio(match choice {
+                            SolverChoice::Single(Some(solver)) => solver.entry().label.as_str(),
+                            _ => t!("assistant-none"),
+                        }, choice, solver_choice)))
+                        .chain((!self.settings.solver_chain.is_empty()).then(||
+                                Item::new(menu_radio(solvers::chain_label(&self.settings.solver_chain), SolverChoice::Chain, solver_choice))))
+                        .chain(self.settings.external_solver.command.as_ref().map(|command|
+                                Item::new(menu_radio(t!("solver-external", program = command.file_name().unwrap_or(command.as_os_str()).display()),
+                                        SolverChoice::External, solver_choice))))
+                        .chain([
+                            Item::new(menu_button(t!("chain-solvers"), Message::SolverChainDialog)),
+                            Item::new(menu_button(t!("configure-external-solver"), Message::ExternalSolverDialog)),
+                        ])
+                        .collect())
+                        .max_width(200.0)),
+            ).max_width(150.0)),
+            (menu_label(t!("menu-gameplay")), menu!(
+                (explained(menu_checkbox(t!("auto-solve"), Message::Auto, self.settings.auto),
+                        t!("auto-solve-explained"))),
+                (submenu_maybe(t!("auto-settings"), self.settings.auto), menu!(
+                    (submenu(t!("auto-custom-solver")), Menu::new([None].into_iter()
+                            .chain(solvers::registry().iter().map(|entry| Some(KnownSolver::from(entry))))
+                            .map(|solver| Item::new(menu_radio(solver.map_or(t!("auto-solver-none"), |solver| solver.entry().label.as_str()),
+                                    solver, self.settings.auto_settings.solver)))
+                            .collect())
+                            .max_width(200.0)),
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct Settings {
-    size: SerializableBoardSize,
-    texture: Texture,
-    solver: KnownSolver,
-    auto: bool,
-    auto_settings: Auto,
-    flag_chord: bool,
-    hover_chord: bool,
-}
+                    (text(t!("auto-delay", delay = self.settings.auto_settings.delay.as_millis()))),
+                    (slider(50..=1000, self.settings.auto_settings.delay.as_millis() as u32,
+                            |millis| Message::ChangeAutoDelay(Duration::from_millis(millis as u64)))),
+                    (submenu(t!("auto-guess")), Menu::new(AutoGuess::ALL.iter()
+                            .map(|guess| Item::new(menu_radio(guess.to_string(), *guess, self.settings.auto_settings.guess)))
+                            .collect())
+                            .max_width(200.0)),
+                    (explained(menu_checkbox(t!("auto-restart"), Message::AutoRestart, self.settings.auto_settings.restart),
+                            t!("auto-restart-explained"))),
+                    (text(t!("auto-restart-delay", delay = self.settings.auto_settings.restart_delay.as_millis()))),
+                    (slider(0..=10000, self.settings.auto_settings.restart_delay.as_millis() as u32,
+                            |millis| Message::ChangeAutoRestartDelay(Duration::from_millis(millis as u64)))
+                            .step(500u32)),
+                    (menu_checkbox(t!("auto-silent"), Message::SilentAuto, self.settings.silent_auto)),
+                    (if power::supported() {
+                        Element::from(menu_checkbox(t!("slow-on-battery"), Message::ThrottleOnBattery, self.settings.throttle_on_battery))
+                    } else {
+                        Element::from(tooltip(
+                            checkbox(false).label(t!("slow-on-battery")).width(Length::Fill),
+                            tooltip_text(t!("battery-unsupported")),
+                            tooltip::Position::FollowCursor))
+                    }),
+                    (text(t!("battery-min-delay", delay = self.settings.battery_min_delay.as_millis()))),
+                    (slider(50..=1000, self.settings.battery_min_delay.as_millis() as u32,
+                            |millis| Message::ChangeBatteryMinDelay(Duration::from_millis(millis as u64)))
+                            .step(50u32)),
+                ).max_width(150.0)),
+                (explained(menu_checkbox(t!("flag-chord"), Message::FlagChord, self.settings.flag_chord),
+                        t!("flag-chord-explained"))),
+                (explained(menu_checkbox(t!("drag-flag"), Message::DragFlag, self.settings.drag_flag),
+                        t!("drag-flag-explained"))),
+                (explained(menu_checkbox(t!("hover-chord"), Message::HoverChord, self.settings.hover_chord),
+                        t!("hover-chord-explained"))),
+                (explained(submenu(t!("neighbour-highlight")), t!("neighbour-highlight-explained")),
+                        Menu::new(std::iter::once(None)
+                                .chain(HighlightModifier::ALL.map(Some))
+                                .map(|modifier| {
+                                    let label = modifier.map_or_else(|| t!("neighbour-highlight-off").to_string(), |modifier| modifier.to_string());
+                                    Item::new(menu_radio(label, modifier, self.settings.neighbour_highlight))
+                                })
+                                .collect())
+                                .max_width(150.0)),
+                (explained(menu_checkbox(t!("tap-to-flag"), Message::FlagMode, self.settings.flag_mode),
+                        t!("tap-to-flag-explained"))),
+                (explained(menu_checkbox(t!("long-press-flag"), Message::LongPressFlag, self.settings.long_press_flag),
+                        t!("long-press-flag-explained"))),
+                (menu_button(t!("mouse-buttons"), Message::MouseBindingsDialog)),
+                (explained(menu_checkbox(t!("solver-preview"), Message::SolverPreview, self.settings.solver_preview),
+                        t!("solver-preview-explained"))),
+                (text(t!("step-preview", delay = self.settings.step_preview.as_millis()))),
+                (slider(250..=3000, self.settings.step_preview.as_millis() as u32,
+                        |millis| Message::ChangeStepPreview(Duration::from_millis(millis as u64)))
+                        .step(250u32)),
+                (text(t!("hover-chord-delay", delay = self.settings.hover_chord_delay.as_millis()))),
+                (slider(0..=500, self.settings.hover_chord_delay.as_millis() as u32,
+                        |millis| Message::ChangeHoverChordDelay(Duration::from_millis(millis as u64)))
+                        .step(50u32)),
+                (text(t!("chord-delay", delay = self.settings.chord_delay.as_millis()))),
+                (slider(0..=500, self.settings.chord_delay.as_millis() as u32,
+                        |millis| Message::ChangeChordDelay(Duration::from_millis(millis as u64)))
+                        .step(50u32)),
+            ).max_width(150.0)),
+            (menu_label(t!("menu-view")), menu!(
+                (submenu(t!("cell-size")), menu!(
+                    (menu_radio(t!("cell-size-fit"), CellScale::Fit, self.settings.cell_scale)),
+                    (menu_radio("24 px", CellScale::Fixed(24), self.settings.cell_scale)),
+                    (menu_radio("32 px", CellScale::Fixed(32), self.settings.cell_scale)),
+                    (menu_radio("48 px", CellScale::Fixed(48), self.settings.cell_scale)),
+                    (menu_radio(t!("cell-size-native"), CellScale::Native, self.settings.cell_scale)),
+                ).max_width(150.0)),
+                (submenu(t!("counter")), Menu::new(CounterMode::ALL.iter()
+                        .map(|mode| Item::new(menu_radio(mode.to_string(), *mode, self.settings.counter_mode)))
+                        .collect())
+                        .max_width(150.0)),
+                (submenu(t!("rotate-board")), Menu::new(BoardRotation::ALL.iter()
+                        .map(|rotation| Item::new(menu_radio(rotation.to_string(), *rotation, self.settings.board_rotation)))
+                        .collect())
+                        .max_width(220.0)),
+                (submenu(t!("language")), Menu::new(Language::ALL.iter()
+                        .map(|language| Item::new(menu_radio(language.name(), *language, self.settings.language)))
+                        .collect())
+                        .max_width(150.0)),
+                (menu_button(t!("fullscreen"), Message::Fullscreen)),
+                (explained(menu_button(t!("spectator-window"), Message::SpectatorWindow),
+                        t!("spectator-window-explained"))),
+                (text(t!("ui-scale", percent = format!("{:.0}", self.settings.ui_scale() * 100.0)))),
+                (slider(50..=300, (self.settings.ui_scale() * 100.0).round() as u32,
+                        |percent| Message::ChangeUiScale(percent as f32 / 100.0))
+                        .step(10u32)),
+                (menu_button(t!("fit-window"), Message::FitWindow)),
+                (menu_checkbox(t!("always-on-top"), Message::AlwaysOnTop, self.settings.always_on_top)),
+                (menu_checkbox(t!("progress-ring"), Message::ProgressRing, self.settings.progress_ring)),
+                (menu_checkbox(t!("show-progress"), Message::ShowProgress, self.settings.show_progress)),
+                (menu_checkbox(t!("animate-reveals"), Message::AnimateReveals, self.settings.animate_reveals)),
+                (explained(menu_checkbox(t!("reduce-motion"), Message::ReduceMotion, self.settings.reduce_motion),
+                        t!("reduce-motion-explained"))),
+                (explained(menu_checkbox(t!("describe-cells"), Message::DescribeCells, self.settings.describe_cells),
+                        t!("describe-cells-explained"))),
+                (explained(menu_checkbox(t!("status-line"), Message::StatusLine, self.settings.status_line),
+                        t!("status-line-explained"))),
+                (explained(menu_checkbox(t!("solver-explanations"), Message::SolverExplanations, self.settings.solver_explanations),
+                        t!("solver-explanations-explained"))),
+                (menu_checkbox(t!("show-provenance"), Message::ShowProvenance, self.settings.show_provenance)),
+                (explained(menu_checkbox(t!("risk-shading"), Message::RiskShading, self.settings.risk_shading),
+                        t!("risk-shading-explained"))),
+                (menu_checkbox(t!("game-over-dialog"), Message::GameOverDialog, self.settings.show_game_over_dialog)),
+                (text(t!("loss-reveal-delay", delay = self.settings.loss_reveal_delay.as_millis()))),
+                (slider(0..=1000, self.settings.loss_reveal_delay.as_millis() as u32,
+                        |millis| Message::ChangeLossRevealDelay(Duration::from_millis(millis as u64)))
+                        .step(100u32)),
+                (explained(menu_checkbox(t!("streamer-overlay"), Message::StreamerOverlay, self.settings.streamer_overlay),
+                        t!("streamer-overlay-explained"))),
+                (submenu_maybe(t!("streamer-overlay-settings"), self.settings.streamer_overlay), menu!(
+                    (menu_checkbox(t!("overlay-show-seed"), Message::OverlaySeed, self.settings.overlay_seed)),
+                    (text(t!("overlay-opacity", percent = (self.settings.overlay_opacity * 100.0).round()))),
+                    (slider((MIN_OVERLAY_OPACITY * 100.0) as u32..=100, (self.settings.overlay_opacity * 100.0).round() as u32,
+                            |percent| Message::ChangeOverlayOpacity(percent as f32 / 100.0))
+                            .step(5u32)),
+                ).max_width(200.0)),
+                (explained(menu_checkbox(t!("streamer-mode"), Message::StreamerMode, self.settings.streamer_mode),
+                        t!("streamer-mode-explained"))),
+                // a build without audio has nothing for these to turn on
+                (cfg!(feature = "sound")
+                        .then(|| menu_checkbox(t!("sound"), Message::Sound, self.settings.sound_enabled))),
+                (cfg!(feature = "sound")
+                        .then(|| text(t!("volume", percent = (self.settings.volume * 100.0).round())))),
+                (cfg!(feature = "sound")
+                        .then(|| slider(0..=100, (self.settings.volume * 100.0).round() as u32,
+                                |percent| Message::ChangeVolume(percent as f32 / 100.0)))),
+                (self.settings.experiments.enabled(experiments::LATENCY_OVERLAY)
+                        .then(|| menu_button(t!("latency-overlay"), Message::ToggleLatencyOverlay))),
+            ).max_width(150.0)),
+            (menu_label(t!("menu-advanced")), menu!(
+                (menu_button(t!("storage"), Message::Storage)),
+                (menu_button(t!("experiments"), Message::ExperimentsDialog(true))),
+                (submenu(t!("log-level")), Menu::new(LogLevel::ALL.iter()
+                        .map(|level| Item::new(menu_radio(level.to_string(), *level, self.settings.log_level)))
+                        .collect())
+                        .max_width(150.0)),
+            ).max_width(150.0)
+            .close_on_item_click(true)),
+            (menu_label(t!("menu-help")), menu!(
+                (menu_button(t!("show-shortcuts"), dialog::Message::Open(DialogId::Shortcuts))),
+                (menu_button(t!("show-logs"), Message::ShowLogs)),
+            ).max_width(150.0)
+            .close_on_item_click(true)),
+        ).close_on_background_click_global(true)
+        .style(move |theme, status| menu::Style {
+            bar_background: manifest.header.into(),
+            menu_background: manifest.background.into(),
+            ..menu::primary(theme, status)
+        }))
+                .into()
+    }
 
-impl Default for Settings {
-    fn default() -> Self {
-        Self {
-            size: SerializableBoardSize(ConventionalSize::Beginner.size()),
-            texture: Texture::default(),
-            solver: KnownSolver::default(),
-            auto: false,
-            auto_settings: Auto::default(),
-            flag_chord: false,
-            hover_chord: false,
+    /// the dialogs of this menu that the [`dialog::DialogStack`] opens and closes
+    pub fn dialog<'a>(&self, id: DialogId) -> Option<Element<'a, Message>> {
+        match id {
+            DialogId::CustomSize => Some(self.custom_size_view()),
+            DialogId::Seed => Some(self.seed_view()),
+            DialogId::MouseBindings => Some(self.mouse_bindings_view()),
+            DialogId::SolverChain => Some(self.solver_chain_view()),
+            DialogId::ExternalSolver => Some(self.external_solver_view()),
+            DialogId::Logs => Some(self.logs_view()),
+            DialogId::Shortcuts => Some(shortcuts_view()),
+            _ => None,
         }
     }
-}
 
-impl Settings {
-    fn save(&self) -> io::Result<()> {
-        let file = File::create(&*SETTINGS_PATH)?;
+    fn custom_size_view<'a>(&self) -> Element<'a, Message> {
+        let width = self.custom_width;
+        let height = self.custom_height;
+        let mines = self.custom_mines;
+        let size = BoardSize::new(width, height, mines);
+        let pathology = pathology::assess(width, height, mines);
+        let refusal = size.as_ref().ok()
+                .and_then(|size| no_guess_refusal(*size, self.settings.needs_no_guess()));
+        let density = mines as f32 / width.saturating_mul(height).max(1) as f32;
+        let done = button(if pathology.is_some() && self.custom_confirmed { t!("use-anyway") } else { t!("done") })
+                .on_press_maybe(self.custom_size_action());
+        widget::column![
+            text(t!("custom-size-title")),
+            row![
+                text(t!("custom-width")),
+                number_input(&self.custom_width, 1.., move |width| Message::CustomSizeUpdate(width, height, mines)),
+            ].align_y(Vertical::Center),
+            row![
+                text(t!("custom-height")),
+                number_input(&self.custom_height, 1.., move |height| Message::CustomSizeUpdate(width, height, mines)),
+            ].align_y(Vertical::Center),
+            checkbox(self.settings.density_mode)
+                    .label(t!("custom-by-density"))
+                    .on_toggle(Message::CustomDensityMode),
+            if self.settings.density_mode {
+                row![
+                    text(t!("custom-density")),
+                    number_input(&self.settings.custom_density, 1..=MAX_DENSITY_PERCENT, Message::CustomDensity),
+                    text(t!("custom-density-mines", mines = mines))
+                            .style(move |theme| density_style(theme, density)),
+                ]
+            } else {
+                row![
+                    text(t!("custom-mines")),
+                    number_input(&self.custom_mines, 1..=max_mines(width, height), move |mines| Message::CustomSizeUpdate(width, height, mines)),
+                    text(t!("custom-mines-density", percent = format!("{:.1}", density * 100.0)))
+                            .style(move |theme| density_style(theme, density)),
+                ]
+            }.align_y(Vertical::Center),
+            text(refusal.clone()
+                    .or_else(|| pathology.map(|pathology| pathology.to_string()))
+                    .unwrap_or_default()),
+            row![
+                button(t!("cancel")).on_press(dialog::Message::Close(DialogId::CustomSize).into()),
 
-        serde_json::to_writer(file, self)?;
-        Ok(())
+                match size {
+                    Ok(_) => Element::new(done),
+                    Err(e) => Element::new(tooltip(done, tooltip_text(text!("{}", e)), tooltip::Position::FollowCursor))
+                }
+            ],
+        ].into()
     }
 
-    pub fn load() -> io::Result<Self> {
-        let file = File::open(&*SETTINGS_PATH)?;
+    fn logs_view<'a>(&self) -> Element<'a, Message> {
+        widget::column![
+            text(t!("logs-title")),
+            scrollable(text(self.logs.clone()).font(Font::MONOSPACE).size(12))
+                    .anchor_bottom()
+                    .height(400)
+                    .width(600),
+            row![
+                button(t!("close")).on_press(dialog::Message::Close(DialogId::Logs).into()),
+                button(t!("logs-copy")).on_press(Message::CopyLogs),
+            ].spacing(10),
+        ].spacing(10).into()
+    }
 
-        let settings = serde_json::from_reader(file)?;
+    pub fn logs(&self) -> &str {
+        &self.logs
+    }
 
-        Ok(settings)
+    fn seed_view<'a>(&self) -> Element<'a, Message> {
+        let seed = parse_seed(&self.seed_input);
+        widget::column![
+            text(t!("seed-title")),
+            text(t!("seed-explained")).size(12),
+            text_input(t!("seed-placeholder"), &self.seed_input)
+                    .on_input(Message::SeedInput)
+                    .on_submit_maybe(seed.map(|seed| Message::ChangeSeed(Some(seed)))),
+            row![
+                button(t!("cancel")).on_press(dialog::Message::Close(DialogId::Seed).into()),
+                button(t!("seed-random")).on_press(Message::ChangeSeed(None)),
+                match seed {
+                    Some(seed) => Element::new(button(t!("seed-set")).on_press(Message::ChangeSeed(Some(seed)))),
+                    None => Element::new(tooltip(button(t!("seed-set")), tooltip_text(text(t!("seed-invalid"))), tooltip::Position::FollowCursor))
+                }
+            ].spacing(10),
+            button(t!("seed-copy")).on_press(Message::CopySeed),
+        ].spacing(10).into()
     }
 
-    pub fn size(&self) -> BoardSize {
-        self.size.0
+    /// why the chain being put together can't be used yet
+    fn chain_problem(&self) -> Option<String> {
+        if self.chain_draft.is_empty() {
+            return Some(t!("chain-empty").to_string())
+        }
+        no_guess_refusal(self.settings.size.0, chain_needs_no_guess(&self.chain_draft))
     }
 
-    pub fn texture(&self) -> Texture {
-        self.texture
+    fn solver_chain_view<'a>(&self) -> Element<'a, Message> {
+        let problem = self.chain_problem();
+        let solvers = solvers::registry().iter()
+                .map(KnownSolver::from)
+                .collect::<Vec<_>>();
+        widget::column![
+            text(t!("chain-title")),
+            text(t!("chain-explained")),
+            widget::Column::with_children(self.chain_draft.iter().enumerate()
+                    .map(|(index, solver)| row![
+                        text!("{}. {}", index + 1, solver).width(Length::Fill),
+                        button("↑").on_press_maybe((index > 0).then_some(ChainEdit::MoveUp(index).into())),
+                        button(t!("chain-remove")).on_press(ChainEdit::Remove(index).into()),
+                    ].spacing(10).align_y(Vertical::Center).into()))
+                    .spacing(5),
+            pick_list(solvers, None::<KnownSolver>, |solver| ChainEdit::Add(solver).into())
+                    .placeholder(t!("chain-add")),
+            text(problem.clone().unwrap_or_default()),
+            row![
+                button(t!("cancel")).on_press(dialog::Message::Close(DialogId::SolverChain).into()),
+                button(t!("done")).on_press_maybe(problem.is_none().then_some(Message::ApplySolverChain)),
+            ].spacing(10),
+        ].spacing(10).width(350).into()
     }
 
-    pub fn solver(&self) -> SolverType {
-        self.solver.into()
+    /// asks the program in the external solver dialog for a move on `game_state`
+    pub fn test_external_solver(&self, game_state: GameState) -> Task<Message> {
+        let Some(command) = self.external_draft.command.clone() else { return Task::none() };
+        let solver = ExternalSolver::new(command, self.external_draft.timeout);
+        Task::future(tokio::task::spawn_blocking(move || match solver.ask(&game_state) {
+            Ok(Some(found)) => match found.actions.len() {
+                1 => t!("external-answered-one").to_string(),
+                count => t!("external-answered", count = count),
+            },
+            Ok(None) => t!("external-no-move").to_string(),
+            Err(e) => e.problem,
+        }))
+                .map(|result| Message::ExternalSolverTested(result.unwrap_or_else(|e| e.to_string())))
     }
 
-    pub fn auto(&self) -> Option<&Auto> {
-        if self.auto {
-            Some(&self.auto_settings)
-        } else {
-            None
-        }
+    fn external_solver_view<'a>(&self) -> Element<'a, Message> {
+        let draft = &self.external_draft;
+        let configured = draft.command.is_some();
+        widget::column![
+            text(t!("external-title")),
+            text(t!("external-explained")),
+            row![
+                text(draft.command.as_ref()
+                        .map(|command| privacy::redact(command.display()))
+                        .unwrap_or_else(|| t!("external-none").to_string()))
+                        .width(Length::Fill),
+                button(t!("browse")).on_press(Message::PickExternalSolver),
+            ].spacing(10).align_y(Vertical::Center),
+            text(t!("external-timeout", seconds = format!("{:.1}", draft.timeout.as_secs_f32()))),
+            slider(5..=100, (draft.timeout.as_millis() / 100) as u32,
+                    |tenths| Message::ChangeExternalTimeout(Duration::from_millis(tenths as u64 * 100))),
+            row![
+                button(t!("external-test")).on_press_maybe(configured.then_some(Message::TestExternalSolver)),
+                text(self.external_test.as_ref().map(privacy::redact).unwrap_or_default()),
+            ].spacing(10).align_y(Vertical::Center),
+            row![
+                button(t!("cancel")).on_press(dialog::Message::Close(DialogId::ExternalSolver).into()),
+                button(t!("external-use")).on_press_maybe(configured.then_some(Message::ApplyExternalSolver)),
+            ].spacing(10),
+        ].spacing(10).width(450).into()
     }
 
-    pub fn flag_chord(&self) -> bool {
-        self.flag_chord
+    fn mouse_bindings_view<'a>(&self) -> Element<'a, Message> {
+        let bindings = self.bindings_draft;
+        let chord_options = [ChordButton(None)].into_iter()
+                .chain(MouseButton::ALL.map(|button| ChordButton(Some(button))))
+                .collect::<Vec<_>>();
+        let problem = bindings.problem();
+        widget::column![
+            text(t!("mouse-title")),
+            row![
+                text(t!("mouse-reveal")).width(60),
+                pick_list(MouseButton::ALL, Some(bindings.reveal),
+                        move |reveal| Message::EditMouseBindings(MouseBindings { reveal, ..bindings })),
+            ].align_y(Vertical::Center),
+            row![
+                text(t!("mouse-flag")).width(60),
+                pick_list(MouseButton::ALL, Some(bindings.flag),
+                        move |flag| Message::EditMouseBindings(MouseBindings { flag, ..bindings })),
+            ].align_y(Vertical::Center),
+            row![
+                text(t!("mouse-chord")).width(60),
+                pick_list(chord_options, Some(ChordButton(bindings.chord)),
+                        move |chord| Message::EditMouseBindings(MouseBindings { chord: chord.0, ..bindings })),
+            ].align_y(Vertical::Center),
+            text(problem.unwrap_or_default()),
+            row![
+                button(t!("cancel")).on_press(dialog::Message::Close(DialogId::MouseBindings).into()),
+                button(t!("mouse-left-handed")).on_press(Message::EditMouseBindings(MouseBindings {
+                    reveal: MouseButton::Right,
+                    flag: MouseButton::Left,
+                    chord: bindings.chord.filter(|chord| *chord == MouseButton::Middle),
+                })),
+                button(t!("done")).on_press_maybe(problem.is_none().then_some(Message::ApplyMouseBindings)),
+            ].spacing(10),
+        ].spacing(10).into()
     }
 
-    pub fn hover_chord(&self) -> bool {
-        self.hover_chord
+    pub fn dialogs<'a>(&self) -> impl Iterator<Item = Flagged<'a, Message>> {
+        let mut vec = vec![];
+
+        if let Some(conflict) = &self.rule_conflict {
+            vec.push(Flagged::new(
+                widget::column![
+                    text(t!("rule-conflict-title")),
+                    text!("{}", conflict),
+                    button(t!("ok")).on_press(Message::DismissRuleConflict),
+                ].spacing(10).into(),
+                Message::DismissRuleConflict));
+        }
+
+        if let Some(error) = self.errors.first() {
+            vec.push(Flagged::new(
+                widget::column![
+                    text!("{}", error.title()),
+                    text(privacy::redact(error)),
+                    text(privacy::redact(error.fallback())),
+                    row![
+                        button(t!("open-settings-folder")).on_press(Message::OpenSettingsFolder),
+                        button(t!("ok")).on_press(Message::DismissError),
+                    ].spacing(10),
+                ].spacing(10).into(),
+                Message::DismissError));
+        }
+
+        if self.experiments_dialog {
+            vec.push(Flagged::new(
+                widget::column![text(t!("experiments-title"))]
+                .extend(experiments::ALL.iter().map(|experiment| widget::column![
+                    menu_checkbox(experiment.label, |value| Message::ToggleExperiment(*experiment, value),
+                            self.settings.experiments.enabled(*experiment)),
+                    text!("{}", experiment.description).size(12),
+                    text!("{}", experiment.stability).size(12).style(text::warning),
+                ].spacing(2).into()))
+                .push(button(t!("close")).on_press(Message::ExperimentsDialog(false)))
+                .spacing(10)
+                .into(),
+                Message::ExperimentsDialog(false)));
+        }
+
+        if let Some(experiment) = self.disabling {
+            vec.push(Flagged::new(
+                widget::column![
+                    text(t!("experiment-disable-title", experiment = experiment.label)),
+                    text!("{}", experiment.confirm_disable.unwrap_or_default()),
+                    row![
+                        button(t!("cancel")).on_press(Message::CancelDisableExperiment),
+                        button(t!("experiment-disable")).on_press(Message::DisableExperiment(experiment)),
+                    ].spacing(10),
+                ].spacing(10).into(),
+                Message::CancelDisableExperiment));
+        }
+
+        if let Some(refusal) = &self.refusal {
+            vec.push(Flagged::new(
+                widget::column![
+                    text(t!("refusal-title")),
+                    text!("{}", refusal),
+                    button(t!("ok")).on_press(Message::DismissRefusal),
+                ].spacing(10).into(),
+                Message::DismissRefusal));
+        }
+
+        vec.into_iter()
     }
 }
 
-#[derive(Debug)]
-pub struct SettingsMenu {
-    settings: Settings,
-    custom_size_dialog: bool,
-    custom_width: usize,
-    custom_height: usize,
-    custom_mines: usize,
+/// settings used to be kept with the rest of the data, they're moved to the config folder the
+/// first time this version runs. the old file is only removed once the copy reads back the same
+/// and parses
+fn move_legacy(legacy: &Path, path: &Path) -> io::Result<()> {
+    std::fs::copy(legacy, path)?;
+    let copy = std::fs::read(path)?;
+    if copy != std::fs::read(legacy)? || serde_json::from_slice::<Value>(&copy).is_err() {
+        let _ = std::fs::remove_file(path);
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "the copy doesn't read back as the same settings"))
+    }
+    std::fs::remove_file(legacy)
 }
 
-impl Default for SettingsMenu {
-    fn default() -> Self {
-        Self {
-            settings: Settings::load()
-                    .unwrap_or_else(|e| {
-                        eprintln!("failed to load settings: {}", e);
-                        settings_menu::Settings::default()
-                    }),
-            custom_size_dialog: false,
-            custom_width: 10,
-            custom_height: 10,
-            custom_mines: 10
-        }
+/// the settings file couldn't be written, or there's nowhere to put one
+fn write_error(source: io::Error) -> SettingsError {
+    match paths::settings_file() {
+        Ok(path) => SettingsError::Write { path, source },
+        Err(source) => SettingsError::NoFolder { source },
     }
 }
 
-#[derive(Clone, Debug, From)]
-pub enum Message {
-    MenuLabel,
-    ChangeSize(BoardSize),
-    ChangeTexture(Texture),
-    ChangeSolver(KnownSolver),
-    #[from(skip)]
-    CustomSizeDialog(bool),
-    CustomSizeUpdate(usize, usize, usize),
-    #[from(skip)]
-    Auto(bool),
-    #[from(skip)]
-    FlagChord(bool),
-    #[from(skip)]
-    HoverChord(bool),
-    ChangeAutoSolver(Option<KnownSolver>),
-    ChangeAutoDelay(Duration),
+/// upgrades a file written by an older version to the current layout, one version at a time
+fn migrate(value: &mut Value, from: u32) {
+    for version in from..CURRENT_VERSION {
+        match version {
+            // from before versioning, every field added since then has a default
+            0 => {}
+            // `solver` picked the opening and the assistant in one, a start solver is now a
+            // first click with no assistant
+            1 => if let Some(fields) = value.as_object_mut()
+                    && let Some(solver) = fields.remove("solver") {
+                match solver.as_str().and_then(FirstClick::replacing) {
+                    Some(first_click) => {
+                        fields.insert("first_click".to_string(), serde_json::to_value(first_click).unwrap_or_default());
+                        fields.insert("assist_solver".to_string(), Value::Null);
+                    }
+                    None => {
+                        fields.insert("assist_solver".to_string(), solver);
+                    }
+                }
+            }
+            // whatever changed in it, the fields that still parse are kept and the rest fall
+            // back to their defaults
+            _ => tracing::warn!("no migration from settings version {}, skipping it", version),
+        }
+    }
+    if let Some(fields) = value.as_object_mut() {
+        fields.insert("version".to_string(), CURRENT_VERSION.into());
+    }
 }
 
-impl SettingsMenu {
+/// settings from a file the user picked, checked the same way as loading but all or nothing,
+/// returns the json to apply
+fn read_import(path: &Path) -> Result<String, ImportError> {
+    let json = std::fs::read_to_string(path)
+            .map_err(|e| ImportError::Unreadable(e.to_string()))?;
+    let mut value: Value = serde_json::from_str(&json)
+            .map_err(|e| ImportError::Unreadable(e.to_string()))?;
+    if !value.is_object() {
+        return Err(ImportError::Unreadable(t!("error-not-settings").to_string()))
+    }
 
-    pub fn settings(&self) -> &Settings {
-        &self.settings
+    let version = value.get("version")
+            .and_then(Value::as_u64)
+            .unwrap_or(0) as u32;
+    if version <= CURRENT_VERSION {
+        migrate(&mut value, version);
+    }
+    let (settings, mut rejected) = Settings::salvage(value);
+    if !rejected.iter().any(|field| field == "size" || field == "assist_solver")
+            && no_guess_refusal(settings.size(), settings.needs_no_guess()).is_some() {
+        rejected.push("size".to_string());
+    }
+    if !rejected.is_empty() {
+        return Err(ImportError::Rejected(rejected))
     }
 
-    pub fn update(&mut self, message: Message) -> Task<Message> {
-        match message {
-            Message::ChangeSize(size) => {
-                self.custom_size_dialog = false;
-                self.settings.size = SerializableBoardSize(size);
-            },
-            Message::ChangeTexture(texture) => self.settings.texture = texture,
-            Message::ChangeSolver(solver) => self.settings.solver = solver,
-            Message::MenuLabel => {},
-            Message::CustomSizeDialog(value) => self.custom_size_dialog = value,
-            Message::CustomSizeUpdate(width, height, mines) =>  {
-                self.custom_width = width;
-                self.custom_height = height;
-                self.custom_mines = mines;
-            },
-            Message::Auto(value) => {
-                self.settings.auto = value;
-            }
-            Message::FlagChord(value) => {
-                self.settings.flag_chord = value;
-            }
-            Message::HoverChord(value) => {
-                self.settings.hover_chord = value;
-            }
-            Message::ChangeAutoSolver(solver) => {
-                self.settings.auto_settings.solver = solver;
-            }
-            Message::ChangeAutoDelay(delay) => {
-                self.settings.auto_settings.delay = delay;
-            }
-        }
+    serde_json::to_string(&settings)
+            .map_err(|e| ImportError::Unreadable(e.to_string()))
+}
+
+#[derive(Clone, Debug)]
+pub enum ImportError {
+    Unreadable(String),
+    /// names of the fields that were missing a valid value
+    Rejected(Vec<String>),
+}
+
+fn unreadable(path: PathBuf, source: serde_json::Error) -> SettingsError {
+    let backup = path.with_extension("json.bak");
+    let backup = std::fs::rename(&path, &backup)
+            .is_ok()
+            .then_some(backup);
+    SettingsError::Parse { path, source, backup }
+}
+
+/// something that went wrong reading or writing the settings file, kept around to show the user
+/// rather than printed where nobody looks
+#[derive(Debug)]
+pub enum SettingsError {
+    Read { path: PathBuf, source: io::Error },
+    /// `backup` is where the unreadable file was moved to, if moving it worked
+    Parse { path: PathBuf, source: serde_json::Error, backup: Option<PathBuf> },
+    Write { path: PathBuf, source: io::Error },
+    /// written by a newer version, `dropped` are the fields this version couldn't make sense of
+    /// and `copy` is where the original was copied to
+    Newer { path: PathBuf, version: u32, dropped: Vec<String>, copy: Option<PathBuf> },
+    Import { path: PathBuf, error: ImportError },
+    OpenFolder { path: PathBuf, source: io::Error },
+    /// saved by a version with a solver this one doesn't have
+    UnknownSolver { id: String },
+    /// saved with a texture this version doesn't have
+    UnknownTexture { name: String },
+    /// there's no settings folder and one couldn't be made
+    NoFolder { source: io::Error },
+}
 
-        if let Err(e) = self.settings.save() {
-            eprintln!("failed to save settings data: {}", e);
+impl SettingsError {
+    fn title(&self) -> &'static str {
+        match self {
+            SettingsError::Read { .. } | SettingsError::Parse { .. } => t!("error-load-title"),
+            SettingsError::Newer { .. } => t!("error-newer-title"),
+            SettingsError::Import { .. } => t!("error-import-title"),
+            SettingsError::Write { .. } => t!("error-save-title"),
+            SettingsError::OpenFolder { .. } => t!("error-open-folder-title"),
+            SettingsError::UnknownSolver { .. } => t!("error-unknown-solver-title"),
+            SettingsError::UnknownTexture { .. } => t!("error-unknown-texture-title"),
+            SettingsError::NoFolder { .. } => t!("error-no-folder-title"),
         }
-        
-        Task::none()
     }
 
-    pub fn view(&self) -> Element<'_, Message> {
-        container(menu_bar!(
-            (menu_label("Size"), menu!(
-                (menu_button("Beginner", ConventionalSize::Beginner.size())),
-                (menu_button("Intermediate", ConventionalSize::Intermediate.size())),
-                (menu_button("Expert", ConventionalSize::Expert.size())),
-                (menu_button("Custom", Message::CustomSizeDialog(true))),
-            ).max_width(100.0)
-            .close_on_item_click(true)),
-            (menu_label("Theme"), menu!(
-                (menu_radio("Dark", Texture::Dark, self.settings.texture)),
-                (menu_radio("Light", Texture::Light, self.settings.texture)),
-                (menu_radio("Gay", Texture::Gay, self.settings.texture)),
-            ).max_width(100.0)),
-            (menu_label("Solver"), menu!(
-                (menu_radio("Mia Solver", KnownSolver::MiaSolver, self.settings.solver)),
-                (menu_radio("Beginner Solver", KnownSolver::BeginnerSolver, self.settings.solver)),
-                (menu_radio("Intermediate Solver", KnownSolver::IntermediateSolver, self.settings.solver)),
-                (menu_radio("Expert Solver", KnownSolver::ExpertSolver, self.settings.solver)),
-                (menu_radio("Intermediate Only Solver", KnownSolver::IntermediateOnlySolver, self.settings.solver)),
-                (menu_radio("Expert Only Solver", KnownSolver::ExpertOnlySolver, self.settings.solver)),
-                (menu_radio("Safe Start", KnownSolver::SafeStart, self.settings.solver)),
-                (menu_radio("Zero Start", KnownSolver::ZeroStart, self.settings.solver)),
-                (menu_radio("Win Start", KnownSolver::WinStart, self.settings.solver)),
-            ).max_width(200.0)),
-            (menu_label("Cheats"), menu!(
-                (menu_checkbox("Auto", Message::Auto, self.settings.auto)),
-                (submenu_maybe("Auto Settings", self.settings.auto), menu!(
-                    (submenu("Custom Solver"), menu!(
-                        (menu_radio("None (same as normal solver)", None, self.settings.auto_settings.solver)),
-                        (menu_radio("Mia Solver", Some(KnownSolver::MiaSolver), self.settings.auto_settings.solver)),
-                        (menu_radio("Beginner Solver", Some(KnownSolver::BeginnerSolver), self.settings.auto_settings.solver)),
-                        (menu_radio("Intermediate Solver", Some(KnownSolver::IntermediateSolver), self.settings.auto_settings.solver)),
-                        (menu_radio("Expert Solver", Some(KnownSolver::ExpertSolver), self.settings.auto_settings.solver)),
-                        (menu_radio("Intermediate Only Solver", Some(KnownSolver::IntermediateOnlySolver), self.settings.auto_settings.solver)),
-                        (menu_radio("Expert Only Solver", Some(KnownSolver::ExpertOnlySolver), self.settings.auto_settings.solver)),
-                        (menu_radio("Safe Start", Some(KnownSolver::SafeStart), self.settings.auto_settings.solver)),
-                        (menu_radio("Zero Start", Some(KnownSolver::ZeroStart), self.settings.auto_settings.solver)),
-                        (menu_radio("Win Start", Some(KnownSolver::WinStart), self.settings.auto_settings.solver)),
-                    ).max_width(200.0)),
-
-                    (text!("Delay: {} ms", self.settings.auto_settings.delay.as_millis())),
-                    (slider(50..=1000, self.settings.auto_settings.delay.as_millis() as u32,
-                            |millis| Message::ChangeAutoDelay(Duration::from_millis(millis as u64)))),
-                ).max_width(150.0)),
-                (menu_checkbox("Flag Chord", Message::FlagChord, self.settings.flag_chord)),
-                (menu_checkbox("Hover Chord", Message::HoverChord, self.settings.hover_chord)),
-            ).max_width(150.0)),
-        ).close_on_background_click_global(true))
-                .into()
+    /// what the app did about it
+    fn fallback(&self) -> String {
+        match self {
+            SettingsError::Read { .. } => t!("error-read-fallback").to_string(),
+            SettingsError::Parse { backup: Some(backup), .. } => t!("error-parse-fallback-backup", path = backup.display()),
+            SettingsError::Parse { backup: None, .. } =>
+                t!("error-parse-fallback").to_string(),
+            SettingsError::Write { .. } => t!("error-save-fallback").to_string(),
+            SettingsError::Newer { copy: Some(copy), .. } => t!("error-newer-fallback-copy", path = copy.display()),
+            SettingsError::Newer { copy: None, .. } =>
+                t!("error-newer-fallback").to_string(),
+            SettingsError::Import { .. } => t!("error-import-fallback").to_string(),
+            SettingsError::OpenFolder { path, .. } => t!("error-open-folder-fallback", path = path.display()),
+            SettingsError::UnknownSolver { .. } => t!("error-unknown-solver-fallback").to_string(),
+            SettingsError::UnknownTexture { .. } => t!("error-unknown-texture-fallback").to_string(),
+            SettingsError::NoFolder { .. } => t!("error-no-folder-fallback").to_string(),
+        }
     }
+}
 
-    pub fn dialogs<'a>(&self) -> impl Iterator<Item = Element<'a, Message>> {
-        let mut vec = vec![];
+impl Display for SettingsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SettingsError::Read { path, source }
+            | SettingsError::Write { path, source }
+            | SettingsError::OpenFolder { path, source } => write!(f, "{}: {}", path.display(), source),
+            SettingsError::Parse { path, source, .. } => write!(f, "{}: {}", path.display(), source),
+            SettingsError::Newer { path, version, dropped, .. } if dropped.is_empty() =>
+                f.write_str(&t!("error-newer", path = path.display(), version = version, known = CURRENT_VERSION)),
+            SettingsError::Newer { path, version, dropped, .. } =>
+                f.write_str(&t!("error-newer-dropped", path = path.display(), version = version, known = CURRENT_VERSION,
+                    fields = dropped.join(", "))),
+            SettingsError::Import { path, error: ImportError::Unreadable(e) } => write!(f, "{}: {}", path.display(), e),
+            SettingsError::Import { path, error: ImportError::Rejected(fields) } =>
+                f.write_str(&t!("error-import-rejected", path = path.display(), fields = fields.join(", "))),
+            SettingsError::UnknownSolver { id } => f.write_str(&t!("error-unknown-solver", solver = format!("{:?}", id))),
+            SettingsError::UnknownTexture { name } => f.write_str(&t!("error-unknown-texture", texture = format!("{:?}", name))),
+            SettingsError::NoFolder { source } => write!(f, "{}", source),
+        }
+    }
+}
 
-        if self.custom_size_dialog {
-            let width = self.custom_width;
-            let height = self.custom_height;
-            let mines = self.custom_mines;
-            vec.push(
-                widget::column![
-                    text!("Custom Size"),
-                    row![
-                        text!("width: "),
-                        number_input(&self.custom_width, 1.., move |width| Message::CustomSizeUpdate(width, height, mines)),
-                    ].align_y(Vertical::Center),
-                    row![
-                        text!("height: "),
-                        number_input(&self.custom_height, 1.., move |height| Message::CustomSizeUpdate(width, height, mines)),
-                    ].align_y(Vertical::Center),
-                    row![
-                        text!("mines: "),
-                        number_input(&self.custom_mines, 1.., move |mines| Message::CustomSizeUpdate(width, height, mines)),
-                    ].align_y(Vertical::Center),
-                    row![
-                        button("Cancel").on_press(Message::CustomSizeDialog(false)),
-                        
-                        match BoardSize::new(width, height, mines) {
-                            Ok(size) => Element::new(button("Done").on_press(Message::ChangeSize(size))),
-                            Err(e) => Element::new(tooltip(button("Done"), tooltip_text(text!("{}", e)), tooltip::Position::FollowCursor))
-                        }
-                    ],
-                ].into()
-            );
+/// solvers that have to finish the whole game can't generate boards too dense for logic, asking
+/// them to would retry forever
+fn no_guess_refusal(size: BoardSize, needs_no_guess: bool) -> Option<String> {
+    let pathology = pathology::assess(size.width().get(), size.height().get(), size.mines().get())?;
+    (needs_no_guess && !pathology.allows_no_guess())
+            .then(|| t!("no-guess-refusal", percent = format!("{:.0}", pathology::MAX_DENSITY * 100.0)))
+}
 
-        }
+fn density_style(theme: &Theme, density: f32) -> text::Style {
+    if density > HIGH_DENSITY {
+        text::danger(theme)
+    } else {
+        text::Style::default()
+    }
+}
 
+/// `BoardSize::new` wants at least one safe cell
+fn max_mines(width: usize, height: usize) -> usize {
+    width.saturating_mul(height).saturating_sub(1).max(1)
+}
 
-        vec.into_iter()
+fn parse_seed(input: &str) -> Option<u64> {
+    let input = input.trim();
+    match input.strip_prefix("0x").or_else(|| input.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => input.parse().ok(),
     }
 }
 
+fn conventional(size: BoardSize) -> bool {
+    [ConventionalSize::Beginner, ConventionalSize::Intermediate, ConventionalSize::Expert].into_iter()
+            .any(|conventional| conventional.size() == size)
+}
+
 fn menu_label<'a>(content: impl Into<Element<'a, Message>>) -> Button<'a, Message> {
     button(content)
             .on_press(Message::MenuLabel)
-            .style(|theme, status| {
-                use iced::widget::button::{Status, Style};
-
-                let palette = theme.extended_palette();
-                let base = Style {
-                    text_color: palette.background.base.text,
-                    border: Border::default(),
-                    ..Style::default()
-                };
-                match status {
-                    Status::Active => base.with_background(Color::TRANSPARENT),
-                    Status::Hovered => base.with_background(Color::from_rgb(
-                        palette.primary.weak.color.r * 1.2,
-                        palette.primary.weak.color.g * 1.2,
-                        palette.primary.weak.color.b * 1.2,
-                    )),
-                    Status::Disabled => base.with_background(Color::from_rgb(0.5, 0.5, 0.5)),
-                    Status::Pressed => base.with_background(palette.primary.weak.color),
-                    // Status::Disabled => base.with_background(Color::from_rgb(1.0, 0.0, 0.0)),
-                    // Status::Pressed => base.with_background(Color::from_rgb(0.0, 1.0, 0.0)),
-                    // _ => iced::widget::button::primary(theme, status)
-                }
-            })
+            .style(|theme, status| menu_button_style(theme, status, Border::default()))
+}
+
+/// see-through until hovered, every colour comes from the palette so any theme looks right
+fn menu_button_style(theme: &Theme, status: button::Status, border: Border) -> button::Style {
+    use iced::widget::button::{Status, Style};
+
+    let palette = theme.extended_palette();
+    let pair = match status {
+        Status::Active => None,
+        Status::Hovered => Some(palette.primary.weak),
+        Status::Pressed => Some(palette.primary.base),
+        Status::Disabled => Some(palette.background.strong),
+    };
+    Style {
+        background: pair.map(|pair| pair.color.into()),
+        text_color: pair.map_or(palette.background.base.text, |pair| pair.text),
+        border,
+        ..Style::default()
+    }
+}
+
+fn shortcuts_view<'a>() -> Element<'a, Message> {
+    widget::column![text(t!("shortcuts-title"))]
+            .extend(shortcuts::table().into_iter()
+                    .map(|(shortcut, keys)| row![
+                        text(keys.join(", ")).font(Font::MONOSPACE).width(160),
+                        text(shortcut.to_string()),
+                    ].into()))
+            .push(button(t!("close")).on_press(dialog::Message::Close(DialogId::Shortcuts).into()))
+            .spacing(10)
+            .into()
 }
 
 fn menu_button<'a>(content: impl Into<Element<'a, Message>>, message: impl Into<Message>) -> Button<'a, Message> {
     button(content)
             .on_press(message.into())
-            .style(|theme, status| {
-                use iced::widget::button::{Status, Style};
-
-                let palette = theme.extended_palette();
-                let base = Style {
-                    text_color: palette.background.base.text,
-                    border: Border::default().rounded(6.0),
-                    ..Style::default()
-                };
-                match status {
-                    Status::Active => base.with_background(Color::TRANSPARENT),
-                    Status::Hovered => base.with_background(Color::from_rgb(
-                        palette.primary.weak.color.r * 1.2,
-                        palette.primary.weak.color.g * 1.2,
-                        palette.primary.weak.color.b * 1.2,
-                    )),
-                    Status::Disabled => base.with_background(Color::from_rgb(0.5, 0.5, 0.5)),
-                    Status::Pressed => base.with_background(palette.primary.weak.color),
-                    // Status::Disabled => base.with_background(Color::from_rgb(1.0, 0.0, 0.0)),
-                    // Status::Pressed => base.with_background(Color::from_rgb(0.0, 1.0, 0.0)),
-                    // _ => iced::widget::button::primary(theme, status)
-                }
-            })
+            .style(|theme, status| menu_button_style(theme, status, Border::default().rounded(6.0)))
             .width(Length::Fill)
 }
 
@@ -352,6 +2497,154 @@ fn menu_radio<'a, T: Into<Message> + Copy + Eq>(label: impl Into<String>, value:
             .width(Length::Fill)
 }
 
+/// a Theme menu entry with a swatch of the texture after it
+fn texture_radio<'a>(label: impl Into<String>, texture: Texture, selected: Texture) -> Row<'a, Message> {
+    const SWATCH: f32 = 18.0;
+
+    let swatch: Vec<Element<'a, Message>> = match texture.preview() {
+        Some(assets) => assets.into_iter()
+                .map(|asset| svg(asset)
+                        .width(SWATCH)
+                        .height(SWATCH)
+                        .into())
+                .collect(),
+        // a square where each asset would be so the entries still line up
+        None => (0..4)
+                .map(|_| container(text("?").size(12))
+                        .center(SWATCH)
+                        .style(container::bordered_box)
+                        .into())
+                .collect(),
+    };
+    row![menu_radio(label, texture, selected)]
+            .extend(swatch)
+            .spacing(2)
+            .align_y(Vertical::Center)
+}
+
+// radios want a copyable value, which a palette name isn't
+fn app_theme_radio<'a>(option: AppTheme, selected: &AppTheme) -> Radio<'a, Message> {
+    let label = match &option {
+        AppTheme::MatchTexture => t!("app-theme-match-texture").to_string(),
+        AppTheme::Light => t!("theme-light").to_string(),
+        AppTheme::Dark => t!("theme-dark").to_string(),
+        AppTheme::Custom(name) => name.clone(),
+    };
+    radio(label, true, Some(option == *selected), move |_| Message::ChangeAppTheme(option))
+            .width(Length::Fill)
+}
+
+/// what the menus, dialogs and buttons are drawn with, the board always uses the texture
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum AppTheme {
+    #[default]
+    MatchTexture,
+    Light,
+    Dark,
+    /// one of iced's other palettes, by name
+    Custom(String),
+}
+
+impl AppTheme {
+    /// every choice in the order the menu lists them
+    fn options() -> impl Iterator<Item = AppTheme> {
+        [AppTheme::MatchTexture, AppTheme::Light, AppTheme::Dark].into_iter()
+                .chain(Theme::ALL.iter()
+                        .filter(|theme| !matches!(theme, Theme::Light | Theme::Dark))
+                        .map(|theme| AppTheme::Custom(theme.to_string())))
+    }
+
+    /// a palette that's no longer around falls back to matching the texture
+    pub fn resolve(&self, texture: Texture) -> Theme {
+        match self {
+            AppTheme::MatchTexture => texture.theme(),
+            AppTheme::Light => Theme::Light,
+            AppTheme::Dark => Theme::Dark,
+            AppTheme::Custom(name) => Theme::ALL.iter()
+                    .find(|theme| theme.to_string() == *name)
+                    .cloned()
+                    .unwrap_or_else(|| texture.theme()),
+        }
+    }
+}
+
+/// a Size menu entry, custom opens the dialog instead of picking a size
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum SizeChoice {
+    Preset(BoardSize),
+    Custom,
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum SolverMode {
+    #[default]
+    Single,
+    Chain,
+    External,
+}
+
+/// an Assistant menu entry, none, one of the registry, the chain or the external solver
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum SolverChoice {
+    Single(Option<KnownSolver>),
+    Chain,
+    External,
+}
+
+impl From<SolverChoice> for Message {
+    fn from(choice: SolverChoice) -> Self {
+        match choice {
+            SolverChoice::Single(solver) => Message::ChangeSolver(solver),
+            SolverChoice::Chain => Message::UseSolverChain,
+            SolverChoice::External => Message::UseExternalSolver,
+        }
+    }
+}
+
+/// a program that's asked for moves, see [`ExternalSolver`]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExternalSettings {
+    command: Option<PathBuf>,
+    timeout: Duration,
+}
+
+impl Default for ExternalSettings {
+    fn default() -> Self {
+        Self {
+            command: None,
+            timeout: external::DEFAULT_TIMEOUT,
+        }
+    }
+}
+
+fn chain_needs_no_guess(chain: &[KnownSolver]) -> bool {
+    chain.iter().any(|solver| solver.needs_no_guess())
+}
+
+impl From<SizeChoice> for Message {
+    fn from(choice: SizeChoice) -> Self {
+        match choice {
+            SizeChoice::Preset(size) => Message::ChangeSize(size),
+            SizeChoice::Custom => dialog::Message::Open(DialogId::CustomSize).into(),
+        }
+    }
+}
+
+/// labelled with the dimensions, custom only shows them while a custom size is being played
+fn size_radio<'a>(name: &str, choice: SizeChoice, current: BoardSize) -> Radio<'a, Message> {
+    let selected = if conventional(current) { SizeChoice::Preset(current) } else { SizeChoice::Custom };
+    let dimensions = match choice {
+        SizeChoice::Preset(size) => Some(size),
+        SizeChoice::Custom => (selected == SizeChoice::Custom).then_some(current),
+    };
+    let label = match dimensions {
+        Some(size) => format!("{} ({}×{}, {})", name, size.width(), size.height(), size.mines()),
+        None => name.to_string(),
+    };
+    menu_radio(label, choice, selected)
+}
+
 fn menu_checkbox<'a>(label: impl Into<String> + text::IntoFragment<'a>, f: impl Fn(bool) -> Message + 'a, selected: bool) -> Checkbox<'a, Message> {
     checkbox(selected)
             .label(label)
@@ -359,19 +2652,27 @@ fn menu_checkbox<'a>(label: impl Into<String> + text::IntoFragment<'a>, f: impl
             .on_toggle(f)
 }
 
+/// a menu item that explains itself when hovered
+fn explained<'a>(content: impl Into<Element<'a, Message>>, explanation: &'a str) -> Element<'a, Message> {
+    tooltip(content, tooltip_text(explanation), tooltip::Position::Right)
+            .into()
+}
+
 fn tooltip_text<'a>(text: impl Into<Text<'a>>) -> Element<'a, Message> {
-    container(text.into().color(Color::WHITE))
+    container(text.into())
             .padding(10)
-            .style(|theme| {
+            .style(|theme: &Theme| {
+                let pair = theme.extended_palette().background.strong;
                 container::rounded_box(theme)
                         .border(Border::default().rounded(8.0))
-                        .background(Color::from_rgb(0.2, 0.2, 0.2))
+                        .background(pair.color)
+                        .color(pair.text)
             })
             .into()
 }
 
-#[derive(Debug)]
-struct SerializableBoardSize(BoardSize);
+#[derive(Copy, Clone, Debug)]
+pub struct SerializableBoardSize(pub BoardSize);
 
 impl Serialize for SerializableBoardSize {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -398,7 +2699,7 @@ impl<'de> Deserialize<'de> for SerializableBoardSize {
             type Value = SerializableBoardSize;
 
             fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
-                write!(formatter, "a struct with fields width height and mines")
+                write!(formatter, "a struct with fields width height and mines, or [width, height, mines]")
             }
 
             fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
@@ -409,79 +2710,103 @@ impl<'de> Deserialize<'de> for SerializableBoardSize {
                 let mut height = None;
                 let mut mines = None;
 
-                while let Some((key, value)) = map.next_entry::<String, _>()? {
+                while let Some(key) = map.next_key::<String>()? {
                     match key.as_str() {
-                        "width" => width = Some(value),
-                        "height" => height = Some(value),
-                        "mines" => mines = Some(value),
-                        _ => return Err(de::Error::unknown_field(&key, &["width", "height", "mines"]))
+                        "width" => width = Some(map.next_value()?),
+                        "height" => height = Some(map.next_value()?),
+                        "mines" => mines = Some(map.next_value()?),
+                        // whatever a newer version keeps alongside
+                        _ => {
+                            map.next_value::<IgnoredAny>()?;
+                        }
                     }
                 }
 
-                BoardSize::new(
+                board_size(
                     width.ok_or(de::Error::missing_field("width"))?,
                     height.ok_or(de::Error::missing_field("height"))?,
                     mines.ok_or(de::Error::missing_field("mines"))?)
-                        .map_err(de::Error::custom)
-                        .map(SerializableBoardSize)
             }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                    A: SeqAccess<'de>,
+            {
+                let width = seq.next_element()?.ok_or(de::Error::invalid_length(0, &self))?;
+                let height = seq.next_element()?.ok_or(de::Error::invalid_length(1, &self))?;
+                let mines = seq.next_element()?.ok_or(de::Error::invalid_length(2, &self))?;
+                if seq.next_element::<IgnoredAny>()?.is_some() {
+                    return Err(de::Error::invalid_length(4, &self))
+                }
+                board_size(width, height, mines)
+            }
+        }
+
+        fn board_size<E: de::Error>(width: usize, height: usize, mines: usize) -> Result<SerializableBoardSize, E> {
+            BoardSize::new(width, height, mines)
+                    .map(SerializableBoardSize)
+                    .map_err(|e| E::custom(format_args!("{}×{} with {} mines isn't a board: {}", width, height, mines, e)))
         }
-        deserializer.deserialize_struct("BoardSize", &["width", "height", "mines"], Mewo)
+
+        deserializer.deserialize_any(Mewo)
     }
 }
 
 
-#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
-pub enum KnownSolver {
-    #[default]
-    MiaSolver,
-    BeginnerSolver,
-    IntermediateSolver,
-    ExpertSolver,
-    IntermediateOnlySolver,
-    ExpertOnlySolver,
-    SafeStart,
-    ZeroStart,
-    WinStart,
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
+pub enum KnownRule {
+    Practice,
+    TimeTrial,
 }
 
-impl From<KnownSolver> for SolverType {
-    fn from(value: KnownSolver) -> Self {
-        match value {
-            KnownSolver::MiaSolver => Arc::new(MiaSolver::default()),
-            KnownSolver::BeginnerSolver => Arc::new(MiaSolver::skill(Level::Beginner)),
-            KnownSolver::IntermediateSolver => Arc::new(MiaSolver::skill(Level::Intermediate)),
-            KnownSolver::ExpertSolver => Arc::new(MiaSolver::skill(Level::Expert)),
-            KnownSolver::IntermediateOnlySolver => Arc::new(MiaSolver::only(Level::Intermediate)),
-            KnownSolver::ExpertOnlySolver => Arc::new(MiaSolver::only(Level::Expert)),
-            KnownSolver::SafeStart => Arc::new(SafeStart),
-            KnownSolver::ZeroStart => Arc::new(ZeroStart),
-            KnownSolver::WinStart => Arc::new(WinStart),
+impl KnownRule {
+    fn make(self, time_limit: Duration) -> Arc<dyn GameRules> {
+        match self {
+            KnownRule::Practice => Arc::new(Practice),
+            KnownRule::TimeTrial => Arc::new(TimeTrial { limit: time_limit }),
         }
     }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Auto {
     solver: Option<KnownSolver>,
-    delay: Duration
+    delay: Duration,
+    guess: AutoGuess,
+    /// starts the next board by itself once a game auto played is over, for leaving it running
+    restart: bool,
+    restart_delay: Duration,
 }
 
 impl Default for Auto {
     fn default() -> Self {
         Self {
             solver: None,
-            delay: Duration::from_millis(50)
+            delay: Duration::from_millis(50),
+            guess: AutoGuess::default(),
+            restart: false,
+            restart_delay: Duration::from_secs(2),
         }
     }
 }
 
-impl Auto {
-    pub fn solver(&self) -> Option<KnownSolver> {
-        self.solver
-    }
+/// the chord button, where `None` shows as off
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+struct ChordButton(Option<MouseButton>);
 
-    pub fn delay(&self) -> Duration {
-        self.delay
+impl Display for ChordButton {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some(button) => button.fmt(f),
+            None => f.write_str(t!("mouse-chord-off")),
+        }
     }
-}
\ No newline at end of file
+}
+
+#[derive(Copy, Clone, Debug)]
+pub enum ChainEdit {
+    Add(KnownSolver),
+    Remove(usize),
+    MoveUp(usize),
+}
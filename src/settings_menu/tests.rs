@@ -0,0 +1,270 @@
+use super::*;
+
+const FULL: &str = include_str!("fixtures/full.json");
+const MINIMAL: &str = include_str!("fixtures/minimal.json");
+const NEWER_FIELDS: &str = include_str!("fixtures/newer_fields.json");
+const UNKNOWN_TEXTURE_AND_SOLVER: &str = include_str!("fixtures/unknown_texture_and_solver.json");
+
+fn parse(json: &str) -> (serde_json::Result<Settings>, Vec<SettingsError>) {
+    let mut warnings = vec![];
+    let settings = Settings::parse(serde_json::from_str(json).unwrap(), &mut warnings);
+    (settings, warnings)
+}
+
+fn size(json: &str) -> serde_json::Result<BoardSize> {
+    serde_json::from_str::<SerializableBoardSize>(json).map(|size| size.0)
+}
+
+#[test]
+fn every_field_round_trips() {
+    let fixture = serde_json::from_str::<Value>(FULL).unwrap();
+    let (settings, warnings) = parse(FULL);
+    assert!(warnings.is_empty(), "{:?}", warnings);
+    assert_eq!(serde_json::to_value(settings.unwrap()).unwrap(), fixture);
+}
+
+#[test]
+fn the_full_fixture_has_every_field_changed() {
+    let Value::Object(fixture) = serde_json::from_str::<Value>(FULL).unwrap() else { unreachable!() };
+    let Value::Object(defaults) = serde_json::to_value(Settings::default()).unwrap() else { unreachable!() };
+    for (name, default) in defaults {
+        let field = fixture.get(&name).unwrap_or_else(|| panic!("full.json is missing {}", name));
+        // `language` only has the one value so far
+        assert!(name == "version" || name == "language" || *field != default, "{} is the default in full.json", name);
+    }
+}
+
+#[test]
+fn missing_fields_default() {
+    let (settings, warnings) = parse(MINIMAL);
+    assert!(warnings.is_empty());
+    assert_eq!(serde_json::to_value(settings.unwrap()).unwrap(), serde_json::to_value(Settings::default()).unwrap());
+}
+
+#[test]
+fn unknown_fields_are_ignored() {
+    let (settings, warnings) = parse(NEWER_FIELDS);
+    assert!(warnings.is_empty());
+    let settings = settings.unwrap();
+    assert_eq!(settings.size(), BoardSize::new(16, 16, 40).unwrap());
+    assert!(settings.flag_chord);
+}
+
+#[test]
+fn unknown_texture_and_solver_fall_back_with_warnings() {
+    let (settings, warnings) = parse(UNKNOWN_TEXTURE_AND_SOLVER);
+    let settings = settings.unwrap();
+    assert_eq!(settings.texture, Texture::default());
+    assert_eq!(settings.assist_solver, Some(KnownSolver::default()));
+    assert_eq!(settings.solver_chain.iter().map(|solver| solver.id()).collect::<Vec<_>>(), ["SafeStart"]);
+    // the rest of the file still loads
+    assert!(settings.flag_chord);
+
+    assert!(warnings.iter().any(|warning| matches!(warning, SettingsError::UnknownTexture { name } if name == "Sepia")));
+    assert_eq!(warnings.iter().filter(|warning| matches!(warning, SettingsError::UnknownSolver { id } if id == "QuantumSolver")).count(), 2);
+}
+
+#[test]
+fn a_texture_pack_is_not_unknown() {
+    let (settings, warnings) = parse(r#"{ "version": 2, "texture": { "Pack": "not installed" } }"#);
+    assert!(warnings.is_empty());
+    assert!(matches!(settings.unwrap().texture, Texture::Pack(_)));
+}
+
+#[test]
+fn a_start_solver_becomes_the_first_click() {
+    let migrated = |solver: &str| {
+        let mut value = serde_json::json!({ "version": 1, "solver": solver });
+        migrate(&mut value, 1);
+        let settings = parse(&value.to_string()).0.unwrap();
+        (settings.first_click, settings.assist_solver.map(KnownSolver::id))
+    };
+    assert_eq!(migrated("SafeStart"), (FirstClick::Safe, None));
+    assert_eq!(migrated("ZeroStart"), (FirstClick::Opening, None));
+    assert_eq!(migrated("ExpertSolver"), (FirstClick::Safe, Some("ExpertSolver")));
+}
+
+#[test]
+fn board_size_as_a_map_or_a_sequence() {
+    let expected = BoardSize::new(30, 16, 99).unwrap();
+    assert_eq!(size(r#"{ "width": 30, "height": 16, "mines": 99 }"#).unwrap(), expected);
+    assert_eq!(size("[30, 16, 99]").unwrap(), expected);
+    assert!(size("[30, 16]").is_err());
+    assert!(size("[30, 16, 99, 1]").is_err());
+}
+
+#[test]
+fn invalid_board_sizes_say_why() {
+    let error = size("[0, 9, 10]").unwrap_err().to_string();
+    assert!(error.contains("0×9 with 10 mines"), "{}", error);
+
+    let error = size(r#"{ "width": 3, "height": 3, "mines": 9 }"#).unwrap_err().to_string();
+    assert!(error.contains("3×3 with 9 mines"), "{}", error);
+    assert!(error.contains("max: 9"), "{}", error);
+
+    let error = size(r#"{ "width": 3, "height": 3 }"#).unwrap_err().to_string();
+    assert!(error.contains("mines"), "{}", error);
+}
+
+#[test]
+fn matching_the_texture_follows_how_dark_it_is() {
+    assert_eq!(AppTheme::MatchTexture.resolve(Texture::Light), Theme::Light);
+    for texture in [Texture::Dark, Texture::Gay, Texture::Accessible] {
+        assert_eq!(AppTheme::MatchTexture.resolve(texture), Theme::Dark, "{:?}", texture);
+    }
+    assert_eq!(AppTheme::Custom("gone".to_string()).resolve(Texture::Gay), Theme::Dark);
+}
+
+#[test]
+fn an_image_cell_size_the_export_does_not_offer_falls_back() {
+    let (settings, _) = parse(r#"{ "image_cell_size": 17 }"#);
+    assert_eq!(settings.unwrap().image_cell_size(), default_image_cell_size());
+    let (settings, _) = parse(r#"{ "image_cell_size": 64 }"#);
+    assert_eq!(settings.unwrap().image_cell_size(), 64);
+}
+
+#[test]
+fn no_guess_solvers_refuse_dense_boards() {
+    let dense = BoardSize::new(10, 10, 70).unwrap();
+    let mut menu = SettingsMenu::default();
+    let before = menu.settings().size();
+
+    let _ = menu.update(Message::ChangeSize(dense));
+    assert!(menu.refusal().is_some());
+    assert_eq!(menu.settings().size(), before);
+
+    let _ = menu.update(Message::DismissRefusal);
+    assert!(menu.refusal().is_none());
+}
+
+// taking a board schedules a save
+#[tokio::test]
+async fn start_only_solvers_take_dense_boards() {
+    let dense = BoardSize::new(10, 10, 70).unwrap();
+    let mut menu = SettingsMenu::default();
+
+    let _ = menu.update(Message::ChangeSolver(None));
+    let _ = menu.update(Message::ChangeSize(dense));
+    assert!(menu.refusal().is_none());
+    assert_eq!(menu.settings().size(), dense);
+
+    let _ = menu.update(Message::ChangeSolver(Some(KnownSolver::default())));
+    assert!(menu.refusal().is_some(), "switching to a no-guess solver on a dense board should be refused");
+    assert_eq!(menu.settings().assist_solver, None);
+}
+
+// taking a board schedules a save
+#[tokio::test]
+async fn degenerate_boards_that_allow_no_guess_are_not_refused() {
+    let strip = BoardSize::new(10, 1, 3).unwrap();
+    let mut menu = SettingsMenu::default();
+
+    let _ = menu.update(Message::ChangeSize(strip));
+    assert!(menu.refusal().is_none());
+    assert_eq!(menu.settings().size(), strip);
+}
+
+/// [`read_import`] of `json` written to a file of its own
+fn import(test: &str, json: &str) -> Result<Settings, ImportError> {
+    let path = std::env::temp_dir().join(format!("minsweeper-import-{}-{}.json", std::process::id(), test));
+    std::fs::write(&path, json).unwrap();
+    let result = read_import(&path);
+    let _ = std::fs::remove_file(&path);
+    result.map(|json| serde_json::from_str(&json).unwrap())
+}
+
+fn rejected(result: Result<Settings, ImportError>) -> Vec<String> {
+    match result {
+        Err(ImportError::Rejected(fields)) => fields,
+        other => panic!("expected fields to be rejected, got {:?}", other.map(|settings| settings.to_pretty_json())),
+    }
+}
+
+#[test]
+fn exported_settings_import_unchanged() {
+    let (settings, _) = parse(FULL);
+    let settings = settings.unwrap();
+    let imported = import("round-trip", &settings.to_pretty_json()).unwrap();
+    assert_eq!(serde_json::to_value(imported).unwrap(), serde_json::to_value(settings).unwrap());
+}
+
+#[test]
+fn importing_is_all_or_nothing() {
+    let json = r#"{ "flag_chord": true, "size": { "width": 0, "height": 9, "mines": 10 }, "volume": "loud" }"#;
+    let mut fields = rejected(import("invalid", json));
+    fields.sort();
+    assert_eq!(fields, ["size", "volume"]);
+}
+
+#[test]
+fn a_board_too_dense_for_the_solver_is_not_imported() {
+    let json = r#"{ "size": { "width": 10, "height": 10, "mines": 70 } }"#;
+    assert_eq!(rejected(import("dense", json)), ["size"]);
+
+    let json = r#"{ "size": { "width": 10, "height": 10, "mines": 70 }, "assist_solver": null }"#;
+    assert_eq!(import("dense-start-only", json).unwrap().size(), BoardSize::new(10, 10, 70).unwrap());
+}
+
+#[test]
+fn files_that_are_not_settings_are_unreadable() {
+    for (test, json) in [("garbage", "not json"), ("array", "[1, 2, 3]")] {
+        assert!(matches!(import(test, json), Err(ImportError::Unreadable(_))), "{}", test);
+    }
+    let missing = std::env::temp_dir().join(format!("minsweeper-import-{}-missing.json", std::process::id()));
+    assert!(matches!(read_import(&missing), Err(ImportError::Unreadable(_))));
+}
+
+#[test]
+fn a_failed_import_leaves_the_settings_alone() {
+    let mut menu = SettingsMenu::default();
+    let before = serde_json::to_value(menu.settings()).unwrap();
+
+    let _ = menu.update(Message::Imported(PathBuf::from("settings.json"), Err(ImportError::Rejected(vec!["size".to_string()]))));
+    assert_eq!(serde_json::to_value(menu.settings()).unwrap(), before);
+    assert!(menu.errors.iter().any(|error| matches!(error, SettingsError::Import { .. })));
+}
+
+// toggling schedules a save
+#[tokio::test]
+async fn experiments_that_lose_something_ask_before_turning_off() {
+    let mut menu = SettingsMenu::default();
+    for experiment in [experiments::LATENCY_OVERLAY, experiments::CANVAS_BOARD] {
+        let _ = menu.update(Message::ToggleExperiment(experiment, true));
+    }
+
+    let _ = menu.update(Message::ToggleExperiment(experiments::CANVAS_BOARD, false));
+    assert!(!menu.settings().experiments().enabled(experiments::CANVAS_BOARD));
+    assert_eq!(menu.disabling, None);
+
+    let _ = menu.update(Message::ToggleExperiment(experiments::LATENCY_OVERLAY, false));
+    assert!(menu.settings().experiments().enabled(experiments::LATENCY_OVERLAY));
+    assert_eq!(menu.disabling, Some(experiments::LATENCY_OVERLAY));
+
+    let _ = menu.update(Message::CancelDisableExperiment);
+    assert!(menu.settings().experiments().enabled(experiments::LATENCY_OVERLAY));
+    assert_eq!(menu.disabling, None);
+
+    let _ = menu.update(Message::ToggleExperiment(experiments::LATENCY_OVERLAY, false));
+    let _ = menu.update(Message::DisableExperiment(experiments::LATENCY_OVERLAY));
+    assert!(!menu.settings().experiments().enabled(experiments::LATENCY_OVERLAY));
+    assert_eq!(menu.disabling, None);
+}
+
+// toggling schedules a save
+#[tokio::test]
+async fn practice_and_time_trial_together_are_refused_with_a_dialog() {
+    for (first, second) in [(KnownRule::Practice, KnownRule::TimeTrial), (KnownRule::TimeTrial, KnownRule::Practice)] {
+        let mut menu = SettingsMenu::default();
+        menu.settings.rules.clear();
+        let _ = menu.update(Message::ToggleRule(first, true));
+        let dialogs = menu.dialogs().count();
+
+        let _ = menu.update(Message::ToggleRule(second, true));
+        assert_eq!(menu.settings.rules, [first]);
+        assert!(menu.rule_conflict.is_some());
+        assert_eq!(menu.dialogs().count(), dialogs + 1);
+
+        let _ = menu.update(Message::DismissRuleConflict);
+        assert_eq!(menu.dialogs().count(), dialogs);
+    }
+}
@@ -0,0 +1,75 @@
+use crate::texture;
+use crate::texture::Texture;
+use minsweeper_rs::{CellState, CellType, GameState};
+use resvg::tiny_skia::{Pixmap, PixmapPaint, Transform};
+use resvg::usvg;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+#[cfg(test)]
+mod tests;
+
+/// what the png export offers for the size of a cell
+pub const IMAGE_CELL_SIZES: [u32; 3] = [16, 32, 64];
+
+const NUMBERS: [&str; 8] = ["1️⃣", "2️⃣", "3️⃣", "4️⃣", "5️⃣", "6️⃣", "7️⃣", "8️⃣"];
+
+/// the board as emoji under `heading`, a line for each row, for pasting wherever
+pub fn text_grid(heading: &str, gamestate: &GameState) -> String {
+    let size = gamestate.board.size();
+    let rows = (0..size.height().get())
+            .map(|y| (0..size.width().get())
+                    .map(|x| {
+                        let cell = gamestate.board[(x, y)];
+                        match (cell.cell_state, cell.cell_type) {
+                            (CellState::Revealed, CellType::Safe(0)) => "⬜",
+                            (CellState::Revealed, CellType::Safe(number @ 1..=8)) => NUMBERS[number as usize - 1],
+                            (CellState::Revealed, CellType::Mine) => "💥",
+                            (CellState::Unknown, CellType::Mine) => "💣",
+                            (CellState::Flagged, CellType::Safe(_)) => "❌",
+                            (CellState::Flagged, _) => "🚩",
+                            _ => "⬛",
+                        }
+                    })
+                    .collect::<String>())
+            .collect::<Vec<_>>();
+    format!("{}\n{}\n", heading, rows.join("\n"))
+}
+
+/// the board drawn with `texture`'s cells at `cell_size` pixels each, as a png
+pub fn render_png(gamestate: &GameState, texture: Texture, cell_size: u32) -> Result<Vec<u8>, String> {
+    let size = gamestate.board.size();
+    let too_big = || "the board is too big for an image".to_string();
+    let pixels = |cells: usize| u32::try_from(cells).ok()
+            .and_then(|cells| cells.checked_mul(cell_size))
+            .ok_or_else(too_big);
+    let mut image = Pixmap::new(pixels(size.width().get())?, pixels(size.height().get())?)
+            .ok_or_else(too_big)?;
+
+    // a board only uses a handful of different assets, each is parsed and drawn once like the
+    // canvas board keys its handles
+    let mut cells = HashMap::<u64, Pixmap>::new();
+    for point in size.points() {
+        let asset = texture.get_cell_asset(gamestate.board[point], false);
+        let key = asset.id();
+        if let Entry::Vacant(entry) = cells.entry(key) {
+            entry.insert(rasterize(texture::bytes(&asset), cell_size)?);
+        }
+        // the image being there means every cell's corner fits
+        image.draw_pixmap((point.0 as u32 * cell_size) as i32, (point.1 as u32 * cell_size) as i32,
+                cells[&key].as_ref(), &PixmapPaint::default(), Transform::identity(), None);
+    }
+
+    image.encode_png().map_err(|e| e.to_string())
+}
+
+fn rasterize(asset: &[u8], cell_size: u32) -> Result<Pixmap, String> {
+    let tree = usvg::Tree::from_data(asset, &usvg::Options::default())
+            .map_err(|e| e.to_string())?;
+    let mut pixmap = Pixmap::new(cell_size, cell_size)
+            .ok_or("cells can't be 0 pixels")?;
+    let scale = tree.size();
+    resvg::render(&tree, Transform::from_scale(cell_size as f32 / scale.width(), cell_size as f32 / scale.height()),
+            &mut pixmap.as_mut());
+    Ok(pixmap)
+}
@@ -0,0 +1,28 @@
+use super::*;
+use crate::minsweeper::layout_from_mines;
+use minsweeper_rs::board::BoardSize;
+
+fn board() -> GameState {
+    layout_from_mines(BoardSize::new(3, 2, 1).unwrap(), &[(0, 0)])
+}
+
+#[test]
+fn a_board_renders_to_a_png_of_its_size() {
+    let png = render_png(&board(), Texture::default(), 16).unwrap();
+    assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+    // the header's width and height, big endian
+    assert_eq!(&png[16..24], [0, 0, 0, 48, 0, 0, 0, 32]);
+}
+
+#[test]
+fn a_size_past_what_fits_is_an_error() {
+    assert!(render_png(&board(), Texture::default(), u32::MAX).is_err());
+    assert!(render_png(&board(), Texture::default(), 0).is_err());
+}
+
+#[test]
+fn the_text_grid_has_a_line_a_row() {
+    let grid = text_grid("heading", &board());
+    assert_eq!(grid.lines().count(), 3);
+    assert_eq!(grid.lines().nth(1).unwrap().chars().count(), 3);
+}
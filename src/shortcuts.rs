@@ -0,0 +1,146 @@
+use crate::i18n::t;
+use iced::keyboard;
+use iced::keyboard::key::Named;
+use iced::keyboard::Modifiers;
+use std::fmt::{Display, Formatter};
+
+/// what a key does, the app turns these into its messages
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Shortcut {
+    NewGame,
+    RestartSameBoard,
+    Pause,
+    Step,
+    Beginner,
+    Intermediate,
+    Expert,
+    CloseDialog,
+    Submit,
+    UiScaleUp,
+    UiScaleDown,
+    UiScaleReset,
+    Fullscreen,
+    DebugOverlay,
+    Quit,
+}
+
+impl Shortcut {
+    /// whether it's meant for the game, those are held back while a blocking dialog is up
+    pub fn for_game(self) -> bool {
+        matches!(self, Shortcut::NewGame | Shortcut::RestartSameBoard | Shortcut::Pause | Shortcut::Step
+                | Shortcut::Beginner | Shortcut::Intermediate | Shortcut::Expert)
+    }
+}
+
+impl Display for Shortcut {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Shortcut::NewGame => t!("shortcut-new-game"),
+            Shortcut::RestartSameBoard => t!("shortcut-restart-same-board"),
+            Shortcut::Pause => t!("shortcut-pause"),
+            Shortcut::Step => t!("shortcut-step"),
+            Shortcut::Beginner => t!("shortcut-beginner"),
+            Shortcut::Intermediate => t!("shortcut-intermediate"),
+            Shortcut::Expert => t!("shortcut-expert"),
+            Shortcut::CloseDialog => t!("shortcut-close-dialog"),
+            Shortcut::Submit => t!("shortcut-submit"),
+            Shortcut::UiScaleUp => t!("shortcut-ui-scale-up"),
+            Shortcut::UiScaleDown => t!("shortcut-ui-scale-down"),
+            Shortcut::UiScaleReset => t!("shortcut-ui-scale-reset"),
+            Shortcut::Fullscreen => t!("shortcut-fullscreen"),
+            Shortcut::DebugOverlay => t!("shortcut-debug-overlay"),
+            Shortcut::Quit => t!("shortcut-quit"),
+        })
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub enum Key {
+    Named(Named),
+    Character(&'static str),
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Binding {
+    pub key: Key,
+    pub modifiers: Modifiers,
+    pub shortcut: Shortcut,
+}
+
+const fn bind(key: Key, modifiers: Modifiers, shortcut: Shortcut) -> Binding {
+    Binding { key, modifiers, shortcut }
+}
+
+const NONE: Modifiers = Modifiers::empty();
+
+/// every shortcut there is, the keyboard subscription and the help dialog both go by this
+pub const BINDINGS: &[Binding] = &[
+    bind(Key::Named(Named::F2), NONE, Shortcut::NewGame),
+    bind(Key::Named(Named::F2), Modifiers::SHIFT, Shortcut::RestartSameBoard),
+    bind(Key::Character("p"), NONE, Shortcut::Pause),
+    bind(Key::Character("s"), NONE, Shortcut::Step),
+    bind(Key::Character("1"), Modifiers::COMMAND, Shortcut::Beginner),
+    bind(Key::Character("2"), Modifiers::COMMAND, Shortcut::Intermediate),
+    bind(Key::Character("3"), Modifiers::COMMAND, Shortcut::Expert),
+    bind(Key::Named(Named::Escape), NONE, Shortcut::CloseDialog),
+    bind(Key::Named(Named::Enter), NONE, Shortcut::Submit),
+    bind(Key::Character("="), Modifiers::COMMAND, Shortcut::UiScaleUp),
+    bind(Key::Character("+"), Modifiers::COMMAND, Shortcut::UiScaleUp),
+    bind(Key::Character("-"), Modifiers::COMMAND, Shortcut::UiScaleDown),
+    bind(Key::Character("0"), Modifiers::COMMAND, Shortcut::UiScaleReset),
+    bind(Key::Named(Named::F11), NONE, Shortcut::Fullscreen),
+    bind(Key::Named(Named::F12), NONE, Shortcut::DebugOverlay),
+    bind(Key::Character("q"), Modifiers::COMMAND, Shortcut::Quit),
+];
+
+impl Binding {
+    /// shift is part of a named key's binding, a character already says whether it was held. letters
+    /// match either case so caps lock doesn't get in the way
+    fn matches(&self, key: &keyboard::Key, modifiers: Modifiers) -> bool {
+        match (self.key, key) {
+            (Key::Named(named), keyboard::Key::Named(pressed)) => named == *pressed && modifiers == self.modifiers,
+            (Key::Character(character), keyboard::Key::Character(pressed)) => character.eq_ignore_ascii_case(pressed)
+                    && modifiers.difference(Modifiers::SHIFT) == self.modifiers,
+            _ => false,
+        }
+    }
+
+    /// how the help dialog writes it, like Ctrl+Shift+F2
+    pub fn keys(&self) -> String {
+        let mut keys = vec![];
+        if self.modifiers.command() {
+            keys.push(if cfg!(target_os = "macos") { "Cmd" } else { "Ctrl" }.to_string());
+        }
+        if self.modifiers.alt() {
+            keys.push("Alt".to_string());
+        }
+        if self.modifiers.shift() {
+            keys.push("Shift".to_string());
+        }
+        keys.push(match self.key {
+            Key::Named(named) => format!("{:?}", named),
+            Key::Character(character) => character.to_uppercase(),
+        });
+        keys.join("+")
+    }
+}
+
+/// what `key` does with `modifiers` held, if anything. keys a focused text field took never get
+/// this far
+pub fn find(key: &keyboard::Key, modifiers: Modifiers) -> Option<Shortcut> {
+    BINDINGS.iter()
+            .find(|binding| binding.matches(key, modifiers))
+            .map(|binding| binding.shortcut)
+}
+
+/// each shortcut once, with every key that does it
+pub fn table() -> Vec<(Shortcut, Vec<String>)> {
+    let mut table: Vec<(Shortcut, Vec<String>)> = vec![];
+    for binding in BINDINGS {
+        match table.iter_mut().find(|(shortcut, _)| *shortcut == binding.shortcut) {
+            Some((_, keys)) => keys.push(binding.keys()),
+            None => table.push((binding.shortcut, vec![binding.keys()])),
+        }
+    }
+    table
+}
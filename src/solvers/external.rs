@@ -0,0 +1,252 @@
+use futures_util::{Stream, StreamExt};
+use minsweeper_rs::board::Point;
+use minsweeper_rs::solver::start::SafeStart;
+use minsweeper_rs::solver::{Action, GameResult, Move, Operation, Solver};
+use minsweeper_rs::{CellState, CellType, GameState, GameStatus, Minsweeper};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::io::{ErrorKind, Read, Write};
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+
+#[cfg(all(test, unix))]
+mod tests;
+
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// processes that are still being waited on, by pid, so they can be killed when the app exits
+static RUNNING: LazyLock<Mutex<HashMap<u32, Child>>> = LazyLock::new(Default::default);
+
+/// the latest failure, solving happens far from the ui so this is how it finds out
+static FAILURES: LazyLock<watch::Sender<Option<ExternalError>>> = LazyLock::new(|| watch::Sender::new(None));
+
+/// a solver that's a separate program, asked for each move by writing the board as json to its
+/// stdin and reading the move back from its stdout
+///
+/// ```json
+/// { "width": 9, "height": 9, "mines": 10, "remaining_mines": 10, "status": "playing",
+///   "cells": [[{ "state": "revealed", "type": 1 }, { "state": "unknown", "type": "unknown" }, ...], ...] }
+/// ```
+///
+/// `cells` is a list of rows, the answer is `{ "actions": [{ "x": 0, "y": 0, "operation": "reveal" }] }`
+/// with `reveal`, `chord` or `flag`, or `null` when it has no move
+#[derive(Clone, Debug)]
+pub struct ExternalSolver {
+    command: PathBuf,
+    timeout: Duration,
+}
+
+#[derive(Clone, Debug)]
+pub struct ExternalError {
+    pub command: PathBuf,
+    pub problem: String,
+}
+
+impl Display for ExternalError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.command.display(), self.problem)
+    }
+}
+
+#[derive(Deserialize)]
+struct Answer {
+    actions: Vec<AnswerAction>,
+}
+
+#[derive(Deserialize)]
+struct AnswerAction {
+    x: usize,
+    y: usize,
+    operation: AnswerOperation,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum AnswerOperation {
+    Reveal,
+    Chord,
+    Flag,
+}
+
+impl From<AnswerOperation> for Operation {
+    fn from(operation: AnswerOperation) -> Self {
+        match operation {
+            AnswerOperation::Reveal => Operation::Reveal,
+            AnswerOperation::Chord => Operation::Chord,
+            AnswerOperation::Flag => Operation::Flag,
+        }
+    }
+}
+
+impl ExternalSolver {
+    pub fn new(command: PathBuf, timeout: Duration) -> Self {
+        Self { command, timeout }
+    }
+
+    /// runs the process once, `Ok(None)` when it has no move
+    pub fn ask(&self, game_state: &GameState) -> Result<Option<Move>, ExternalError> {
+        let error = |problem: String| ExternalError { command: self.command.clone(), problem };
+        let output = self.run(request(game_state).to_string()).map_err(error)?;
+        let answer = serde_json::from_str::<Option<Answer>>(&output)
+                .map_err(|e| error(format!("answered with something that isn't a move: {}", e)))?;
+        let Some(answer) = answer else { return Ok(None) };
+
+        let size = game_state.board.size();
+        let mut actions = HashSet::new();
+        for action in answer.actions {
+            if action.x >= size.width().get() || action.y >= size.height().get() {
+                return Err(error(format!("answered with ({}, {}), which is off the board", action.x, action.y)))
+            }
+            let point: Point = (action.x, action.y);
+            actions.insert(Action::new(point, action.operation.into()));
+        }
+        Ok((!actions.is_empty()).then(|| Move::multi(actions, None)))
+    }
+
+    fn run(&self, input: String) -> Result<String, String> {
+        let started = Instant::now();
+        let mut child = Command::new(&self.command)
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .spawn()
+                .map_err(|e| format!("couldn't be started: {}", e))?;
+        let (Some(mut stdin), Some(mut stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            stop(child);
+            return Err("couldn't be connected to".to_string())
+        };
+        let pid = child.id();
+        if let Ok(mut running) = RUNNING.lock() {
+            running.insert(pid, child);
+        }
+
+        // written and read on threads of their own, a program that answers before it's read the
+        // whole board would otherwise fill its stdout while this is still stuck writing its stdin
+        let (sender, receiver) = channel();
+        let writer = sender.clone();
+        std::thread::spawn(move || {
+            // one that answered without reading it all has closed its end, which is fine
+            if let Err(e) = stdin.write_all(input.as_bytes())
+                    && e.kind() != ErrorKind::BrokenPipe {
+                let _ = writer.send(Err(e));
+            }
+        });
+        std::thread::spawn(move || {
+            let mut output = String::new();
+            let _ = sender.send(stdout.read_to_string(&mut output).map(|_| output));
+        });
+        let result = receiver.recv_timeout(self.timeout);
+
+        let Some(mut child) = RUNNING.lock().ok().and_then(|mut running| running.remove(&pid)) else {
+            return Err("was stopped".to_string())
+        };
+        let timed_out = || format!("didn't answer within {:.1}s", self.timeout.as_secs_f32());
+        let output = match result {
+            Ok(Ok(output)) => output,
+            Ok(Err(e)) => {
+                stop(child);
+                return Err(format!("couldn't be talked to: {}", e))
+            }
+            Err(RecvTimeoutError::Timeout | RecvTimeoutError::Disconnected) => {
+                stop(child);
+                return Err(timed_out())
+            }
+        };
+        // closing stdout doesn't mean it's done yet
+        loop {
+            match child.try_wait() {
+                Ok(Some(status)) if status.success() => return Ok(output),
+                Ok(Some(status)) => return Err(format!("failed with {}", status)),
+                Ok(None) if started.elapsed() < self.timeout => std::thread::sleep(Duration::from_millis(5)),
+                Ok(None) => {
+                    stop(child);
+                    return Err(timed_out())
+                }
+                Err(e) => {
+                    stop(child);
+                    return Err(e.to_string())
+                }
+            }
+        }
+    }
+}
+
+fn stop(mut child: Child) {
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+impl Solver for ExternalSolver {
+    fn solve(&self, game_state: &GameState) -> Option<Move> {
+        self.ask(game_state)
+                .unwrap_or_else(|e| {
+                    FAILURES.send_replace(Some(e));
+                    None
+                })
+    }
+
+    /// boards are only made safe to start, running the process for every move of every candidate
+    /// board would take minutes
+    fn solve_game(&self, minsweeper: &mut dyn Minsweeper) -> GameResult {
+        SafeStart.solve_game(minsweeper)
+    }
+}
+
+fn request(game_state: &GameState) -> Value {
+    let size = game_state.board.size();
+    let cells = (0..size.height().get())
+            .map(|y| (0..size.width().get())
+                    .map(|x| {
+                        let cell = game_state.board[(x, y)];
+                        let state = match cell.cell_state {
+                            CellState::Unknown => "unknown",
+                            CellState::Revealed => "revealed",
+                            CellState::Flagged => "flagged",
+                        };
+                        let kind = match cell.cell_type {
+                            CellType::Safe(number) => json!(number),
+                            CellType::Mine => json!("mine"),
+                            CellType::Unknown => json!("unknown"),
+                        };
+                        json!({ "state": state, "type": kind })
+                    })
+                    .collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+    json!({
+        "width": size.width().get(),
+        "height": size.height().get(),
+        "mines": size.mines().get(),
+        "remaining_mines": game_state.remaining_mines,
+        "status": match game_state.status {
+            GameStatus::Playing => "playing",
+            GameStatus::Won => "won",
+            GameStatus::Lost => "lost",
+            GameStatus::Never => "never",
+        },
+        "cells": cells,
+    })
+}
+
+/// every failure of a solve from here on
+pub fn failures() -> impl Stream<Item = ExternalError> {
+    futures_util::stream::unfold(FAILURES.subscribe(), |mut receiver| async move {
+        receiver.changed().await.ok()?;
+        let failure = receiver.borrow_and_update().clone();
+        Some((failure, receiver))
+    }).filter_map(|failure| async move { failure })
+}
+
+/// for when the app exits, whatever was asked can't be answered anymore
+pub fn kill_all() {
+    if let Ok(mut running) = RUNNING.lock() {
+        for (_, child) in running.drain() {
+            stop(child);
+        }
+    }
+}
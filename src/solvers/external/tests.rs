@@ -0,0 +1,85 @@
+use super::*;
+use minsweeper_rs::board::BoardSize;
+use std::os::unix::fs::PermissionsExt;
+
+/// a script being written while another test starts a process can be inherited open by it, and
+/// then can't be run
+static SPAWNING: Mutex<()> = Mutex::new(());
+
+/// a shell script standing in for a solver, gone again when it's dropped
+struct Script(PathBuf);
+
+impl Script {
+    fn new(name: &str, body: &str) -> Self {
+        let _spawning = SPAWNING.lock().unwrap_or_else(|e| e.into_inner());
+        let path = std::env::temp_dir().join(format!("minsweeper-solver-{}-{}.sh", std::process::id(), name));
+        std::fs::write(&path, format!("#!/bin/sh\n{}\n", body)).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        Self(path)
+    }
+
+    fn ask(&self, game_state: &GameState) -> Result<Option<Move>, ExternalError> {
+        self.ask_within(game_state, DEFAULT_TIMEOUT)
+    }
+
+    fn ask_within(&self, game_state: &GameState, timeout: Duration) -> Result<Option<Move>, ExternalError> {
+        let _spawning = SPAWNING.lock().unwrap_or_else(|e| e.into_inner());
+        ExternalSolver::new(self.0.clone(), timeout).ask(game_state)
+    }
+}
+
+impl Drop for Script {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+fn board(width: usize, height: usize) -> GameState {
+    let size = BoardSize::new(width, height, 1).unwrap();
+    GameState::new(GameStatus::Playing, minsweeper_rs::board::Board::empty(size), 1)
+}
+
+#[test]
+fn an_answer_is_read_as_a_move() {
+    let script = Script::new("answer", r#"cat > /dev/null
+echo '{ "actions": [{ "x": 1, "y": 0, "operation": "flag" }] }'"#);
+    let found = script.ask(&board(3, 3)).unwrap().unwrap();
+    assert_eq!(found.actions.into_iter().collect::<Vec<_>>(), [Action::new((1, 0), Operation::Flag)]);
+}
+
+#[test]
+fn null_is_no_move() {
+    let script = Script::new("null", "cat > /dev/null\necho null");
+    assert!(script.ask(&board(3, 3)).unwrap().is_none());
+}
+
+#[test]
+fn a_big_answer_before_reading_a_big_board_does_not_deadlock() {
+    // both well past what a pipe holds, each side is writing before the other reads
+    let script = Script::new("eager", r#"printf '{ "actions": [] %200000s}' ''
+cat > /dev/null"#);
+    assert!(script.ask(&board(300, 300)).unwrap().is_none());
+}
+
+#[test]
+fn answering_without_reading_the_board_is_fine() {
+    let script = Script::new("unread", "echo null");
+    assert!(script.ask(&board(300, 300)).unwrap().is_none());
+}
+
+#[test]
+fn moves_off_the_board_and_failures_are_errors() {
+    let off = Script::new("off", r#"cat > /dev/null
+echo '{ "actions": [{ "x": 3, "y": 0, "operation": "reveal" }] }'"#);
+    assert!(off.ask(&board(3, 3)).unwrap_err().problem.contains("off the board"));
+
+    let failed = Script::new("failed", "cat > /dev/null\nexit 3");
+    assert!(failed.ask(&board(3, 3)).is_err());
+}
+
+#[test]
+fn a_slow_program_times_out() {
+    let script = Script::new("slow", "sleep 5");
+    let answer = script.ask_within(&board(3, 3), Duration::from_millis(100));
+    assert!(answer.unwrap_err().problem.starts_with("didn't answer"));
+}
@@ -0,0 +1,221 @@
+pub mod external;
+
+use crate::i18n::t;
+use crate::minsweeper::SolverType;
+use minsweeper_rs::solver::mia::{Level, MiaSolver};
+use minsweeper_rs::solver::start::{SafeStart, WinStart, ZeroStart};
+use minsweeper_rs::solver::{GameResult, Move, Solver};
+use minsweeper_rs::{GameState, Minsweeper};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+use std::fmt::{Debug, Display, Formatter};
+use std::sync::{Arc, LazyLock};
+
+/// a solver that can be picked in the menus, adding one here is all it takes to offer it
+pub struct SolverEntry {
+    /// what it's stored under in settings, never reuse or rename one
+    pub id: &'static str,
+    pub label: String,
+    pub build: fn() -> SolverType,
+    /// whether generated boards have to be solvable start to finish rather than just at the start
+    pub no_guess: bool,
+    /// whether it ever plays a move, the start solvers only judge how a board opens
+    pub assists: bool,
+}
+
+const DEFAULT: &str = "MiaSolver";
+
+/// the ids are the names these had back when they were an enum, so older settings still load
+static REGISTRY: LazyLock<Vec<SolverEntry>> = LazyLock::new(|| vec![
+    entry("MiaSolver", "Mia Solver", true, || Arc::new(MiaSolver::default())),
+    entry("BeginnerSolver", "Beginner Solver", true, || Arc::new(MiaSolver::skill(Level::Beginner))),
+    entry("IntermediateSolver", "Intermediate Solver", true, || Arc::new(MiaSolver::skill(Level::Intermediate))),
+    entry("ExpertSolver", "Expert Solver", true, || Arc::new(MiaSolver::skill(Level::Expert))),
+    entry("BeginnerOnlySolver", "Beginner Only Solver", true, || Arc::new(MiaSolver::only(Level::Beginner))),
+    entry("IntermediateOnlySolver", "Intermediate Only Solver", true, || Arc::new(MiaSolver::only(Level::Intermediate))),
+    entry("ExpertOnlySolver", "Expert Only Solver", true, || Arc::new(MiaSolver::only(Level::Expert))),
+    start("SafeStart", "Safe Start", || Arc::new(SafeStart)),
+    start("ZeroStart", "Zero Start", || Arc::new(ZeroStart)),
+    start("WinStart", "Win Start", || Arc::new(WinStart)),
+]);
+
+fn entry(id: &'static str, label: &str, no_guess: bool, build: fn() -> SolverType) -> SolverEntry {
+    SolverEntry { id, label: label.to_string(), build, no_guess, assists: true }
+}
+
+fn start(id: &'static str, label: &str, build: fn() -> SolverType) -> SolverEntry {
+    SolverEntry { id, label: label.to_string(), build, no_guess: false, assists: false }
+}
+
+/// in menu order
+pub fn registry() -> &'static [SolverEntry] {
+    &REGISTRY
+}
+
+/// the ones that can be picked as the assistant
+pub fn assistants() -> impl Iterator<Item = &'static SolverEntry> {
+    REGISTRY.iter().filter(|entry| entry.assists)
+}
+
+pub fn find(id: &str) -> Option<&'static SolverEntry> {
+    REGISTRY.iter().find(|entry| entry.id == id)
+}
+
+/// replaces the solver id at `pointer` with `fallback` if it isn't one [`registry`] knows, or
+/// drops the ones it doesn't know if it's a list of ids, returning the ids that were thrown away
+pub fn forget_unknown(value: &mut Value, pointer: &str, fallback: Value) -> Vec<String> {
+    let unknown = |id: &Value| id.as_str().filter(|id| find(id).is_none()).map(str::to_string);
+    match value.pointer_mut(pointer) {
+        Some(Value::Array(ids)) => {
+            let forgotten = ids.iter().filter_map(unknown).collect();
+            ids.retain(|id| unknown(id).is_none());
+            forgotten
+        }
+        Some(field) => match unknown(field) {
+            Some(id) => {
+                *field = fallback;
+                vec![id]
+            }
+            None => vec![],
+        },
+        None => vec![],
+    }
+}
+
+/// asks each solver in turn and plays the first move any of them has, so a start solver can hand
+/// over to one that plays the rest
+pub struct ChainedSolver(pub Vec<SolverType>);
+
+impl Solver for ChainedSolver {
+    fn solve(&self, game_state: &GameState) -> Option<Move> {
+        self.0.iter().find_map(|solver| solver.solve(game_state))
+    }
+
+    /// each one has to get through its part of the game for the next to take over, an empty chain
+    /// takes any board
+    fn solve_game(&self, minsweeper: &mut dyn Minsweeper) -> GameResult {
+        let mut result = GameResult::Won;
+        for solver in &self.0 {
+            result = solver.solve_game(minsweeper);
+            if !matches!(result, GameResult::Won) {
+                break
+            }
+        }
+        result
+    }
+}
+
+/// what the first click is guaranteed, boards are generated until it holds
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum FirstClick {
+    /// it can be a mine
+    Any,
+    #[default]
+    Safe,
+    /// it opens up a zero
+    Opening,
+}
+
+impl FirstClick {
+    pub const ALL: [FirstClick; 3] = [FirstClick::Any, FirstClick::Safe, FirstClick::Opening];
+
+    /// the start solver that checks for it, nothing needs checking for `Any`
+    pub fn solver(self) -> Option<SolverType> {
+        match self {
+            FirstClick::Any => None,
+            FirstClick::Safe => Some(Arc::new(SafeStart)),
+            FirstClick::Opening => Some(Arc::new(ZeroStart)),
+        }
+    }
+
+    /// what picking the start solver `id` as the solver used to mean, from back when the opening
+    /// was picked that way. a win on the first click is closest to an opening
+    pub fn replacing(id: &str) -> Option<FirstClick> {
+        match id {
+            "SafeStart" => Some(FirstClick::Safe),
+            "ZeroStart" | "WinStart" => Some(FirstClick::Opening),
+            _ => None,
+        }
+    }
+}
+
+impl Display for FirstClick {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            FirstClick::Any => t!("first-click-any"),
+            FirstClick::Safe => t!("first-click-safe"),
+            FirstClick::Opening => t!("first-click-opening"),
+        })
+    }
+}
+
+/// how a chain shows in the menus
+pub fn chain_label(chain: &[KnownSolver]) -> String {
+    chain.iter()
+            .map(|solver| solver.entry().label.as_str())
+            .collect::<Vec<_>>()
+            .join(" → ")
+}
+
+/// a handle to an entry of the [`registry`], saved as its id
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub struct KnownSolver(&'static str);
+
+impl KnownSolver {
+    pub fn entry(self) -> &'static SolverEntry {
+        find(self.0).expect("known solvers only come from the registry")
+    }
+
+    pub fn id(self) -> &'static str {
+        self.0
+    }
+
+    pub fn needs_no_guess(self) -> bool {
+        self.entry().no_guess
+    }
+}
+
+impl Default for KnownSolver {
+    fn default() -> Self {
+        KnownSolver(DEFAULT)
+    }
+}
+
+impl Debug for KnownSolver {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+impl Display for KnownSolver {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.entry().label)
+    }
+}
+
+impl From<&'static SolverEntry> for KnownSolver {
+    fn from(entry: &'static SolverEntry) -> Self {
+        KnownSolver(entry.id)
+    }
+}
+
+impl From<KnownSolver> for SolverType {
+    fn from(value: KnownSolver) -> Self {
+        (value.entry().build)()
+    }
+}
+
+impl Serialize for KnownSolver {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for KnownSolver {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = String::deserialize(deserializer)?;
+        find(&id)
+                .map(KnownSolver::from)
+                .ok_or_else(|| de::Error::custom(format!("unknown solver {:?}", id)))
+    }
+}
@@ -0,0 +1,101 @@
+use std::sync::mpsc::{channel, Sender};
+use std::sync::{LazyLock, Mutex};
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Sound {
+    Reveal,
+    Flag,
+    Explosion,
+    Fanfare,
+}
+
+impl Sound {
+    #[cfg_attr(not(feature = "sound"), allow(dead_code))]
+    fn asset(self) -> &'static [u8] {
+        match self {
+            Sound::Reveal => include_bytes!("reveal.wav"),
+            Sound::Flag => include_bytes!("flag.wav"),
+            Sound::Explosion => include_bytes!("explosion.wav"),
+            Sound::Fanfare => include_bytes!("fanfare.wav"),
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "sound"), allow(dead_code))]
+enum Command {
+    Play(Sound),
+    Volume(f32),
+}
+
+// audio output isn't Send on every platform so it lives on its own thread, if the device can't be
+// opened that thread just ends and every send after that quietly fails
+static PLAYER: LazyLock<Mutex<Sender<Command>>> = LazyLock::new(|| {
+    let (sender, receiver) = channel();
+    let spawned = std::thread::Builder::new()
+            .name("sound".to_string())
+            .spawn(move || run(receiver));
+    if let Err(e) = spawned {
+        tracing::warn!("failed to start sound thread: {}", e);
+    }
+    Mutex::new(sender)
+});
+
+fn send(command: Command) {
+    if let Ok(sender) = PLAYER.lock() {
+        let _ = sender.send(command);
+    }
+}
+
+pub fn play(sound: Sound) {
+    send(Command::Play(sound))
+}
+
+pub fn set_volume(volume: f32) {
+    send(Command::Volume(volume))
+}
+
+#[cfg(feature = "sound")]
+fn run(receiver: std::sync::mpsc::Receiver<Command>) {
+    use rodio::{Decoder, DeviceSinkBuilder, Source};
+    use std::collections::HashMap;
+    use std::io::Cursor;
+    use std::time::{Duration, Instant};
+
+    // a cascade or a fast auto solve shouldn't turn into a wall of identical clicks
+    const DEBOUNCE: Duration = Duration::from_millis(80);
+
+    let mut sink = match DeviceSinkBuilder::open_default_sink() {
+        Ok(sink) => sink,
+        Err(e) => {
+            tracing::warn!("failed to open audio device: {}", e);
+            return
+        }
+    };
+    sink.log_on_drop(false);
+
+    let mut volume = 1.0;
+    let mut last_played = HashMap::new();
+
+    for command in receiver {
+        match command {
+            Command::Volume(value) => volume = value,
+            Command::Play(sound) => {
+                if volume <= 0.0 || last_played.get(&sound)
+                        .is_some_and(|last: &Instant| last.elapsed() < DEBOUNCE) {
+                    continue
+                }
+                last_played.insert(sound, Instant::now());
+
+                match Decoder::try_from(Cursor::new(sound.asset())) {
+                    Ok(source) => sink.mixer().add(source.amplify(volume)),
+                    Err(e) => tracing::warn!("failed to decode sound {:?}: {}", sound, e),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "sound"))]
+fn run(receiver: std::sync::mpsc::Receiver<Command>) {
+    drop(receiver);
+}
@@ -0,0 +1,374 @@
+use crate::achievements::{self, Achievement, Achievements};
+use crate::dialog::Flagged;
+use crate::i18n::t;
+use crate::minsweeper::GameOver;
+use crate::minsweeper::size_label;
+use crate::settings_menu::SerializableBoardSize;
+use crate::paths;
+use iced::widget::{button, container, row, scrollable, text, Container};
+use iced::{widget, Element, Length, Task};
+use minsweeper_rs::board::BoardSize;
+use minsweeper_rs::GameStatus;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io;
+use std::io::Write;
+use std::time::Duration;
+
+#[cfg(test)]
+mod tests;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Stats {
+    entries: Vec<Entry>,
+    #[serde(default)]
+    blitz: BlitzRecord,
+    #[serde(default)]
+    achievements: Achievements,
+}
+
+/// timed runs of beginner boards
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
+struct BlitzRecord {
+    runs: u32,
+    /// the most boards cleared in one run
+    best: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Entry {
+    size: SerializableBoardSize,
+    player: Record,
+    auto: Record,
+    #[serde(default)]
+    retried: Record,
+    /// games saved from a loss by an undo or the mistake guard
+    #[serde(default)]
+    assisted: Record,
+    /// games played without flags
+    #[serde(default)]
+    no_flags: Record,
+    /// auto games where the solver had to guess along the way
+    #[serde(default)]
+    guessed: Record,
+}
+
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Record {
+    started: u32,
+    won: u32,
+    streak: u32,
+    best_streak: u32,
+    time_played: Duration,
+    win_time: Duration,
+    #[serde(default)]
+    best_time: Option<Duration>,
+}
+
+impl Record {
+    fn record(&mut self, status: GameStatus, elapsed: Duration) {
+        self.started += 1;
+        self.time_played += elapsed;
+        if status == GameStatus::Won {
+            self.won += 1;
+            self.win_time += elapsed;
+            self.best_time = Some(self.best_time.map_or(elapsed, |best| best.min(elapsed)));
+            self.streak += 1;
+            self.best_streak = u32::max(self.best_streak, self.streak);
+        } else {
+            self.streak = 0;
+        }
+    }
+
+    pub fn win_rate(&self) -> Option<f32> {
+        (self.started > 0).then(|| self.won as f32 / self.started as f32)
+    }
+
+    pub fn average_win_time(&self) -> Option<Duration> {
+        (self.won > 0).then(|| self.win_time / self.won)
+    }
+}
+
+impl Stats {
+    fn save(&self) -> io::Result<()> {
+        let path = paths::stats_file()?;
+        let temp = path.with_extension("json.tmp");
+
+        let mut file = File::create(&temp)?;
+        serde_json::to_writer(&mut file, self)?;
+        file.flush()?;
+        file.sync_all()?;
+        drop(file);
+
+        std::fs::rename(temp, path)
+    }
+
+    pub fn load() -> io::Result<Self> {
+        let file = File::open(paths::stats_file()?)?;
+
+        let stats = serde_json::from_reader(file)?;
+
+        Ok(stats)
+    }
+
+    fn entry(&mut self, size: BoardSize) -> &mut Entry {
+        let index = match self.entries.iter().position(|e| e.size.0 == size) {
+            Some(index) => index,
+            None => {
+                self.entries.push(Entry {
+                    size: SerializableBoardSize(size),
+                    player: Record::default(),
+                    auto: Record::default(),
+                    retried: Record::default(),
+                    assisted: Record::default(),
+                    no_flags: Record::default(),
+                    guessed: Record::default(),
+                });
+                self.entries.len() - 1
+            }
+        };
+        &mut self.entries[index]
+    }
+
+    /// returns the achievements it unlocked
+    pub fn record(&mut self, game_over: &GameOver) -> Vec<Achievement> {
+        if !game_over.eligible {
+            return vec![]
+        }
+        let unlocked = self.achievements.record(game_over);
+        let entry = self.entry(game_over.size);
+        let record = if game_over.auto && game_over.guesses > 0 {
+            &mut entry.guessed
+        } else if game_over.auto {
+            &mut entry.auto
+        } else if game_over.assisted() {
+            &mut entry.assisted
+        } else if game_over.retry {
+            &mut entry.retried
+        } else if game_over.no_flags {
+            &mut entry.no_flags
+        } else {
+            &mut entry.player
+        };
+        record.record(game_over.status, game_over.elapsed);
+        unlocked
+    }
+}
+
+#[derive(Debug)]
+pub struct Statistics {
+    stats: Stats,
+    dialog: bool,
+    achievements: bool,
+}
+
+impl Default for Statistics {
+    fn default() -> Self {
+        Self {
+            stats: Stats::load()
+                    .unwrap_or_else(|e| {
+                        tracing::error!("failed to load stats: {}", e);
+                        Stats::default()
+                    }),
+            dialog: false,
+            achievements: false,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Message {
+    Dialog(bool),
+    Achievements(bool),
+    Record(GameOver),
+    /// achievements that were just unlocked, for the app to announce
+    Unlocked(Vec<Achievement>),
+    /// a blitz ended with this many boards cleared
+    RecordBlitz(u32),
+    Reset(BoardSize),
+    ResetAll,
+    Export,
+    Detach,
+    DetachAchievements,
+}
+
+impl Statistics {
+
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        let mut task = Task::none();
+        match message {
+            Message::Dialog(value) => {
+                self.dialog = value;
+                return Task::none()
+            }
+            Message::Achievements(value) => {
+                self.achievements = value;
+                return Task::none()
+            }
+            Message::Record(game_over) => {
+                let unlocked = self.stats.record(&game_over);
+                if !unlocked.is_empty() {
+                    task = Task::done(Message::Unlocked(unlocked));
+                }
+            }
+            Message::RecordBlitz(cleared) => {
+                self.stats.blitz.runs += 1;
+                self.stats.blitz.best = self.stats.blitz.best.max(cleared);
+            }
+            Message::Reset(size) => self.stats.entries.retain(|e| e.size.0 != size),
+            // achievements stay earned
+            Message::ResetAll => self.stats = Stats {
+                achievements: std::mem::take(&mut self.stats.achievements),
+                ..Stats::default()
+            },
+            Message::Export | Message::Detach | Message::DetachAchievements | Message::Unlocked(_) => return Task::none(),
+        }
+
+        if let Err(e) = self.stats.save() {
+            tracing::error!("failed to save stats: {}", e);
+        }
+
+        task
+    }
+
+    pub fn blitz_best(&self) -> u32 {
+        self.stats.blitz.best
+    }
+
+    pub fn csv(&self) -> String {
+        let mut csv = "size,played,won,streak,best streak,seconds played,seconds winning,auto played,auto won,retries played,retries won,assisted played,assisted won,nf played,nf won,nf best seconds,guessed played,guessed won\n".to_string();
+        for entry in &self.stats.entries {
+            let (player, auto, retried, assisted, no_flags, guessed) = (entry.player, entry.auto, entry.retried, entry.assisted, entry.no_flags, entry.guessed);
+            csv += &format!("\"{}\",{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}\n",
+                size_label(entry.size.0), player.started, player.won, player.streak, player.best_streak,
+                player.time_played.as_secs(), player.win_time.as_secs(),
+                auto.started, auto.won, retried.started, retried.won, assisted.started, assisted.won,
+                no_flags.started, no_flags.won, no_flags.best_time.map(|best| best.as_secs().to_string()).unwrap_or_default(),
+                guessed.started, guessed.won);
+        }
+        csv
+    }
+
+    /// the statistics table without any dialog chrome, shared by the dialog and the detached window
+    pub fn content<'a>(&self) -> Element<'a, Message> {
+        let header = row![
+            cell(text(t!("statistics-size"))).width(180),
+            cell(text(t!("statistics-played"))),
+            cell(text(t!("statistics-won"))),
+            cell(text(t!("statistics-win-rate"))),
+            cell(text(t!("statistics-streak"))),
+            cell(text(t!("statistics-best-streak"))),
+            cell(text(t!("statistics-time-played"))),
+            cell(text(t!("statistics-average-win"))),
+            cell(text(t!("statistics-auto-won"))),
+            cell(text(t!("statistics-retries-won"))),
+            cell(text(t!("statistics-assisted-won"))),
+            cell(text(t!("statistics-no-flags-won"))),
+            cell(text(t!("statistics-no-flags-best"))),
+            cell(text(t!("statistics-guessed-won"))),
+            cell(text("")),
+        ];
+
+        let rows = self.stats.entries.iter()
+                .map(|entry| {
+                    let player = entry.player;
+                    row![
+                        cell(text(size_label(entry.size.0))).width(180),
+                        cell(text(player.started.to_string())),
+                        cell(text(player.won.to_string())),
+                        cell(text(player.win_rate()
+                                .map(|rate| format!("{:.1}%", rate * 100.0))
+                                .unwrap_or_else(|| "-".to_string()))),
+                        cell(text(player.streak.to_string())),
+                        cell(text(player.best_streak.to_string())),
+                        cell(text(format_duration(player.time_played))),
+                        cell(text(player.average_win_time()
+                                .map(format_duration)
+                                .unwrap_or_else(|| "-".to_string()))),
+                        cell(text(format!("{}/{}", entry.auto.won, entry.auto.started))),
+                        cell(text(format!("{}/{}", entry.retried.won, entry.retried.started))),
+                        cell(text(format!("{}/{}", entry.assisted.won, entry.assisted.started))),
+                        cell(text(format!("{}/{}", entry.no_flags.won, entry.no_flags.started))),
+                        cell(text(entry.no_flags.best_time
+                                .map(format_duration)
+                                .unwrap_or_else(|| "-".to_string()))),
+                        cell(text(format!("{}/{}", entry.guessed.won, entry.guessed.started))),
+                        cell(button(t!("statistics-reset")).on_press(Message::Reset(entry.size.0))),
+                    ].into()
+                });
+
+        widget::column![header]
+                .extend(rows)
+                .into()
+    }
+
+    pub fn dialogs<'a>(&self) -> impl Iterator<Item = Flagged<'a, Message>> {
+        let mut vec = vec![];
+
+        if self.dialog {
+            vec.push(Flagged::new(
+                widget::column![
+                    text(t!("statistics")),
+                    scrollable(self.content())
+                            .height(Length::Shrink),
+                    row![
+                        button(t!("close")).on_press(Message::Dialog(false)),
+                        button(t!("statistics-reset-all")).on_press(Message::ResetAll),
+                        button(t!("statistics-export")).on_press(Message::Export),
+                        button(t!("detach")).on_press(Message::Detach),
+                    ].spacing(10),
+                ].spacing(10).into(),
+                Message::Dialog(false)));
+        }
+
+        if self.achievements {
+            vec.push(Flagged::new(
+                widget::column![
+                    text(t!("achievements")),
+                    scrollable(self.achievements_content())
+                            .height(Length::Shrink),
+                    row![
+                        button(t!("close")).on_press(Message::Achievements(false)),
+                        button(t!("detach")).on_press(Message::DetachAchievements),
+                    ].spacing(10),
+                ].spacing(10).into(),
+                Message::Achievements(false)));
+        }
+
+        vec.into_iter()
+    }
+
+    /// every achievement, the unlocked ones with the day they were, shared by the dialog and the
+    /// detached window
+    pub fn achievements_content<'a>(&self) -> Element<'a, Message> {
+        widget::column(Achievement::all()
+                .map(|achievement| {
+                    let unlocked = self.stats.achievements.unlocked(achievement);
+                    row![
+                        text(if unlocked.is_some() { "★" } else { "☆" }).width(20),
+                        widget::column![
+                            text(achievement.to_string()),
+                            text(achievement.description()).size(12),
+                        ].width(Length::Fill),
+                        text(unlocked.map_or_else(|| t!("achievement-locked").to_string(), |unlocked| achievements::date(unlocked.at))),
+                    ].spacing(10).into()
+                }))
+                .spacing(8)
+                .width(420)
+                .into()
+    }
+}
+
+fn cell<'a>(content: impl Into<Element<'a, Message>>) -> Container<'a, Message> {
+    container(content)
+            .width(90)
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    if seconds >= 3600 {
+        format!("{}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60)
+    } else {
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    }
+}
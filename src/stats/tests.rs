@@ -0,0 +1,65 @@
+use super::Stats;
+use crate::minsweeper::GameOver;
+use minsweeper_rs::board::BoardSize;
+use minsweeper_rs::GameStatus;
+use std::time::Duration;
+
+fn game_over(status: GameStatus) -> GameOver {
+    GameOver {
+        size: BoardSize::new(20, 20, 50).unwrap(),
+        status,
+        elapsed: Duration::from_secs(600),
+        clicks: 0,
+        player_clicks: 0,
+        bbbv: None,
+        seed: 0,
+        revealed_by_player: 0,
+        revealed_by_solver: 0,
+        auto: false,
+        retry: false,
+        undos: 0,
+        guarded: 0,
+        guesses: 0,
+        no_flags: false,
+        flagged: true,
+        eligible: true,
+    }
+}
+
+/// a win where the player finished the board but took a step from the solver on the way
+fn stepped() -> GameOver {
+    GameOver { revealed_by_player: 300, revealed_by_solver: 4, auto: true, ..game_over(GameStatus::Won) }
+}
+
+#[test]
+fn a_game_the_solver_moved_in_stays_out_of_the_player_column() {
+    let mut stats = Stats::default();
+    stats.record(&stepped());
+
+    let entry = &stats.entries[0];
+    assert_eq!(entry.player.started, 0);
+    assert_eq!((entry.auto.started, entry.auto.won), (1, 1));
+}
+
+#[test]
+fn a_game_the_solver_moved_in_leaves_the_streak_alone() {
+    let mut stats = Stats::default();
+    stats.record(&game_over(GameStatus::Won));
+    stats.record(&stepped());
+    stats.record(&GameOver { status: GameStatus::Lost, ..stepped() });
+    stats.record(&game_over(GameStatus::Won));
+
+    let player = stats.entries[0].player;
+    assert_eq!((player.started, player.won), (2, 2));
+    assert_eq!((player.streak, player.best_streak), (2, 2));
+}
+
+#[test]
+fn a_game_the_player_played_alone_is_theirs() {
+    let mut stats = Stats::default();
+    stats.record(&game_over(GameStatus::Won));
+
+    let entry = &stats.entries[0];
+    assert_eq!((entry.player.started, entry.player.won, entry.player.streak), (1, 1, 1));
+    assert_eq!(entry.auto.started, 0);
+}
@@ -0,0 +1,286 @@
+use crate::dialog::Flagged;
+use crate::journal;
+use crate::paths;
+use crate::privacy;
+use iced::widget::{button, row, scrollable, text};
+use iced::{widget, Element, Length, Task};
+use iced_aw::number_input;
+use iced_core::alignment::Vertical;
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+#[cfg(test)]
+mod tests;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Category {
+    Journals,
+}
+
+impl Category {
+    pub const ALL: [Category; 1] = [Category::Journals];
+
+    fn name(self) -> &'static str {
+        match self {
+            Category::Journals => "Interrupted games",
+        }
+    }
+
+    fn dir(self) -> io::Result<PathBuf> {
+        match self {
+            Category::Journals => paths::journals_dir(),
+        }
+    }
+
+    /// only files named the way the app names them are ever counted or deleted
+    fn matches(self, name: &str) -> bool {
+        match self {
+            Category::Journals => name.strip_prefix("journal-")
+                    .and_then(|rest| rest.strip_suffix(".bin"))
+                    .is_some_and(|timestamp| !timestamp.is_empty() && timestamp.bytes().all(|b| b.is_ascii_digit())),
+        }
+    }
+}
+
+/// limits applied to every category, 0 means unlimited
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Retention {
+    pub keep_last: usize,
+    pub max_days: u64,
+    pub max_mb: u64,
+}
+
+#[derive(Clone, Debug)]
+pub struct StoredFile {
+    path: PathBuf,
+    size: u64,
+    modified: SystemTime,
+}
+
+#[derive(Clone, Debug)]
+pub struct Usage {
+    category: Category,
+    files: usize,
+    bytes: u64,
+}
+
+fn list(category: Category) -> Vec<StoredFile> {
+    let Ok(entries) = category.dir().and_then(std::fs::read_dir) else { return vec![] };
+    let active = journal::active_path();
+
+    let mut files = entries
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_name().to_str().is_some_and(|name| category.matches(name)))
+            .filter(|entry| active.as_ref() != Some(&entry.path()))
+            .filter_map(|entry| {
+                let metadata = entry.metadata().ok()?;
+                metadata.is_file().then(|| StoredFile {
+                    path: entry.path(),
+                    size: metadata.len(),
+                    modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                })
+            })
+            .collect::<Vec<_>>();
+
+    files.sort_by_key(|file| std::cmp::Reverse(file.modified));
+    files
+}
+
+/// which of the files, newest first, fall outside the retention limits
+fn expired(files: &[StoredFile], retention: Retention, now: SystemTime) -> Vec<PathBuf> {
+    // a limit too big to count in is one nothing will reach
+    let max_age = retention.max_days.checked_mul(24 * 60 * 60)
+            .map_or(Duration::MAX, Duration::from_secs);
+    let max_bytes = retention.max_mb.saturating_mul(1024 * 1024);
+
+    let mut total: u64 = 0;
+    files.iter()
+            .enumerate()
+            .filter(|(index, file)| {
+                total = total.saturating_add(file.size);
+                (retention.keep_last != 0 && *index >= retention.keep_last)
+                        || (retention.max_days != 0 && now.duration_since(file.modified).unwrap_or_default() > max_age)
+                        || (retention.max_mb != 0 && total > max_bytes)
+            })
+            .map(|(_, file)| file.path.clone())
+            .collect()
+}
+
+fn usage() -> Vec<Usage> {
+    Category::ALL.into_iter()
+            .map(|category| {
+                let files = list(category);
+                Usage {
+                    category,
+                    files: files.len(),
+                    bytes: files.iter().fold(0, |bytes: u64, file| bytes.saturating_add(file.size)),
+                }
+            })
+            .collect()
+}
+
+fn plan(retention: Retention) -> Vec<PathBuf> {
+    let now = SystemTime::now();
+    Category::ALL.into_iter()
+            .flat_map(|category| expired(&list(category), retention, now))
+            .collect()
+}
+
+fn sweep(paths: Vec<PathBuf>) -> Vec<String> {
+    let active = journal::active_path();
+    paths.into_iter()
+            .filter(|path| active.as_ref() != Some(path))
+            .filter_map(|path| std::fs::remove_file(&path)
+                    .err()
+                    .map(|e| format!("{}: {}", path.display(), e)))
+            .collect()
+}
+
+async fn blocking<T: Send + 'static>(f: impl FnOnce() -> T + Send + 'static) -> T {
+    tokio::task::spawn_blocking(f).await
+            .expect("storage task should not panic")
+}
+
+#[derive(Debug, Default)]
+pub struct Storage {
+    dialog: bool,
+    usage: Option<Vec<Usage>>,
+    pending: Option<Vec<PathBuf>>,
+    failures: Vec<String>,
+    enforced: bool,
+}
+
+#[derive(Clone, Debug)]
+pub enum Message {
+    Dialog(bool),
+    Usage(Vec<Usage>),
+    ChangeRetention(Retention),
+    Clean(Retention),
+    Planned(Vec<PathBuf>),
+    Confirm,
+    Cancel,
+    Enforce(Retention),
+    Swept(Vec<String>),
+}
+
+impl Storage {
+
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        match message {
+            Message::Dialog(value) => {
+                self.dialog = value;
+                self.pending = None;
+                if value {
+                    self.failures.clear();
+                    return self.refresh()
+                }
+            }
+            Message::Usage(usage) => self.usage = Some(usage),
+            Message::ChangeRetention(_) => {}
+            Message::Clean(retention) => {
+                return Task::future(blocking(move || plan(retention)))
+                        .map(Message::Planned)
+            }
+            Message::Planned(paths) => self.pending = Some(paths),
+            Message::Confirm => {
+                let Some(paths) = self.pending.take() else { return Task::none() };
+                return Task::future(blocking(move || sweep(paths)))
+                        .map(Message::Swept)
+            }
+            Message::Cancel => self.pending = None,
+            Message::Enforce(retention) => {
+                if !std::mem::replace(&mut self.enforced, true) {
+                    return Task::future(blocking(move || sweep(plan(retention))))
+                            .map(Message::Swept)
+                }
+            }
+            Message::Swept(failures) => {
+                for failure in &failures {
+                    tracing::warn!("failed to delete {}", failure);
+                }
+                self.failures = failures;
+                if self.dialog {
+                    return self.refresh()
+                }
+            }
+        }
+        Task::none()
+    }
+
+    fn refresh(&self) -> Task<Message> {
+        Task::future(blocking(usage))
+                .map(Message::Usage)
+    }
+
+    pub fn dialogs<'a>(&self, retention: Retention) -> impl Iterator<Item = Flagged<'a, Message>> {
+        let mut vec = vec![];
+
+        if self.dialog {
+            let usage: Element<_> = match &self.usage {
+                Some(usage) => widget::column(usage.iter()
+                        .map(|usage| text!("{}: {} files, {}", usage.category.name(), usage.files, format_bytes(usage.bytes))
+                                .into()))
+                        .into(),
+                None => text("Calculating…").into(),
+            };
+
+            vec.push(Flagged::new(
+                widget::column![
+                    text!("Storage"),
+                    usage,
+                    row![
+                        text!("Keep last (0 = all): "),
+                        number_input(&retention.keep_last, 0.., move |keep_last| Message::ChangeRetention(Retention { keep_last, ..retention })),
+                    ].align_y(Vertical::Center),
+                    row![
+                        text!("Max age in days (0 = forever): "),
+                        number_input(&retention.max_days, 0.., move |max_days| Message::ChangeRetention(Retention { max_days, ..retention })),
+                    ].align_y(Vertical::Center),
+                    row![
+                        text!("Max MB per category (0 = unlimited): "),
+                        number_input(&retention.max_mb, 0.., move |max_mb| Message::ChangeRetention(Retention { max_mb, ..retention })),
+                    ].align_y(Vertical::Center),
+                    widget::column(self.failures.iter()
+                            .map(|failure| text!("couldn't delete {}", privacy::redact(failure)).into())),
+                    row![
+                        button("Close").on_press(Message::Dialog(false)),
+                        button("Clean now").on_press(Message::Clean(retention)),
+                    ].spacing(10),
+                ].spacing(10).into(),
+                Message::Dialog(false)));
+        }
+
+        if let Some(pending) = &self.pending {
+            vec.push(Flagged::new(
+                widget::column![
+                    text!("Delete {} files?", pending.len()),
+                    scrollable(widget::column(pending.iter()
+                            .map(|path| text(file_name(path)).into())))
+                            .height(Length::Shrink),
+                    row![
+                        button("Cancel").on_press(Message::Cancel),
+                        button("Delete").on_press_maybe((!pending.is_empty()).then_some(Message::Confirm)),
+                    ].spacing(10),
+                ].spacing(10).into(),
+                Message::Cancel));
+        }
+
+        vec.into_iter()
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+}
+
+fn format_bytes(bytes: u64) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    } else {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    }
+}
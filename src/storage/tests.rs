@@ -0,0 +1,40 @@
+use super::{expired, Retention, StoredFile};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+fn files(now: SystemTime, sizes: &[u64]) -> Vec<StoredFile> {
+    sizes.iter()
+            .enumerate()
+            .map(|(index, size)| StoredFile {
+                path: PathBuf::from(format!("journal-{}.bin", index)),
+                size: *size,
+                modified: now - Duration::from_secs(index as u64 * 24 * 60 * 60),
+            })
+            .collect()
+}
+
+fn names(paths: Vec<PathBuf>) -> Vec<String> {
+    paths.into_iter().map(|path| path.display().to_string()).collect()
+}
+
+#[test]
+fn no_limits_keep_everything() {
+    let now = SystemTime::now();
+    assert!(expired(&files(now, &[1, 2, 3]), Retention::default(), now).is_empty());
+}
+
+#[test]
+fn each_limit_expires_the_oldest() {
+    let now = SystemTime::now();
+    let files = files(now, &[512 * 1024, 512 * 1024, 512 * 1024]);
+    assert_eq!(names(expired(&files, Retention { keep_last: 2, ..Retention::default() }, now)), ["journal-2.bin"]);
+    assert_eq!(names(expired(&files, Retention { max_days: 1, ..Retention::default() }, now)), ["journal-2.bin"]);
+    assert_eq!(names(expired(&files, Retention { max_mb: 1, ..Retention::default() }, now)), ["journal-2.bin"]);
+}
+
+#[test]
+fn huge_limits_dont_overflow() {
+    let now = SystemTime::now();
+    let retention = Retention { keep_last: 0, max_days: u64::MAX, max_mb: u64::MAX };
+    assert!(expired(&files(now, &[u64::MAX, u64::MAX]), retention, now).is_empty());
+}
@@ -0,0 +1,142 @@
+use super::manifest::Manifest;
+use iced_core::svg::Handle;
+
+// the asset tables and the macros that fill them in for the built-in textures, kept apart from the
+// rest of the texture module so the compile-fail test can build them on their own
+
+macro_rules! textures {
+    ($($(#[$attr:meta])* $variant:ident => $dir:literal;)*) => {
+        #[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+        pub enum Texture {
+            $($(#[$attr])* $variant,)*
+            /// installed at runtime into the packs folder
+            Pack(pack::PackId),
+        }
+
+        impl Texture {
+            pub const BUILT_IN: &[Texture] = &[$(Texture::$variant),*];
+
+            fn assets(self) -> Arc<Assets> {
+                match self {
+                    $(Texture::$variant => {
+                        static ASSETS: LazyLock<Arc<Assets>> = LazyLock::new(|| Arc::new(assets!($dir, stringify!($variant))));
+                        ASSETS.clone()
+                    })*
+                    Texture::Pack(id) => id.assets()
+                            .unwrap_or_else(|| Texture::default().assets()),
+                }
+            }
+        }
+    };
+}
+
+macro_rules! assets {
+    ($dir:literal, $name:expr) => {
+        Assets {
+            cell: CellAssets {
+                up: embedded(include_bytes!(concat!($dir, "/cell/cellup.svg"))),
+                down: embedded(include_bytes!(concat!($dir, "/cell/celldown.svg"))),
+                numbers: [
+                    embedded(include_bytes!(concat!($dir, "/cell/cell1.svg"))),
+                    embedded(include_bytes!(concat!($dir, "/cell/cell2.svg"))),
+                    embedded(include_bytes!(concat!($dir, "/cell/cell3.svg"))),
+                    embedded(include_bytes!(concat!($dir, "/cell/cell4.svg"))),
+                    embedded(include_bytes!(concat!($dir, "/cell/cell5.svg"))),
+                    embedded(include_bytes!(concat!($dir, "/cell/cell6.svg"))),
+                    embedded(include_bytes!(concat!($dir, "/cell/cell7.svg"))),
+                    embedded(include_bytes!(concat!($dir, "/cell/cell8.svg"))),
+                ],
+                blast: embedded(include_bytes!(concat!($dir, "/cell/blast.svg"))),
+                mine: embedded(include_bytes!(concat!($dir, "/cell/cellmine.svg"))),
+                flag: embedded(include_bytes!(concat!($dir, "/cell/cellflag.svg"))),
+                false_mine: embedded(include_bytes!(concat!($dir, "/cell/falsemine.svg"))),
+            },
+            faces: FaceAssets {
+                smile: embedded(include_bytes!(concat!($dir, "/faces/smileface.svg"))),
+                smile_down: embedded(include_bytes!(concat!($dir, "/faces/smilefacedown.svg"))),
+                click: embedded(include_bytes!(concat!($dir, "/faces/clickface.svg"))),
+                win: embedded(include_bytes!(concat!($dir, "/faces/winface.svg"))),
+                lost: embedded(include_bytes!(concat!($dir, "/faces/lostface.svg"))),
+            },
+            digits: [
+                embedded(include_bytes!(concat!($dir, "/counter/counter0.svg"))),
+                embedded(include_bytes!(concat!($dir, "/counter/counter1.svg"))),
+                embedded(include_bytes!(concat!($dir, "/counter/counter2.svg"))),
+                embedded(include_bytes!(concat!($dir, "/counter/counter3.svg"))),
+                embedded(include_bytes!(concat!($dir, "/counter/counter4.svg"))),
+                embedded(include_bytes!(concat!($dir, "/counter/counter5.svg"))),
+                embedded(include_bytes!(concat!($dir, "/counter/counter6.svg"))),
+                embedded(include_bytes!(concat!($dir, "/counter/counter7.svg"))),
+                embedded(include_bytes!(concat!($dir, "/counter/counter8.svg"))),
+                embedded(include_bytes!(concat!($dir, "/counter/counter9.svg"))),
+            ],
+            minus: embedded(include_bytes!(concat!($dir, "/counter/counter-.svg"))),
+            borders: BorderAssets {
+                top_left: embedded(include_bytes!(concat!($dir, "/border/topleft.svg"))),
+                top_bottom: embedded(include_bytes!(concat!($dir, "/border/topbottom.svg"))),
+                top_right: embedded(include_bytes!(concat!($dir, "/border/topright.svg"))),
+                bottom_left: embedded(include_bytes!(concat!($dir, "/border/bottomleft.svg"))),
+                bottom_right: embedded(include_bytes!(concat!($dir, "/border/bottomright.svg"))),
+                left_right: embedded(include_bytes!(concat!($dir, "/border/leftright.svg"))),
+                middle_left: embedded(include_bytes!(concat!($dir, "/border/middleleft.svg"))),
+                middle_right: embedded(include_bytes!(concat!($dir, "/border/middleright.svg"))),
+                counter_left: embedded(include_bytes!(concat!($dir, "/border/counterleft.svg"))),
+                counter_right: embedded(include_bytes!(concat!($dir, "/border/counterright.svg"))),
+                counter_top: embedded(include_bytes!(concat!($dir, "/border/countertop.svg"))),
+                counter_bottom: embedded(include_bytes!(concat!($dir, "/border/counterbottom.svg"))),
+            },
+            manifest: Manifest::parse($name, include_str!(concat!($dir, "/manifest.toml")))
+                    .unwrap_or_else(|e| {
+                        tracing::warn!("{}", e);
+                        Manifest::default()
+                    }),
+        }
+    };
+}
+
+pub(super) struct Assets {
+    pub(super) cell: CellAssets,
+    pub(super) faces: FaceAssets,
+    pub(super) digits: [Handle; 10],
+    pub(super) minus: Handle,
+    pub(super) borders: BorderAssets,
+    pub(super) manifest: Manifest,
+}
+
+pub(super) struct CellAssets {
+    pub(super) up: Handle,
+    pub(super) down: Handle,
+    pub(super) numbers: [Handle; 8],
+    pub(super) blast: Handle,
+    pub(super) mine: Handle,
+    pub(super) flag: Handle,
+    pub(super) false_mine: Handle,
+}
+
+pub(super) struct FaceAssets {
+    pub(super) smile: Handle,
+    pub(super) smile_down: Handle,
+    pub(super) click: Handle,
+    pub(super) win: Handle,
+    pub(super) lost: Handle,
+}
+
+pub(super) struct BorderAssets {
+    pub(super) top_left: Handle,
+    pub(super) top_bottom: Handle,
+    pub(super) top_right: Handle,
+    pub(super) bottom_left: Handle,
+    pub(super) bottom_right: Handle,
+    pub(super) left_right: Handle,
+    pub(super) middle_left: Handle,
+    pub(super) middle_right: Handle,
+    pub(super) counter_left: Handle,
+    pub(super) counter_right: Handle,
+    pub(super) counter_top: Handle,
+    pub(super) counter_bottom: Handle,
+}
+
+/// a built-in asset, borrowed from the binary rather than copied
+pub(super) fn embedded(asset: &'static [u8]) -> Handle {
+    Handle::from_memory(asset)
+}
@@ -0,0 +1,154 @@
+use crate::dialog::Flagged;
+use crate::export;
+use crate::texture::pack;
+use crate::paths;
+use crate::privacy;
+use crate::texture::pack::{PackId, Validated};
+use iced::widget::{button, container, row, svg, text};
+use iced::{widget, Background, Task};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// what the preview shows of a pack before it's installed
+const SWATCHES: &[&str] = &[
+    "cell/cellup.svg", "cell/celldown.svg", "cell/cell1.svg", "cell/cell2.svg", "cell/cell3.svg",
+    "cell/cellflag.svg", "cell/cellmine.svg", "cell/blast.svg", "faces/smileface.svg", "counter/counter7.svg",
+];
+
+#[derive(Debug)]
+struct Preview {
+    name: String,
+    /// a pack of the same name is already installed
+    exists: bool,
+    pack: Arc<Validated>,
+}
+
+#[derive(Debug, Default)]
+pub struct Installer {
+    preview: Option<Preview>,
+    error: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub enum Message {
+    Pick,
+    Open(Option<PathBuf>),
+    Read { name: String, result: Result<Arc<Validated>, String> },
+    /// `replace` overwrites a pack of the same name, otherwise the new one gets a free name
+    Install { replace: bool },
+    Cancel,
+    Installed(Result<PackId, String>),
+    DismissError,
+}
+
+impl Installer {
+
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        match message {
+            Message::Pick => {
+                return Task::future(rfd::AsyncFileDialog::new()
+                        .add_filter("Texture pack", &["zip"])
+                        .pick_file())
+                        .map(|handle| Message::Open(handle.map(|handle| handle.path().to_path_buf())))
+            }
+            Message::Open(None) => {}
+            Message::Open(Some(path)) => {
+                let name = pack::sanitize(&path.file_stem()
+                        .map(|stem| stem.to_string_lossy().into_owned())
+                        .unwrap_or_default());
+                return Task::future(async move {
+                    tokio::task::spawn_blocking(move || pack::read_archive(&path)
+                            .map(Arc::new)
+                            .map_err(|e| e.to_string()))
+                            .await
+                            .unwrap_or_else(|e| Err(e.to_string()))
+                }).map(move |result| Message::Read { name: name.clone(), result })
+            }
+            Message::Read { name, result } => match result {
+                Ok(pack) => self.preview = Some(Preview {
+                    exists: paths::textures_dir().is_ok_and(|folder| folder.join(&name).exists()),
+                    name,
+                    pack,
+                }),
+                Err(e) => self.error = Some(e),
+            },
+            Message::Install { replace } => {
+                let Some(preview) = self.preview.take() else { return Task::none() };
+                let name = match paths::textures_dir() {
+                    Ok(folder) if preview.exists && !replace => export::available_name(&folder.join(&preview.name))
+                            .file_name()
+                            .map(|name| name.to_string_lossy().into_owned())
+                            .unwrap_or(preview.name),
+                    _ => preview.name,
+                };
+                let pack = preview.pack;
+                return Task::future(async move {
+                    tokio::task::spawn_blocking(move || pack::install(&name, &pack, replace)
+                            .map_err(|e| e.to_string()))
+                            .await
+                            .unwrap_or_else(|e| Err(e.to_string()))
+                }).map(Message::Installed)
+            }
+            Message::Cancel => self.preview = None,
+            Message::Installed(Ok(_)) => {}
+            Message::Installed(Err(e)) => self.error = Some(e),
+            Message::DismissError => self.error = None,
+        }
+        Task::none()
+    }
+
+    pub fn dialogs<'a>(&self) -> impl Iterator<Item = Flagged<'a, Message>> {
+        let mut vec = vec![];
+
+        if let Some(preview) = &self.preview {
+            let background = preview.pack.manifest.background;
+            let swatches = container(row(SWATCHES.iter()
+                    .filter_map(|file| preview.pack.files.get(file))
+                    .map(|contents| svg(svg::Handle::from_memory(contents.clone()))
+                            .width(32)
+                            .height(32)
+                            .into()))
+                    .spacing(4))
+                    .padding(8)
+                    .style(move |_| container::Style::default().background(Background::Color(background)));
+
+            let buttons = if preview.exists {
+                row![
+                    button("Cancel").on_press(Message::Cancel),
+                    button("Keep both").on_press(Message::Install { replace: false }),
+                    button("Replace").on_press(Message::Install { replace: true }),
+                ]
+            } else {
+                row![
+                    button("Cancel").on_press(Message::Cancel),
+                    button("Install").on_press(Message::Install { replace: false }),
+                ]
+            };
+
+            vec.push(Flagged::new(
+                widget::column![
+                    text!("Install texture pack \"{}\"?", preview.name),
+                    swatches,
+                ]
+                .extend(preview.pack.warnings.iter()
+                        .map(|warning| text!("⚠ {}", warning).into()))
+                .push(preview.exists.then(|| text!("A texture pack called \"{}\" is already installed", preview.name)))
+                .push(buttons.spacing(10))
+                .spacing(10)
+                .into(),
+                Message::Cancel));
+        }
+
+        if let Some(error) = &self.error {
+            vec.push(Flagged::new(
+                widget::column![
+                    text!("Can't install texture pack"),
+                    text(privacy::redact(error)),
+                    button("OK").on_press(Message::DismissError),
+                ].spacing(10).into(),
+                Message::DismissError));
+        }
+
+        vec.into_iter()
+    }
+}
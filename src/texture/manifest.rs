@@ -0,0 +1,155 @@
+use crate::texture::{MAX_TINT_ALPHA, TINT};
+use iced_core::Color;
+use std::fmt::{Display, Formatter};
+use toml_edit::{DocumentMut, Item, Value};
+
+#[cfg(test)]
+mod tests;
+
+/// the newest manifest layout this version understands
+pub const VERSION: i64 = 1;
+
+const DEFAULT_BACKGROUND: Color = Color::from_rgb(0.3, 0.33, 0.36);
+const DEFAULT_ACCENT: Color = Color::from_rgb8(0x5d, 0x9c, 0xec);
+/// the classic colours of 1 to 8
+const DEFAULT_NUMBERS: [Color; 8] = [
+    Color::from_rgb8(0x00, 0x00, 0xff),
+    Color::from_rgb8(0x00, 0x80, 0x00),
+    Color::from_rgb8(0xff, 0x00, 0x00),
+    Color::from_rgb8(0x00, 0x00, 0x80),
+    Color::from_rgb8(0x80, 0x00, 0x00),
+    Color::from_rgb8(0x00, 0x80, 0x80),
+    Color::from_rgb8(0x00, 0x00, 0x00),
+    Color::from_rgb8(0x80, 0x80, 0x80),
+];
+
+/// the colours of a texture, read from the `manifest.toml` next to its assets
+///
+/// ```toml
+/// version = 1
+/// background = "#4c545c"
+/// header = "#4c545c"   # optional, the background by default
+/// accent = "#5d9cec"   # optional
+/// tint = ["#fff26610", ...]   # optional, one for each number from 1 to 8
+/// numbers = ["#0000ff", ...]   # optional, one for each number from 1 to 8
+/// has_small_assets = false   # optional
+/// has_win_animation = false   # optional
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Manifest {
+    pub background: Color,
+    /// behind the menu bar
+    pub header: Color,
+    pub accent: Color,
+    /// risk shading for 1 to 8
+    pub tint: [Color; 8],
+    /// what colour 1 to 8 are drawn in where there's no svg for them
+    pub numbers: [Color; 8],
+    pub capabilities: Capabilities,
+}
+
+/// what a texture ships beyond the required assets
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct Capabilities {
+    pub has_small_assets: bool,
+    pub has_win_animation: bool,
+}
+
+impl Default for Manifest {
+    fn default() -> Self {
+        Self {
+            background: DEFAULT_BACKGROUND,
+            header: DEFAULT_BACKGROUND,
+            accent: DEFAULT_ACCENT,
+            tint: TINT,
+            numbers: DEFAULT_NUMBERS,
+            capabilities: Capabilities::default(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ManifestError {
+    pub texture: String,
+    /// `None` when the file as a whole is the problem
+    pub field: Option<&'static str>,
+    pub problem: String,
+}
+
+impl Display for ManifestError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.field {
+            Some(field) => write!(f, "{} manifest: {} {}", self.texture, field, self.problem),
+            None => write!(f, "{} manifest: {}", self.texture, self.problem),
+        }
+    }
+}
+
+impl Manifest {
+    /// missing fields are left at their defaults, anything present has to be right
+    pub fn parse(texture: &str, source: &str) -> Result<Self, ManifestError> {
+        let error = |field, problem: String| ManifestError { texture: texture.to_string(), field, problem };
+        let document = source.parse::<DocumentMut>()
+                .map_err(|e| error(None, e.message().to_string()))?;
+
+        let version = match document.get("version") {
+            Some(item) => item.as_integer().ok_or_else(|| error(Some("version"), "should be a whole number".to_string()))?,
+            None => return Err(error(Some("version"), "is missing".to_string())),
+        };
+        if !(1..=VERSION).contains(&version) {
+            return Err(error(Some("version"), format!("{} isn't supported, the newest is {}", version, VERSION)))
+        }
+
+        let colour = |field: &'static str| -> Result<Option<Color>, ManifestError> {
+            document.get(field)
+                    .map(|item| parse_colour(item).ok_or_else(|| error(Some(field), match item.as_str() {
+                        Some(value) => format!("isn't a valid colour: {:?}", value),
+                        None => "should be a colour like \"#4c545c\"".to_string(),
+                    })))
+                    .transpose()
+        };
+
+        let background = colour("background")?.unwrap_or(DEFAULT_BACKGROUND);
+        let header = colour("header")?.unwrap_or(background);
+        let accent = colour("accent")?.unwrap_or(DEFAULT_ACCENT);
+        let tint = match document.get("tint") {
+            Some(item) => parse_tint(item)
+                    .ok_or_else(|| error(Some("tint"), "should be a list of 8 colours".to_string()))?,
+            None => TINT,
+        };
+        let numbers = match document.get("numbers") {
+            Some(item) => parse_colours(item)
+                    .ok_or_else(|| error(Some("numbers"), "should be a list of 8 colours".to_string()))?,
+            None => DEFAULT_NUMBERS,
+        };
+
+        let flag = |field: &'static str| -> Result<bool, ManifestError> {
+            document.get(field)
+                    .map(|item| item.as_bool().ok_or_else(|| error(Some(field), "should be true or false".to_string())))
+                    .unwrap_or(Ok(false))
+        };
+        let capabilities = Capabilities {
+            has_small_assets: flag("has_small_assets")?,
+            has_win_animation: flag("has_win_animation")?,
+        };
+
+        Ok(Self { background, header, accent, tint, numbers, capabilities })
+    }
+}
+
+fn parse_colour(item: &Item) -> Option<Color> {
+    item.as_str()?.trim().parse().ok()
+}
+
+/// opaque ones are toned down to [`MAX_TINT_ALPHA`]
+fn parse_tint(item: &Item) -> Option<[Color; 8]> {
+    parse_colours(item).map(|colours| colours.map(|colour| Color { a: colour.a.min(MAX_TINT_ALPHA), ..colour }))
+}
+
+fn parse_colours(item: &Item) -> Option<[Color; 8]> {
+    let colours = item.as_array()?
+            .iter()
+            .map(|value| Value::as_str(value)?.trim().parse::<Color>().ok())
+            .collect::<Option<Vec<_>>>()?;
+    colours.try_into().ok()
+}
@@ -0,0 +1,109 @@
+use super::*;
+
+fn error(source: &str) -> ManifestError {
+    Manifest::parse("test", source).expect_err("the manifest is broken")
+}
+
+fn field(source: &str) -> Option<&'static str> {
+    error(source).field
+}
+
+#[test]
+fn every_built_in_manifest_parses() {
+    for (name, source) in [
+        ("dark", include_str!("../dark/manifest.toml")),
+        ("light", include_str!("../light/manifest.toml")),
+        ("gay", include_str!("../gay/manifest.toml")),
+        ("accessible", include_str!("../accessible/manifest.toml")),
+    ] {
+        Manifest::parse(name, source).unwrap_or_else(|e| panic!("{}", e));
+    }
+}
+
+#[test]
+fn the_built_in_backgrounds_are_what_they_always_were() {
+    let background = |source| Manifest::parse("test", source).unwrap().background;
+    assert_eq!(background(include_str!("../light/manifest.toml")), Color::from_rgb8(0xc0, 0xc0, 0xc0));
+    assert_eq!(background(include_str!("../gay/manifest.toml")), Color::from_rgb8(0x4c, 0x54, 0x5c));
+    assert_eq!(background(include_str!("../dark/manifest.toml")), Color::from_rgb8(0x4c, 0x54, 0x5c));
+}
+
+#[test]
+fn a_minimal_manifest_gets_the_defaults() {
+    let manifest = Manifest::parse("pack", "version = 1").unwrap();
+    let default = Manifest::default();
+    assert_eq!(manifest.background, default.background);
+    assert_eq!(manifest.header, default.header);
+    assert_eq!(manifest.accent, default.accent);
+    assert_eq!(manifest.tint, default.tint);
+    assert_eq!(manifest.numbers, DEFAULT_NUMBERS);
+    assert_eq!(manifest.capabilities, Capabilities::default());
+}
+
+#[test]
+fn the_header_follows_the_background_unless_it_is_given() {
+    let manifest = Manifest::parse("pack", "version = 1\nbackground = \"#102030\"").unwrap();
+    assert_eq!(manifest.header, Color::from_rgb8(0x10, 0x20, 0x30));
+
+    let manifest = Manifest::parse("pack", "version = 1\nbackground = \"#102030\"\nheader = \"#ffffff\"").unwrap();
+    assert_eq!(manifest.header, Color::WHITE);
+}
+
+#[test]
+fn every_field_is_read() {
+    let manifest = Manifest::parse("pack", r##"
+        version = 1
+        numbers = ["#010101", "#020202", "#030303", "#040404", "#050505", "#060606", "#070707", "#080808"]
+        tint = ["#ff0000ff", "#ff0000", "#ff0000", "#ff0000", "#ff0000", "#ff0000", "#ff0000", "#ff000010"]
+        has_small_assets = true
+        has_win_animation = true
+    "##).unwrap();
+    assert_eq!(manifest.numbers[7], Color::from_rgb8(8, 8, 8));
+    assert_eq!(manifest.tint[0].a, MAX_TINT_ALPHA, "an opaque tint is toned down");
+    assert!(manifest.tint[7].a < MAX_TINT_ALPHA);
+    assert_eq!(manifest.capabilities, Capabilities { has_small_assets: true, has_win_animation: true });
+}
+
+#[test]
+fn errors_name_the_texture_and_the_field() {
+    let e = error("version = 1\nbackground = \"purple-ish\"");
+    assert_eq!(e.texture, "test");
+    assert_eq!(e.field, Some("background"));
+    assert!(e.to_string().starts_with("test manifest: background"), "{}", e);
+}
+
+#[test]
+fn broken_toml_is_about_the_whole_file() {
+    assert_eq!(field("version = "), None);
+}
+
+#[test]
+fn the_version_has_to_be_there_and_known() {
+    assert_eq!(field("background = \"#000000\""), Some("version"));
+    assert_eq!(field("version = \"1\""), Some("version"));
+    assert_eq!(field("version = 0"), Some("version"));
+    assert_eq!(field(&format!("version = {}", VERSION + 1)), Some("version"));
+}
+
+#[test]
+fn every_colour_field_is_checked() {
+    for name in ["background", "header", "accent"] {
+        assert_eq!(field(&format!("version = 1\n{} = \"nope\"", name)), Some(name));
+        assert_eq!(field(&format!("version = 1\n{} = 4", name)), Some(name));
+    }
+}
+
+#[test]
+fn colour_lists_need_eight_colours() {
+    for name in ["tint", "numbers"] {
+        assert_eq!(field(&format!("version = 1\n{} = [\"#000000\"]", name)), Some(name));
+        assert_eq!(field(&format!("version = 1\n{} = \"#000000\"", name)), Some(name));
+        assert_eq!(field(&format!("version = 1\n{} = [{}\"nope\"]", name, "\"#000000\", ".repeat(7))), Some(name));
+    }
+}
+
+#[test]
+fn capability_flags_are_true_or_false() {
+    assert_eq!(field("version = 1\nhas_small_assets = \"yes\""), Some("has_small_assets"));
+    assert_eq!(field("version = 1\nhas_win_animation = 1"), Some("has_win_animation"));
+}
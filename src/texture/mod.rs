@@ -1,231 +1,195 @@
-use iced_core::Color;
+#[macro_use]
+mod assets;
+pub mod install;
+pub mod manifest;
+pub mod pack;
+mod zip;
+
+use crate::texture::assets::{embedded, Assets, BorderAssets, CellAssets, FaceAssets};
+use crate::texture::manifest::Manifest;
+use iced_core::svg::{Data, Handle};
+use iced_core::{Color, Theme};
 use minsweeper_rs::{Cell, CellState, CellType, GameStatus};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::{Arc, LazyLock};
+
+#[cfg(test)]
+mod tests;
 
-#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
-pub enum Texture {
+// every built-in texture is one line here plus its asset directory and manifest, a missing file
+// is a compile error from the include_bytes! in `assets!`, tests/ui/missing_asset.rs makes sure
+textures! {
     #[default]
-    Dark,
-    Light,
-    Gay
+    Dark => "dark";
+    Light => "light";
+    Gay => "gay";
+    Accessible => "accessible";
 }
 
+/// risk shading for 1 to 8, manifests can bring their own
+const TINT: [Color; 8] = [
+    Color::from_rgba(1.0, 0.95, 0.4, 0.06),
+    Color::from_rgba(1.0, 0.85, 0.3, 0.09),
+    Color::from_rgba(1.0, 0.75, 0.25, 0.12),
+    Color::from_rgba(1.0, 0.65, 0.2, 0.15),
+    Color::from_rgba(1.0, 0.55, 0.15, 0.18),
+    Color::from_rgba(1.0, 0.45, 0.1, 0.21),
+    Color::from_rgba(1.0, 0.35, 0.1, 0.24),
+    Color::from_rgba(1.0, 0.25, 0.1, 0.27),
+];
+
+/// a tint any stronger starts hiding the number under it
+pub const MAX_TINT_ALPHA: f32 = 0.3;
+
 impl Texture {
-    pub fn get_cell_asset(self, cell: Cell, down: bool) -> &'static [u8] {
-        use Texture::*;
+    /// the iced theme the menus and dialogs get when they match this texture, packs go by how
+    /// light their background is
+    pub fn theme(self) -> Theme {
         match self {
-            Dark => match (cell.cell_state, cell.cell_type) {
-                (CellState::Revealed, CellType::Safe(0)) => include_bytes!("dark/cell/celldown.svg"),
-                (CellState::Revealed, CellType::Safe(1)) => include_bytes!("dark/cell/cell1.svg"),
-                (CellState::Revealed, CellType::Safe(2)) => include_bytes!("dark/cell/cell2.svg"),
-                (CellState::Revealed, CellType::Safe(3)) => include_bytes!("dark/cell/cell3.svg"),
-                (CellState::Revealed, CellType::Safe(4)) => include_bytes!("dark/cell/cell4.svg"),
-                (CellState::Revealed, CellType::Safe(5)) => include_bytes!("dark/cell/cell5.svg"),
-                (CellState::Revealed, CellType::Safe(6)) => include_bytes!("dark/cell/cell6.svg"),
-                (CellState::Revealed, CellType::Safe(7)) => include_bytes!("dark/cell/cell7.svg"),
-                (CellState::Revealed, CellType::Safe(8)) => include_bytes!("dark/cell/cell8.svg"),
-                (CellState::Revealed, CellType::Safe(_)) => unreachable!(),
-                (CellState::Revealed, CellType::Mine) => include_bytes!("dark/cell/blast.svg"),
-                (CellState::Revealed, CellType::Unknown) if down => include_bytes!("dark/cell/celldown.svg"),
-                (CellState::Revealed, CellType::Unknown) => include_bytes!("dark/cell/cellup.svg"),
-
-                (CellState::Unknown, CellType::Mine) => include_bytes!("dark/cell/cellmine.svg"),
-                (CellState::Unknown, _) if down => include_bytes!("dark/cell/celldown.svg"),
-                (CellState::Unknown, _) => include_bytes!("dark/cell/cellup.svg"),
-
-                (CellState::Flagged, CellType::Safe(_)) => include_bytes!("dark/cell/falsemine.svg"),
-                (CellState::Flagged, _) => include_bytes!("dark/cell/cellflag.svg"),
-            }
-            Light => match (cell.cell_state, cell.cell_type) {
-                (CellState::Revealed, CellType::Safe(0)) => include_bytes!("light/cell/celldown.svg"),
-                (CellState::Revealed, CellType::Safe(1)) => include_bytes!("light/cell/cell1.svg"),
-                (CellState::Revealed, CellType::Safe(2)) => include_bytes!("light/cell/cell2.svg"),
-                (CellState::Revealed, CellType::Safe(3)) => include_bytes!("light/cell/cell3.svg"),
-                (CellState::Revealed, CellType::Safe(4)) => include_bytes!("light/cell/cell4.svg"),
-                (CellState::Revealed, CellType::Safe(5)) => include_bytes!("light/cell/cell5.svg"),
-                (CellState::Revealed, CellType::Safe(6)) => include_bytes!("light/cell/cell6.svg"),
-                (CellState::Revealed, CellType::Safe(7)) => include_bytes!("light/cell/cell7.svg"),
-                (CellState::Revealed, CellType::Safe(8)) => include_bytes!("light/cell/cell8.svg"),
-                (CellState::Revealed, CellType::Safe(_)) => unreachable!(),
-                (CellState::Revealed, CellType::Mine) => include_bytes!("light/cell/blast.svg"),
-                (CellState::Revealed, CellType::Unknown) if down => include_bytes!("light/cell/celldown.svg"),
-                (CellState::Revealed, CellType::Unknown) => include_bytes!("light/cell/cellup.svg"),
-
-                (CellState::Unknown, CellType::Mine) => include_bytes!("light/cell/cellmine.svg"),
-                (CellState::Unknown, _) if down => include_bytes!("light/cell/celldown.svg"),
-                (CellState::Unknown, _) => include_bytes!("light/cell/cellup.svg"),
-
-                (CellState::Flagged, CellType::Safe(_)) => include_bytes!("light/cell/falsemine.svg"),
-                (CellState::Flagged, _) => include_bytes!("light/cell/cellflag.svg"),
-            }
-            Gay => match (cell.cell_state, cell.cell_type) {
-                (CellState::Revealed, CellType::Safe(0)) => include_bytes!("gay/cell/celldown.svg"),
-                (CellState::Revealed, CellType::Safe(1)) => include_bytes!("gay/cell/cell1.svg"),
-                (CellState::Revealed, CellType::Safe(2)) => include_bytes!("gay/cell/cell2.svg"),
-                (CellState::Revealed, CellType::Safe(3)) => include_bytes!("gay/cell/cell3.svg"),
-                (CellState::Revealed, CellType::Safe(4)) => include_bytes!("gay/cell/cell4.svg"),
-                (CellState::Revealed, CellType::Safe(5)) => include_bytes!("gay/cell/cell5.svg"),
-                (CellState::Revealed, CellType::Safe(6)) => include_bytes!("gay/cell/cell6.svg"),
-                (CellState::Revealed, CellType::Safe(7)) => include_bytes!("gay/cell/cell7.svg"),
-                (CellState::Revealed, CellType::Safe(8)) => include_bytes!("gay/cell/cell8.svg"),
-                (CellState::Revealed, CellType::Safe(_)) => unreachable!(),
-                (CellState::Revealed, CellType::Mine) => include_bytes!("gay/cell/blast.svg"),
-                (CellState::Revealed, CellType::Unknown) if down => include_bytes!("gay/cell/celldown.svg"),
-                (CellState::Revealed, CellType::Unknown) => include_bytes!("gay/cell/cellup.svg"),
-
-                (CellState::Unknown, CellType::Mine) => include_bytes!("gay/cell/cellmine.svg"),
-                (CellState::Unknown, _) if down => include_bytes!("gay/cell/celldown.svg"),
-                (CellState::Unknown, _) => include_bytes!("gay/cell/cellup.svg"),
-
-                (CellState::Flagged, CellType::Safe(_)) => include_bytes!("gay/cell/falsemine.svg"),
-                (CellState::Flagged, _) => include_bytes!("gay/cell/cellflag.svg"),
-            }
+            Texture::Dark | Texture::Accessible | Texture::Gay => Theme::Dark,
+            Texture::Light => Theme::Light,
+            Texture::Pack(_) if self.manifest().background.relative_luminance() > 0.5 => Theme::Light,
+            Texture::Pack(_) => Theme::Dark,
         }
     }
 
-    pub fn get_restart_button(self, game_status: GameStatus, down: bool, revealing: bool) -> &'static [u8] {
-        use Texture::*;
-        match self {
-            Dark => if down {
-                include_bytes!("dark/faces/smilefacedown.svg")
-            } else if revealing {
-                include_bytes!("dark/faces/clickface.svg")
-            } else {
-                match game_status {
-                    GameStatus::Playing | GameStatus::Never => include_bytes!("dark/faces/smileface.svg"),
-                    GameStatus::Won => include_bytes!("dark/faces/winface.svg").as_slice(),
-                    GameStatus::Lost => include_bytes!("dark/faces/lostface.svg"),
-                }
-            }
-            Light => if down {
-                include_bytes!("light/faces/smilefacedown.svg")
-            } else if revealing {
-                include_bytes!("light/faces/clickface.svg")
-            } else {
-                match game_status {
-                    GameStatus::Playing | GameStatus::Never => include_bytes!("light/faces/smileface.svg"),
-                    GameStatus::Won => include_bytes!("light/faces/winface.svg"),
-                    GameStatus::Lost => include_bytes!("light/faces/lostface.svg"),
-                }
-            }
-            Gay => if down {
-                include_bytes!("gay/faces/smilefacedown.svg")
-            } else if revealing {
-                include_bytes!("gay/faces/clickface.svg")
-            } else {
-                match game_status {
-                    GameStatus::Playing | GameStatus::Never => include_bytes!("gay/faces/smileface.svg"),
-                    GameStatus::Won => include_bytes!("gay/faces/winface.svg"),
-                    GameStatus::Lost => include_bytes!("gay/faces/lostface.svg"),
-                }
-            }
+    pub fn get_cell_asset(self, cell: Cell, down: bool) -> Handle {
+        let assets = &self.assets().cell;
+        match (cell.cell_state, cell.cell_type) {
+            (CellState::Revealed, CellType::Safe(0)) => assets.down.clone(),
+            (CellState::Revealed, CellType::Safe(number @ 1..=8)) => assets.numbers[number as usize - 1].clone(),
+            (CellState::Revealed, CellType::Safe(_)) => unreachable!(),
+            (CellState::Revealed, CellType::Mine) => assets.blast.clone(),
+            (CellState::Revealed, CellType::Unknown) if down => assets.down.clone(),
+            (CellState::Revealed, CellType::Unknown) => assets.up.clone(),
+
+            (CellState::Unknown, CellType::Mine) => assets.mine.clone(),
+            (CellState::Unknown, _) if down => assets.down.clone(),
+            (CellState::Unknown, _) => assets.up.clone(),
+
+            (CellState::Flagged, CellType::Safe(_)) => assets.false_mine.clone(),
+            (CellState::Flagged, _) => assets.flag.clone(),
         }
     }
 
-    pub fn get_digit(self, digit: char) -> &'static [u8] {
-        match self {
-            Texture::Dark => match digit {
-                '0' => include_bytes!("dark/counter/counter0.svg"),
-                '1' => include_bytes!("dark/counter/counter1.svg"),
-                '2' => include_bytes!("dark/counter/counter2.svg"),
-                '3' => include_bytes!("dark/counter/counter3.svg"),
-                '4' => include_bytes!("dark/counter/counter4.svg"),
-                '5' => include_bytes!("dark/counter/counter5.svg"),
-                '6' => include_bytes!("dark/counter/counter6.svg"),
-                '7' => include_bytes!("dark/counter/counter7.svg"),
-                '8' => include_bytes!("dark/counter/counter8.svg"),
-                '9' => include_bytes!("dark/counter/counter9.svg"),
-                '-' => include_bytes!("dark/counter/counter-.svg"),
-                _ => unimplemented!()
-            }
-            Texture::Light => match digit {
-                '0' => include_bytes!("light/counter/counter0.svg"),
-                '1' => include_bytes!("light/counter/counter1.svg"),
-                '2' => include_bytes!("light/counter/counter2.svg"),
-                '3' => include_bytes!("light/counter/counter3.svg"),
-                '4' => include_bytes!("light/counter/counter4.svg"),
-                '5' => include_bytes!("light/counter/counter5.svg"),
-                '6' => include_bytes!("light/counter/counter6.svg"),
-                '7' => include_bytes!("light/counter/counter7.svg"),
-                '8' => include_bytes!("light/counter/counter8.svg"),
-                '9' => include_bytes!("light/counter/counter9.svg"),
-                '-' => include_bytes!("light/counter/counter-.svg"),
-                _ => unimplemented!()
+    /// the tap mode toggle, a flag while taps flag and a hidden cell while they reveal
+    pub fn get_tap_mode(self, flag: bool) -> Handle {
+        let assets = &self.assets().cell;
+        if flag {
+            assets.flag.clone()
+        } else {
+            assets.up.clone()
+        }
+    }
+
+    pub fn get_restart_button(self, game_status: GameStatus, down: bool, revealing: bool) -> Handle {
+        let faces = &self.assets().faces;
+        if down {
+            faces.smile_down.clone()
+        } else if revealing {
+            faces.click.clone()
+        } else {
+            match game_status {
+                GameStatus::Playing | GameStatus::Never => faces.smile.clone(),
+                GameStatus::Won => faces.win.clone(),
+                GameStatus::Lost => faces.lost.clone(),
             }
-            Texture::Gay => match digit {
-                '0' => include_bytes!("gay/counter/counter0.svg"),
-                '1' => include_bytes!("gay/counter/counter1.svg"),
-                '2' => include_bytes!("gay/counter/counter2.svg"),
-                '3' => include_bytes!("gay/counter/counter3.svg"),
-                '4' => include_bytes!("gay/counter/counter4.svg"),
-                '5' => include_bytes!("gay/counter/counter5.svg"),
-                '6' => include_bytes!("gay/counter/counter6.svg"),
-                '7' => include_bytes!("gay/counter/counter7.svg"),
-                '8' => include_bytes!("gay/counter/counter8.svg"),
-                '9' => include_bytes!("gay/counter/counter9.svg"),
-                '-' => include_bytes!("gay/counter/counter-.svg"),
-                _ => unimplemented!()
+        }
+    }
+
+    pub fn get_digit(self, digit: char) -> Handle {
+        let assets = self.assets();
+        match digit {
+            '-' => assets.minus.clone(),
+            _ => match digit.to_digit(10) {
+                Some(digit) => assets.digits[digit as usize].clone(),
+                None => unimplemented!()
             }
         }
     }
 
-    pub fn get_background_colour(self) -> Color {
-        match self {
-            Texture::Dark => include_str!("dark/background").parse()
-                    .expect("dark/background should contain valid colour data"),
-            Texture::Light => include_str!("light/background").parse()
-                    .expect("light/background should contain valid colour data"),
-            Texture::Gay => include_str!("dark/background").parse()
-                    .expect("gay/background should contain valid colour data"),
+    /// the swatch the Theme menu shows beside it, a covered cell, a 3, a flag and the face.
+    /// `None` for a pack that isn't installed, it'd only be showing the default's
+    pub fn preview(self) -> Option<[Handle; 4]> {
+        if let Texture::Pack(id) = self && id.assets().is_none() {
+            return None
         }
+        Some([
+            self.get_cell_asset(Cell::EMPTY, false),
+            self.get_cell_asset(Cell::new(CellType::Safe(3), CellState::Revealed), false),
+            self.get_tap_mode(true),
+            self.get_restart_button(GameStatus::Playing, false, false),
+        ])
     }
 
-    pub fn get_border(self, border: Border) -> &'static [u8] {
-        match self {
-            Texture::Dark => match border {
-                Border::TopLeft => include_bytes!("dark/border/topleft.svg"),
-                Border::TopBottom => include_bytes!("dark/border/topbottom.svg"),
-                Border::TopRight => include_bytes!("dark/border/topright.svg"),
-                Border::BottomLeft => include_bytes!("dark/border/bottomleft.svg"),
-                Border::BottomRight => include_bytes!("dark/border/bottomright.svg"),
-                Border::LeftRight => include_bytes!("dark/border/leftright.svg"),
-                Border::MiddleLeft => include_bytes!("dark/border/middleleft.svg"),
-                Border::MiddleRight => include_bytes!("dark/border/middleright.svg"),
-                Border::CounterLeft => include_bytes!("dark/border/counterleft.svg"),
-                Border::CounterRight => include_bytes!("dark/border/counterright.svg"),
-                Border::CounterTop => include_bytes!("dark/border/countertop.svg"),
-                Border::CounterBottom => include_bytes!("dark/border/counterbottom.svg"),
-            }
-            Texture::Light => match border {
-                Border::TopLeft => include_bytes!("light/border/topleft.svg"),
-                Border::TopBottom => include_bytes!("light/border/topbottom.svg"),
-                Border::TopRight => include_bytes!("light/border/topright.svg"),
-                Border::BottomLeft => include_bytes!("light/border/bottomleft.svg"),
-                Border::BottomRight => include_bytes!("light/border/bottomright.svg"),
-                Border::LeftRight => include_bytes!("light/border/leftright.svg"),
-                Border::MiddleLeft => include_bytes!("light/border/middleleft.svg"),
-                Border::MiddleRight => include_bytes!("light/border/middleright.svg"),
-                Border::CounterLeft => include_bytes!("light/border/counterleft.svg"),
-                Border::CounterRight => include_bytes!("light/border/counterright.svg"),
-                Border::CounterTop => include_bytes!("light/border/countertop.svg"),
-                Border::CounterBottom => include_bytes!("light/border/counterbottom.svg"),
-            }
-            Texture::Gay => match border {
-                Border::TopLeft => include_bytes!("gay/border/topleft.svg"),
-                Border::TopBottom => include_bytes!("gay/border/topbottom.svg"),
-                Border::TopRight => include_bytes!("gay/border/topright.svg"),
-                Border::BottomLeft => include_bytes!("gay/border/bottomleft.svg"),
-                Border::BottomRight => include_bytes!("gay/border/bottomright.svg"),
-                Border::LeftRight => include_bytes!("gay/border/leftright.svg"),
-                Border::MiddleLeft => include_bytes!("gay/border/middleleft.svg"),
-                Border::MiddleRight => include_bytes!("gay/border/middleright.svg"),
-                Border::CounterLeft => include_bytes!("gay/border/counterleft.svg"),
-                Border::CounterRight => include_bytes!("gay/border/counterright.svg"),
-                Border::CounterTop => include_bytes!("gay/border/countertop.svg"),
-                Border::CounterBottom => include_bytes!("gay/border/counterbottom.svg"),
-            }
+    pub fn manifest(self) -> Manifest {
+        self.assets().manifest
+    }
+
+    /// parses every built-in manifest up front so a broken one is reported at startup, it's
+    /// drawn with the default colours
+    pub fn check_manifests() {
+        for texture in Self::BUILT_IN {
+            texture.assets();
         }
     }
+
+    /// the risk shading drawn under a revealed number, warmer the higher it is
+    pub fn get_tint(self, cell: Cell) -> Option<Color> {
+        match (cell.cell_state, cell.cell_type) {
+            (CellState::Revealed, CellType::Safe(number @ 1..=8)) => Some(self.manifest().tint[number as usize - 1]),
+            _ => None,
+        }
+    }
+
+    /// the cell asset and the risk shading to fill in under it. the shading is never baked into
+    /// the asset, so the handle is the same one with it on or off and nothing drawn tinted is
+    /// ever handed out for an untinted cell
+    pub fn get_shaded_cell_asset(self, cell: Cell, down: bool, risk_shading: bool) -> (Handle, Option<Color>) {
+        (self.get_cell_asset(cell, down), self.get_tint(cell).filter(|_| risk_shading))
+    }
+
+    pub fn get_border(self, border: Border) -> Handle {
+        let borders = &self.assets().borders;
+        match border {
+            Border::TopLeft => borders.top_left.clone(),
+            Border::TopBottom => borders.top_bottom.clone(),
+            Border::TopRight => borders.top_right.clone(),
+            Border::BottomLeft => borders.bottom_left.clone(),
+            Border::BottomRight => borders.bottom_right.clone(),
+            Border::LeftRight => borders.left_right.clone(),
+            Border::MiddleLeft => borders.middle_left.clone(),
+            Border::MiddleRight => borders.middle_right.clone(),
+            Border::CounterLeft => borders.counter_left.clone(),
+            Border::CounterRight => borders.counter_right.clone(),
+            Border::CounterTop => borders.counter_top.clone(),
+            Border::CounterBottom => borders.counter_bottom.clone(),
+        }
+    }
+}
+
+/// what an asset was made from, for drawing it somewhere other than iced
+pub fn bytes(asset: &Handle) -> &[u8] {
+    match asset.data() {
+        Data::Bytes(bytes) => bytes,
+        Data::Path(_) => &[],
+    }
+}
+
+/// replaces the texture at `pointer` with the default if it isn't one this version has, returning
+/// what was there
+pub fn forget_unknown(value: &mut Value, pointer: &str) -> Option<String> {
+    let field = value.pointer_mut(pointer)?;
+    if Texture::deserialize(&*field).is_ok() {
+        return None
+    }
+    let unknown = match &*field {
+        Value::String(name) => name.clone(),
+        other => other.to_string(),
+    };
+    *field = serde_json::to_value(Texture::default()).ok()?;
+    Some(unknown)
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
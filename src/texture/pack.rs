@@ -0,0 +1,467 @@
+use crate::texture::manifest::{Manifest, ManifestError};
+use crate::texture::{zip, Assets, BorderAssets, CellAssets, FaceAssets, Texture};
+use crate::paths;
+use crate::texture;
+use futures_util::{Stream, StreamExt};
+use iced_core::svg::Handle;
+use notify::{RecursiveMode, Watcher};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::Path;
+use std::sync::{Arc, LazyLock, RwLock};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+#[cfg(test)]
+mod tests;
+
+/// a pack is laid out like the built-in textures, these have to be there
+pub const REQUIRED: &[&str] = &[
+    "cell/cellup.svg", "cell/celldown.svg",
+    "cell/cell1.svg", "cell/cell2.svg", "cell/cell3.svg", "cell/cell4.svg",
+    "cell/cell5.svg", "cell/cell6.svg", "cell/cell7.svg", "cell/cell8.svg",
+    "cell/blast.svg", "cell/cellmine.svg", "cell/cellflag.svg", "cell/falsemine.svg",
+    "faces/smileface.svg", "faces/smilefacedown.svg", "faces/clickface.svg", "faces/winface.svg", "faces/lostface.svg",
+    "counter/counter0.svg", "counter/counter1.svg", "counter/counter2.svg", "counter/counter3.svg", "counter/counter4.svg",
+    "counter/counter5.svg", "counter/counter6.svg", "counter/counter7.svg", "counter/counter8.svg", "counter/counter9.svg",
+    "counter/counter-.svg",
+];
+
+/// and these fall back to the default texture
+pub const OPTIONAL: &[&str] = &[
+    "border/topleft.svg", "border/topbottom.svg", "border/topright.svg",
+    "border/bottomleft.svg", "border/bottomright.svg", "border/leftright.svg",
+    "border/middleleft.svg", "border/middleright.svg",
+    "border/counterleft.svg", "border/counterright.svg", "border/countertop.svg", "border/counterbottom.svg",
+    "manifest.toml",
+];
+
+pub const MAX_ARCHIVE_SIZE: u64 = 16 * 1024 * 1024;
+const LIMITS: zip::Limits = zip::Limits { entry: 2 * 1024 * 1024, total: 32 * 1024 * 1024 };
+
+#[derive(Debug)]
+pub enum PackError {
+    Io(io::Error),
+    Corrupt(&'static str),
+    Unsupported(&'static str),
+    TooLarge,
+    UnsafePath(String),
+    Missing(Vec<&'static str>),
+    BadSvg(String),
+    BadManifest(ManifestError),
+}
+
+impl Display for PackError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PackError::Io(e) => write!(f, "{}", e),
+            PackError::Corrupt(reason) => write!(f, "the archive is damaged: {}", reason),
+            PackError::Unsupported(what) => write!(f, "{} aren't supported", what),
+            PackError::TooLarge => write!(f, "the archive is too large to be a texture pack"),
+            PackError::UnsafePath(path) => write!(f, "{} points outside the pack", path),
+            PackError::Missing(files) => write!(f, "missing {}", files.join(", ")),
+            PackError::BadSvg(file) => write!(f, "{} isn't a valid svg", file),
+            PackError::BadManifest(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<io::Error> for PackError {
+    fn from(value: io::Error) -> Self {
+        PackError::Io(value)
+    }
+}
+
+/// a pack that passed validation, only holding files that are part of the layout
+#[derive(Debug)]
+pub struct Validated {
+    pub files: HashMap<&'static str, Vec<u8>>,
+    pub manifest: Manifest,
+    pub warnings: Vec<String>,
+}
+
+/// reads a zip from disk and checks it's a usable pack without touching the packs directory
+pub fn read_archive(path: &Path) -> Result<Validated, PackError> {
+    if std::fs::metadata(path)?.len() > MAX_ARCHIVE_SIZE {
+        return Err(PackError::TooLarge)
+    }
+    let entries = zip::read(&std::fs::read(path)?, LIMITS)?;
+
+    if let Some((name, _)) = entries.iter().find(|(name, _)| !is_safe(name)) {
+        return Err(PackError::UnsafePath(name.clone()))
+    }
+
+    // packs are often zipped as their folder, look inside it
+    let root = common_root(entries.iter().map(|(name, _)| name.as_str()));
+    let files = entries.into_iter()
+            .map(|(name, contents)| (name[root.len()..].to_string(), contents));
+
+    let name = path.file_stem().map(|stem| stem.to_string_lossy()).unwrap_or_default();
+    validate(&name, files)
+}
+
+fn validate(name: &str, files: impl IntoIterator<Item = (String, Vec<u8>)>) -> Result<Validated, PackError> {
+    let mut known = HashMap::new();
+    let mut ignored = 0;
+    for (name, contents) in files {
+        match REQUIRED.iter().chain(OPTIONAL).find(|file| **file == name) {
+            Some(file) => {
+                known.insert(*file, contents);
+            }
+            None => ignored += 1,
+        }
+    }
+
+    let missing = REQUIRED.iter()
+            .copied()
+            .filter(|file| !known.contains_key(file))
+            .collect::<Vec<_>>();
+    if !missing.is_empty() {
+        return Err(PackError::Missing(missing))
+    }
+
+    let options = usvg::Options::default();
+    for (file, contents) in &known {
+        if file.ends_with(".svg") && usvg::Tree::from_data(contents, &options).is_err() {
+            return Err(PackError::BadSvg(file.to_string()))
+        }
+    }
+    let manifest = match known.get("manifest.toml") {
+        Some(contents) => Manifest::parse(name, &String::from_utf8_lossy(contents))
+                .map_err(PackError::BadManifest)?,
+        None => Manifest::default(),
+    };
+
+    let mut warnings = OPTIONAL.iter()
+            .filter(|file| !known.contains_key(*file))
+            .map(|file| format!("no {}, the default one will be used", file))
+            .collect::<Vec<_>>();
+    if ignored > 0 {
+        warnings.push(format!("{} files that aren't part of a texture pack will be skipped", ignored));
+    }
+
+    Ok(Validated { files: known, manifest, warnings })
+}
+
+/// relative, forward slashes only, and never climbing out of where it's extracted
+fn is_safe(name: &str) -> bool {
+    !name.is_empty()
+            && !name.starts_with('/')
+            && !name.contains('\\')
+            && !name.contains(':')
+            && name.split('/').all(|component| component != ".." && component != ".")
+}
+
+/// the folder every entry is in, unless that folder is part of the layout itself
+fn common_root<'a>(mut names: impl Iterator<Item = &'a str>) -> String {
+    let Some(first) = names.next() else { return String::new() };
+    let Some((root, _)) = first.split_once('/') else { return String::new() };
+    let root = format!("{}/", root);
+    let layout = REQUIRED.iter().chain(OPTIONAL).any(|file| file.starts_with(&root));
+    if !layout && names.all(|name| name.starts_with(&root)) {
+        root
+    } else {
+        String::new()
+    }
+}
+
+/// keeps letters, digits, spaces, dashes and underscores so the name is a valid folder everywhere
+pub fn sanitize(name: &str) -> String {
+    let name = name.chars()
+            .filter(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_'))
+            .collect::<String>();
+    let name = name.trim();
+    if name.is_empty() {
+        "texture pack".to_string()
+    } else {
+        name.chars().take(64).collect()
+    }
+}
+
+/// writes the pack into its own folder under [`paths::textures_dir`], through a temporary folder so a
+/// failed install never leaves half a pack behind, and makes it available right away
+pub fn install(name: &str, pack: &Validated, replace: bool) -> Result<PackId, PackError> {
+    let folder = paths::textures_dir()?;
+    let target = folder.join(name);
+    let temp = folder.join(format!(".{}.tmp", name));
+    let _ = std::fs::remove_dir_all(&temp);
+
+    let result = pack.files.iter().try_for_each(|(file, contents)| {
+        let path = temp.join(file);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, contents)
+    }).and_then(|_| {
+        if replace && target.exists() {
+            std::fs::remove_dir_all(&target)?;
+        }
+        std::fs::rename(&temp, &target)
+    });
+    if let Err(e) = result {
+        let _ = std::fs::remove_dir_all(&temp);
+        return Err(e.into())
+    }
+
+    Ok(register(name, pack))
+}
+
+fn load(name: &str, folder: &Path) -> Result<Validated, PackError> {
+    let files = REQUIRED.iter()
+            .chain(OPTIONAL)
+            .filter_map(|file| std::fs::read(folder.join(file))
+                    .ok()
+                    .map(|contents| (file.to_string(), contents)));
+    validate(name, files)
+}
+
+fn load_installed() -> Vec<Pack> {
+    let Ok(entries) = paths::textures_dir().and_then(std::fs::read_dir) else { return vec![] };
+    let mut packs = entries.filter_map(Result::ok)
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                if name.starts_with('.') {
+                    return None
+                }
+                match load(&name, &entry.path()) {
+                    Ok(pack) => {
+                        let files = handles(&HashMap::new(), pack.files);
+                        Some(Pack { assets: Some(build(&files, pack.manifest)), files, name })
+                    }
+                    Err(e) => {
+                        tracing::warn!("skipping texture pack {}: {}", name, e);
+                        None
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+    packs.sort_by(|a, b| a.name.cmp(&b.name));
+    packs
+}
+
+/// a handle for each file, the ones that are the same as in `old` keep their handle so a reload
+/// or reinstall that didn't touch them doesn't have them rasterized again
+fn handles(old: &HashMap<&'static str, Handle>, files: impl IntoIterator<Item = (&'static str, Vec<u8>)>) -> HashMap<&'static str, Handle> {
+    files.into_iter()
+            .map(|(file, contents)| match old.get(file) {
+                Some(handle) if texture::bytes(handle) == contents => (file, handle.clone()),
+                _ => (file, Handle::from_memory(contents)),
+            })
+            .collect()
+}
+
+fn build(files: &HashMap<&'static str, Handle>, manifest: Manifest) -> Arc<Assets> {
+    let fallback = Texture::default().assets();
+    let file = |name: &str, fallback: &Handle| -> Handle {
+        files.get(name).unwrap_or(fallback).clone()
+    };
+
+    Arc::new(Assets {
+        cell: CellAssets {
+            up: file("cell/cellup.svg", &fallback.cell.up),
+            down: file("cell/celldown.svg", &fallback.cell.down),
+            numbers: std::array::from_fn(|i| file(&format!("cell/cell{}.svg", i + 1), &fallback.cell.numbers[i])),
+            blast: file("cell/blast.svg", &fallback.cell.blast),
+            mine: file("cell/cellmine.svg", &fallback.cell.mine),
+            flag: file("cell/cellflag.svg", &fallback.cell.flag),
+            false_mine: file("cell/falsemine.svg", &fallback.cell.false_mine),
+        },
+        faces: FaceAssets {
+            smile: file("faces/smileface.svg", &fallback.faces.smile),
+            smile_down: file("faces/smilefacedown.svg", &fallback.faces.smile_down),
+            click: file("faces/clickface.svg", &fallback.faces.click),
+            win: file("faces/winface.svg", &fallback.faces.win),
+            lost: file("faces/lostface.svg", &fallback.faces.lost),
+        },
+        digits: std::array::from_fn(|i| file(&format!("counter/counter{}.svg", i), &fallback.digits[i])),
+        minus: file("counter/counter-.svg", &fallback.minus),
+        borders: BorderAssets {
+            top_left: file("border/topleft.svg", &fallback.borders.top_left),
+            top_bottom: file("border/topbottom.svg", &fallback.borders.top_bottom),
+            top_right: file("border/topright.svg", &fallback.borders.top_right),
+            bottom_left: file("border/bottomleft.svg", &fallback.borders.bottom_left),
+            bottom_right: file("border/bottomright.svg", &fallback.borders.bottom_right),
+            left_right: file("border/leftright.svg", &fallback.borders.left_right),
+            middle_left: file("border/middleleft.svg", &fallback.borders.middle_left),
+            middle_right: file("border/middleright.svg", &fallback.borders.middle_right),
+            counter_left: file("border/counterleft.svg", &fallback.borders.counter_left),
+            counter_right: file("border/counterright.svg", &fallback.borders.counter_right),
+            counter_top: file("border/countertop.svg", &fallback.borders.counter_top),
+            counter_bottom: file("border/counterbottom.svg", &fallback.borders.counter_bottom),
+        },
+        manifest,
+    })
+}
+
+/// a pack's assets are freed once it's replaced and nothing's drawing them anymore
+struct Pack {
+    name: String,
+    /// `None` for packs the settings mention but that aren't installed (anymore)
+    assets: Option<Arc<Assets>>,
+    /// what `assets` was made from, a reload keeps these for files that broke
+    files: HashMap<&'static str, Handle>,
+}
+
+static PACKS: LazyLock<RwLock<Vec<Pack>>> = LazyLock::new(|| RwLock::new(load_installed()));
+
+/// an installed texture pack, stored in settings by name
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct PackId(usize);
+
+impl PackId {
+    fn find_or_insert(name: &str) -> Self {
+        let mut packs = PACKS.write().unwrap();
+        match packs.iter().position(|pack| pack.name == name) {
+            Some(index) => PackId(index),
+            None => {
+                packs.push(Pack { name: name.to_string(), assets: None, files: HashMap::new() });
+                PackId(packs.len() - 1)
+            }
+        }
+    }
+
+    pub fn name(self) -> String {
+        PACKS.read().unwrap()[self.0].name.clone()
+    }
+
+    pub(super) fn assets(self) -> Option<Arc<Assets>> {
+        PACKS.read().unwrap()[self.0].assets.clone()
+    }
+}
+
+fn register(name: &str, pack: &Validated) -> PackId {
+    let id = PackId::find_or_insert(name);
+    let mut packs = PACKS.write().unwrap();
+    let files = handles(&packs[id.0].files, pack.files.iter().map(|(file, contents)| (*file, contents.clone())));
+    packs[id.0].assets = Some(build(&files, pack.manifest));
+    packs[id.0].files = files;
+    id
+}
+
+/// what changed on disk in a watched pack
+#[derive(Clone, Debug)]
+pub struct Reloaded {
+    pub id: PackId,
+    /// files that broke with the change, they're still drawn as they were
+    pub kept: Vec<&'static str>,
+}
+
+/// how long a burst of events has to go quiet before the pack is read again, an editor's save
+/// is usually a few of them
+const SETTLE: Duration = Duration::from_millis(150);
+
+/// reloads the pack whenever something in its folder changes, for as long as the stream is held.
+/// the whole packs folder is watched since replacing a pack swaps its folder out
+pub fn watch(id: &PackId) -> impl Stream<Item = Reloaded> + use<> {
+    let id = *id;
+    let (sender, receiver) = mpsc::unbounded_channel();
+    let folder = paths::textures_dir().map(|folder| folder.join(id.name()));
+    let watcher = folder.map_err(notify::Error::from)
+            .and_then(|folder| {
+                let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                    if event.is_ok_and(|event| event.paths.iter().any(|path| path.starts_with(&folder))) {
+                        let _ = sender.send(());
+                    }
+                })?;
+                watcher.watch(&paths::textures_dir()?, RecursiveMode::Recursive)?;
+                Ok(watcher)
+            })
+            .inspect_err(|e| tracing::warn!("can't watch texture pack {}: {}", id.name(), e))
+            .ok();
+
+    // the watcher stops when it's dropped with the stream, without it the channel's closed
+    futures_util::stream::unfold((watcher, receiver), move |(watcher, mut receiver)| async move {
+        receiver.recv().await?;
+        tokio::time::sleep(SETTLE).await;
+        while receiver.try_recv().is_ok() {}
+        let kept = tokio::task::spawn_blocking(move || reload(id)).await.ok();
+        Some((kept, (watcher, receiver)))
+    }).filter_map(move |kept| async move {
+        match kept? {
+            Ok(kept) => Some(Reloaded { id, kept }),
+            Err(e) => {
+                tracing::warn!("can't reload texture pack {}: {}", id.name(), e);
+                None
+            }
+        }
+    })
+}
+
+/// reads an installed pack's folder again. a file that doesn't parse or a required one that's
+/// gone (likely mid-save) keeps its last good version and is returned, an optional one that's
+/// gone falls back to the default
+fn reload(id: PackId) -> io::Result<Vec<&'static str>> {
+    let name = id.name();
+    let folder = paths::textures_dir()?.join(&name);
+    let (old, manifest) = {
+        let packs = PACKS.read().unwrap();
+        let pack = &packs[id.0];
+        (pack.files.clone(), pack.assets.as_ref().map(|assets| assets.manifest).unwrap_or_default())
+    };
+
+    let (files, manifest, kept) = reread(&name, &folder, &old, manifest);
+    let mut packs = PACKS.write().unwrap();
+    packs[id.0].assets = Some(build(&files, manifest));
+    packs[id.0].files = files;
+    Ok(kept)
+}
+
+/// the files in `folder` and the manifest they make, with `old` and `manifest` standing in for
+/// whatever broke
+fn reread(name: &str, folder: &Path, old: &HashMap<&'static str, Handle>, manifest: Manifest)
+        -> (HashMap<&'static str, Handle>, Manifest, Vec<&'static str>) {
+    let options = usvg::Options::default();
+    let mut kept = vec![];
+    let mut read = vec![];
+    for file in REQUIRED.iter().chain(OPTIONAL).copied() {
+        match std::fs::read(folder.join(file)) {
+            Ok(contents) if !file.ends_with(".svg") || usvg::Tree::from_data(&contents, &options).is_ok() => {
+                read.push((file, contents));
+            }
+            Err(_) if OPTIONAL.contains(&file) => {}
+            _ => kept.push(file),
+        }
+    }
+    // unchanged files keep their handles so they stay cached
+    let mut files = handles(old, read);
+    for file in &kept {
+        if let Some(handle) = old.get(file) {
+            files.insert(file, handle.clone());
+        }
+    }
+
+    let manifest = match files.get("manifest.toml") {
+        Some(contents) => Manifest::parse(name, &String::from_utf8_lossy(texture::bytes(contents)))
+                .unwrap_or_else(|e| {
+                    tracing::warn!("{}", e);
+                    kept.push("manifest.toml");
+                    manifest
+                }),
+        None => Manifest::default(),
+    };
+    (files, manifest, kept)
+}
+
+/// every pack that can be selected, by name
+pub fn installed() -> Vec<(PackId, String)> {
+    PACKS.read().unwrap().iter()
+            .enumerate()
+            .filter(|(_, pack)| pack.assets.is_some())
+            .map(|(index, pack)| (PackId(index), pack.name.clone()))
+            .collect()
+}
+
+impl Serialize for PackId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for PackId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(|name| PackId::find_or_insert(&name))
+    }
+}
@@ -0,0 +1,136 @@
+use super::*;
+
+const SVG: &[u8] = br#"<svg xmlns="http://www.w3.org/2000/svg" width="16" height="16"/>"#;
+
+fn required() -> Vec<(String, Vec<u8>)> {
+    REQUIRED.iter()
+            .map(|file| (file.to_string(), SVG.to_vec()))
+            .collect()
+}
+
+#[test]
+fn a_pack_without_a_manifest_gets_the_default_colours() {
+    let pack = validate("pack", required()).unwrap();
+    assert_eq!(pack.manifest.background, Manifest::default().background);
+    assert!(pack.warnings.iter().any(|warning| warning.contains("manifest.toml")));
+}
+
+#[test]
+fn a_pack_with_a_broken_manifest_is_refused() {
+    let mut files = required();
+    files.push(("manifest.toml".to_string(), b"version = 1\naccent = \"nope\"".to_vec()));
+    match validate("pack", files) {
+        Err(PackError::BadManifest(e)) => assert_eq!(e.field, Some("accent")),
+        other => panic!("{:?}", other),
+    }
+}
+
+#[test]
+fn paths_out_of_the_pack_are_unsafe() {
+    for name in ["../cell/cellup.svg", "cell/../../x", "/etc/passwd", "C:/x", "cell\\cellup.svg", "./cell", ""] {
+        assert!(!is_safe(name), "{:?}", name);
+    }
+    assert!(is_safe("my pack/cell/cellup.svg"));
+}
+
+#[test]
+fn a_pack_zipped_as_its_folder_is_looked_inside() {
+    assert_eq!(common_root(["pack/cell/cellup.svg", "pack/manifest.toml"].into_iter()), "pack/");
+    // the folder is part of the layout, or not everything is in it
+    assert_eq!(common_root(["cell/cellup.svg", "cell/celldown.svg"].into_iter()), "");
+    assert_eq!(common_root(["pack/cell/cellup.svg", "manifest.toml"].into_iter()), "");
+}
+
+#[test]
+fn files_that_did_not_change_keep_their_handles() {
+    let old = handles(&HashMap::new(), [("cell/cellup.svg", SVG.to_vec()), ("cell/celldown.svg", SVG.to_vec())]);
+    let changed = br#"<svg xmlns="http://www.w3.org/2000/svg" width="8" height="8"/>"#;
+    let new = handles(&old, [("cell/cellup.svg", SVG.to_vec()), ("cell/celldown.svg", changed.to_vec())]);
+
+    let same = |file| std::ptr::eq(texture::bytes(&old[file]).as_ptr(), texture::bytes(&new[file]).as_ptr());
+    assert!(same("cell/cellup.svg"));
+    assert!(!same("cell/celldown.svg"));
+    assert_eq!(texture::bytes(&new["cell/celldown.svg"]), changed);
+}
+
+/// a pack folder of its own under the temp folder, gone again when it's dropped
+struct Folder(std::path::PathBuf);
+
+impl Folder {
+    fn new(test: &str) -> Self {
+        let folder = std::env::temp_dir().join(format!("minsweeper-pack-{}-{}", std::process::id(), test));
+        let _ = std::fs::remove_dir_all(&folder);
+        for file in REQUIRED.iter().chain(["border/topleft.svg"].iter()) {
+            let path = folder.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, SVG).unwrap();
+        }
+        std::fs::write(folder.join("manifest.toml"), "version = 1\nbackground = \"#102030\"").unwrap();
+        Self(folder)
+    }
+
+    /// what the pack is after being loaded from the folder as it is now
+    fn load(&self) -> (HashMap<&'static str, Handle>, Manifest) {
+        let (files, manifest, kept) = reread("pack", &self.0, &HashMap::new(), Manifest::default());
+        assert!(kept.is_empty(), "{:?}", kept);
+        (files, manifest)
+    }
+}
+
+impl Drop for Folder {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+#[test]
+fn a_reload_picks_up_what_changed() {
+    let folder = Folder::new("changed");
+    let (old, manifest) = folder.load();
+    let changed = br#"<svg xmlns="http://www.w3.org/2000/svg" width="8" height="8"/>"#;
+    std::fs::write(folder.0.join("cell/cell1.svg"), changed).unwrap();
+
+    let (files, _, kept) = reread("pack", &folder.0, &old, manifest);
+    assert!(kept.is_empty());
+    assert_eq!(texture::bytes(&files["cell/cell1.svg"]), changed);
+    assert_eq!(files["cell/cell2.svg"], old["cell/cell2.svg"]);
+}
+
+#[test]
+fn a_broken_svg_keeps_its_last_good_version() {
+    let folder = Folder::new("broken");
+    let (old, manifest) = folder.load();
+    std::fs::write(folder.0.join("cell/cell1.svg"), b"<svg").unwrap();
+    // half way through a save
+    std::fs::remove_file(folder.0.join("cell/cell2.svg")).unwrap();
+
+    let (files, _, kept) = reread("pack", &folder.0, &old, manifest);
+    assert_eq!(kept, ["cell/cell1.svg", "cell/cell2.svg"]);
+    assert_eq!(texture::bytes(&files["cell/cell1.svg"]), SVG);
+    assert_eq!(texture::bytes(&files["cell/cell2.svg"]), SVG);
+}
+
+#[test]
+fn a_removed_optional_file_falls_back_to_the_default() {
+    let folder = Folder::new("optional");
+    let (old, manifest) = folder.load();
+    std::fs::remove_file(folder.0.join("border/topleft.svg")).unwrap();
+    std::fs::remove_file(folder.0.join("manifest.toml")).unwrap();
+
+    let (files, reloaded, kept) = reread("pack", &folder.0, &old, manifest);
+    assert!(kept.is_empty());
+    assert!(!files.contains_key("border/topleft.svg"));
+    assert_eq!(reloaded.background, Manifest::default().background);
+}
+
+#[test]
+fn a_broken_manifest_keeps_the_last_good_colours() {
+    let folder = Folder::new("manifest");
+    let (old, manifest) = folder.load();
+    std::fs::write(folder.0.join("manifest.toml"), "version = 1\nbackground = \"nope\"").unwrap();
+
+    let (_, reloaded, kept) = reread("pack", &folder.0, &old, manifest);
+    assert_eq!(kept, ["manifest.toml"]);
+    assert_eq!(reloaded.background, manifest.background);
+    assert_ne!(reloaded.background, Manifest::default().background);
+}
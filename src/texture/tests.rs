@@ -0,0 +1,109 @@
+use super::*;
+use serde_json::json;
+
+fn svg(asset: &Handle) -> bool {
+    std::str::from_utf8(bytes(asset)).is_ok_and(|text| text.contains("<svg"))
+}
+
+fn revealed(cell_type: CellType) -> Cell {
+    Cell::new(cell_type, CellState::Revealed)
+}
+
+/// every state a cell can be drawn in
+fn cells() -> impl Iterator<Item = Cell> {
+    [CellState::Unknown, CellState::Revealed, CellState::Flagged].into_iter()
+            .flat_map(|state| (0..=8).map(CellType::Safe).chain([CellType::Mine, CellType::Unknown])
+                    .map(move |cell_type| Cell::new(cell_type, state)))
+}
+
+#[test]
+fn every_built_in_texture_has_every_asset() {
+    for texture in Texture::BUILT_IN {
+        for cell in cells() {
+            assert!(svg(&texture.get_cell_asset(cell, false)), "{:?} {:?}", texture, cell);
+            assert!(svg(&texture.get_cell_asset(cell, true)), "{:?} {:?}", texture, cell);
+        }
+        for digit in "0123456789-".chars() {
+            assert!(svg(&texture.get_digit(digit)), "{:?} {}", texture, digit);
+        }
+        for status in [GameStatus::Never, GameStatus::Playing, GameStatus::Won, GameStatus::Lost] {
+            assert!(svg(&texture.get_restart_button(status, false, false)), "{:?} {:?}", texture, status);
+        }
+        assert!(texture.preview().is_some());
+    }
+}
+
+#[test]
+fn each_number_gets_its_own_asset() {
+    let texture = Texture::Light;
+    assert_eq!(bytes(&texture.get_cell_asset(revealed(CellType::Safe(3)), false)), include_bytes!("light/cell/cell3.svg"));
+    assert_eq!(bytes(&texture.get_cell_asset(revealed(CellType::Safe(8)), false)), include_bytes!("light/cell/cell8.svg"));
+    assert_eq!(bytes(&texture.get_cell_asset(revealed(CellType::Safe(0)), false)), include_bytes!("light/cell/celldown.svg"));
+    assert_eq!(bytes(&texture.get_cell_asset(revealed(CellType::Mine), false)), include_bytes!("light/cell/blast.svg"));
+}
+
+#[test]
+fn a_wrong_flag_and_a_pressed_cell_look_the_part() {
+    let texture = Texture::Dark;
+    let flagged = |cell_type| texture.get_cell_asset(Cell::new(cell_type, CellState::Flagged), false);
+    assert_eq!(bytes(&flagged(CellType::Safe(2))), include_bytes!("dark/cell/falsemine.svg"));
+    assert_eq!(bytes(&flagged(CellType::Unknown)), include_bytes!("dark/cell/cellflag.svg"));
+    assert_eq!(bytes(&texture.get_cell_asset(Cell::EMPTY, true)), include_bytes!("dark/cell/celldown.svg"));
+    assert_eq!(bytes(&texture.get_cell_asset(Cell::EMPTY, false)), include_bytes!("dark/cell/cellup.svg"));
+}
+
+#[test]
+fn the_risk_ramp_goes_from_its_first_band_for_a_1_to_its_last_for_an_8() {
+    for texture in Texture::BUILT_IN {
+        let ramp = texture.manifest().tint;
+        assert_eq!(texture.get_tint(revealed(CellType::Safe(1))), Some(ramp[0]), "{:?}", texture);
+        assert_eq!(texture.get_tint(revealed(CellType::Safe(2))), Some(ramp[1]), "{:?}", texture);
+        assert_eq!(texture.get_tint(revealed(CellType::Safe(7))), Some(ramp[6]), "{:?}", texture);
+        assert_eq!(texture.get_tint(revealed(CellType::Safe(8))), Some(ramp[7]), "{:?}", texture);
+        // past either end of the ramp there's nothing to shade
+        assert_eq!(texture.get_tint(revealed(CellType::Safe(0))), None, "{:?}", texture);
+        assert_eq!(texture.get_tint(revealed(CellType::Mine)), None, "{:?}", texture);
+        for state in [CellState::Unknown, CellState::Flagged] {
+            assert_eq!(texture.get_tint(Cell::new(CellType::Safe(8), state)), None, "{:?} {:?}", texture, state);
+        }
+    }
+}
+
+#[test]
+fn the_asset_under_the_shading_is_the_same_one_with_it_off() {
+    for texture in Texture::BUILT_IN {
+        for cell in cells() {
+            for down in [false, true] {
+                let (shaded, tint) = texture.get_shaded_cell_asset(cell, down, true);
+                let (plain, none) = texture.get_shaded_cell_asset(cell, down, false);
+                assert_eq!(shaded.id(), plain.id(), "{:?} {:?}", texture, cell);
+                assert_eq!(tint, texture.get_tint(cell), "{:?} {:?}", texture, cell);
+                assert_eq!(none, None, "{:?} {:?}", texture, cell);
+            }
+        }
+    }
+}
+
+#[test]
+fn with_the_shading_off_every_asset_is_the_textures_own() {
+    for texture in Texture::BUILT_IN {
+        for cell in cells() {
+            let (plain, _) = texture.get_shaded_cell_asset(cell, false, false);
+            assert_eq!(bytes(&plain), bytes(&texture.get_cell_asset(cell, false)), "{:?} {:?}", texture, cell);
+        }
+    }
+    let numbered = |number, risk_shading| Texture::Dark.get_shaded_cell_asset(revealed(CellType::Safe(number)), false, risk_shading).0;
+    assert_eq!(bytes(&numbered(3, false)), include_bytes!("dark/cell/cell3.svg"));
+    assert_eq!(bytes(&numbered(8, false)), include_bytes!("dark/cell/cell8.svg"));
+    assert_eq!(bytes(&numbered(8, true)), include_bytes!("dark/cell/cell8.svg"));
+}
+
+#[test]
+fn an_unknown_texture_falls_back_to_the_default() {
+    let mut value = json!({ "texture": "Sepia" });
+    assert_eq!(forget_unknown(&mut value, "/texture"), Some("Sepia".to_string()));
+    assert_eq!(value["texture"], serde_json::to_value(Texture::default()).unwrap());
+
+    let mut value = json!({ "texture": "Light" });
+    assert_eq!(forget_unknown(&mut value, "/texture"), None);
+}
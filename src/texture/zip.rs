@@ -0,0 +1,80 @@
+use crate::texture::pack::PackError;
+use std::io;
+use std::io::{Cursor, Read};
+use zip::result::ZipError;
+use zip::{CompressionMethod, ZipArchive};
+
+#[cfg(test)]
+mod tests;
+
+// texture packs only need stored and deflated entries, anything else is refused before it's read
+
+const MAX_ENTRIES: usize = 1024;
+
+/// how much a single entry and the whole archive may decompress to, checked against the headers
+/// up front and against what actually comes out of the decoder
+#[derive(Copy, Clone, Debug)]
+pub struct Limits {
+    pub entry: u64,
+    pub total: u64,
+}
+
+/// every file in the archive as `(name, contents)`, directory entries are skipped
+pub fn read(data: &[u8], limits: Limits) -> Result<Vec<(String, Vec<u8>)>, PackError> {
+    let mut archive = ZipArchive::new(Cursor::new(data)).map_err(refused)?;
+    if archive.len() > MAX_ENTRIES {
+        return Err(PackError::TooLarge)
+    }
+
+    let mut files = vec![];
+    let mut total = 0u64;
+    for index in 0..archive.len() {
+        // the headers are checked without decompressing anything
+        let (name, size) = {
+            let entry = archive.by_index_raw(index).map_err(refused)?;
+            if entry.is_dir() {
+                continue
+            }
+            if entry.encrypted() {
+                return Err(PackError::Unsupported("encrypted entries"))
+            }
+            if !matches!(entry.compression(), CompressionMethod::Stored | CompressionMethod::Deflated) {
+                return Err(PackError::Unsupported("compression methods other than deflate"))
+            }
+            (entry.name().to_string(), entry.size())
+        };
+
+        total = total.saturating_add(size);
+        if size > limits.entry || total > limits.total {
+            return Err(PackError::TooLarge)
+        }
+
+        // the header can lie, never take more than it promised plus one byte to notice
+        let mut contents = Vec::with_capacity(size as usize);
+        archive.by_index(index).map_err(refused)?
+                .take(size + 1)
+                .read_to_end(&mut contents)
+                // the checksum is checked once the entry's been read to the end, a stream that
+                // doesn't decompress is invalid input instead
+                .map_err(|e| PackError::Corrupt(match e.kind() {
+                    io::ErrorKind::InvalidData => "checksum mismatch",
+                    _ => "can't decompress",
+                }))?;
+        if contents.len() as u64 != size {
+            return Err(PackError::Corrupt("size doesn't match the header"))
+        }
+
+        files.push((name, contents));
+    }
+
+    Ok(files)
+}
+
+fn refused(e: ZipError) -> PackError {
+    match e {
+        ZipError::InvalidArchive(reason) => PackError::Corrupt(reason),
+        ZipError::UnsupportedArchive(_) => PackError::Unsupported("some of the archive's features"),
+        ZipError::Io(_) => PackError::Corrupt("truncated archive"),
+        _ => PackError::Corrupt("not a zip archive"),
+    }
+}
@@ -0,0 +1,185 @@
+use super::*;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use std::io::Write;
+
+const END_OF_CENTRAL_DIRECTORY: u32 = 0x06054b50;
+const CENTRAL_DIRECTORY_HEADER: u32 = 0x02014b50;
+const LOCAL_FILE_HEADER: u32 = 0x04034b50;
+
+const LIMITS: Limits = Limits { entry: 1024, total: 4096 };
+
+/// an entry as it's written, the fields after `contents` are what the headers claim and can be
+/// made to lie
+#[derive(Clone)]
+struct Entry {
+    name: String,
+    contents: Vec<u8>,
+    method: u16,
+    flags: u16,
+    crc: u32,
+    uncompressed: u32,
+}
+
+impl Entry {
+    fn stored(name: &str, contents: &[u8]) -> Self {
+        Self {
+            name: name.to_string(),
+            contents: contents.to_vec(),
+            method: 0,
+            flags: 0,
+            crc: crc32fast::hash(contents),
+            uncompressed: contents.len() as u32,
+        }
+    }
+
+    fn deflated(name: &str, contents: &[u8]) -> Self {
+        Self { method: 8, ..Self::stored(name, contents) }
+    }
+
+    fn data(&self) -> Vec<u8> {
+        match self.method {
+            8 => {
+                let mut encoder = DeflateEncoder::new(vec![], Compression::default());
+                encoder.write_all(&self.contents).unwrap();
+                encoder.finish().unwrap()
+            }
+            _ => self.contents.clone(),
+        }
+    }
+}
+
+/// just enough of a zip writer for the reader to have something to read
+fn archive(entries: &[Entry]) -> Vec<u8> {
+    let mut out = vec![];
+    let mut directory = vec![];
+    for entry in entries {
+        let data = entry.data();
+        let local = out.len() as u32;
+        out.extend(LOCAL_FILE_HEADER.to_le_bytes());
+        out.extend([20, 0]);
+        out.extend(entry.flags.to_le_bytes());
+        out.extend(entry.method.to_le_bytes());
+        out.extend([0; 4]);
+        out.extend(entry.crc.to_le_bytes());
+        out.extend((data.len() as u32).to_le_bytes());
+        out.extend(entry.uncompressed.to_le_bytes());
+        out.extend((entry.name.len() as u16).to_le_bytes());
+        out.extend([0, 0]);
+        out.extend(entry.name.as_bytes());
+        out.extend(&data);
+
+        directory.extend(CENTRAL_DIRECTORY_HEADER.to_le_bytes());
+        directory.extend([20, 0, 20, 0]);
+        directory.extend(entry.flags.to_le_bytes());
+        directory.extend(entry.method.to_le_bytes());
+        directory.extend([0; 4]);
+        directory.extend(entry.crc.to_le_bytes());
+        directory.extend((data.len() as u32).to_le_bytes());
+        directory.extend(entry.uncompressed.to_le_bytes());
+        directory.extend((entry.name.len() as u16).to_le_bytes());
+        directory.extend([0; 12]);
+        directory.extend(local.to_le_bytes());
+        directory.extend(entry.name.as_bytes());
+    }
+
+    let start = out.len() as u32;
+    out.extend(&directory);
+    out.extend(END_OF_CENTRAL_DIRECTORY.to_le_bytes());
+    out.extend([0; 4]);
+    out.extend((entries.len() as u16).to_le_bytes());
+    out.extend((entries.len() as u16).to_le_bytes());
+    out.extend((directory.len() as u32).to_le_bytes());
+    out.extend(start.to_le_bytes());
+    out.extend([0, 0]);
+    out
+}
+
+fn corrupt(data: &[u8]) -> bool {
+    matches!(read(data, LIMITS), Err(PackError::Corrupt(_)))
+}
+
+#[test]
+fn stored_and_deflated_entries_come_out_as_they_went_in() {
+    let svg = b"<svg xmlns=\"http://www.w3.org/2000/svg\"/>".repeat(4);
+    let files = read(&archive(&[
+        Entry::stored("cell/cellup.svg", &svg),
+        Entry::deflated("cell/celldown.svg", &svg),
+        Entry::stored("cell/", b""),
+    ]), LIMITS).unwrap();
+    assert_eq!(files, [("cell/cellup.svg".to_string(), svg.clone()), ("cell/celldown.svg".to_string(), svg)]);
+}
+
+#[test]
+fn anything_that_is_not_a_zip_is_refused() {
+    assert!(corrupt(b""));
+    assert!(corrupt(b"<svg xmlns=\"http://www.w3.org/2000/svg\"/>"));
+}
+
+#[test]
+fn a_truncated_archive_is_refused_wherever_it_is_cut() {
+    let data = archive(&[Entry::deflated("a.svg", &[b'a'; 300]), Entry::stored("b.svg", b"b")]);
+    // losing the end record makes it not a zip at all, anywhere else the headers point past the end
+    for length in 0..data.len() {
+        assert!(corrupt(&data[..length]), "cut at {}", length);
+    }
+}
+
+#[test]
+fn the_central_directory_has_to_be_where_it_says() {
+    let mut data = archive(&[Entry::stored("a.svg", b"a")]);
+    let end = data.len() - 22;
+    data[end + 16..end + 20].copy_from_slice(&0u32.to_le_bytes());
+    assert!(corrupt(&data));
+}
+
+#[test]
+fn a_local_header_pointing_anywhere_else_is_refused() {
+    let mut data = archive(&[Entry::stored("a.svg", b"a"), Entry::stored("b.svg", b"b")]);
+    let end = data.len() - 22;
+    let directory = u32::from_le_bytes(data[end + 16..end + 20].try_into().unwrap()) as usize;
+    // the first entry's local header offset, pointed into the middle of its data
+    data[directory + 42..directory + 46].copy_from_slice(&5u32.to_le_bytes());
+    assert!(corrupt(&data));
+}
+
+#[test]
+fn a_checksum_that_does_not_match_is_refused() {
+    let entry = Entry { crc: 0xdeadbeef, ..Entry::deflated("a.svg", b"aaaa") };
+    assert!(matches!(read(&archive(&[entry]), LIMITS), Err(PackError::Corrupt("checksum mismatch"))));
+}
+
+#[test]
+fn a_bomb_that_lies_about_its_size_stops_at_what_it_claimed() {
+    let entry = Entry { uncompressed: 16, ..Entry::deflated("a.svg", &[0; 1000]) };
+    assert!(matches!(read(&archive(&[entry]), LIMITS), Err(PackError::Corrupt("size doesn't match the header"))));
+}
+
+#[test]
+fn entries_that_are_too_big_are_refused_from_their_headers() {
+    let entry = Entry::deflated("a.svg", &[0; LIMITS.entry as usize + 1]);
+    assert!(matches!(read(&archive(&[entry]), LIMITS), Err(PackError::TooLarge)));
+
+    // each small enough, too much together
+    let entries = (0..5).map(|index| Entry::deflated(&format!("{}.svg", index), &[0; 1000])).collect::<Vec<_>>();
+    assert!(matches!(read(&archive(&entries), LIMITS), Err(PackError::TooLarge)));
+
+    // a zip64 size without the record to go with it is as big as it says
+    let zip64 = Entry { uncompressed: u32::MAX, ..Entry::stored("a.svg", b"a") };
+    assert!(matches!(read(&archive(&[zip64]), LIMITS), Err(PackError::TooLarge)));
+}
+
+#[test]
+fn too_many_entries_are_refused() {
+    let entries = (0..=MAX_ENTRIES).map(|index| Entry::stored(&format!("{}/", index), b"")).collect::<Vec<_>>();
+    assert!(matches!(read(&archive(&entries), LIMITS), Err(PackError::TooLarge)));
+}
+
+#[test]
+fn encrypted_and_unknown_entries_are_refused() {
+    let encrypted = Entry { flags: 1, ..Entry::stored("a.svg", b"a") };
+    assert!(matches!(read(&archive(&[encrypted]), LIMITS), Err(PackError::Unsupported(_))));
+
+    let bzip2 = Entry { method: 12, ..Entry::stored("a.svg", b"a") };
+    assert!(matches!(read(&archive(&[bzip2]), LIMITS), Err(PackError::Unsupported(_))));
+}
@@ -0,0 +1,107 @@
+use crate::geometry::Geometry;
+use crate::paths;
+use iced::window;
+use std::collections::HashMap;
+
+#[cfg(test)]
+mod tests;
+
+/// views that can be moved out of their dialog into a window of their own
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Detached {
+    Statistics,
+    /// the unlocked achievements and when each was
+    Achievements,
+    /// the game read only, kept up by the same repaints as the main window
+    Spectator,
+}
+
+impl Detached {
+    pub const ALL: [Detached; 3] = [Detached::Statistics, Detached::Achievements, Detached::Spectator];
+
+    pub fn size(self) -> iced::Size {
+        match self {
+            Detached::Statistics => iced::Size::new(1100.0, 500.0),
+            Detached::Achievements => iced::Size::new(500.0, 500.0),
+            Detached::Spectator => iced::Size::new(800.0, 600.0),
+        }
+    }
+
+    /// what its placement is kept under
+    fn name(self) -> &'static str {
+        match self {
+            Detached::Statistics => "statistics",
+            Detached::Achievements => "achievements",
+            Detached::Spectator => "spectator",
+        }
+    }
+
+    pub fn load_placement(self) -> Option<Geometry> {
+        Geometry::load_from(&paths::detached_window_file(self.name()).ok()?)
+    }
+
+    pub fn save_placement(self, placement: &Geometry) {
+        if let Err(e) = paths::detached_window_file(self.name())
+                .and_then(|path| placement.save_to(&path)) {
+            tracing::error!("failed to save where the {} window was: {}", self.name(), e);
+        }
+    }
+}
+
+/// which window shows what, the main one and every view detached from it
+#[derive(Debug, Default)]
+pub struct Windows {
+    main: Option<window::Id>,
+    detached: HashMap<window::Id, Detached>,
+}
+
+impl Windows {
+    pub fn with_main(id: window::Id) -> Self {
+        Self {
+            main: Some(id),
+            ..Self::default()
+        }
+    }
+
+    pub fn main(&self) -> Option<window::Id> {
+        self.main
+    }
+
+    pub fn is_main(&self, id: window::Id) -> bool {
+        self.main == Some(id)
+    }
+
+    /// the view the window was detached for, `None` for the main window and any it doesn't know
+    pub fn view(&self, id: window::Id) -> Option<Detached> {
+        self.detached.get(&id).copied()
+    }
+
+    /// the window the view is detached into, if it is
+    pub fn find(&self, view: Detached) -> Option<window::Id> {
+        self.detached.iter()
+                .find(|(_, detached)| **detached == view)
+                .map(|(id, _)| *id)
+    }
+
+    pub fn detach(&mut self, id: window::Id, view: Detached) {
+        self.detached.insert(id, view);
+    }
+
+    pub fn views(&self) -> impl Iterator<Item = Detached> + '_ {
+        self.detached.values().copied()
+    }
+
+    /// forgets a detached window that closed, returning what it showed
+    pub fn close(&mut self, id: window::Id) -> Option<Detached> {
+        self.detached.remove(&id)
+    }
+
+    /// every window to close along with the main one, its detached children first and the main
+    /// one last, none of them are known afterwards
+    pub fn close_all(&mut self) -> Vec<window::Id> {
+        self.detached.drain()
+                .map(|(id, _)| id)
+                .chain(self.main.take())
+                .collect()
+    }
+}
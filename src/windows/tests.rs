@@ -0,0 +1,45 @@
+use super::*;
+
+/// a main window with the statistics and the spectator detached from it
+fn windows() -> (Windows, window::Id, window::Id, window::Id) {
+    let (main, statistics, spectator) = (window::Id::unique(), window::Id::unique(), window::Id::unique());
+    let mut windows = Windows::with_main(main);
+    windows.detach(statistics, Detached::Statistics);
+    windows.detach(spectator, Detached::Spectator);
+    (windows, main, statistics, spectator)
+}
+
+#[test]
+fn a_board_update_is_drawn_in_the_window_the_spectator_was_detached_into() {
+    let (windows, main, statistics, spectator) = windows();
+
+    assert_eq!(windows.find(Detached::Spectator), Some(spectator));
+    assert_eq!(windows.view(spectator), Some(Detached::Spectator));
+    assert_eq!(windows.view(statistics), Some(Detached::Statistics));
+    assert_eq!(windows.view(main), None, "the main window draws the game itself");
+    assert_eq!(windows.find(Detached::Achievements), None);
+}
+
+#[test]
+fn closing_a_detached_window_leaves_the_rest() {
+    let (mut windows, main, statistics, spectator) = windows();
+
+    assert_eq!(windows.close(spectator), Some(Detached::Spectator));
+    assert_eq!(windows.find(Detached::Spectator), None);
+    assert_eq!(windows.view(statistics), Some(Detached::Statistics));
+    assert!(windows.is_main(main));
+}
+
+#[test]
+fn closing_the_main_window_closes_its_children_before_it() {
+    let (mut windows, main, statistics, spectator) = windows();
+
+    let closed = windows.close_all();
+    assert_eq!(closed.len(), 3);
+    assert_eq!(closed.last(), Some(&main));
+    assert!(closed.contains(&statistics) && closed.contains(&spectator));
+
+    assert_eq!(windows.views().count(), 0);
+    assert_eq!(windows.main(), None);
+    assert!(windows.close_all().is_empty(), "nothing is closed twice");
+}
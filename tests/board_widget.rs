@@ -0,0 +1,170 @@
+use futures_util::StreamExt;
+use iced::{mouse, Task};
+use iced_runtime::task::into_stream;
+use iced_runtime::Action;
+use minsweeper_native_client::board_widget::{Assist, BoardConfig, BoardEvent, BoardWidget, Message};
+use minsweeper_native_client::minsweeper::{layout_from_mines, Provenance};
+use minsweeper_rs::board::{BoardSize, Point};
+use minsweeper_rs::solver::mia::MiaSolver;
+use minsweeper_rs::GameStatus;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+use tokio::sync::mpsc::UnboundedReceiver;
+
+/// ```text
+/// . 2 M 2 .
+/// . 2 M 2 .
+/// . 1 1 1 .
+/// ```
+const MINES: [Point; 2] = [(2, 0), (2, 1)];
+
+/// the widget on its own with no window, its tasks run to the end and fed back in the way an
+/// embedding app would
+struct Embedder {
+    board: BoardWidget,
+    events: UnboundedReceiver<BoardEvent>,
+    runtime: Runtime,
+}
+
+impl Embedder {
+    fn new(config: BoardConfig, solver: bool) -> Self {
+        let layout = layout_from_mines(BoardSize::new(5, 3, MINES.len()).unwrap(), &MINES);
+        let solver = solver.then(|| Arc::new(MiaSolver::default()) as _);
+        let (mut board, start) = BoardWidget::with_layout(layout, solver, config);
+        let events = board.events();
+        let mut embedder = Self {
+            board,
+            events,
+            runtime: tokio::runtime::Builder::new_current_thread()
+                    .enable_time()
+                    .build()
+                    .unwrap(),
+        };
+        embedder.drain(start);
+        embedder
+    }
+
+    fn send(&mut self, message: Message) {
+        let task = self.update(message);
+        self.drain(task);
+    }
+
+    fn update(&mut self, message: Message) -> Task<Message> {
+        let _context = self.runtime.enter();
+        self.board.update(message)
+    }
+
+    fn drain(&mut self, task: Task<Message>) {
+        let mut pending = VecDeque::from([task]);
+        while let Some(task) = pending.pop_front() {
+            let Some(mut stream) = into_stream(task) else { continue };
+            while let Some(action) = self.runtime.block_on(stream.next()) {
+                let Action::Output(message) = action else { continue };
+                pending.push_back(self.update(message));
+            }
+        }
+    }
+
+    fn click(&mut self, point: Point) {
+        self.send(Message::Press(point, mouse::Button::Left));
+        self.send(Message::Release(point, mouse::Button::Left));
+    }
+
+    fn flag(&mut self, point: Point) {
+        self.send(Message::Press(point, mouse::Button::Right));
+        self.send(Message::Release(point, mouse::Button::Right));
+    }
+
+    fn events(&mut self) -> Vec<BoardEvent> {
+        let mut events = vec![];
+        while let Ok(event) = self.events.try_recv() {
+            events.push(event);
+        }
+        events
+    }
+}
+
+#[test]
+fn a_full_game_says_what_happened_in_order() {
+    let mut embedder = Embedder::new(BoardConfig::default(), false);
+    embedder.click((4, 1));
+    embedder.flag((2, 0));
+    embedder.flag((2, 0));
+    embedder.click((0, 0));
+    embedder.click((2, 2));
+
+    assert_eq!(embedder.board.status(), GameStatus::Won);
+    assert_eq!(embedder.events(), [
+        BoardEvent::Started,
+        BoardEvent::Revealed { point: (4, 1), by: Provenance::Player, cells: 6 },
+        BoardEvent::Flagged { point: (2, 0), by: Provenance::Player },
+        BoardEvent::Unflagged { point: (2, 0), by: Provenance::Player },
+        BoardEvent::Revealed { point: (0, 0), by: Provenance::Player, cells: 6 },
+        BoardEvent::Revealed { point: (2, 2), by: Provenance::Player, cells: 1 },
+        BoardEvent::Won,
+    ]);
+}
+
+#[test]
+fn a_mine_loses_and_nothing_goes_in_after() {
+    let mut embedder = Embedder::new(BoardConfig::default(), false);
+    embedder.click((2, 1));
+    embedder.click((0, 0));
+
+    assert_eq!(embedder.board.status(), GameStatus::Lost);
+    assert_eq!(embedder.events(), [
+        BoardEvent::Started,
+        BoardEvent::Revealed { point: (2, 1), by: Provenance::Player, cells: 1 },
+        BoardEvent::Lost,
+    ]);
+}
+
+#[test]
+fn auto_plays_out_the_rest_after_the_first_click() {
+    let mut embedder = Embedder::new(BoardConfig { assist: Assist::Auto, ..BoardConfig::default() }, true);
+    embedder.click((4, 1));
+
+    assert_eq!(embedder.board.status(), GameStatus::Won);
+    let events = embedder.events();
+    assert_eq!(events[..2], [BoardEvent::Started, BoardEvent::Revealed { point: (4, 1), by: Provenance::Player, cells: 6 }]);
+    assert_eq!(events.last(), Some(&BoardEvent::Won));
+    assert!(events[2..events.len() - 1].iter().all(|event| matches!(event,
+        BoardEvent::Revealed { by: Provenance::Solver(_), .. } | BoardEvent::Flagged { by: Provenance::Solver(_), .. })),
+        "{:?}", events);
+}
+
+#[test]
+fn steps_only_come_when_asked_for() {
+    let mut embedder = Embedder::new(BoardConfig { assist: Assist::Steps, ..BoardConfig::default() }, true);
+    embedder.click((4, 1));
+    embedder.events();
+
+    embedder.send(Message::Step);
+    let events = embedder.events();
+    assert!(!events.is_empty());
+    assert!(events.iter().all(|event| matches!(event,
+        BoardEvent::Revealed { by: Provenance::Solver(1), .. } | BoardEvent::Flagged { by: Provenance::Solver(1), .. })),
+        "{:?}", events);
+
+    let mut embedder = Embedder::new(BoardConfig::default(), true);
+    embedder.click((4, 1));
+    embedder.events();
+    embedder.send(Message::Step);
+    assert_eq!(embedder.events(), [], "steps are off");
+}
+
+#[test]
+fn a_restart_drops_the_click_still_in_flight() {
+    let mut embedder = Embedder::new(BoardConfig::default(), false);
+    embedder.send(Message::Press((4, 1), mouse::Button::Left));
+    let click = embedder.update(Message::Release((4, 1), mouse::Button::Left));
+    embedder.send(Message::Restart);
+    embedder.drain(click);
+
+    assert_eq!(embedder.events(), [BoardEvent::Started, BoardEvent::Started]);
+    assert_eq!(embedder.board.gamestate().board[(4, 1)].cell_state, minsweeper_rs::CellState::Unknown);
+
+    embedder.click((4, 1));
+    assert_eq!(embedder.events(), [BoardEvent::Revealed { point: (4, 1), by: Provenance::Player, cells: 6 }]);
+}
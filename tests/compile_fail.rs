@@ -0,0 +1,4 @@
+#[test]
+fn a_texture_missing_an_asset_does_not_compile() {
+    trybuild::TestCases::new().compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,47 @@
+// a built-in texture whose directory is missing its flag, next to one that has everything. the
+// real asset tables and macros are used, with just enough of the texture module around them
+
+#[macro_use]
+#[path = "../../src/texture/assets.rs"]
+mod assets;
+
+mod manifest {
+    #[derive(Default)]
+    pub struct Manifest;
+
+    impl Manifest {
+        pub fn parse(_name: &str, _toml: &str) -> Result<Self, String> {
+            Ok(Self)
+        }
+    }
+}
+
+mod pack {
+    use crate::assets::Assets;
+    use serde::{Deserialize, Serialize};
+    use std::sync::Arc;
+
+    #[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+    pub struct PackId;
+
+    impl PackId {
+        pub fn assets(self) -> Option<Arc<Assets>> {
+            None
+        }
+    }
+}
+
+use assets::{embedded, Assets, BorderAssets, CellAssets, FaceAssets};
+use manifest::Manifest;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, LazyLock};
+
+textures! {
+    #[default]
+    Dark => "../../src/texture/dark";
+    NoFlag => "no_flag";
+}
+
+fn main() {
+    let _ = Texture::BUILT_IN.iter().map(|texture| texture.assets());
+}